@@ -688,6 +688,44 @@ fn main() -> Result<(), SetupError> {
         }',
         NULL,
         0, 0, 'system', 0
+    ),
+    (
+        'RSI',
+        'rsi',
+        'A momentum oscillator strategy that trades overbought and oversold readings of the Relative Strength Index.',
+        'The RSI strategy uses Wilder''s Relative Strength Index to spot stretched moves. It enters long when RSI falls below the oversold level and short when it rises above the overbought level, then exits when RSI returns to the midline or reaches the opposite band. An optional divergence filter only takes entries where price makes a new extreme that RSI does not confirm.',
+        '{
+            "options": {
+                "exitMode": ["midline", "opposite"]
+            },
+            "default": {
+                "rsiPeriod": 14,
+                "overbought": 70,
+                "oversold": 30,
+                "exitMode": "midline",
+                "midline": 50,
+                "divergence": false,
+                "divergenceLookback": 14,
+                "positionType": "both",
+                "rebalanceInterval": "daily"
+            }
+        }',
+        '{
+            "stopLoss": 5.0,
+            "takeProfit": 10.0,
+            "riskPerTrade": 2.0,
+            "positionSize": 30.0,
+            "maxConcurrentPositions": 1
+        }',
+        '{
+            "slippage": 0.1,
+            "commission": 0.05,
+            "entryDelay": 1,
+            "minHoldingPeriod": 3,
+            "maxHoldingPeriod": 10
+        }',
+        NULL,
+        0, 0, 'system', 0
     );
     "#;
 
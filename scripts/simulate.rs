@@ -0,0 +1,73 @@
+use std::{
+    env,
+    fs::{self, File},
+    io::{BufWriter, Write},
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use quantedge_x::matcher::{
+    book::orderbook::OrderBook,
+    engine::engine::Engine,
+    policy::price_level::fifo::FifoPriceLevel,
+    runtime::actor::BookActor,
+    simulation::{scenario::ScenarioConfig, simulator::Simulator},
+    storage::localfile_storage::LocalFileStorage,
+};
+use serde::Serialize;
+use tokio::{sync::mpsc, task::JoinHandle};
+
+fn spawn_jsonl_writer<T>(mut rx: mpsc::Receiver<T>, path: PathBuf) -> JoinHandle<anyhow::Result<()>>
+where
+    T: Serialize + Send + 'static,
+{
+    tokio::spawn(async move {
+        let mut writer = BufWriter::new(File::create(&path)?);
+        while let Some(msg) = rx.recv().await {
+            serde_json::to_writer(&mut writer, &msg)?;
+            writer.write_all(b"\n")?;
+            writer.flush()?;
+        }
+        Ok(())
+    })
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let scenario_path = env::args()
+        .nth(1)
+        .unwrap_or_else(|| "scenarios/default.json".to_string());
+    let out_dir = env::args()
+        .nth(2)
+        .unwrap_or_else(|| ".simulation".to_string());
+
+    let scenario = ScenarioConfig::from_file(&scenario_path)?;
+    fs::create_dir_all(&out_dir)?;
+
+    let (engine, ob_rx, trade_rx) = Engine::build_with_publisher();
+    let order_book: OrderBook<FifoPriceLevel, fn() -> FifoPriceLevel> =
+        OrderBook::new(FifoPriceLevel::new);
+    let (client, _jh) = BookActor::<
+        OrderBook<FifoPriceLevel, fn() -> FifoPriceLevel>, // T
+        FifoPriceLevel,                                    // L
+        fn() -> FifoPriceLevel,                            // F
+        LocalFileStorage,                                  // S
+    >::build_actor(order_book, 1024, 3600, engine);
+
+    let out = Path::new(&out_dir);
+    spawn_jsonl_writer(ob_rx, out.join("depth.jsonl"));
+    spawn_jsonl_writer(trade_rx, out.join("trades.jsonl"));
+
+    println!(
+        "Running scenario '{}' from {}",
+        scenario.name, scenario_path
+    );
+    let report = Simulator::new(scenario).run(&client).await?;
+
+    // give the publisher one more tick to flush the last depth update
+    tokio::time::sleep(Duration::from_millis(300)).await;
+
+    println!("{}", serde_json::to_string_pretty(&report)?);
+    println!("Depth and trade streams written to {}", out.display());
+    Ok(())
+}
@@ -4,7 +4,9 @@ use axum::{
     Json,
     extract::{Path, Query, State},
     http::StatusCode,
+    response::Response,
 };
+use axum_extra::extract::CookieJar;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
@@ -15,7 +17,8 @@ use crate::{
         sleddb::ChartDB,
     },
     engine::{
-        backtest_result::{Balance, Trade},
+        backtest_result::{Balance, RegimePerformance, Trade},
+        chart::StrategyChart,
         parameters::StrategyRunParameters,
     },
     indicators::calculator::{DistributionData, MonthlyReturnData},
@@ -23,11 +26,14 @@ use crate::{
         RunBacktestData, RunStrategyCoreParameters, RunStrategyExecutionParameters,
         RunStrategyParameters, RunStrategyRiskParameters,
     },
+    strategy::strategy_factory::{StrategyFactory, StrategyInfo},
 };
 
 use super::{
     PageQuery, StrategyTemplateResponse,
     backtest::{LabBacktestRunRequest, Metrics},
+    backtest_error_response, field_error_response, get_current_user_from_cookie,
+    parse_backtest_request,
 };
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -91,6 +97,11 @@ pub struct LabBacktestRunResponse {
     pub metrics: Metrics,
     pub version: Option<i64>,
     pub date: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub chart: Option<StrategyChart>,
+    /// 按市场状态拆分的绩效，配置了状态识别时才有
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub regimes: Vec<RegimePerformance>,
 }
 
 impl Algorithm {
@@ -276,6 +287,11 @@ pub async fn get_strategy_template_by_id(
     Ok(Json(strategy_template))
 }
 
+/// 每个已注册策略的参数类型、范围、默认值、说明和依赖，前端据此生成表单
+pub async fn get_strategy_schemas() -> Json<Vec<StrategyInfo>> {
+    Json(StrategyFactory::new().catalog())
+}
+
 pub async fn get_strategy_templates(
     Query(query): Query<PageQuery>,
     State(state): State<AppState>,
@@ -327,14 +343,18 @@ pub async fn get_strategy_templates(
 
 pub async fn run_lab_backtest(
     State(state): State<AppState>,
-    Json(req): Json<LabBacktestRunRequest>,
-) -> Result<Json<LabBacktestRunResponse>, StatusCode> {
+    jar: CookieJar,
+    Json(body): Json<Value>,
+) -> Result<Json<LabBacktestRunResponse>, Response> {
+    // 实验室不要求登录，登录后才能用自己保存的市场状态模型
+    let user_id = get_current_user_from_cookie(jar).ok().map(|user| user.id);
+    let req: LabBacktestRunRequest = parse_backtest_request(body).map_err(field_error_response)?;
     let params: StrategyRunParameters = req.params.clone();
 
     let backtest_result = state
         .backtest_service
-        .run_lab_backtest(req)
-        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+        .run_lab_backtest(user_id, req)
+        .map_err(backtest_error_response)?;
 
     let response = LabBacktestRunResponse {
         metrics: backtest_result.metrics.into(),
@@ -345,6 +365,8 @@ pub async fn run_lab_backtest(
         return_distribution: backtest_result.return_distribution,
         version: backtest_result.version,
         date: backtest_result.date,
+        chart: backtest_result.chart,
+        regimes: backtest_result.regimes,
     };
 
     Ok(Json(response))
@@ -385,6 +407,8 @@ pub async fn lab_run_history_backtest_data(
         return_distribution: backtest_result.return_distribution,
         version: backtest_result.version,
         date: backtest_result.date,
+        chart: backtest_result.chart,
+        regimes: backtest_result.regimes,
     };
 
     println!("lab backtest_data res {:?}", response);
@@ -426,6 +450,8 @@ pub async fn lab_run_comparison_data(
                     return_distribution: backtest_result.return_distribution,
                     version: backtest_result.version,
                     date: backtest_result.date,
+                    chart: None,
+                    regimes: backtest_result.regimes,
                 },
             }
         })
@@ -3,7 +3,8 @@ use serde::{Deserialize, Serialize};
 use crate::{
     data::duckdb::schema::backtest_run_history::BacktestRunHistory,
     engine::{
-        backtest_result::{Balance, Trade},
+        backtest_result::{Balance, RegimePerformance, Trade},
+        chart::StrategyChart,
         parameters::StrategyRunParameters,
     },
     indicators::calculator::{DistributionData, MonthlyReturnData},
@@ -73,6 +74,9 @@ pub struct LabBacktestRunRequest {
     #[serde(rename = "positionType")]
     #[serde(default = "default_position_type")]
     pub position_type: String,
+    /// 结果附带策略指标线和信号标记
+    #[serde(rename = "includeChart", default)]
+    pub include_chart: bool,
 }
 
 #[derive(Debug, Serialize)]
@@ -87,6 +91,11 @@ pub struct StrategyBacktestRunResponse {
     pub metrics: Metrics,
     pub version: Option<i64>,
     pub date: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub chart: Option<StrategyChart>,
+    /// 按市场状态拆分的绩效，配置了状态识别时才有
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub regimes: Vec<RegimePerformance>,
 }
 
 fn default_position_type() -> String {
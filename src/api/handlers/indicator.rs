@@ -0,0 +1,111 @@
+use axum::{Json, http::StatusCode};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    data::{market_data_feed::MarketDataFeed, timeframe::Timeframe},
+    indicators::{
+        overlay::{ChartSeries, compute_series},
+        registry::{IndicatorCatalog, IndicatorRegistry},
+        spec::IndicatorSpec,
+    },
+    strategy::market_data::MarketData,
+};
+
+use super::PriceData;
+
+/// 可用的指标、参数默认值、输出、价格源和运算符，前端据此拼指标描述
+pub async fn get_indicator_catalog() -> Json<IndicatorCatalog> {
+    Json(IndicatorRegistry::new().catalog())
+}
+
+#[derive(Debug, Deserialize)]
+pub struct IndicatorChartRequest {
+    pub symbol: String,
+    pub timeframe: String,
+    /// 起止时间，格式同 MarketData::parse_timestamp；缺省为全部数据
+    pub start: Option<String>,
+    pub end: Option<String>,
+    pub indicators: Vec<IndicatorSpec>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct IndicatorOverlay {
+    pub spec: IndicatorSpec,
+    pub series: Vec<ChartSeries>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct IndicatorChartResponse {
+    pub symbol: String,
+    pub timeframe: String,
+    pub data: Vec<PriceData>,
+    pub indicators: Vec<IndicatorOverlay>,
+}
+
+/// 时间范围内的 K 线和指标线，指标用范围之前的数据预热，每条线与 data 一一对应
+pub async fn get_indicator_chart(
+    Json(req): Json<IndicatorChartRequest>,
+) -> Result<Json<IndicatorChartResponse>, (StatusCode, String)> {
+    let bad_request = |msg: String| (StatusCode::BAD_REQUEST, msg);
+    let timeframe = Timeframe::parse(&req.timeframe)
+        .ok_or_else(|| bad_request(format!("invalid timeframe `{}`", req.timeframe)))?;
+    let parse_time = |raw: &Option<String>| match raw {
+        Some(raw) => MarketData::parse_timestamp(raw)
+            .map(Some)
+            .ok_or_else(|| bad_request(format!("invalid time `{}`", raw))),
+        None => Ok(None),
+    };
+    let (start, end) = (parse_time(&req.start)?, parse_time(&req.end)?);
+
+    let registry = IndicatorRegistry::new();
+    let mut indicators = req
+        .indicators
+        .iter()
+        .enumerate()
+        .map(|(i, spec)| {
+            registry
+                .build_spec(spec)
+                .map_err(|e| bad_request(format!("indicators[{}]: {}", i, e)))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let feed = MarketDataFeed::from_coins_market(&req.symbol).map_err(|e| {
+        eprintln!("load {} ohlcv failed: {}", req.symbol, e);
+        (StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
+    })?;
+    let mut bars = timeframe.resample(&feed.records);
+    if let Some(end) = end {
+        bars.retain(|bar| bar.timestamp <= end);
+    }
+    let from = start
+        .map(|start| bars.partition_point(|bar| bar.timestamp < start))
+        .unwrap_or(0);
+
+    let overlays = req
+        .indicators
+        .iter()
+        .zip(indicators.iter_mut())
+        .map(|(spec, indicator)| IndicatorOverlay {
+            spec: spec.clone(),
+            series: compute_series(indicator.as_mut(), &bars, from),
+        })
+        .collect();
+    let data = bars[from..]
+        .iter()
+        .map(|bar| PriceData {
+            timestamp: bar.timestamp.to_rfc3339(),
+            open: bar.open,
+            high: bar.high,
+            low: bar.low,
+            close: bar.close_price,
+            volume: bar.volume,
+        })
+        .collect();
+
+    Ok(Json(IndicatorChartResponse {
+        symbol: req.symbol,
+        timeframe: req.timeframe,
+        data,
+        indicators: overlays,
+    }))
+}
@@ -1,22 +1,34 @@
-use axum::Json;
+use axum::{
+    Json,
+    http::StatusCode,
+    response::{IntoResponse, Response as HttpResponse},
+};
 use backtest::{Metrics, StrategyParams};
 use chrono::Utc;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use serde_json::Value;
 
 pub mod algorithm;
 pub mod backtest;
+pub mod indicator;
 pub mod market_price;
+pub mod regime;
 pub mod trade;
 pub mod trade_strategy;
 pub mod user_auth;
 
 pub use algorithm::*;
+pub use indicator::*;
 pub use market_price::*;
+pub use regime::*;
 pub use trade::*;
 pub use trade_strategy::*;
 pub use user_auth::*;
 
-use crate::service::backtest_service::{BacktestMetrics, RunBacktestParameters};
+use crate::{
+    service::backtest_service::{BacktestError, BacktestMetrics, RunBacktestParameters},
+    strategy::{param_schema::FieldError, strategy_factory::StrategyFactory},
+};
 
 #[derive(Debug, Deserialize, Serialize)]
 pub struct PageQuery {
@@ -50,6 +62,50 @@ pub async fn ping() -> Json<PingResponse> {
     })
 }
 
+/// 请求校验失败时的 400 响应体，每个错误对应一个字段
+#[derive(Debug, Serialize)]
+pub struct FieldErrorResponse {
+    pub errors: Vec<FieldError>,
+}
+
+/// 字段错误统一返回 400
+pub fn field_error_response(errors: Vec<FieldError>) -> HttpResponse {
+    (StatusCode::BAD_REQUEST, Json(FieldErrorResponse { errors })).into_response()
+}
+
+/// 先按 type 对应策略的 schema 校验 params，再解析成请求结构；
+/// 任何一步出错都返回逐字段的错误，而不是在回测里 panic
+pub fn parse_backtest_request<T: DeserializeOwned>(body: Value) -> Result<T, Vec<FieldError>> {
+    let Some(kind) = body.get("type").and_then(Value::as_str) else {
+        return Err(vec![FieldError::new("type", "is required")]);
+    };
+    let params = body.get("params").unwrap_or(&Value::Null);
+    StrategyFactory::new()
+        .validate(kind, params)
+        .map_err(|errors| {
+            errors
+                .into_iter()
+                .map(|e| match e.field.as_str() {
+                    "type" => e,
+                    _ => FieldError::new(format!("params.{}", e.field), e.message),
+                })
+                .collect::<Vec<_>>()
+        })?;
+
+    serde_json::from_value(body).map_err(|e| vec![FieldError::new("request", e.to_string())])
+}
+
+/// 回测运行时才发现的参数错误同样按字段返回 400，其余为 500
+pub fn backtest_error_response(e: BacktestError) -> HttpResponse {
+    match e {
+        BacktestError::Invalid(errors) => field_error_response(errors),
+        BacktestError::Db(e) => {
+            println!("run backtest failed: {:#?}", e);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
+
 impl From<StrategyParams> for RunBacktestParameters {
     fn from(value: StrategyParams) -> Self {
         RunBacktestParameters {
@@ -76,3 +132,121 @@ impl From<BacktestMetrics> for Metrics {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, TimeZone};
+    use serde_json::json;
+
+    use super::{backtest::LabBacktestRunRequest, *};
+    use crate::{
+        data::aligned_data_feed::AlignedDataFeed,
+        engine::{
+            backtester::BacktestDriver,
+            parameters::{BacktestInput, StrategyRunParameters},
+        },
+        strategy::market_data::MarketData,
+    };
+
+    fn daily(symbol: &str, prices: impl Iterator<Item = f64>) -> Vec<MarketData> {
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        prices
+            .enumerate()
+            .map(|(i, p)| {
+                MarketData::from_close(start + Duration::days(i as i64), p).with_symbol(symbol)
+            })
+            .collect()
+    }
+
+    fn market(pair: bool) -> Vec<Vec<MarketData>> {
+        let x = |i: usize| 40.0 + (i as f64 * 0.05).sin() * 5.0 + i as f64 * 0.02;
+        let y = |i: usize| 2.0 * x(i) + 5.0 + (i as f64 * 0.2).sin() * 1.5;
+        let mut series = vec![daily("YYY", (0..300).map(y))];
+        if pair {
+            series.push(daily("XXX", (0..300).map(x)));
+        }
+        series
+    }
+
+    // 按实验室回测的流程：校验并解析请求，换算百分比，再交给回测驱动
+    fn run(kind: &str, params: Value) -> (usize, f64) {
+        let req: LabBacktestRunRequest = parse_backtest_request(json!({
+            "templateId": 1,
+            "type": kind,
+            "pairs": "YYY",
+            "timeframe": "1d",
+            "initialCapital": 10_000.0,
+            "positionType": "both",
+            "params": params,
+        }))
+        .unwrap();
+        let mut params = req.params;
+        params.normalize_percentages();
+        params.position_type = Some(req.position_type);
+        let input = BacktestInput {
+            r#type: req.r#type,
+            initial_capital: req.initial_capital,
+            strategy_run_params: serde_json::to_value(&params).unwrap(),
+        };
+        let multi = StrategyFactory::is_multi_asset(kind);
+        let result = BacktestDriver::new(input, AlignedDataFeed::from_series(market(multi)))
+            .build_and_run_backtest()
+            .unwrap();
+        (result.trades.len(), result.final_capital)
+    }
+
+    #[test]
+    fn test_strategy_params_reach_the_strategy() {
+        // 每 n 根 bar 开仓，半程平仓
+        let script = |n: u64| {
+            let source = format!(
+                "if bar.index % {n} == 0 {{ \"enterLong\" }} \
+                 else if bar.index % {n} == {} {{ \"exit\" }}",
+                n / 2
+            );
+            json!({ "script": source })
+        };
+        let composite = |period: u64| {
+            let child = json!({ "type": "rsi", "params": { "rsiPeriod": period } });
+            json!({ "strategies": [child] })
+        };
+        let cases = [
+            ("rsi", json!({}), json!({ "rsiPeriod": 5 })),
+            ("macd", json!({}), json!({ "signalPeriod": 3 })),
+            ("bollinger-bands", json!({}), json!({ "stdDev": 1.0 })),
+            ("turtle", json!({}), json!({ "entryPeriod": 10 })),
+            (
+                "pairs",
+                json!({ "lookback": 40, "entryZ": 1.2 }),
+                json!({ "lookback": 40, "entryZ": 1.0 }),
+            ),
+            ("grid", json!({}), json!({ "grids": 4 })),
+            ("dca", json!({}), json!({ "amount": 500.0 })),
+            ("rebalance", json!({}), json!({ "weights": [0.9, 0.1] })),
+            ("composite", composite(14), composite(5)),
+            ("script", script(20), script(30)),
+            (
+                "ma-crossover",
+                json!({ "fastPeriod": 5, "slowPeriod": 20 }),
+                json!({
+                    "fastPeriod": 5,
+                    "slowPeriod": 20,
+                    "trendTimeframe": "1w",
+                    "trendPeriod": 8
+                }),
+            ),
+        ];
+        for (kind, defaults, changed) in cases {
+            assert_ne!(run(kind, defaults), run(kind, changed), "{}", kind);
+        }
+
+        // 有专门字段的参数不会在 extra 里重复一份
+        let params: StrategyRunParameters = serde_json::from_value(json!({
+            "stopLoss": 2.0,
+            "regimeMethod": "rules",
+            "rsiPeriod": 5,
+        }))
+        .unwrap();
+        assert_eq!(params.extra.keys().collect::<Vec<_>>(), ["rsiPeriod"]);
+    }
+}
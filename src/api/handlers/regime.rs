@@ -0,0 +1,73 @@
+use axum::{
+    Json,
+    http::StatusCode,
+    response::{IntoResponse, Response as HttpResponse},
+};
+use axum_extra::extract::CookieJar;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    data::sleddb::regime_model_store::RegimeModelStore,
+    regime::hmm_classifier::{HMM_ITERATIONS, HMM_PERIOD, HmmModel},
+    strategy::{market_data::MarketData, param_schema::FieldError},
+};
+
+use super::{field_error_response, get_current_user_from_cookie};
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RegimeModelRequest {
+    pub symbol: String,
+    /// 只用早于这个时间的数据拟合，格式同 MarketData::parse_timestamp；回测从训练数据之后开始
+    pub before: String,
+    /// 隐状态数，1~8，缺省为 4
+    pub states: Option<u64>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RegimeModelResponse {
+    /// 回测参数 regimeModelId
+    pub id: u64,
+    pub symbol: String,
+    pub states: usize,
+    pub trained_until: DateTime<Utc>,
+}
+
+/// 用 before 之前的历史日线离线拟合 HMM 并保存，数据不够时返回逐字段的 400
+pub async fn fit_regime_model(
+    jar: CookieJar,
+    Json(req): Json<RegimeModelRequest>,
+) -> Result<Json<RegimeModelResponse>, HttpResponse> {
+    let user_info = get_current_user_from_cookie(jar).map_err(IntoResponse::into_response)?;
+    let invalid =
+        |field: &str, message: String| field_error_response(vec![FieldError::new(field, message)]);
+
+    let before = MarketData::parse_timestamp(&req.before)
+        .ok_or_else(|| invalid("before", format!("invalid time `{}`", req.before)))?;
+    let states = req.states.unwrap_or(4);
+    if !(1..=8).contains(&states) {
+        return Err(invalid("states", "must be between 1 and 8".to_string()));
+    }
+
+    let bars = HmmModel::stored_bars(&req.symbol, before).map_err(|e| {
+        println!("load {} bars failed: {}", req.symbol, e);
+        StatusCode::INTERNAL_SERVER_ERROR.into_response()
+    })?;
+    let model = HmmModel::fit(&bars, states as usize, HMM_PERIOD, HMM_ITERATIONS)
+        .map_err(|e| invalid("before", e.to_string()))?;
+
+    let id = RegimeModelStore::build(user_info.id)
+        .save(&model)
+        .map_err(|e| {
+            println!("save regime model failed: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        })?;
+    Ok(Json(RegimeModelResponse {
+        id,
+        symbol: req.symbol,
+        states: model.states(),
+        trained_until: model.trained_until,
+    }))
+}
@@ -2,25 +2,36 @@ use axum::{
     Json,
     extract::{Path, Query, State},
     http::StatusCode,
+    response::{IntoResponse, Response as HttpResponse},
 };
 use axum_extra::extract::CookieJar;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
 use crate::{
-    api::{AppState, handlers::get_current_user_from_cookie},
+    api::{
+        AppState,
+        handlers::{
+            backtest_error_response, field_error_response, get_current_user_from_cookie,
+            parse_backtest_request,
+        },
+    },
     data::{
         duckdb::{
             repository::{
                 TradeStrategyRepository, backtest_run_repository::BacktestRunHistoryRepository,
             },
-            schema::{StrategyTemplate, TradeStrategy, backtest_run_history::BacktestRunHistory},
+            schema::{
+                StrategyScript, StrategyTemplate, TradeStrategy,
+                backtest_run_history::BacktestRunHistory,
+            },
         },
         sleddb::ChartDB,
     },
     engine::{
-        backtest_result::{Balance, Trade},
+        backtest_result::{Balance, RegimePerformance, Trade},
         backtester::AssetAllocation,
+        chart::StrategyChart,
         parameters::StrategyRunParameters,
     },
     indicators::{DistributionData, MonthlyReturnData},
@@ -191,6 +202,53 @@ pub async fn delete_draft_strategie_by_id(
     }))
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StrategyScriptRequest {
+    pub name: String,
+    pub source: String,
+}
+
+pub async fn save_strategy_script(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    Json(payload): Json<StrategyScriptRequest>,
+) -> Result<Json<StrategyScript>, StatusCode> {
+    let user_info = get_current_user_from_cookie(jar)?;
+    let strategy_service = StrategyService::build(user_info.id);
+    let script = strategy_service
+        .save_script(&payload.name, &payload.source)
+        .map_err(|e| {
+            eprintln!("save script {} failed: {}", payload.name, e);
+            StatusCode::BAD_REQUEST
+        })?;
+    Ok(Json(script))
+}
+
+pub async fn get_strategy_scripts(
+    State(state): State<AppState>,
+    jar: CookieJar,
+) -> Result<Json<Vec<StrategyScript>>, StatusCode> {
+    let user_info = get_current_user_from_cookie(jar)?;
+    let strategy_service = StrategyService::build(user_info.id);
+    let scripts = strategy_service
+        .get_scripts()
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    Ok(Json(scripts))
+}
+
+pub async fn delete_strategy_script(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    Path(id): Path<i64>,
+) -> Result<Json<Response>, StatusCode> {
+    let user_info = get_current_user_from_cookie(jar)?;
+    let strategy_service = StrategyService::build(user_info.id);
+    let deleted = strategy_service
+        .delete_script(id)
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    Ok(Json(Response { success: deleted }))
+}
+
 pub async fn build_strategy(
     State(state): State<AppState>,
     Path(stage): Path<String>,
@@ -410,6 +468,9 @@ pub struct StrategyBacktestRunRequest {
     #[serde(rename = "initialCapital")]
     pub initial_capital: f64,
     pub params: StrategyRunParameters,
+    /// 结果附带策略指标线和信号标记
+    #[serde(rename = "includeChart", default)]
+    pub include_chart: bool,
 }
 
 #[derive(Debug, Serialize)]
@@ -424,6 +485,11 @@ pub struct StrategyBacktestRunResponse {
     pub metrics: Metrics,
     pub version: Option<i64>,
     pub date: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub chart: Option<StrategyChart>,
+    /// 按市场状态拆分的绩效，配置了状态识别时才有
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub regimes: Vec<RegimePerformance>,
 }
 
 #[derive(Debug, Serialize)]
@@ -449,18 +515,17 @@ pub struct StrategyRunHistoryRes {
 pub async fn run_strategy_backtest(
     State(state): State<AppState>,
     jar: CookieJar,
-    Json(req): Json<StrategyBacktestRunRequest>,
-) -> Result<Json<StrategyBacktestRunResponse>, StatusCode> {
-    let user_info = get_current_user_from_cookie(jar)?;
+    Json(body): Json<Value>,
+) -> Result<Json<StrategyBacktestRunResponse>, HttpResponse> {
+    let user_info = get_current_user_from_cookie(jar).map_err(IntoResponse::into_response)?;
+    let req: StrategyBacktestRunRequest =
+        parse_backtest_request(body).map_err(field_error_response)?;
 
     let params: StrategyRunParameters = req.params.clone();
     let backtest_result = state
         .backtest_service
         .run_strategy_backtest(user_info.id, req)
-        .map_err(|e| {
-            println!("run_strategy_backtest failed: {:#?}", e);
-            StatusCode::INTERNAL_SERVER_ERROR
-        })?;
+        .map_err(backtest_error_response)?;
 
     let response = StrategyBacktestRunResponse {
         metrics: backtest_result.metrics.into(),
@@ -471,6 +536,8 @@ pub async fn run_strategy_backtest(
         return_distribution: backtest_result.return_distribution,
         version: backtest_result.version,
         date: backtest_result.date,
+        chart: backtest_result.chart,
+        regimes: backtest_result.regimes,
     };
 
     Ok(Json(response))
@@ -518,6 +585,8 @@ pub async fn backtest_history_data(
         return_distribution: backtest_result.return_distribution,
         version: backtest_result.version,
         date: backtest_result.date,
+        chart: backtest_result.chart,
+        regimes: backtest_result.regimes,
     };
 
     println!("strategy backtest_data res {:?}", response);
@@ -587,6 +656,8 @@ pub async fn strategy_run_comparison_data(
                     return_distribution: backtest_result.return_distribution,
                     version: backtest_result.version,
                     date: backtest_result.date,
+                    chart: None,
+                    regimes: backtest_result.regimes,
                 },
             }
         })
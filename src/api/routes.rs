@@ -17,12 +17,13 @@ use tokio::sync::RwLock;
 
 use super::handlers::{
     add_trade_strategy, appy_strategy_run, backtest_history_data, backtest_run_history,
-    build_strategy, delete_draft_strategie_by_id, get_current_user, get_price, get_recent_trades,
-    get_strategy_details, get_strategy_summarys, get_strategy_template_by_id,
-    get_strategy_templates, lab_run_comparison_data, lab_run_history_backtest_data,
-    lab_run_history_data, ping, revoke_current_user, run_lab_backtest, run_strategy_backtest,
-    strategy_run_comparison_data, update_strategy, update_strategy_status, user_auth, user_login,
-    user_register,
+    build_strategy, delete_draft_strategie_by_id, delete_strategy_script, fit_regime_model,
+    get_current_user, get_indicator_catalog, get_indicator_chart, get_price, get_recent_trades,
+    get_strategy_details, get_strategy_schemas, get_strategy_scripts, get_strategy_summarys,
+    get_strategy_template_by_id, get_strategy_templates, lab_run_comparison_data,
+    lab_run_history_backtest_data, lab_run_history_data, ping, revoke_current_user,
+    run_lab_backtest, run_strategy_backtest, save_strategy_script, strategy_run_comparison_data,
+    update_strategy, update_strategy_status, user_auth, user_login, user_register,
 };
 
 #[derive(Clone)]
@@ -92,7 +93,11 @@ pub fn create_router(client: BookClient) -> Router {
         .route("/api/strategies/:id", put(update_strategy))
         .route("/api/strategies/details", get(get_strategy_details))
         .route("/api/algorithms", get(get_strategy_templates))
+        .route("/api/algorithms/schemas", get(get_strategy_schemas))
         .route("/api/algorithms/:id", get(get_strategy_template_by_id))
+        .route("/api/indicators", get(get_indicator_catalog))
+        .route("/api/indicators/chart", post(get_indicator_chart))
+        .route("/api/regime/models", post(fit_regime_model))
         .route("/api/lab", get(get_strategy_templates))
         .route("/api/lab/:id", get(get_strategy_template_by_id))
         .route("/api/lab/run", post(run_lab_backtest))
@@ -103,5 +108,8 @@ pub fn create_router(client: BookClient) -> Router {
         .route("/api/strategies/:id/status", patch(update_strategy_status))
         // .route("/api/backtest/history", get(backtest_run_history))
         .route("/api/strategies/draft/:stage", post(build_strategy))
+        .route("/api/scripts", get(get_strategy_scripts))
+        .route("/api/scripts", post(save_strategy_script))
+        .route("/api/scripts/:id", delete(delete_strategy_script))
         .with_state(state)
 }
@@ -0,0 +1,114 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+
+use super::data_feed::DataFeed;
+use crate::strategy::market_data::MarketData;
+
+/// 多品种按时间戳对齐的数据源：只保留所有品种都有 bar 的时间点（内连接），
+/// 每次 `next_bars` 按构造时的顺序返回同一时刻的全部 bar。
+pub struct AlignedDataFeed {
+    rows: Vec<Vec<MarketData>>,
+    cursor: usize,
+}
+
+impl AlignedDataFeed {
+    /// 读空各个子数据源后对齐
+    pub fn new(feeds: Vec<Box<dyn DataFeed>>) -> Self {
+        let series = feeds
+            .into_iter()
+            .map(|mut feed| std::iter::from_fn(|| feed.next()).collect())
+            .collect();
+        Self::from_series(series)
+    }
+
+    pub fn from_series(series: Vec<Vec<MarketData>>) -> Self {
+        let Some((first, rest)) = series.split_first() else {
+            return Self {
+                rows: Vec::new(),
+                cursor: 0,
+            };
+        };
+        let lookups: Vec<HashMap<DateTime<Utc>, &MarketData>> = rest
+            .iter()
+            .map(|bars| bars.iter().map(|b| (b.timestamp, b)).collect())
+            .collect();
+
+        let mut rows: Vec<Vec<MarketData>> = first
+            .iter()
+            .filter_map(|bar| {
+                let mut row = vec![bar.clone()];
+                for lookup in &lookups {
+                    row.push((*lookup.get(&bar.timestamp)?).clone());
+                }
+                Some(row)
+            })
+            .collect();
+        rows.sort_by_key(|row| row[0].timestamp);
+        rows.dedup_by_key(|row| row[0].timestamp);
+
+        Self { rows, cursor: 0 }
+    }
+
+    pub fn len(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+}
+
+impl DataFeed for AlignedDataFeed {
+    /// 单品种调用方只拿到第一个品种
+    fn next(&mut self) -> Option<MarketData> {
+        self.next_bars().and_then(|row| row.into_iter().next())
+    }
+
+    fn next_bars(&mut self) -> Option<Vec<MarketData>> {
+        let row = self.rows.get(self.cursor)?.clone();
+        self.cursor += 1;
+        Some(row)
+    }
+
+    fn reset(&mut self) {
+        self.cursor = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    fn series(symbol: &str, bars: &[(i64, f64)]) -> Vec<MarketData> {
+        bars.iter()
+            .map(|&(t, p)| {
+                MarketData::from_close(Utc.timestamp_opt(t, 0).unwrap(), p).with_symbol(symbol)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_aligns_on_common_timestamps() {
+        let a = series("AAA", &[(3, 13.0), (1, 11.0), (2, 12.0), (4, 14.0)]);
+        let b = series("BBB", &[(1, 21.0), (3, 23.0), (4, 24.0), (5, 25.0)]);
+        let mut feed = AlignedDataFeed::from_series(vec![a, b]);
+
+        assert_eq!(feed.len(), 3);
+        let rows: Vec<Vec<MarketData>> = std::iter::from_fn(|| feed.next_bars()).collect();
+        let closes: Vec<(f64, f64)> = rows
+            .iter()
+            .map(|r| (r[0].close_price, r[1].close_price))
+            .collect();
+        assert_eq!(closes, vec![(11.0, 21.0), (13.0, 23.0), (14.0, 24.0)]);
+        assert!(
+            rows.iter()
+                .all(|r| r[0].symbol == "AAA" && r[1].symbol == "BBB")
+        );
+
+        feed.reset();
+        assert_eq!(feed.next().unwrap().close_price, 11.0);
+    }
+}
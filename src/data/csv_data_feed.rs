@@ -19,6 +19,8 @@ impl CsvDataFeed {
 
         #[derive(serde::Deserialize)]
         struct RawRecord {
+            #[serde(rename = "Symbol", default)]
+            symbol: String,
             #[serde(rename = "Start")]
             start: String,
             #[serde(rename = "Open")]
@@ -29,14 +31,23 @@ impl CsvDataFeed {
             low: f64,
             #[serde(rename = "Close")]
             close: f64,
+            #[serde(rename = "Volume", default)]
+            volume: f64,
         }
 
         let mut records = Vec::new();
         for result in rdr.deserialize() {
             let raw: RawRecord = result?;
+            let timestamp = MarketData::parse_timestamp(&raw.start)
+                .ok_or_else(|| format!("invalid Start timestamp: {}", raw.start))?;
             records.push(MarketData {
-                timestamp: raw.start,
+                symbol: raw.symbol,
+                timestamp,
+                open: raw.open,
+                high: raw.high,
+                low: raw.low,
                 close_price: raw.close,
+                volume: raw.volume,
             });
         }
 
@@ -3,4 +3,23 @@ use crate::strategy::market_data::MarketData;
 pub trait DataFeed {
     fn next(&mut self) -> Option<MarketData>;
     fn reset(&mut self);
+    /// 多品种数据源一次返回同一时刻的所有 bar；单品种默认包成一个元素
+    fn next_bars(&mut self) -> Option<Vec<MarketData>> {
+        self.next().map(|bar| vec![bar])
+    }
+}
+
+/// 按策略类型在运行时选择数据源时使用
+impl<T: DataFeed + ?Sized> DataFeed for Box<T> {
+    fn next(&mut self) -> Option<MarketData> {
+        (**self).next()
+    }
+
+    fn reset(&mut self) {
+        (**self).reset()
+    }
+
+    fn next_bars(&mut self) -> Option<Vec<MarketData>> {
+        (**self).next_bars()
+    }
 }
@@ -40,6 +40,16 @@ pub fn initialize_schema(conn: Arc<Mutex<Connection>>) {
         end_time   TIMESTAMP, 
         created_at  TIMESTAMP DEFAULT CURRENT_TIMESTAMP
     );    
+
+    CREATE SEQUENCE IF NOT EXISTS strategy_scripts_seq START 1;
+    CREATE TABLE IF NOT EXISTS strategy_scripts (
+        id BIGINT PRIMARY KEY DEFAULT nextval('strategy_scripts_seq'),
+        name VARCHAR NOT NULL,
+        source TEXT NOT NULL,
+        created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+        updated_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+        UNIQUE(name)
+    );
     "#;
 
     conn.lock()
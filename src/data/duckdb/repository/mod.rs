@@ -6,6 +6,7 @@ pub mod connection;
 pub mod lab_run_history;
 pub mod ohlcv;
 pub mod strategy_builds;
+pub mod strategy_script;
 pub mod strategy_template;
 pub mod trade;
 pub mod trade_strategy;
@@ -19,6 +20,7 @@ pub use connection::get_chart_db;
 pub use lab_run_history::LabRunHistoryRepository;
 pub use ohlcv::OhlcvRepository;
 pub use strategy_builds::StrategyBuildsRepository;
+pub use strategy_script::StrategyScriptRepository;
 pub use strategy_template::StrategyTemplateRepository;
 pub use trade::TradeRepository;
 pub use trade_strategy::TradeStrategyRepository;
@@ -0,0 +1,84 @@
+use std::sync::{Arc, Mutex};
+
+use duckdb::{Connection, Row, params};
+
+use crate::data::duckdb::schema::StrategyScript;
+
+use super::connection::get_user_connection_manager;
+
+pub struct StrategyScriptRepository {
+    conn: Arc<Mutex<Connection>>,
+}
+
+fn to_script(row: &Row<'_>) -> Result<StrategyScript, duckdb::Error> {
+    Ok(StrategyScript {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        source: row.get(2)?,
+        created_at: row.get(3)?,
+        updated_at: row.get(4)?,
+    })
+}
+
+impl StrategyScriptRepository {
+    pub fn build(user_id: i64) -> Self {
+        Self {
+            conn: get_user_connection_manager().get_connection(user_id),
+        }
+    }
+
+    /// 同名脚本覆盖源码，返回脚本 id
+    pub fn save(&self, name: &str, source: &str) -> Result<i64, duckdb::Error> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            r#"
+            INSERT INTO strategy_scripts (name, source)
+            VALUES (?, ?)
+            ON CONFLICT (name) DO UPDATE
+                SET source = excluded.source, updated_at = CURRENT_TIMESTAMP
+            RETURNING id
+            "#,
+        )?;
+        let id: i64 = stmt.query_row(params![name, source], |row| row.get(0))?;
+        Ok(id)
+    }
+
+    pub fn get_by_id(&self, id: i64) -> Result<Option<StrategyScript>, duckdb::Error> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT id, name, source, created_at, updated_at FROM strategy_scripts WHERE id = ?",
+        )?;
+        let mut rows = stmt.query_map([id], to_script)?;
+
+        match rows.next() {
+            Some(script) => Ok(Some(script?)),
+            None => Ok(None),
+        }
+    }
+
+    pub fn get_all(&self) -> Result<Vec<StrategyScript>, duckdb::Error> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            r#"
+            SELECT id, name, source, created_at, updated_at
+            FROM strategy_scripts
+            ORDER BY updated_at DESC
+            "#,
+        )?;
+        let rows = stmt.query_map([], to_script)?;
+
+        let mut scripts = Vec::new();
+        for script in rows {
+            scripts.push(script?);
+        }
+
+        Ok(scripts)
+    }
+
+    pub fn delete_by_id(&self, id: i64) -> Result<usize, duckdb::Error> {
+        let conn = self.conn.lock().unwrap();
+        let result = conn.execute("DELETE FROM strategy_scripts WHERE id = ?", [id])?;
+
+        Ok(result)
+    }
+}
@@ -5,6 +5,7 @@ pub mod backtest_run_trade;
 pub mod lab_run_history;
 pub mod ohlcv;
 pub mod strategy_builds;
+pub mod strategy_script;
 pub mod strategy_template;
 pub mod trade;
 pub mod trade_strategy;
@@ -17,6 +18,7 @@ pub use backtest_run_trade::BacktestRunTrade;
 pub use lab_run_history::LabRunHistory;
 pub use ohlcv::Ohlcv;
 pub use strategy_builds::StrategyBuilds;
+pub use strategy_script::StrategyScript;
 pub use strategy_template::StrategyTemplate;
 pub use trade::Trade;
 pub use trade_strategy::TradeStrategy;
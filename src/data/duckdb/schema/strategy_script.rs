@@ -0,0 +1,13 @@
+use serde::{Deserialize, Serialize};
+
+use crate::data::duckdb::types::Timestamp;
+
+/// 用户保存的脚本策略源码（Rhai），按名字唯一
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StrategyScript {
+    pub id: Option<i64>,
+    pub name: String,
+    pub source: String,
+    pub created_at: Option<Timestamp>,
+    pub updated_at: Option<Timestamp>,
+}
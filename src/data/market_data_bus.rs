@@ -48,6 +48,8 @@ pub async fn combine_ticks(
                     let weighted_price = ticks.iter().map(|t| t.qty * t.price).sum::<f64>() / total_qty;
                     let trade_tick = TradeTick {
                         symbol,
+                        trade_id: None,
+                        exec_seq: None,
                         price: weighted_price,
                         qty: total_qty,
                         ts: now,
@@ -76,6 +78,8 @@ mod tests {
             loop {
                 let tick = TradeTick {
                     symbol: "BTC/USDT".into(),
+                    trade_id: None,
+                    exec_seq: None,
                     price: rand::random::<f64>() * 30000.0,
                     qty: rand::random::<f64>() * 5.0,
                     ts: Utc::now().timestamp_millis(),
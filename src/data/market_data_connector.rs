@@ -14,6 +14,8 @@ pub async fn start_binance_ws(tx: broadcast::Sender<TradeTick>, symbol: String)
         let symbol = symbols[idx];
         let tick = TradeTick {
             symbol: symbol.to_string(),
+            trade_id: None,
+            exec_seq: None,
             ts: chrono::Utc::now().timestamp_millis(),
             price: rand::random::<f64>() * 30000.0,
             qty: rand::random::<f64>() * 5.0,
@@ -31,6 +33,8 @@ pub async fn start_coinbase_ws(tx: broadcast::Sender<TradeTick>, symbol: String)
         let symbol = symbols[idx];
         let tick = TradeTick {
             symbol: symbol.to_string(),
+            trade_id: None,
+            exec_seq: None,
             ts: chrono::Utc::now().timestamp_millis(),
             price: rand::random::<f64>() * 2000.0,
             qty: rand::random::<f64>() * 10.0,
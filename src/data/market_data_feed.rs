@@ -4,6 +4,7 @@ use tokio::sync::broadcast;
 
 use crate::{
     data::{
+        aligned_data_feed::AlignedDataFeed,
         data_feed::DataFeed,
         market_data_bus::{combine_ticks, start_market_data_bus},
     },
@@ -30,14 +31,30 @@ impl MarketDataFeed {
         let records = ohlcv_datas
             .iter()
             .map(|ohlc| MarketData {
-                timestamp: ohlc.timestamp.0.to_rfc3339(),
+                symbol: ohlc.symbol.clone(),
+                timestamp: ohlc.timestamp.0,
+                open: ohlc.open,
+                high: ohlc.high,
+                low: ohlc.low,
                 close_price: ohlc.close,
+                volume: ohlc.volume,
             })
             .collect();
         Ok(Self { records, cursor: 0 })
     }
 }
 
+impl MarketDataFeed {
+    /// 多个币种按时间戳对齐，顺序与 coins 一致
+    pub fn aligned(coins: &[&str]) -> Result<AlignedDataFeed, Box<dyn std::error::Error>> {
+        let series = coins
+            .iter()
+            .map(|coin| Self::from_coins_market(coin).map(|feed| feed.records))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(AlignedDataFeed::from_series(series))
+    }
+}
+
 impl DataFeed for MarketDataFeed {
     fn next(&mut self) -> Option<MarketData> {
         if self.cursor < self.records.len() {
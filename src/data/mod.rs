@@ -1,3 +1,4 @@
+pub mod aligned_data_feed;
 pub mod coin_market;
 pub mod csv_data_feed;
 pub mod data_feed;
@@ -7,3 +8,4 @@ pub mod market_data_bus;
 pub mod market_data_connector;
 pub mod market_data_feed;
 pub mod sleddb;
+pub mod timeframe;
@@ -0,0 +1,50 @@
+use std::{
+    error::Error,
+    sync::{Arc, Mutex},
+};
+
+use sled::Db;
+
+use crate::engine::checkpoint::{CheckpointStore, EngineCheckpoint};
+
+use super::user_db_manager::get_user_chart_manager;
+
+/// 在 sled 中按 key 保存实盘 / 模拟盘策略最近一次的 checkpoint
+pub struct SledCheckpointStore {
+    db: Arc<Mutex<Db>>,
+    key: String,
+}
+
+impl SledCheckpointStore {
+    pub fn new(db: Arc<Mutex<Db>>, key: impl Into<String>) -> Self {
+        Self {
+            db,
+            key: key.into(),
+        }
+    }
+
+    /// 存在用户自己的 chart 库里，key 为 checkpoint-{strategy_id}
+    pub fn for_strategy(user_id: i64, strategy_id: i64) -> Self {
+        Self::new(
+            get_user_chart_manager().get_chart_conn(user_id),
+            format!("checkpoint-{strategy_id}"),
+        )
+    }
+}
+
+impl CheckpointStore for SledCheckpointStore {
+    fn save(&mut self, checkpoint: &EngineCheckpoint) -> Result<(), Box<dyn Error>> {
+        let json_str = serde_json::to_string(checkpoint)?;
+        let db = self.db.lock().unwrap();
+        db.insert(self.key.as_str(), json_str.as_bytes())?;
+        db.flush()?;
+        Ok(())
+    }
+
+    fn load(&self) -> Result<Option<EngineCheckpoint>, Box<dyn Error>> {
+        match self.db.lock().unwrap().get(self.key.as_str())? {
+            Some(ivec) => Ok(Some(serde_json::from_slice(&ivec)?)),
+            None => Ok(None),
+        }
+    }
+}
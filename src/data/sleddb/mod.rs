@@ -1,4 +1,6 @@
 pub mod chart_db;
+pub mod checkpoint_store;
+pub mod regime_model_store;
 pub mod user_db_manager;
 
 pub use chart_db::ChartDB;
@@ -0,0 +1,47 @@
+use std::{
+    error::Error,
+    sync::{Arc, Mutex},
+};
+
+use sled::Db;
+
+use crate::regime::hmm_classifier::HmmModel;
+
+use super::user_db_manager::get_user_chart_manager;
+
+/// 用户离线拟合的 HMM 市场状态模型，回测时按 id 取出，不再每次回测重新拟合
+pub struct RegimeModelStore {
+    db: Arc<Mutex<Db>>,
+}
+
+impl RegimeModelStore {
+    pub fn new(db: Arc<Mutex<Db>>) -> Self {
+        Self { db }
+    }
+
+    /// 存在用户自己的 chart 库里，key 为 regime-model-{id}
+    pub fn build(user_id: i64) -> Self {
+        Self::new(get_user_chart_manager().get_chart_conn(user_id))
+    }
+
+    /// 保存并返回新模型的 id
+    pub fn save(&self, model: &HmmModel) -> Result<u64, Box<dyn Error>> {
+        let json_str = serde_json::to_string(model)?;
+        let db = self.db.lock().unwrap();
+        let id = db.generate_id()?;
+        db.insert(Self::key(id), json_str.as_bytes())?;
+        db.flush()?;
+        Ok(id)
+    }
+
+    pub fn load(&self, id: u64) -> Result<Option<HmmModel>, Box<dyn Error>> {
+        match self.db.lock().unwrap().get(Self::key(id))? {
+            Some(ivec) => Ok(Some(serde_json::from_slice(&ivec)?)),
+            None => Ok(None),
+        }
+    }
+
+    fn key(id: u64) -> String {
+        format!("regime-model-{id}")
+    }
+}
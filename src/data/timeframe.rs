@@ -0,0 +1,294 @@
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::strategy::market_data::MarketData;
+
+/// K 线周期，如 15m、4h、1d、1w；按 UTC 从 1970-01-01 起等长切分，周线从周一开始
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Timeframe {
+    seconds: i64,
+}
+
+// 1970-01-01 是周四，周线往前挪到周一对齐
+const WEEK_OFFSET: i64 = 3 * 86_400;
+
+impl Timeframe {
+    pub fn from_seconds(seconds: i64) -> Option<Self> {
+        (seconds > 0).then_some(Self { seconds })
+    }
+
+    /// 数字加单位 m/h/d/w，如 "15m"、"4h"、"1d"
+    pub fn parse(raw: &str) -> Option<Self> {
+        let raw = raw.trim();
+        let unit = raw.chars().last()?;
+        let n: i64 = raw[..raw.len() - unit.len_utf8()].parse().ok()?;
+        let unit_seconds = match unit {
+            'm' => 60,
+            'h' => 3_600,
+            'd' | 'D' => 86_400,
+            'w' | 'W' => 7 * 86_400,
+            _ => return None,
+        };
+        Self::from_seconds(n.checked_mul(unit_seconds)?)
+    }
+
+    pub fn duration(&self) -> Duration {
+        Duration::seconds(self.seconds)
+    }
+
+    fn offset(&self) -> i64 {
+        if self.seconds % (7 * 86_400) == 0 {
+            WEEK_OFFSET
+        } else {
+            0
+        }
+    }
+
+    /// ts 所在周期的开始时间
+    pub fn bucket(&self, ts: &DateTime<Utc>) -> DateTime<Utc> {
+        let offset = self.offset();
+        let start = (ts.timestamp() + offset).div_euclid(self.seconds) * self.seconds - offset;
+        Utc.timestamp_opt(start, 0).single().unwrap_or(*ts)
+    }
+
+    /// 把有序的 K 线合并成本周期的 K 线，时间戳取周期开始；比原始数据更细的周期原样返回
+    pub fn resample(&self, bars: &[MarketData]) -> Vec<MarketData> {
+        let mut out: Vec<MarketData> = Vec::new();
+        for bar in bars {
+            let start = self.bucket(&bar.timestamp);
+            match out.last_mut() {
+                Some(last) if last.timestamp == start => {
+                    last.high = last.high.max(bar.high);
+                    last.low = last.low.min(bar.low);
+                    last.close_price = bar.close_price;
+                    last.volume += bar.volume;
+                }
+                _ => out.push(MarketData {
+                    timestamp: start,
+                    ..bar.clone()
+                }),
+            }
+        }
+        out
+    }
+}
+
+/// 流式重采样：高周期 K 线只在收完时产出，不看未来数据。
+/// 基础周期取目前见过的相邻 bar 的最小间隔，一根 bar 的结束时间到达周期末尾时即收线；
+/// 周期末尾的 bar 缺失时，由下一个周期的第一根 bar 触发收线。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Resampler {
+    timeframe: Timeframe,
+    building: Option<MarketData>,
+    last: Option<DateTime<Utc>>,
+    base: Option<i64>,
+    // 最近收线的周期开始时间，已收线的周期不再改动
+    closed: Option<DateTime<Utc>>,
+}
+
+impl Resampler {
+    pub fn new(timeframe: Timeframe) -> Self {
+        Self {
+            timeframe,
+            building: None,
+            last: None,
+            base: None,
+            closed: None,
+        }
+    }
+
+    pub fn timeframe(&self) -> Timeframe {
+        self.timeframe
+    }
+
+    /// 推入一根基础 K 线，返回因此收完的高周期 K 线（按时间顺序，通常 0 或 1 根）
+    pub fn push(&mut self, bar: &MarketData) -> Vec<MarketData> {
+        if let Some(last) = self.last {
+            let gap = (bar.timestamp - last).num_seconds();
+            if gap > 0 {
+                self.base = Some(self.base.map_or(gap, |base| base.min(gap)));
+            }
+        }
+        self.last = Some(bar.timestamp);
+
+        let mut closed = Vec::new();
+        let start = self.timeframe.bucket(&bar.timestamp);
+        if self.closed.is_some_and(|c| start <= c) {
+            return closed;
+        }
+        match &mut self.building {
+            Some(building) if building.timestamp == start => {
+                building.high = building.high.max(bar.high);
+                building.low = building.low.min(bar.low);
+                building.close_price = bar.close_price;
+                building.volume += bar.volume;
+            }
+            building => {
+                closed.extend(building.take());
+                *building = Some(MarketData {
+                    timestamp: start,
+                    ..bar.clone()
+                });
+            }
+        }
+        let end = start.timestamp() + self.timeframe.seconds;
+        if self
+            .base
+            .is_some_and(|base| bar.timestamp.timestamp() + base >= end)
+        {
+            closed.extend(self.building.take());
+        }
+        if let Some(bar) = closed.last() {
+            self.closed = Some(bar.timestamp);
+        }
+        closed
+    }
+
+    pub fn reset(&mut self) {
+        self.building = None;
+        self.last = None;
+        self.base = None;
+        self.closed = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_bucket() {
+        assert_eq!(
+            Timeframe::parse("4h").unwrap().duration(),
+            Duration::hours(4)
+        );
+        assert_eq!(
+            Timeframe::parse("15m").unwrap().duration(),
+            Duration::minutes(15)
+        );
+        assert!(Timeframe::parse("0d").is_none());
+        assert!(Timeframe::parse("1y").is_none());
+
+        // 2024-01-03 是周三，所在周从 2024-01-01 周一开始
+        let ts = Utc.with_ymd_and_hms(2024, 1, 3, 13, 30, 0).unwrap();
+        let week = Timeframe::parse("1w").unwrap();
+        assert_eq!(
+            week.bucket(&ts),
+            Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap()
+        );
+        let four_hours = Timeframe::parse("4h").unwrap();
+        assert_eq!(
+            four_hours.bucket(&ts),
+            Utc.with_ymd_and_hms(2024, 1, 3, 12, 0, 0).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_resample_daily_into_weekly() {
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let bars: Vec<MarketData> = (0..10)
+            .map(|i| MarketData {
+                high: 101.0 + i as f64,
+                low: 99.0 - i as f64,
+                volume: 1.0,
+                ..MarketData::from_close(start + Duration::days(i), 100.0 + i as f64)
+            })
+            .collect();
+        let weekly = Timeframe::parse("1w").unwrap().resample(&bars);
+        assert_eq!(weekly.len(), 2);
+        assert_eq!(weekly[0].timestamp, start);
+        assert_eq!(weekly[0].open, 100.0);
+        assert_eq!(weekly[0].close_price, 106.0);
+        assert_eq!(weekly[0].high, 107.0);
+        assert_eq!(weekly[0].low, 93.0);
+        assert_eq!(weekly[0].volume, 7.0);
+        assert_eq!(weekly[1].volume, 3.0);
+    }
+
+    // 暴力参照：只看 bars[..=i]，找出此刻最近一个已经收完的周期，再用全部已见 bar 聚合
+    fn reference_closed(bars: &[MarketData], tf: Timeframe, i: usize) -> Option<MarketData> {
+        let seen = &bars[..=i];
+        let base = seen
+            .windows(2)
+            .map(|w| (w[1].timestamp - w[0].timestamp).num_seconds())
+            .filter(|gap| *gap > 0)
+            .min();
+        let now = &bars[i];
+        let current = tf.bucket(&now.timestamp);
+        let current_closed = base.is_some_and(|base| {
+            now.timestamp.timestamp() + base >= current.timestamp() + tf.duration().num_seconds()
+        });
+        let bucket = if current_closed {
+            current
+        } else {
+            seen.iter()
+                .map(|bar| tf.bucket(&bar.timestamp))
+                .filter(|b| *b < current)
+                .max()?
+        };
+        let in_bucket: Vec<MarketData> = seen
+            .iter()
+            .filter(|bar| tf.bucket(&bar.timestamp) == bucket)
+            .cloned()
+            .collect();
+        tf.resample(&in_bucket).pop()
+    }
+
+    fn ohlcv(bar: &MarketData) -> (DateTime<Utc>, f64, f64, f64, f64, f64) {
+        (
+            bar.timestamp,
+            bar.open,
+            bar.high,
+            bar.low,
+            bar.close_price,
+            bar.volume,
+        )
+    }
+
+    #[test]
+    fn test_closed_bars_have_no_lookahead() {
+        use crate::strategy::strategy_context::StrategyContext;
+
+        // 15 分钟线，中间有零星缺失和一段 5 小时的停机
+        let start = Utc.with_ymd_and_hms(2024, 3, 1, 0, 0, 0).unwrap();
+        let bars: Vec<MarketData> = (0..600i64)
+            .filter(|i| i % 37 != 15 && !(200..220).contains(i))
+            .map(|i| {
+                let close = 100.0 + (i as f64 * 0.3).sin() * 5.0 + i as f64 * 0.01;
+                MarketData {
+                    open: close - 0.5,
+                    high: close + 1.0 + (i % 5) as f64 * 0.1,
+                    low: close - 1.0 - (i % 3) as f64 * 0.1,
+                    volume: 1.0 + (i % 7) as f64,
+                    ..MarketData::from_close(start + Duration::minutes(15 * i), close)
+                }
+            })
+            .collect();
+        let timeframes = [
+            Timeframe::parse("4h").unwrap(),
+            Timeframe::parse("1d").unwrap(),
+        ];
+
+        let mut ctx = StrategyContext::new(10_000.0);
+        ctx.subscribe(&timeframes);
+        for (i, bar) in bars.iter().enumerate() {
+            ctx.advance_timeframes(bar);
+            for tf in timeframes {
+                let expected = reference_closed(&bars, tf, i);
+                let actual = ctx.closed_bar(tf);
+                assert_eq!(
+                    actual.map(ohlcv),
+                    expected.as_ref().map(ohlcv),
+                    "{:?} at {}",
+                    tf,
+                    bar.timestamp
+                );
+                // 已收线的周期不会晚于当前 bar 所在的周期
+                if let Some(closed) = actual {
+                    assert!(closed.timestamp <= tf.bucket(&bar.timestamp));
+                }
+            }
+        }
+        assert!(ctx.closed_bar(timeframes[1]).is_some());
+    }
+}
@@ -1,6 +1,19 @@
+use std::error::Error;
+
+use serde_json::Value;
+
 use super::{OrderRequest, order::OrderResponse};
 
 pub trait OrderExecutor {
     fn execute(&mut self, order: OrderRequest) -> OrderResponse;
     fn sync_positions(&mut self) -> f64;
+
+    /// 断点续跑：模拟账户导出持仓和现金；实盘账户的状态在交易所，返回 None
+    fn save_state(&self) -> Option<Value> {
+        None
+    }
+
+    fn load_state(&mut self, _state: &Value) -> Result<(), Box<dyn Error>> {
+        Ok(())
+    }
 }
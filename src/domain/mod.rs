@@ -1,11 +1,14 @@
 pub mod executor;
 pub mod order;
 pub mod position_sizer;
+pub mod regime_classifier;
 pub mod risk_manager;
 pub mod trade_observer;
 
 pub use order::OrderRequest;
 pub use order::OrderSide;
+pub use order::OrderType;
 pub use position_sizer::PositionSizer;
+pub use regime_classifier::RegimeClassifier;
 pub use risk_manager::RiskManager;
 pub use trade_observer::TradeObserver;
@@ -1,11 +1,24 @@
+use serde::{Deserialize, Serialize};
+
 #[derive(Debug, Clone)]
 pub struct OrderRequest {
+    pub symbol: String,
     pub side: OrderSide,
+    pub order_type: OrderType,
     pub price: f64,
     pub quantity: f64,
     pub timestamp: String,
 }
 
+/// 订单类型：市价单按参考价成交，限价单按挂单价或更优价格成交，止损单触价后按市价成交
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum OrderType {
+    #[default]
+    Market,
+    Limit,
+    Stop,
+}
+
 #[derive(Debug, Clone)]
 pub enum OrderSide {
     Buy,
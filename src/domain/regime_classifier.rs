@@ -0,0 +1,23 @@
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+
+use crate::{regime::Regime, strategy::market_data::MarketData};
+
+pub trait RegimeClassifier {
+    /// 每根基础 bar 调用一次，返回这根 bar 收盘后的市场状态；预热期内返回 None
+    fn update(&mut self, bar: &MarketData) -> Option<Regime>;
+
+    /// 离线拟合的识别器返回训练数据最后一根 bar 的时间，回测只能从这之后开始
+    fn trained_until(&self) -> Option<DateTime<Utc>> {
+        None
+    }
+
+    /// 断点续跑时导出内部窗口，None 表示不支持，续跑后重新预热
+    fn save_state(&self) -> Option<Value> {
+        None
+    }
+
+    fn load_state(&mut self, _state: &Value) -> bool {
+        false
+    }
+}
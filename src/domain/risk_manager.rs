@@ -1,7 +1,37 @@
-use crate::strategy::signal::Signal;
+use crate::strategy::{
+    market_data::MarketData,
+    signal::{Order, Signal},
+};
 
 pub trait RiskManager {
     /// 每个 Tick 调用，传入当前价格与持仓信息
     /// 返回 Some(Signal::Exit) 表示要平仓，否则 None
     fn check_risk(&self, price: f64, position: f64, entry_price: Option<f64>) -> Option<Signal>;
+
+    /// 持仓的止损价和止盈价，没有设置的一侧为 None
+    fn levels(&self, position: f64, entry_price: f64) -> (Option<f64>, Option<f64>);
+
+    /// 按整根 K 线检查：止损单和止盈单与挂单一样按触价价位成交，跳空越过时按开盘价；
+    /// 同一根 bar 两者都触及时按止损处理。返回全部平仓的市价 Reduce
+    fn check_bar(
+        &self,
+        bar: &MarketData,
+        position: f64,
+        entry_price: Option<f64>,
+    ) -> Option<Signal> {
+        let entry_price = entry_price?;
+        if position == 0.0 {
+            return None;
+        }
+        // 平多是卖出，平空是买入
+        let buy = position < 0.0;
+        let (stop, take) = self.levels(position, entry_price);
+        let price = stop
+            .and_then(|price| Order::stop(price).touched(buy, bar))
+            .or_else(|| take.and_then(|price| Order::limit(price).touched(buy, bar)))?;
+        Some(Signal::Reduce {
+            fraction: 1.0,
+            order: Order::market(price),
+        })
+    }
 }
@@ -2,11 +2,15 @@ use crate::strategy::direction::Direction;
 
 pub trait TradeObserver {
     fn on_trade(&mut self, record: &TradeRecord);
+    /// 持有组合时每根 bar 收盘后的按市值计价的净值
+    fn on_mark(&mut self, _time: &str, _equity: f64) {}
 }
 
 /// 一笔完整交易的记录
 #[derive(Debug, Clone)]
 pub struct TradeRecord {
+    /// 交易品种，配对交易为 "A/B"
+    pub symbol: String,
     /// 开仓时间，ISO 8601 格式字符串或 DateTime<Utc>
     pub entry_time: String,
     /// 平仓时间
@@ -21,6 +25,8 @@ pub struct TradeRecord {
     pub direction: Direction,
     /// 单笔盈亏
     pub pnl: f64,
+    /// 对应的 lot id（网格的格子编号），普通交易为 None
+    pub lot: Option<usize>,
     /// 持仓时长（以分钟/条 bar 数等计）
     pub holding_time: String,
 }
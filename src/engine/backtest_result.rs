@@ -1,5 +1,9 @@
 use serde::{Deserialize, Serialize};
 
+use crate::regime::Regime;
+
+use super::chart::StrategyChart;
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct BacktestResult {
     pub total_return: f64,
@@ -11,6 +15,15 @@ pub struct BacktestResult {
     pub final_capital: f64,
     pub trades: Vec<Trade>,
     pub balances: Vec<Balance>,
+    /// 网格等 lot 交易已实现的往返利润
+    #[serde(default)]
+    pub grid_profit: f64,
+    /// 策略指标和信号标记，BacktestDriver::with_chart 开启时才有
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub chart: Option<StrategyChart>,
+    /// 按入场时市场状态拆分的绩效，配置了状态识别时才有
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub regimes: Vec<RegimePerformance>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -26,12 +39,27 @@ pub struct Balance {
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Trade {
     pub date: String,
+    /// 开仓时间，用于按入场时的市场状态归类
+    #[serde(default)]
+    pub entry_date: String,
     #[serde(rename = "type")]
     pub trade_type: TradeType,
     pub result: TradeResultType,
     pub profit: f64,
 }
 
+/// 某个市场状态下的交易统计
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegimePerformance {
+    pub regime: Regime,
+    /// 处于该状态的 bar 占已识别 bar 的比例
+    pub time_share: f64,
+    pub trades: usize,
+    pub win_rate: f64,
+    pub profit: f64,
+    pub avg_profit: f64,
+}
+
 #[derive(Debug, Serialize, Deserialize, PartialEq, PartialOrd, Clone)]
 pub enum TradeResultType {
     #[serde(rename = "win")]
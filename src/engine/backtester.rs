@@ -1,3 +1,5 @@
+use std::error::Error;
+
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
@@ -6,6 +8,7 @@ use crate::{
     domain::PositionSizer,
     executor::backtest_executor::BacktestExecutor,
     processor::SignalProcessor,
+    regime::RegimeFactory,
     risk::risk_manager_factory::RiskManagerFactory,
     sizer::sizer_factory::SizerFactory,
     strategy::{
@@ -57,10 +60,10 @@ impl PortfolioAsset {
         all_data
             .into_iter()
             .filter_map(|entry| {
+                let timestamp = MarketData::parse_timestamp(&entry.timestamp)?;
                 self.calculate_portfolio_price(&entry)
-                    .map(|portfolio_price| MarketData {
-                        timestamp: entry.timestamp,
-                        close_price: portfolio_price,
+                    .map(|portfolio_price| {
+                        MarketData::from_close(timestamp, portfolio_price).with_symbol("portfolio")
                     })
             })
             .collect()
@@ -188,7 +191,8 @@ impl Backtester {
 
             // Track Returns
             let daily_return = (self.capital - prev_capital) / prev_capital;
-            self.returns.push((data.timestamp.clone(), daily_return));
+            self.returns
+                .push((data.timestamp.to_rfc3339(), daily_return));
 
             // Update max drawdown
             if self.capital > peak_capital {
@@ -251,6 +255,9 @@ impl Backtester {
             final_capital: self.capital,
             balances: balance,
             trades: trades,
+            grid_profit: 0.0,
+            chart: None,
+            regimes: Vec::new(),
         })
     }
 
@@ -419,7 +426,7 @@ pub fn build_and_run_backtest(
         .with_slippage(slippage.unwrap_or(0.0))
         .with_commission(commission.unwrap_or(0.0));
 
-    let sizer: Box<dyn PositionSizer> = SizerFactory::build(&config.strategy_run_params);
+    let sizer: Box<dyn PositionSizer> = SizerFactory::build(&config.strategy_run_params).unwrap();
     let rm = RiskManagerFactory::build(&config.strategy_run_params);
 
     // 6. SignalProcessor：挂载 executor、sizer 和日志观察者
@@ -451,12 +458,16 @@ pub fn build_and_run_backtest(
 
 #[cfg(test)]
 mod tests {
-    use chrono::{TimeZone, Utc};
+    use chrono::{DateTime, TimeZone, Utc};
     use serde_json::json;
 
     use crate::{
-        data::{coin_market::CoinsMarket, data_feed::DataFeed, market_data_feed::MarketDataFeed},
+        data::{
+            aligned_data_feed::AlignedDataFeed, coin_market::CoinsMarket, data_feed::DataFeed,
+            market_data_feed::MarketDataFeed,
+        },
         indicators::moving_average::MovingAverageType,
+        regime::hmm_classifier::HmmModel,
         strategy::strategy_type::StrategyType,
     };
 
@@ -473,10 +484,7 @@ mod tests {
             let data = prices
                 .iter()
                 .enumerate()
-                .map(|(i, &p)| MarketData {
-                    timestamp: Utc.timestamp(i as i64, 0).to_rfc3339(),
-                    close_price: p,
-                })
+                .map(|(i, &p)| MarketData::from_close(Utc.timestamp(i as i64, 0), p))
                 .collect();
             DummyDataFeed { idx: 0, data }
         }
@@ -519,8 +527,13 @@ mod tests {
             let market_data: Vec<MarketData> = ohlcv
                 .iter()
                 .map(|ohlc| MarketData {
-                    timestamp: ohlc.timestamp.0.to_rfc3339(),
+                    symbol: ohlc.symbol.clone(),
+                    timestamp: ohlc.timestamp.0,
+                    open: ohlc.open,
+                    high: ohlc.high,
+                    low: ohlc.low,
                     close_price: ohlc.close,
+                    volume: ohlc.volume,
                 })
                 .collect();
             backtester.run(market_data);
@@ -558,31 +571,412 @@ mod tests {
 
         let backtester = BacktestDriver::new(config, datafeed);
 
-        let result: BacktestResult = backtester.build_and_run_backtest();
+        let result: BacktestResult = backtester.build_and_run_backtest().unwrap();
 
         println!("result {:?}", serde_json::to_string(&result));
     }
+
+    #[test]
+    fn test_build_and_run_backtest_macd() {
+        let prices: Vec<f64> = (0..200)
+            .map(|i| 100.0 + (i as f64 * 0.2).sin() * 10.0 + i as f64 * 0.05)
+            .collect();
+
+        for (name, trigger) in [
+            ("ma-crossover", "signal"),
+            ("macd", "signal"),
+            ("macd", "zero"),
+            ("macd", "histogram"),
+        ] {
+            let config = BacktestInput {
+                r#type: name.to_string(),
+                initial_capital: 1_000.0,
+                strategy_run_params: json!({
+                    "maType": "ema",
+                    "fastPeriod": 5,
+                    "slowPeriod": 13,
+                    "signalPeriod": 4,
+                    "trigger": trigger,
+                    "positionType": "both"
+                }),
+            };
+
+            let backtester = BacktestDriver::new(config, DummyDataFeed::new(&prices));
+            let result: BacktestResult = backtester.build_and_run_backtest().unwrap();
+
+            assert!(
+                !result.trades.is_empty(),
+                "{} {} made no trades",
+                name,
+                trigger
+            );
+            assert!(result.final_capital > 0.0);
+        }
+    }
+
+    #[test]
+    fn test_build_and_run_backtest_with_chart() {
+        let prices: Vec<f64> = (0..200)
+            .map(|i| 100.0 + (i as f64 * 0.2).sin() * 10.0 + i as f64 * 0.05)
+            .collect();
+        let config = || BacktestInput {
+            r#type: "ma-crossover".to_string(),
+            initial_capital: 1_000.0,
+            strategy_run_params: json!({
+                "maType": "ema",
+                "fastPeriod": 5,
+                "slowPeriod": 13,
+                "positionType": "both"
+            }),
+        };
+
+        let plain = BacktestDriver::new(config(), DummyDataFeed::new(&prices))
+            .build_and_run_backtest()
+            .unwrap();
+        assert!(plain.chart.is_none());
+
+        let result = BacktestDriver::new(config(), DummyDataFeed::new(&prices))
+            .with_chart(true)
+            .build_and_run_backtest()
+            .unwrap();
+        let chart = result.chart.expect("chart requested");
+        assert_eq!(chart.dates.len(), prices.len());
+        let names: Vec<&str> = chart.indicators.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, ["fast", "slow"]);
+        assert!(
+            chart
+                .indicators
+                .iter()
+                .all(|s| s.values.len() == prices.len())
+        );
+        // 慢线 13 根 bar 才有值
+        assert_eq!(chart.indicators[1].values[11], None);
+        assert!(chart.indicators[1].values[12].is_some());
+        assert!(!chart.markers.is_empty());
+        assert!(chart.markers.iter().all(|m| chart.dates.contains(&m.date)));
+        assert_eq!(result.trades.len(), plain.trades.len());
+    }
+
+    #[test]
+    fn test_build_and_run_backtest_rules() {
+        let prices: Vec<f64> = (0..200)
+            .map(|i| 100.0 + (i as f64 * 0.2).sin() * 10.0 + i as f64 * 0.05)
+            .collect();
+        let config = BacktestInput {
+            r#type: "rules".to_string(),
+            initial_capital: 1_000.0,
+            strategy_run_params: json!({
+                "positionType": "both",
+                "rules": [
+                    {
+                        "when": { "crossesAbove": [
+                            { "indicator": "ema", "params": [5] },
+                            { "indicator": "ema", "params": [13] }
+                        ] },
+                        "then": "enterLong"
+                    },
+                    {
+                        "when": { "crossesBelow": [
+                            { "indicator": "ema", "params": [5] },
+                            { "indicator": "ema", "params": [13] }
+                        ] },
+                        "then": "enterShort"
+                    }
+                ]
+            }),
+        };
+
+        let backtester = BacktestDriver::new(config, DummyDataFeed::new(&prices));
+        let result: BacktestResult = backtester.build_and_run_backtest().unwrap();
+
+        assert!(!result.trades.is_empty());
+        assert!(result.final_capital > 0.0);
+    }
+
+    #[test]
+    fn test_build_and_run_backtest_turtle() {
+        let prices: Vec<f64> = (0..300)
+            .map(|i| 100.0 + (i as f64 * 0.05).sin() * 20.0 + (i as f64 * 0.9).sin())
+            .collect();
+        let config = BacktestInput {
+            r#type: "turtle".to_string(),
+            initial_capital: 10_000.0,
+            strategy_run_params: json!({
+                "entryPeriod": 20,
+                "exitPeriod": 10,
+                "atrPeriod": 14,
+                "sizing": "atr",
+                "riskPerTrade": 0.01,
+                "positionType": "both"
+            }),
+        };
+
+        let backtester = BacktestDriver::new(config, DummyDataFeed::new(&prices));
+        let result: BacktestResult = backtester.build_and_run_backtest().unwrap();
+
+        assert!(!result.trades.is_empty());
+        assert!(result.final_capital > 0.0);
+    }
+
+    #[test]
+    fn test_build_and_run_backtest_pairs() {
+        let (ys, xs): (Vec<MarketData>, Vec<MarketData>) = (0..300)
+            .map(|i| {
+                let t = Utc.timestamp_opt(i as i64 * 60, 0).unwrap();
+                let x = 40.0 + (i as f64 * 0.05).sin() * 5.0;
+                let y = 2.0 * x + 5.0 + (i as f64 * 0.2).sin() * 1.5;
+                (
+                    MarketData::from_close(t, y).with_symbol("YYY"),
+                    MarketData::from_close(t, x).with_symbol("XXX"),
+                )
+            })
+            .unzip();
+        let config = BacktestInput {
+            r#type: "pairs".to_string(),
+            initial_capital: 10_000.0,
+            strategy_run_params: json!({
+                "lookback": 40,
+                "entryZ": 1.5,
+                "exitZ": 0.3,
+                "positionSize": 10.0
+            }),
+        };
+
+        let feed = AlignedDataFeed::from_series(vec![ys, xs]);
+        let result = BacktestDriver::new(config, feed)
+            .build_and_run_backtest()
+            .unwrap();
+
+        assert!(!result.trades.is_empty());
+        // 均值回归的价差上每笔配对交易都应该赚钱，资金变化等于各笔组合盈亏之和
+        let total: f64 = result.trades.iter().map(|t| t.profit).sum();
+        assert!(total > 0.0);
+        assert!((result.final_capital - 10_000.0 - total).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_build_and_run_backtest_grid() {
+        let prices: Vec<f64> = (0..200)
+            .map(|i| 100.0 + (i as f64 * 0.15).sin() * 12.0)
+            .collect();
+        let config = BacktestInput {
+            r#type: "grid".to_string(),
+            initial_capital: 10_000.0,
+            strategy_run_params: json!({
+                "lower": 90.0,
+                "upper": 110.0,
+                "grids": 8,
+                "spacing": "geometric",
+                "mode": "neutral",
+                "positionSize": 2.0
+            }),
+        };
+
+        let backtester = BacktestDriver::new(config, DummyDataFeed::new(&prices));
+        let result = backtester.build_and_run_backtest().unwrap();
+
+        // 每一笔网格往返都按相邻网格线成交，必然盈利
+        assert!(result.trades.len() > 8);
+        assert!(result.trades.iter().all(|t| t.profit > 0.0));
+        let total: f64 = result.trades.iter().map(|t| t.profit).sum();
+        assert!((result.grid_profit - total).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_build_and_run_backtest_regimes() {
+        // 震荡段和趋势段交替
+        let mut price = 100.0;
+        let prices: Vec<f64> = (0..300)
+            .map(|i| {
+                price = if (i / 60) % 2 == 0 {
+                    100.0 + (i as f64 * 0.5).sin() * 3.0
+                } else {
+                    price * 1.01 + (i as f64 * 0.7).sin()
+                };
+                price
+            })
+            .collect();
+        let config = |gate: Value| BacktestInput {
+            r#type: "ma-crossover".to_string(),
+            initial_capital: 1_000.0,
+            strategy_run_params: json!({
+                "maType": "ema",
+                "fastPeriod": 5,
+                "slowPeriod": 13,
+                "positionType": "both",
+                "regimeMethod": "rules",
+                "regimeVolPeriod": 10,
+                "regimeVolLookback": 50,
+                "tradeRegimes": gate
+            }),
+        };
+
+        let plain = BacktestDriver::new(config(Value::Null), DummyDataFeed::new(&prices))
+            .build_and_run_backtest()
+            .unwrap();
+        assert!(plain.regimes.len() > 1);
+        let share: f64 = plain.regimes.iter().map(|r| r.time_share).sum();
+        assert!((share - 1.0).abs() < 1e-9);
+        let counted: usize = plain.regimes.iter().map(|r| r.trades).sum();
+        assert!(counted > 0 && counted <= plain.trades.len());
+
+        let gated = BacktestDriver::new(config(json!(["trending"])), DummyDataFeed::new(&prices))
+            .build_and_run_backtest()
+            .unwrap();
+        assert!(gated.trades.len() < plain.trades.len());
+        assert!(
+            gated
+                .regimes
+                .iter()
+                .filter(|r| r.trades > 0)
+                .all(|r| r.regime.trend == crate::regime::Trend::Trending)
+        );
+    }
+
+    #[test]
+    fn test_hmm_regimes_skip_the_training_window() {
+        let mut price = 100.0;
+        let prices: Vec<f64> = (0..400)
+            .map(|i| {
+                price = if (i / 50) % 2 == 0 {
+                    100.0 + (i as f64 * 0.5).sin() * 3.0
+                } else {
+                    price * 1.01 + (i as f64 * 0.7).sin()
+                };
+                price
+            })
+            .collect();
+        let model = HmmModel::fit(&daily("BTC", &prices[..200]), 2, 10, 30).unwrap();
+        let trained_until = model.trained_until;
+        let config = |model: Option<&HmmModel>| BacktestInput {
+            r#type: "ma-crossover".to_string(),
+            initial_capital: 1_000.0,
+            strategy_run_params: json!({
+                "maType": "ema",
+                "fastPeriod": 5,
+                "slowPeriod": 13,
+                "positionType": "both",
+                "regimeMethod": "hmm",
+                "regimeModel": model
+            }),
+        };
+
+        let feed = AlignedDataFeed::from_series(vec![daily("BTC", &prices)]);
+        let result = BacktestDriver::new(config(Some(&model)), feed)
+            .build_and_run_backtest()
+            .unwrap();
+        // 训练用过的 bar 不参与回测
+        assert!(!result.trades.is_empty());
+        assert!(result.trades.iter().all(|t| {
+            DateTime::parse_from_rfc3339(&t.entry_date).is_ok_and(|at| at > trained_until)
+        }));
+        assert!(result.balances.len() <= prices.len() - 200);
+
+        // 没有模型时不再退回规则识别
+        let feed = AlignedDataFeed::from_series(vec![daily("BTC", &prices)]);
+        assert!(
+            BacktestDriver::new(config(None), feed)
+                .build_and_run_backtest()
+                .is_err()
+        );
+    }
+
+    fn daily(symbol: &str, prices: &[f64]) -> Vec<MarketData> {
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        prices
+            .iter()
+            .enumerate()
+            .map(|(i, &p)| {
+                MarketData::from_close(start + chrono::Duration::days(i as i64), p)
+                    .with_symbol(symbol)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_build_and_run_backtest_dca_marks_balances() {
+        let prices: Vec<f64> = (0..120).map(|i| 100.0 + i as f64 * 0.5).collect();
+        let config = BacktestInput {
+            r#type: "dca".to_string(),
+            initial_capital: 10_000.0,
+            strategy_run_params: json!({ "amount": 500.0, "every": "week" }),
+        };
+        let feed = AlignedDataFeed::from_series(vec![daily("BTC", &prices)]);
+        let result = BacktestDriver::new(config, feed)
+            .build_and_run_backtest()
+            .unwrap();
+
+        // 定投从不卖出，余额曲线来自逐 bar 的市值
+        assert!(result.trades.is_empty());
+        assert_eq!(result.balances.len(), prices.len());
+        assert!(result.final_capital > 10_000.0);
+        let monthly = crate::indicators::calculate_monthly_returns(&result.balances);
+        assert_eq!(monthly.len(), 4);
+        assert!(monthly.iter().all(|m| m.strategy_return > 0.0));
+    }
+
+    #[test]
+    fn test_build_and_run_backtest_rebalance() {
+        let btc: Vec<f64> = (0..90).map(|i| 100.0 * 1.01_f64.powi(i)).collect();
+        let eth: Vec<f64> = (0..90)
+            .map(|i| 50.0 + (i as f64 * 0.2).sin() * 5.0)
+            .collect();
+        let config = BacktestInput {
+            r#type: "rebalance".to_string(),
+            initial_capital: 10_000.0,
+            strategy_run_params: json!({
+                "allocations": [
+                    { "symbol": "BTC", "allocation": 50 },
+                    { "symbol": "ETH", "allocation": 50 }
+                ],
+                "every": "month",
+                "driftThreshold": 0.0
+            }),
+        };
+        let feed = AlignedDataFeed::from_series(vec![daily("BTC", &btc), daily("ETH", &eth)]);
+        let result = BacktestDriver::new(config, feed)
+            .build_and_run_backtest()
+            .unwrap();
+
+        // 2、3 月初卖出涨多了的 BTC 兑现利润
+        let btc_sales: Vec<_> = result.trades.iter().filter(|t| t.profit > 0.0).collect();
+        assert!(btc_sales.len() >= 2, "{:?}", result.trades);
+        assert_eq!(result.balances.len(), 90);
+        assert!(result.final_capital > 10_000.0);
+    }
 }
 
 /// 把 DataFeed、BacktestInput 和 build_and_run_backtest 串起来
 pub struct BacktestDriver<DF: DataFeed> {
     config: BacktestInput,
     datafeed: DF,
+    chart: bool,
 }
 
 impl<DF: DataFeed> BacktestDriver<DF> {
     pub fn new(config: BacktestInput, datafeed: DF) -> Self {
-        Self { config, datafeed }
+        Self {
+            config,
+            datafeed,
+            chart: false,
+        }
     }
 
-    /// 真正触发回测，并返回结果
-    pub fn build_and_run_backtest(self) -> BacktestResult {
+    /// 结果里附带策略的指标线和信号标记
+    pub fn with_chart(mut self, enabled: bool) -> Self {
+        self.chart = enabled;
+        self
+    }
+
+    /// 真正触发回测，并返回结果；策略参数无法构建时返回错误
+    pub fn build_and_run_backtest(self) -> Result<BacktestResult, Box<dyn Error>> {
         let datafeed = self.datafeed;
 
         let config = &self.config;
 
         let strategy_factory = StrategyFactory::new();
-        let strategy = strategy_factory.build(config.r#type.as_str(), &config.strategy_run_params);
+        let strategy =
+            strategy_factory.build(config.r#type.as_str(), &config.strategy_run_params)?;
 
         // 3. Executor：回测专用，传入初始资金与滑点、手续费参数
         let slippage = self
@@ -599,7 +993,7 @@ impl<DF: DataFeed> BacktestDriver<DF> {
             .with_slippage(slippage.unwrap_or(0.0))
             .with_commission(commission.unwrap_or(0.0));
 
-        let sizer: Box<dyn PositionSizer> = SizerFactory::build(&config.strategy_run_params);
+        let sizer: Box<dyn PositionSizer> = SizerFactory::build(&config.strategy_run_params)?;
         let rm = RiskManagerFactory::build(&config.strategy_run_params);
 
         // 6. SignalProcessor：挂载 executor、sizer 和日志观察者
@@ -612,13 +1006,14 @@ impl<DF: DataFeed> BacktestDriver<DF> {
         // let mut ctx = StrategyContext::new(config.initial_capital);
 
         // 8. 构造并运行引擎
-        let mut engine = TradingEngine::new(
-            rm,
-            datafeed,
-            strategy.unwrap(),
-            processor,
-            config.initial_capital,
-        );
+        let mut engine =
+            TradingEngine::new(rm, datafeed, strategy, processor, config.initial_capital);
+        if self.chart {
+            engine = engine.with_chart();
+        }
+        if let Some(regimes) = RegimeFactory::build(&config.strategy_run_params)? {
+            engine = engine.with_regimes(regimes);
+        }
         engine.run();
 
         // 9. 回测结束后，取出绩效
@@ -626,6 +1021,11 @@ impl<DF: DataFeed> BacktestDriver<DF> {
         // let balances = perf_logger.balances();
         // perf_logger.finalize(trades, balances)
 
-        perf_logger.finalize()
+        let mut result = perf_logger.finalize();
+        result.chart = engine.take_chart();
+        if let Some(regimes) = engine.take_regimes() {
+            result.regimes = regimes.breakdown(&result.trades);
+        }
+        Ok(result)
     }
 }
@@ -0,0 +1,122 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    indicators::overlay::ChartSeries,
+    strategy::{market_data::MarketData, signal::Signal},
+};
+
+/// 策略在某根 bar 上发出的信号
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SignalMarker {
+    pub date: String,
+    pub signal: String,
+    pub price: f64,
+    /// 由风控（止损/止盈）而不是策略发出
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub risk: bool,
+}
+
+/// 回测过程中策略自身的指标值和信号，indicators 中每条线与 dates 一一对应
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct StrategyChart {
+    pub dates: Vec<String>,
+    pub indicators: Vec<ChartSeries>,
+    pub markers: Vec<SignalMarker>,
+}
+
+impl StrategyChart {
+    /// 记录一根 bar；中途才出现的指标在之前的 bar 上补 null，本根没给值的线记 null
+    pub fn record(
+        &mut self,
+        bar: &MarketData,
+        signal: &Signal,
+        values: Vec<(String, Option<f64>)>,
+        risk: bool,
+    ) {
+        let date = bar.timestamp.to_rfc3339();
+        let index = self.dates.len();
+        for (name, value) in values {
+            let pos = match self.indicators.iter().position(|s| s.name == name) {
+                Some(pos) => pos,
+                None => {
+                    self.indicators.push(ChartSeries {
+                        name,
+                        values: vec![None; index],
+                    });
+                    self.indicators.len() - 1
+                }
+            };
+            let line = &mut self.indicators[pos].values;
+            if line.len() == index {
+                line.push(value);
+            }
+        }
+        for line in &mut self.indicators {
+            line.values.resize(index + 1, None);
+        }
+        self.mark(&date, bar, signal, risk);
+        self.dates.push(date);
+    }
+
+    fn mark(&mut self, date: &str, bar: &MarketData, signal: &Signal, risk: bool) {
+        let price = match signal {
+            Signal::Hold => return,
+            Signal::Batch(signals) => {
+                for s in signals {
+                    self.mark(date, bar, s, risk);
+                }
+                return;
+            }
+            Signal::EnterLong(p) | Signal::EnterShort(p) | Signal::ScaleIn(p) => *p,
+            Signal::OpenLot(lot) | Signal::CloseLot(lot) => lot.price,
+            Signal::Enter(entry) => entry.order.price,
+            Signal::Reduce { order, .. } => order.price,
+            _ => bar.close_price,
+        };
+        self.markers.push(SignalMarker {
+            date: date.to_string(),
+            signal: signal.name().to_string(),
+            price,
+            risk,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, TimeZone, Utc};
+
+    use super::*;
+
+    #[test]
+    fn test_record_aligns_late_series_and_flattens_batches() {
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let bar = |i: i64, p: f64| MarketData::from_close(start + Duration::days(i), p);
+        let mut chart = StrategyChart::default();
+        chart.record(
+            &bar(0, 10.0),
+            &Signal::Hold,
+            vec![("fast".into(), Some(10.0))],
+            false,
+        );
+        chart.record(
+            &bar(1, 11.0),
+            &Signal::EnterLong(11.0),
+            vec![("fast".into(), Some(10.5)), ("slow".into(), Some(10.2))],
+            false,
+        );
+        chart.record(
+            &bar(2, 9.0),
+            &Signal::Batch(vec![Signal::Exit, Signal::EnterShort(9.0)]),
+            vec![],
+            true,
+        );
+
+        assert_eq!(chart.dates.len(), 3);
+        assert_eq!(chart.indicators[0].values, [Some(10.0), Some(10.5), None]);
+        assert_eq!(chart.indicators[1].values, [None, Some(10.2), None]);
+        let signals: Vec<&str> = chart.markers.iter().map(|m| m.signal.as_str()).collect();
+        assert_eq!(signals, ["enterLong", "exit", "enterShort"]);
+        assert!(chart.markers[1].risk && chart.markers[1].price == 9.0);
+    }
+}
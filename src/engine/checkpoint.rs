@@ -0,0 +1,53 @@
+use std::{
+    error::Error,
+    sync::{Arc, Mutex},
+};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{
+    processor::signal_processor::ProcessorState, strategy::strategy_context::StrategyContext,
+};
+
+/// 引擎处理完某根 bar 之后的完整状态，续跑时从下一根 bar 开始
+#[derive(Clone, Serialize, Deserialize)]
+pub struct EngineCheckpoint {
+    /// 导出状态的策略名，恢复时必须一致
+    pub strategy: String,
+    /// 最后处理完的 bar 时间，续跑时跳过不晚于它的 bar
+    pub last_bar: DateTime<Utc>,
+    /// Checkpoint::save_state 的结果
+    pub strategy_state: Value,
+    pub context: StrategyContext,
+    pub processor: ProcessorState,
+    /// 市场状态识别器的窗口，没有配置或不支持导出时为 None
+    #[serde(default)]
+    pub regime: Option<Value>,
+}
+
+/// checkpoint 存放位置，只需保留最近一次
+pub trait CheckpointStore {
+    fn save(&mut self, checkpoint: &EngineCheckpoint) -> Result<(), Box<dyn Error>>;
+
+    /// 没有存过返回 None
+    fn load(&self) -> Result<Option<EngineCheckpoint>, Box<dyn Error>>;
+}
+
+/// 存在内存里，clone 出来的 store 共享同一份 checkpoint，进程内重建引擎时使用
+#[derive(Clone, Default)]
+pub struct MemoryCheckpointStore {
+    checkpoint: Arc<Mutex<Option<EngineCheckpoint>>>,
+}
+
+impl CheckpointStore for MemoryCheckpointStore {
+    fn save(&mut self, checkpoint: &EngineCheckpoint) -> Result<(), Box<dyn Error>> {
+        *self.checkpoint.lock().unwrap() = Some(checkpoint.clone());
+        Ok(())
+    }
+
+    fn load(&self) -> Result<Option<EngineCheckpoint>, Box<dyn Error>> {
+        Ok(self.checkpoint.lock().unwrap().clone())
+    }
+}
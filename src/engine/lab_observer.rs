@@ -32,7 +32,8 @@ impl LabObserver {
             &run_lab_strategy.sub_type.clone().unwrap(),
             run_lab_strategy.strategy_run_params.fast_period.unwrap() as u32,
             run_lab_strategy.strategy_run_params.slow_period.unwrap() as u32,
-        );
+        )
+        .expect("unsupported lab strategy");
         let capital = run_lab_strategy.initial_capital;
         let position = None;
         let trade_log = Vec::new();
@@ -96,7 +97,7 @@ impl LabObserver {
         );
 
         for data in market_data {
-            let (ts, price) = (data.timestamp.clone(), data.close_price);
+            let (ts, price) = (data.timestamp.to_rfc3339(), data.close_price);
 
             println!(
                 "Price: {}, Stop Loss Trigger: {}, Take Profit Trigger: {}",
@@ -503,6 +504,9 @@ impl LabObserver {
             final_capital: self.capital,
             balances,
             trades,
+            grid_profit: 0.0,
+            chart: None,
+            regimes: Vec::new(),
         })
     }
 
@@ -688,6 +692,9 @@ mod tests {
             mean_type: None,
             reversion_style: None,
             lookback_period: None,
+            rules: None,
+            regime: Default::default(),
+            extra: Default::default(),
         };
         let run_lab_strategy = RunLabStrategy {
             r#type: "ma".to_string(),
@@ -703,8 +710,13 @@ mod tests {
             let market_data: Vec<MarketData> = ohlcv
                 .iter()
                 .map(|ohlc| MarketData {
-                    timestamp: ohlc.timestamp.0.to_rfc3339(),
+                    symbol: ohlc.symbol.clone(),
+                    timestamp: ohlc.timestamp.0,
+                    open: ohlc.open,
+                    high: ohlc.high,
+                    low: ohlc.low,
                     close_price: ohlc.close,
+                    volume: ohlc.volume,
                 })
                 .collect();
             assert!(!market_data.is_empty(), "Market data should not be empty");
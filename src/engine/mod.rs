@@ -1,5 +1,7 @@
 pub mod backtest_result;
 pub mod backtester;
+pub mod chart;
+pub mod checkpoint;
 pub mod lab_observer;
 pub mod observer;
 pub mod parameters;
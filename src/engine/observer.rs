@@ -11,6 +11,9 @@ pub struct PerformanceObserver {
     trades: Rc<RefCell<Vec<Trade>>>,
     balances: Rc<RefCell<Vec<Balance>>>,
     current_capital: Rc<RefCell<f64>>,
+    grid_profit: Rc<RefCell<f64>>,
+    // 收到过 on_mark 后余额曲线改由逐 bar 市值驱动
+    marked: Rc<RefCell<bool>>,
 }
 
 impl PerformanceObserver {
@@ -29,6 +32,8 @@ impl PerformanceObserver {
             trades,
             balances,
             current_capital,
+            grid_profit: Rc::new(RefCell::new(0.0)),
+            marked: Rc::new(RefCell::new(false)),
         }
     }
 
@@ -149,17 +154,35 @@ impl PerformanceObserver {
             final_capital: end,
             trades,
             balances,
+            grid_profit: *self.grid_profit.borrow(),
+            chart: None,
+            regimes: Vec::new(),
         }
     }
 }
 
 impl TradeObserver for PerformanceObserver {
+    fn on_mark(&mut self, time: &str, equity: f64) {
+        *self.marked.borrow_mut() = true;
+        *self.current_capital.borrow_mut() = equity;
+        let count = self.trades.borrow().len() as u32;
+        self.balances.borrow_mut().push(Balance {
+            date: time.to_string(),
+            capital: equity,
+            trades: count,
+        });
+    }
+
     fn on_trade(&mut self, record: &TradeRecord) {
         // 更新资金
         *self.current_capital.borrow_mut() += record.pnl;
+        if record.lot.is_some() {
+            *self.grid_profit.borrow_mut() += record.pnl;
+        }
         // 记录交易
         self.trades.borrow_mut().push(Trade {
             date: record.exit_time.clone(),
+            entry_date: record.entry_time.clone(),
             trade_type: if record.direction == Direction::Long {
                 TradeType::Buy
             } else {
@@ -172,6 +195,9 @@ impl TradeObserver for PerformanceObserver {
             },
             profit: record.pnl,
         });
+        if *self.marked.borrow() {
+            return;
+        }
         // 记录余额快照
         let cap = *self.current_capital.borrow();
         let count = self.trades.borrow().len() as u32;
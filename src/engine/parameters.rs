@@ -1,5 +1,5 @@
 use serde::{Deserialize, Serialize};
-use serde_json::Value;
+use serde_json::{Map, Value};
 
 use crate::strategy::{market_data::MarketData, strategy_type::StrategyType};
 
@@ -43,6 +43,37 @@ pub struct StrategyRunParameters {
     pub entry_z_score: Option<f64>,
     pub band_multiplier: Option<f64>,
     pub cooldown_period: Option<u32>,
+    /// 规则策略（"rules"）的规则列表，格式见 strategy::rules
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rules: Option<Value>,
+    #[serde(flatten, default)]
+    pub regime: RegimeParameters,
+    /// 其余策略自己的参数（rsiPeriod、strategies、trendTimeframe 等），
+    /// 按 schema 校验后原样交给策略
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// 市场状态识别和按状态开仓的参数，含义见 param_schema 的执行参数
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RegimeParameters {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trade_regimes: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub regime_method: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub regime_adx_period: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub regime_adx_threshold: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub regime_vol_period: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub regime_vol_lookback: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub regime_vol_percentile: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub regime_model_id: Option<u64>,
 }
 
 impl StrategyRunParameters {
@@ -89,6 +120,9 @@ impl StrategyRunParameters {
             entry_z_score: self.entry_z_score,
             band_multiplier: self.band_multiplier,
             cooldown_period: self.cooldown_period,
+            rules: self.rules.clone(),
+            regime: self.regime.clone(),
+            extra: self.extra.clone(),
         }
     }
 }
@@ -1,10 +1,20 @@
+use std::error::Error;
+
+use chrono::{DateTime, Utc};
+
 use crate::{
     data::data_feed::DataFeed,
     domain::{RiskManager, executor::OrderExecutor},
     processor::SignalProcessor,
+    regime::RegimeTracker,
     strategy::{strategy_context::StrategyContext, strategy_trait::Strategy},
 };
 
+use super::{
+    chart::StrategyChart,
+    checkpoint::{CheckpointStore, EngineCheckpoint},
+};
+
 /// 主交易引擎：驱动数据源、风控、策略决策和信号执行
 pub struct TradingEngine<DF, EX>
 where
@@ -21,6 +31,54 @@ where
     processor: SignalProcessor<EX>,
     /// 策略上下文：持仓、资金和当前入场信息
     strategy_context: StrategyContext,
+    /// 开启后逐 bar 记录策略指标和信号
+    chart: Option<StrategyChart>,
+    /// 开启后每隔若干根 bar 保存一次 checkpoint
+    checkpoints: Option<Checkpoints>,
+    /// run 跳过不晚于这个时间的 bar：resume 之后是最后处理过的 bar，
+    /// 用离线模型识别市场状态时是模型的训练截止时间
+    resume_after: Option<DateTime<Utc>>,
+    /// 市场状态识别，结果写入 StrategyContext::regime
+    regimes: Option<RegimeTracker>,
+}
+
+struct Checkpoints {
+    store: Box<dyn CheckpointStore>,
+    every: usize,
+    bars: usize,
+}
+
+impl Checkpoints {
+    /// 每 every 根 bar 保存一次，force 时立即保存；策略未开启 checkpoint 时什么都不做
+    fn tick<EX: OrderExecutor>(
+        &mut self,
+        strategy: &mut dyn Strategy,
+        processor: &SignalProcessor<EX>,
+        regimes: Option<&RegimeTracker>,
+        ctx: &StrategyContext,
+        last_bar: DateTime<Utc>,
+        force: bool,
+    ) {
+        self.bars += 1;
+        if !force && !self.bars.is_multiple_of(self.every) {
+            return;
+        }
+        let name = strategy.name().to_string();
+        let Some(strategy_state) = strategy.checkpoint().and_then(|s| s.save_state()) else {
+            return;
+        };
+        let checkpoint = EngineCheckpoint {
+            strategy: name,
+            last_bar,
+            strategy_state,
+            context: ctx.clone(),
+            processor: processor.save_state(),
+            regime: regimes.and_then(RegimeTracker::save_state),
+        };
+        if let Err(e) = self.store.save(&checkpoint) {
+            eprintln!("save checkpoint failed: {}", e);
+        }
+    }
 }
 
 impl<DF, EX> TradingEngine<DF, EX>
@@ -43,6 +101,10 @@ where
             strategy,
             processor,
             strategy_context: StrategyContext::new(initial_equity),
+            chart: None,
+            checkpoints: None,
+            resume_after: None,
+            regimes: None,
         };
         engine
     }
@@ -53,28 +115,236 @@ where
         self
     }
 
+    /// 记录策略图表（指标值和信号标记），回测结束后用 take_chart 取出
+    pub fn with_chart(mut self) -> Self {
+        self.chart = Some(StrategyChart::default());
+        self
+    }
+
+    pub fn take_chart(&mut self) -> Option<StrategyChart> {
+        self.chart.take()
+    }
+
+    /// 逐 bar 识别市场状态，配置了 tradeRegimes 时按状态拦截开仓；
+    /// 识别器是离线拟合的模型时跳过训练用过的 bar，避免用到未来数据
+    pub fn with_regimes(mut self, regimes: RegimeTracker) -> Self {
+        self.resume_after = self.resume_after.max(regimes.trained_until());
+        self.regimes = Some(regimes);
+        self
+    }
+
+    /// 取出状态记录，用于按市场状态拆分回测绩效
+    pub fn take_regimes(&mut self) -> Option<RegimeTracker> {
+        self.regimes.take()
+    }
+
+    /// 每处理 every 根 bar 以及数据源耗尽时把状态写入 store，策略需实现 Checkpoint
+    pub fn with_checkpoints(mut self, store: Box<dyn CheckpointStore>, every: usize) -> Self {
+        self.checkpoints = Some(Checkpoints {
+            store,
+            every: every.max(1),
+            bars: 0,
+        });
+        self
+    }
+
+    /// 从 store 中最近一次 checkpoint 恢复策略、上下文、挂单和模拟账户，run 时跳过已处理过的 bar。
+    /// 没有 checkpoint 时返回 Ok(false)；出错时引擎可能只恢复了一部分，应重新构建后从头运行
+    pub fn resume(&mut self) -> Result<bool, Box<dyn Error>> {
+        let Some(checkpoints) = &self.checkpoints else {
+            return Ok(false);
+        };
+        let Some(checkpoint) = checkpoints.store.load()? else {
+            return Ok(false);
+        };
+        let name = self.strategy.name().to_string();
+        if checkpoint.strategy != name {
+            return Err(format!(
+                "checkpoint belongs to strategy `{}`, not `{}`",
+                checkpoint.strategy, name
+            )
+            .into());
+        }
+        let strategy = self
+            .strategy
+            .checkpoint()
+            .ok_or_else(|| format!("strategy `{}` does not support checkpoints", name))?;
+        strategy.load_state(&checkpoint.strategy_state)?;
+        self.processor.load_state(checkpoint.processor)?;
+        let restored = match (&mut self.regimes, &checkpoint.regime) {
+            (Some(regimes), Some(state)) => regimes.load_state(state),
+            _ => true,
+        };
+        if !restored {
+            return Err("cannot restore regime classifier".into());
+        }
+        self.strategy_context = checkpoint.context;
+        self.resume_after = Some(checkpoint.last_bar);
+        Ok(true)
+    }
+
     /// 运行引擎：循环拉取行情，执行风控与策略信号
     pub fn run(&mut self) {
         let ctx = &mut self.strategy_context;
-        while let Some(data) = self.datafeed.next() {
-            // 1. 同步仓位
+        ctx.subscribe(&self.strategy.timeframes());
+        self.processor.start(self.strategy.as_mut(), ctx);
+        let mut last_bar = None;
+        while let Some(bars) = self.datafeed.next_bars() {
+            let Some(data) = bars.first() else {
+                continue;
+            };
+            // 续跑：checkpoint 之前的 bar 已经处理过
+            if self.resume_after.is_some_and(|t| data.timestamp <= t) {
+                continue;
+            }
+            last_bar = Some(data.timestamp);
+            // 高周期 K 线按基础 bar 推进，包括风控接管的 bar
+            ctx.advance_timeframes(data);
+            if let Some(regimes) = &mut self.regimes {
+                ctx.regime = regimes.update(data);
+            }
+
+            // 1. 同步仓位，撮合之前挂的限价/止损单和止损/止盈
             ctx.position = self.processor.sync_positions();
+            self.processor
+                .fill_resting(ctx, &bars, self.strategy.as_mut());
 
-            // 2. 风控优先：止损/止盈检查
+            // 2. 风控优先：止损/止盈检查（配对组合没有单一入场价，不在这里检查）
             let entry_price: Option<f64> = ctx.current_entry.as_ref().map(|e| e.1);
-            if let Some(sig) =
-                self.risk_manager
-                    .check_risk(data.close_price, ctx.position, entry_price)
-            {
-                self.processor.process(sig, ctx, &data);
-                continue;
-            }
+            let sig = match self.risk_manager.check_bar(data, ctx.position, entry_price) {
+                Some(sig) => {
+                    // 这根 bar 策略没有看到，指标线留空
+                    if let Some(chart) = &mut self.chart {
+                        chart.record(data, &sig, Vec::new(), true);
+                    }
+                    sig
+                }
+                None => {
+                    // 3. 策略决策
+                    let sig = self.strategy.on_bars(ctx, &bars);
+                    let sig = match &self.regimes {
+                        Some(regimes) => regimes.gate(ctx.regime, sig),
+                        None => sig,
+                    };
+                    if let Some(chart) = &mut self.chart {
+                        chart.record(data, &sig, self.strategy.chart_values(), false);
+                    }
+                    sig
+                }
+            };
+
+            // 4. 执行信号，成交和平仓结果回调给策略
+            self.processor
+                .process_bars(sig, ctx, &bars, self.strategy.as_mut());
 
-            // 3. 策略决策
-            let sig = self.strategy.on_tick(ctx, &data);
+            // 5. 组合持仓按收盘价计价
+            self.processor.mark_to_market(ctx, &bars);
 
-            // 4. 执行信号
-            self.processor.process(sig, ctx, &data);
+            if let Some(checkpoints) = &mut self.checkpoints {
+                checkpoints.tick(
+                    self.strategy.as_mut(),
+                    &self.processor,
+                    self.regimes.as_ref(),
+                    ctx,
+                    data.timestamp,
+                    false,
+                );
+            }
+        }
+        // 数据源耗尽时保存最终状态，之后接上新数据继续跑
+        if let (Some(checkpoints), Some(last_bar)) = (&mut self.checkpoints, last_bar) {
+            checkpoints.tick(
+                self.strategy.as_mut(),
+                &self.processor,
+                self.regimes.as_ref(),
+                ctx,
+                last_bar,
+                true,
+            );
         }
+        self.processor.finish(self.strategy.as_mut(), ctx);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+    use serde_json::json;
+
+    use crate::{
+        data::market_data_feed::MarketDataFeed,
+        engine::checkpoint::MemoryCheckpointStore,
+        executor::backtest_executor::BacktestExecutor,
+        risk::risk_manager_factory::RiskManagerFactory,
+        sizer::sizer_factory::SizerFactory,
+        strategy::{market_data::MarketData, strategy_factory::StrategyFactory},
+    };
+
+    use super::*;
+
+    fn engine(records: Vec<MarketData>) -> TradingEngine<MarketDataFeed, BacktestExecutor> {
+        let params = json!({
+            "maType": "ema",
+            "fastPeriod": 5,
+            "slowPeriod": 13,
+            "positionType": "both"
+        });
+        let strategy = StrategyFactory::new()
+            .build("ma-crossover", &params)
+            .unwrap();
+        let processor = SignalProcessor::new(
+            BacktestExecutor::new(1_000.0),
+            SizerFactory::build(&params).unwrap(),
+        );
+        TradingEngine::new(
+            RiskManagerFactory::build(&params),
+            MarketDataFeed { records, cursor: 0 },
+            strategy,
+            processor,
+            1_000.0,
+        )
+        .with_chart()
+    }
+
+    #[test]
+    fn test_resume_from_checkpoint() {
+        let records: Vec<MarketData> = (0..200)
+            .map(|i| {
+                let price = 100.0 + (i as f64 * 0.2).sin() * 10.0 + i as f64 * 0.05;
+                MarketData::from_close(Utc.timestamp_opt(i, 0).unwrap(), price)
+            })
+            .collect();
+
+        let mut full = engine(records.clone());
+        full.run();
+        let full = full.take_chart().unwrap();
+
+        // 跑到第 120 根 bar 停下，用同一个 store 重建引擎接着跑完整数据
+        let store = MemoryCheckpointStore::default();
+        let mut first =
+            engine(records[..120].to_vec()).with_checkpoints(Box::new(store.clone()), 50);
+        assert!(!first.resume().unwrap());
+        first.run();
+        let first = first.take_chart().unwrap();
+
+        let mut second = engine(records).with_checkpoints(Box::new(store), 50);
+        assert!(second.resume().unwrap());
+        second.run();
+        let second = second.take_chart().unwrap();
+
+        assert_eq!(second.dates.len(), 80);
+        let markers = |chart: &StrategyChart| {
+            serde_json::to_value(&chart.markers)
+                .unwrap()
+                .as_array()
+                .unwrap()
+                .clone()
+        };
+        assert!(!markers(&full).is_empty());
+        assert_eq!(markers(&full), [markers(&first), markers(&second)].concat());
+        assert_eq!(
+            full.indicators[0].values[120..],
+            second.indicators[0].values[..]
+        );
     }
 }
@@ -1,6 +1,11 @@
+use std::error::Error;
+
 use chrono::Utc;
+use serde_json::{Value, json};
 
-use crate::domain::{OrderRequest, OrderSide, executor::OrderExecutor, order::OrderResponse};
+use crate::domain::{
+    OrderRequest, OrderSide, OrderType, executor::OrderExecutor, order::OrderResponse,
+};
 
 /// 回测执行器：模拟市价立即成交，支持滑点和佣金
 pub struct BacktestExecutor {
@@ -39,13 +44,18 @@ impl BacktestExecutor {
 }
 
 impl OrderExecutor for BacktestExecutor {
-    /// 执行订单：模拟市价/限价/止损统一按市价立即成交
+    /// 执行订单：按请求价格立即成交，触价判断由 SignalProcessor 完成；
+    /// 限价单按挂单价成交不计滑点，市价和止损单计滑点
     fn execute(&mut self, req: OrderRequest) -> OrderResponse {
         // 计算执行价格：考虑滑点
         let base_price = req.price;
+        let slippage = match req.order_type {
+            OrderType::Limit => 0.0,
+            OrderType::Market | OrderType::Stop => self.slippage,
+        };
         let exec_price = match req.side {
-            OrderSide::Buy => base_price * (1.0 + self.slippage),
-            OrderSide::Sell => base_price * (1.0 - self.slippage),
+            OrderSide::Buy => base_price * (1.0 + slippage),
+            OrderSide::Sell => base_price * (1.0 - slippage),
         };
 
         // 计算成交数量及成本
@@ -81,6 +91,22 @@ impl OrderExecutor for BacktestExecutor {
         self.position
     }
 
+    /// 滑点和佣金来自配置，只存持仓和现金
+    fn save_state(&self) -> Option<Value> {
+        Some(json!({ "position": self.position, "cash": self.cash }))
+    }
+
+    fn load_state(&mut self, state: &Value) -> Result<(), Box<dyn Error>> {
+        let field = |key: &str| {
+            state[key]
+                .as_f64()
+                .ok_or_else(|| format!("executor state missing `{}`", key))
+        };
+        self.position = field("position")?;
+        self.cash = field("cash")?;
+        Ok(())
+    }
+
     // /// 同步账户净值（仓位转市价 + 现金）
     // fn sync_equity(&mut self) -> f64 {
     //     // equity = cash + position * last_price。
@@ -0,0 +1,234 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::strategy::market_data::MarketData;
+
+use super::{
+    atr_indicator::true_range,
+    indicator::{Indicator, IndicatorValue, deserialize_state, serialize_state},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AdxValue {
+    pub adx: f64,
+    pub plus_di: f64,
+    pub minus_di: f64,
+}
+
+impl IndicatorValue for AdxValue {
+    fn to_vec(self) -> Vec<f64> {
+        vec![self.adx, self.plus_di, self.minus_di]
+    }
+}
+
+// 一根 bar 相对前一根的 (TR, +DM, -DM)
+fn directional_move(bar: &MarketData, prev: &MarketData) -> (f64, f64, f64) {
+    let up = bar.high - prev.high;
+    let down = prev.low - bar.low;
+    let plus = if up > down && up > 0.0 { up } else { 0.0 };
+    let minus = if down > up && down > 0.0 { down } else { 0.0 };
+    (
+        true_range(bar.high, bar.low, Some(prev.close_price)),
+        plus,
+        minus,
+    )
+}
+
+// (+DI, -DI, DX)
+fn directional_index(tr: f64, plus: f64, minus: f64) -> (f64, f64, f64) {
+    let (plus_di, minus_di) = if tr == 0.0 {
+        (0.0, 0.0)
+    } else {
+        (100.0 * plus / tr, 100.0 * minus / tr)
+    };
+    let sum = plus_di + minus_di;
+    let dx = if sum == 0.0 {
+        0.0
+    } else {
+        100.0 * (plus_di - minus_di).abs() / sum
+    };
+    (plus_di, minus_di, dx)
+}
+
+// Wilder ADX：TR 与 ±DM 先累加 period 个再按 s - s/n + x 平滑，
+// DX 的前 period 个取均值作为第一个 ADX，之后按 1/period 平滑
+#[derive(Serialize, Deserialize)]
+pub struct AdxIndicator {
+    period: usize,
+    prev: Option<MarketData>,
+    seen: usize,
+    smoothed: (f64, f64, f64),
+    dx_seed: Vec<f64>,
+    di: Option<(f64, f64)>,
+    adx: Option<f64>,
+}
+
+impl AdxIndicator {
+    pub fn new(period: usize) -> Self {
+        let period = period.max(1);
+        Self {
+            period,
+            prev: None,
+            seen: 0,
+            smoothed: (0.0, 0.0, 0.0),
+            dx_seed: Vec::with_capacity(period),
+            di: None,
+            adx: None,
+        }
+    }
+
+    pub fn output(&self) -> Option<AdxValue> {
+        let (plus_di, minus_di) = self.di?;
+        Some(AdxValue {
+            adx: self.adx?,
+            plus_di,
+            minus_di,
+        })
+    }
+
+    pub fn compute(&self, bars: &[MarketData]) -> Vec<Option<AdxValue>> {
+        let n = self.period as f64;
+        let moves: Vec<(f64, f64, f64)> = bars
+            .windows(2)
+            .map(|w| directional_move(&w[1], &w[0]))
+            .collect();
+
+        let mut out = vec![None; bars.len()];
+        let mut smoothed = (0.0, 0.0, 0.0);
+        let mut dx = Vec::new();
+        let mut adx: Option<f64> = None;
+        for (k, &(tr, plus, minus)) in moves.iter().enumerate() {
+            if k < self.period {
+                smoothed = (smoothed.0 + tr, smoothed.1 + plus, smoothed.2 + minus);
+            } else {
+                smoothed = (
+                    smoothed.0 - smoothed.0 / n + tr,
+                    smoothed.1 - smoothed.1 / n + plus,
+                    smoothed.2 - smoothed.2 / n + minus,
+                );
+            }
+            if k + 1 < self.period {
+                continue;
+            }
+            let (plus_di, minus_di, value) = directional_index(smoothed.0, smoothed.1, smoothed.2);
+            dx.push(value);
+            adx = match adx {
+                Some(adx) => Some((adx * (n - 1.0) + value) / n),
+                None if dx.len() == self.period => Some(dx.iter().sum::<f64>() / n),
+                None => None,
+            };
+            out[k + 1] = adx.map(|adx| AdxValue {
+                adx,
+                plus_di,
+                minus_di,
+            });
+        }
+        out
+    }
+}
+
+impl Indicator for AdxIndicator {
+    fn update(&mut self, price: f64) {
+        self.update_bar(&MarketData::from_close(Default::default(), price));
+    }
+
+    fn update_bar(&mut self, bar: &MarketData) {
+        let Some(prev) = self.prev.replace(bar.clone()) else {
+            return;
+        };
+        let (tr, plus, minus) = directional_move(bar, &prev);
+        let n = self.period as f64;
+        let s = self.smoothed;
+        self.smoothed = if self.seen < self.period {
+            (s.0 + tr, s.1 + plus, s.2 + minus)
+        } else {
+            (
+                s.0 - s.0 / n + tr,
+                s.1 - s.1 / n + plus,
+                s.2 - s.2 / n + minus,
+            )
+        };
+        self.seen += 1;
+        if self.seen < self.period {
+            return;
+        }
+
+        let (plus_di, minus_di, dx) =
+            directional_index(self.smoothed.0, self.smoothed.1, self.smoothed.2);
+        self.di = Some((plus_di, minus_di));
+        self.adx = match self.adx {
+            Some(adx) => Some((adx * (n - 1.0) + dx) / n),
+            None => {
+                self.dx_seed.push(dx);
+                if self.dx_seed.len() == self.period {
+                    Some(self.dx_seed.drain(..).sum::<f64>() / n)
+                } else {
+                    None
+                }
+            }
+        };
+    }
+
+    fn value(&self) -> Option<f64> {
+        self.adx
+    }
+
+    fn values(&self) -> Option<Vec<f64>> {
+        self.output().map(IndicatorValue::to_vec)
+    }
+
+    fn outputs(&self) -> &'static [&'static str] {
+        &["adx", "plusDi", "minusDi"]
+    }
+
+    fn warmup_period(&self) -> usize {
+        2 * self.period
+    }
+
+    fn reset(&mut self) {
+        *self = Self::new(self.period);
+    }
+
+    fn save_state(&self) -> Option<Value> {
+        serialize_state(self)
+    }
+
+    fn load_state(&mut self, state: &Value) -> bool {
+        deserialize_state(self, state)
+    }
+
+    fn name(&self) -> &'static str {
+        "adx"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::indicators::indicator::testing::{assert_stream_matches, sample_bars};
+
+    #[test]
+    fn test_adx_batch_matches_stream() {
+        let bars = sample_bars(80);
+        let mut adx = AdxIndicator::new(14);
+        let batch = adx.compute(&bars);
+        assert_stream_matches(&mut adx, &bars, &batch);
+    }
+
+    #[test]
+    fn test_adx_is_strong_in_a_one_sided_trend() {
+        let mut adx = AdxIndicator::new(5);
+        for i in 0..30 {
+            let close = 100.0 + i as f64;
+            adx.update_bar(&MarketData {
+                high: close + 0.5,
+                low: close - 0.5,
+                ..MarketData::from_close(Default::default(), close)
+            });
+        }
+        let out = adx.output().unwrap();
+        assert_eq!(out.minus_di, 0.0);
+        assert!(out.plus_di > 0.0);
+        assert!((out.adx - 100.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,145 @@
+use crate::strategy::market_data::MarketData;
+
+use super::indicator::Indicator;
+
+/// 真实波幅；第一根 bar 没有前收盘价，取高低差
+pub fn true_range(high: f64, low: f64, prev_close: Option<f64>) -> f64 {
+    let range = high - low;
+    match prev_close {
+        Some(prev) => range.max((high - prev).abs()).max((low - prev).abs()),
+        None => range,
+    }
+}
+
+// Wilder ATR：前 period 个真实波幅取均值，之后按 1/period 平滑
+pub struct AtrIndicator {
+    period: usize,
+    prev_close: Option<f64>,
+    seed: Vec<f64>,
+    atr: Option<f64>,
+}
+
+impl AtrIndicator {
+    pub fn new(period: usize) -> Self {
+        let period = period.max(1);
+        Self {
+            period,
+            prev_close: None,
+            seed: Vec::with_capacity(period),
+            atr: None,
+        }
+    }
+
+    pub fn period(&self) -> usize {
+        self.period
+    }
+
+    pub fn compute(&self, bars: &[MarketData]) -> Vec<Option<f64>> {
+        let n = self.period as f64;
+        let mut atr: Option<f64> = None;
+        bars.iter()
+            .enumerate()
+            .map(|(i, bar)| {
+                let prev_close = i.checked_sub(1).map(|j| bars[j].close_price);
+                let tr = true_range(bar.high, bar.low, prev_close);
+                atr = match atr {
+                    Some(atr) => Some((atr * (n - 1.0) + tr) / n),
+                    None if i + 1 == self.period => {
+                        let seed: Vec<f64> = (0..=i)
+                            .map(|j| {
+                                let prev = j.checked_sub(1).map(|k| bars[k].close_price);
+                                true_range(bars[j].high, bars[j].low, prev)
+                            })
+                            .collect();
+                        Some(seed.iter().sum::<f64>() / n)
+                    }
+                    None => None,
+                };
+                atr
+            })
+            .collect()
+    }
+
+    fn push(&mut self, high: f64, low: f64, close: f64) {
+        let true_range = true_range(high, low, self.prev_close.replace(close));
+
+        let n = self.period as f64;
+        self.atr = match self.atr {
+            Some(atr) => Some((atr * (n - 1.0) + true_range) / n),
+            None => {
+                self.seed.push(true_range);
+                if self.seed.len() == self.period {
+                    Some(self.seed.drain(..).sum::<f64>() / n)
+                } else {
+                    None
+                }
+            }
+        };
+    }
+}
+
+impl Indicator for AtrIndicator {
+    fn update(&mut self, price: f64) {
+        self.push(price, price, price);
+    }
+
+    fn update_bar(&mut self, bar: &MarketData) {
+        self.push(bar.high, bar.low, bar.close_price);
+    }
+
+    fn value(&self) -> Option<f64> {
+        self.atr
+    }
+
+    fn warmup_period(&self) -> usize {
+        self.period
+    }
+
+    fn reset(&mut self) {
+        self.prev_close = None;
+        self.seed.clear();
+        self.atr = None;
+    }
+
+    fn name(&self) -> &'static str {
+        "atr"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::indicators::indicator::testing::{assert_stream_matches, sample_bars};
+
+    fn bar(high: f64, low: f64, close: f64) -> MarketData {
+        MarketData {
+            high,
+            low,
+            ..MarketData::from_close(Default::default(), close)
+        }
+    }
+
+    #[test]
+    fn test_atr_uses_true_range_and_wilder_smoothing() {
+        let mut atr = AtrIndicator::new(3);
+        // TR: 2, max(3, |13-10|, |10-10|) = 3, max(2, |12-12|, |10-12|) = 2
+        atr.update_bar(&bar(11.0, 9.0, 10.0));
+        atr.update_bar(&bar(13.0, 10.0, 12.0));
+        assert_eq!(atr.value(), None);
+        atr.update_bar(&bar(12.0, 10.0, 11.0));
+        assert!((atr.value().unwrap() - 7.0 / 3.0).abs() < 1e-12);
+
+        // 跳空：TR = |15 - 11| = 4
+        atr.update_bar(&bar(15.0, 14.5, 14.8));
+        let expected = (7.0 / 3.0 * 2.0 + 4.0) / 3.0;
+        assert!((atr.value().unwrap() - expected).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_atr_batch_matches_stream() {
+        let bars = sample_bars(60);
+        let mut atr = AtrIndicator::new(14);
+        let batch = atr.compute(&bars);
+        assert_stream_matches(&mut atr, &bars, &batch);
+    }
+}
@@ -0,0 +1,104 @@
+use std::collections::VecDeque;
+
+use crate::strategy::market_data::MarketData;
+
+use super::indicator::Indicator;
+
+// 典型价偏离其 period 均值的程度，以平均绝对偏差的 1/0.015 为单位
+fn cci(window: impl Iterator<Item = f64> + Clone, typical: f64, period: usize) -> f64 {
+    let n = period as f64;
+    let mean = window.clone().sum::<f64>() / n;
+    let mean_deviation = window.map(|tp| (tp - mean).abs()).sum::<f64>() / n;
+    if mean_deviation == 0.0 {
+        0.0
+    } else {
+        (typical - mean) / (0.015 * mean_deviation)
+    }
+}
+
+pub struct CciIndicator {
+    period: usize,
+    window: VecDeque<f64>,
+}
+
+impl CciIndicator {
+    pub fn new(period: usize) -> Self {
+        let period = period.max(1);
+        Self {
+            period,
+            window: VecDeque::with_capacity(period),
+        }
+    }
+
+    pub fn compute(&self, bars: &[MarketData]) -> Vec<Option<f64>> {
+        let typical: Vec<f64> = bars.iter().map(MarketData::typical_price).collect();
+        (0..bars.len())
+            .map(|i| {
+                let window = &typical[(i + 1).checked_sub(self.period)?..=i];
+                Some(cci(window.iter().copied(), typical[i], self.period))
+            })
+            .collect()
+    }
+}
+
+impl Indicator for CciIndicator {
+    fn update(&mut self, price: f64) {
+        self.update_bar(&MarketData::from_close(Default::default(), price));
+    }
+
+    fn update_bar(&mut self, bar: &MarketData) {
+        self.window.push_back(bar.typical_price());
+        if self.window.len() > self.period {
+            self.window.pop_front();
+        }
+    }
+
+    fn value(&self) -> Option<f64> {
+        if self.window.len() < self.period {
+            return None;
+        }
+        let typical = *self.window.back()?;
+        Some(cci(self.window.iter().copied(), typical, self.period))
+    }
+
+    fn warmup_period(&self) -> usize {
+        self.period
+    }
+
+    fn reset(&mut self) {
+        self.window.clear();
+    }
+
+    fn name(&self) -> &'static str {
+        "cci"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::indicators::indicator::testing::{assert_stream_matches, sample_bars};
+
+    #[test]
+    fn test_cci_batch_matches_stream() {
+        let bars = sample_bars(60);
+        let mut cci = CciIndicator::new(20);
+        let batch = cci.compute(&bars);
+        assert_stream_matches(&mut cci, &bars, &batch);
+    }
+
+    #[test]
+    fn test_cci_reference_values() {
+        let mut cci = CciIndicator::new(3);
+        for price in [10.0, 11.0, 12.0] {
+            cci.update(price);
+        }
+        // 均值 11，平均绝对偏差 2/3
+        assert!((cci.value().unwrap() - 1.0 / (0.015 * 2.0 / 3.0)).abs() < 1e-9);
+        cci.reset();
+        for _ in 0..3 {
+            cci.update(5.0);
+        }
+        assert_eq!(cci.value(), Some(0.0));
+    }
+}
@@ -0,0 +1,200 @@
+use crate::strategy::market_data::MarketData;
+
+use super::{
+    indicator::Indicator,
+    spec::{BinaryOp, PriceSource},
+};
+
+/// 常数序列，用于 `{"type": "mul", "left": ..., "right": 2}` 这样的算术
+pub struct Constant(pub f64);
+
+impl Indicator for Constant {
+    fn update(&mut self, _price: f64) {}
+
+    fn value(&self) -> Option<f64> {
+        Some(self.0)
+    }
+
+    fn warmup_period(&self) -> usize {
+        1
+    }
+
+    fn reset(&mut self) {}
+
+    fn name(&self) -> &'static str {
+        "const"
+    }
+}
+
+/// K 线上的一个价格（或 hl2、hlc3 等组合价）
+pub struct PriceSeries {
+    source: PriceSource,
+    last: Option<f64>,
+}
+
+impl PriceSeries {
+    pub fn new(source: PriceSource) -> Self {
+        Self { source, last: None }
+    }
+}
+
+impl Indicator for PriceSeries {
+    fn update(&mut self, price: f64) {
+        self.last = Some(price);
+    }
+
+    fn update_bar(&mut self, bar: &MarketData) {
+        self.last = Some(self.source.of(bar));
+    }
+
+    fn value(&self) -> Option<f64> {
+        self.last
+    }
+
+    fn warmup_period(&self) -> usize {
+        1
+    }
+
+    fn reset(&mut self) {
+        self.last = None;
+    }
+
+    fn name(&self) -> &'static str {
+        self.source.name()
+    }
+}
+
+/// 指标的指标：source 就绪后把它的值喂给 inner，inner 只看这一条序列
+pub struct Chained {
+    source: Box<dyn Indicator>,
+    inner: Box<dyn Indicator>,
+}
+
+impl Chained {
+    pub fn new(source: Box<dyn Indicator>, inner: Box<dyn Indicator>) -> Self {
+        Self { source, inner }
+    }
+
+    fn feed(&mut self) {
+        if let Some(v) = self.source.value() {
+            self.inner.update(v);
+        }
+    }
+}
+
+impl Indicator for Chained {
+    fn update(&mut self, price: f64) {
+        self.source.update(price);
+        self.feed();
+    }
+
+    fn update_bar(&mut self, bar: &MarketData) {
+        self.source.update_bar(bar);
+        self.feed();
+    }
+
+    fn value(&self) -> Option<f64> {
+        self.inner.value()
+    }
+
+    fn values(&self) -> Option<Vec<f64>> {
+        self.inner.values()
+    }
+
+    fn outputs(&self) -> &'static [&'static str] {
+        self.inner.outputs()
+    }
+
+    fn warmup_period(&self) -> usize {
+        self.source.warmup_period() + self.inner.warmup_period() - 1
+    }
+
+    fn reset(&mut self) {
+        self.source.reset();
+        self.inner.reset();
+    }
+
+    fn name(&self) -> &'static str {
+        self.inner.name()
+    }
+}
+
+/// 多输出指标中的一路
+pub struct OutputSelect {
+    inner: Box<dyn Indicator>,
+    index: usize,
+}
+
+impl OutputSelect {
+    pub fn new(inner: Box<dyn Indicator>, index: usize) -> Self {
+        Self { inner, index }
+    }
+}
+
+impl Indicator for OutputSelect {
+    fn update(&mut self, price: f64) {
+        self.inner.update(price);
+    }
+
+    fn update_bar(&mut self, bar: &MarketData) {
+        self.inner.update_bar(bar);
+    }
+
+    fn value(&self) -> Option<f64> {
+        self.inner.values()?.get(self.index).copied()
+    }
+
+    fn warmup_period(&self) -> usize {
+        self.inner.warmup_period()
+    }
+
+    fn reset(&mut self) {
+        self.inner.reset();
+    }
+
+    fn name(&self) -> &'static str {
+        self.inner.name()
+    }
+}
+
+/// 两条序列逐 bar 做四则运算，任一边未就绪时没有值
+pub struct Arithmetic {
+    op: BinaryOp,
+    left: Box<dyn Indicator>,
+    right: Box<dyn Indicator>,
+}
+
+impl Arithmetic {
+    pub fn new(op: BinaryOp, left: Box<dyn Indicator>, right: Box<dyn Indicator>) -> Self {
+        Self { op, left, right }
+    }
+}
+
+impl Indicator for Arithmetic {
+    fn update(&mut self, price: f64) {
+        self.left.update(price);
+        self.right.update(price);
+    }
+
+    fn update_bar(&mut self, bar: &MarketData) {
+        self.left.update_bar(bar);
+        self.right.update_bar(bar);
+    }
+
+    fn value(&self) -> Option<f64> {
+        self.op.apply(self.left.value()?, self.right.value()?)
+    }
+
+    fn warmup_period(&self) -> usize {
+        self.left.warmup_period().max(self.right.warmup_period())
+    }
+
+    fn reset(&mut self) {
+        self.left.reset();
+        self.right.reset();
+    }
+
+    fn name(&self) -> &'static str {
+        self.op.name()
+    }
+}
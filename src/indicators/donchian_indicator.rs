@@ -0,0 +1,167 @@
+use std::collections::VecDeque;
+
+use crate::strategy::market_data::MarketData;
+
+use super::indicator::{Indicator, IndicatorValue};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Channel {
+    pub upper: f64,
+    pub lower: f64,
+}
+
+impl Channel {
+    pub fn middle(&self) -> f64 {
+        (self.upper + self.lower) / 2.0
+    }
+}
+
+impl IndicatorValue for Channel {
+    fn to_vec(self) -> Vec<f64> {
+        vec![self.middle(), self.upper, self.lower]
+    }
+}
+
+// Donchian 通道：最近 period 根 bar 的最高价与最低价
+pub struct DonchianIndicator {
+    period: usize,
+    window: VecDeque<(f64, f64)>,
+}
+
+impl DonchianIndicator {
+    pub fn new(period: usize) -> Self {
+        Self {
+            period: period.max(1),
+            window: VecDeque::with_capacity(period),
+        }
+    }
+
+    pub fn compute(&self, bars: &[MarketData]) -> Vec<Option<Channel>> {
+        (0..bars.len())
+            .map(|i| {
+                let window = &bars[(i + 1).checked_sub(self.period)?..=i];
+                Some(Channel {
+                    upper: window
+                        .iter()
+                        .map(|b| b.high)
+                        .fold(f64::NEG_INFINITY, f64::max),
+                    lower: window.iter().map(|b| b.low).fold(f64::INFINITY, f64::min),
+                })
+            })
+            .collect()
+    }
+
+    fn push(&mut self, high: f64, low: f64) {
+        self.window.push_back((high, low));
+        if self.window.len() > self.period {
+            self.window.pop_front();
+        }
+    }
+
+    pub fn channel(&self) -> Option<Channel> {
+        if self.window.len() < self.period {
+            return None;
+        }
+        let upper = self
+            .window
+            .iter()
+            .map(|b| b.0)
+            .fold(f64::NEG_INFINITY, f64::max);
+        let lower = self
+            .window
+            .iter()
+            .map(|b| b.1)
+            .fold(f64::INFINITY, f64::min);
+        Some(Channel { upper, lower })
+    }
+}
+
+impl Indicator for DonchianIndicator {
+    fn update(&mut self, price: f64) {
+        self.push(price, price);
+    }
+
+    fn update_bar(&mut self, bar: &MarketData) {
+        self.push(bar.high, bar.low);
+    }
+
+    fn value(&self) -> Option<f64> {
+        self.channel().map(|c| c.middle())
+    }
+
+    fn values(&self) -> Option<Vec<f64>> {
+        self.channel().map(IndicatorValue::to_vec)
+    }
+
+    fn outputs(&self) -> &'static [&'static str] {
+        &["middle", "upper", "lower"]
+    }
+
+    fn warmup_period(&self) -> usize {
+        self.period
+    }
+
+    fn reset(&mut self) {
+        self.window.clear();
+    }
+
+    fn name(&self) -> &'static str {
+        "donchian"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::indicators::indicator::testing::{assert_stream_matches, sample_bars};
+
+    #[test]
+    fn test_donchian_tracks_rolling_extremes() {
+        let mut donchian = DonchianIndicator::new(3);
+        let bars = [
+            (10.0, 8.0),
+            (12.0, 9.0),
+            (11.0, 7.0),
+            (9.5, 8.5),
+            (9.0, 8.8),
+        ];
+
+        donchian.push(bars[0].0, bars[0].1);
+        donchian.push(bars[1].0, bars[1].1);
+        assert_eq!(donchian.channel(), None);
+
+        donchian.push(bars[2].0, bars[2].1);
+        assert_eq!(
+            donchian.channel(),
+            Some(Channel {
+                upper: 12.0,
+                lower: 7.0
+            })
+        );
+        donchian.push(bars[3].0, bars[3].1);
+        assert_eq!(
+            donchian.channel(),
+            Some(Channel {
+                upper: 12.0,
+                lower: 7.0
+            })
+        );
+        donchian.push(bars[4].0, bars[4].1);
+        assert_eq!(
+            donchian.channel(),
+            Some(Channel {
+                upper: 11.0,
+                lower: 7.0
+            })
+        );
+        assert_eq!(donchian.value(), Some(9.0));
+    }
+
+    #[test]
+    fn test_donchian_batch_matches_stream() {
+        let bars = sample_bars(60);
+        let mut donchian = DonchianIndicator::new(20);
+        let batch = donchian.compute(&bars);
+        assert_stream_matches(&mut donchian, &bars, &batch);
+    }
+}
@@ -0,0 +1,206 @@
+use std::collections::VecDeque;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HedgeEstimate {
+    /// y = alpha + beta * x
+    pub beta: f64,
+    pub alpha: f64,
+    /// 当前 bar 的价差 y - alpha - beta * x
+    pub spread: f64,
+    /// 价差标准化后的 z-score
+    pub zscore: f64,
+}
+
+// 滚动窗口 OLS：每根 bar 用最近 period 组 (x, y) 重新回归，
+// z-score 为当前残差除以窗口内残差的标准差
+pub struct RollingOls {
+    window: VecDeque<(f64, f64)>,
+    period: usize,
+}
+
+impl RollingOls {
+    pub fn new(period: usize) -> Self {
+        Self {
+            window: VecDeque::with_capacity(period),
+            period: period.max(2),
+        }
+    }
+
+    pub fn update(&mut self, x: f64, y: f64) -> Option<HedgeEstimate> {
+        self.window.push_back((x, y));
+        if self.window.len() > self.period {
+            self.window.pop_front();
+        }
+        if self.window.len() < self.period {
+            return None;
+        }
+
+        let n = self.window.len() as f64;
+        let mean_x = self.window.iter().map(|p| p.0).sum::<f64>() / n;
+        let mean_y = self.window.iter().map(|p| p.1).sum::<f64>() / n;
+        let cov = self
+            .window
+            .iter()
+            .map(|(px, py)| (px - mean_x) * (py - mean_y))
+            .sum::<f64>();
+        let var = self
+            .window
+            .iter()
+            .map(|(px, _)| (px - mean_x).powi(2))
+            .sum::<f64>();
+        if var == 0.0 {
+            return None;
+        }
+
+        let beta = cov / var;
+        let alpha = mean_y - beta * mean_x;
+        let residual_var = self
+            .window
+            .iter()
+            .map(|(px, py)| (py - alpha - beta * px).powi(2))
+            .sum::<f64>()
+            / n;
+        let spread = y - alpha - beta * x;
+        let zscore = if residual_var > 0.0 {
+            spread / residual_var.sqrt()
+        } else {
+            0.0
+        };
+        Some(HedgeEstimate {
+            beta,
+            alpha,
+            spread,
+            zscore,
+        })
+    }
+}
+
+// 卡尔曼滤波估计时变的 [beta, alpha]，状态按随机游走演化：
+// delta 控制系数漂移速度，observation_var 为观测噪声。
+// z-score 取预测误差除以其预测标准差，前 warmup 根 bar 只更新不输出。
+pub struct KalmanHedge {
+    delta: f64,
+    observation_var: f64,
+    warmup: usize,
+    seen: usize,
+    state: [f64; 2],
+    cov: [[f64; 2]; 2],
+}
+
+impl KalmanHedge {
+    pub fn new(delta: f64, observation_var: f64, warmup: usize) -> Self {
+        Self {
+            delta,
+            observation_var,
+            warmup,
+            seen: 0,
+            state: [0.0, 0.0],
+            cov: [[0.0, 0.0], [0.0, 0.0]],
+        }
+    }
+
+    pub fn update(&mut self, x: f64, y: f64) -> Option<HedgeEstimate> {
+        let drift = self.delta / (1.0 - self.delta);
+        let mut r = self.cov;
+        r[0][0] += drift;
+        r[1][1] += drift;
+
+        // 观测向量 F = [x, 1]
+        let f = [x, 1.0];
+        let rf = [
+            r[0][0] * f[0] + r[0][1] * f[1],
+            r[1][0] * f[0] + r[1][1] * f[1],
+        ];
+        let q = f[0] * rf[0] + f[1] * rf[1] + self.observation_var;
+        let error = y - (self.state[0] * x + self.state[1]);
+        let gain = [rf[0] / q, rf[1] / q];
+
+        self.state[0] += gain[0] * error;
+        self.state[1] += gain[1] * error;
+        // P = R - K F R，R 对称所以 F R = rf'
+        for (i, row) in self.cov.iter_mut().enumerate() {
+            for (j, cell) in row.iter_mut().enumerate() {
+                *cell = r[i][j] - gain[i] * rf[j];
+            }
+        }
+
+        self.seen += 1;
+        if self.seen <= self.warmup {
+            return None;
+        }
+        Some(HedgeEstimate {
+            beta: self.state[0],
+            alpha: self.state[1],
+            spread: error,
+            zscore: error / q.sqrt(),
+        })
+    }
+}
+
+pub enum HedgeRatio {
+    Ols(RollingOls),
+    Kalman(KalmanHedge),
+}
+
+impl HedgeRatio {
+    /// `method` 为 "kalman" 时用卡尔曼滤波，否则用滚动 OLS
+    pub fn parse(method: &str, lookback: usize, delta: f64, observation_var: f64) -> Self {
+        match method {
+            "kalman" => HedgeRatio::Kalman(KalmanHedge::new(delta, observation_var, lookback)),
+            _ => HedgeRatio::Ols(RollingOls::new(lookback)),
+        }
+    }
+
+    pub fn update(&mut self, x: f64, y: f64) -> Option<HedgeEstimate> {
+        match self {
+            HedgeRatio::Ols(ols) => ols.update(x, y),
+            HedgeRatio::Kalman(kalman) => kalman.update(x, y),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // y = 3 + 1.5 x 加上确定性的小扰动
+    fn pair(n: usize) -> Vec<(f64, f64)> {
+        (0..n)
+            .map(|i| {
+                let x = 50.0 + (i as f64 * 0.3).sin() * 10.0 + i as f64 * 0.05;
+                let noise = (i as f64 * 1.7).sin() * 0.2;
+                (x, 3.0 + 1.5 * x + noise)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_rolling_ols_recovers_hedge_ratio() {
+        let mut ols = RollingOls::new(30);
+        let mut last = None;
+        for (i, (x, y)) in pair(80).into_iter().enumerate() {
+            let est = ols.update(x, y);
+            assert_eq!(est.is_some(), i >= 29);
+            last = est.or(last);
+        }
+        let est = last.unwrap();
+        assert!((est.beta - 1.5).abs() < 0.02, "beta {}", est.beta);
+        assert!((est.alpha - 3.0).abs() < 1.0, "alpha {}", est.alpha);
+        assert!(est.zscore.abs() < 3.0);
+    }
+
+    #[test]
+    fn test_kalman_converges_and_flags_spread_shock() {
+        let mut kalman = KalmanHedge::new(1e-4, 1e-2, 20);
+        let mut est = None;
+        for (x, y) in pair(300) {
+            est = kalman.update(x, y);
+        }
+        let est = est.unwrap();
+        assert!((est.beta - 1.5).abs() < 0.05, "beta {}", est.beta);
+
+        // 价差突然拉大，预测误差的 z-score 应该显著为正
+        let shock = kalman.update(60.0, 3.0 + 1.5 * 60.0 + 3.0).unwrap();
+        assert!(shock.zscore > 3.0, "zscore {}", shock.zscore);
+    }
+}
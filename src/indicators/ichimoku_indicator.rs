@@ -0,0 +1,192 @@
+use std::collections::VecDeque;
+
+use crate::strategy::market_data::MarketData;
+
+use super::indicator::{Indicator, IndicatorValue};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct IchimokuValue {
+    pub tenkan: f64,
+    pub kijun: f64,
+    /// 当前 bar 上的云层：displacement 根 bar 之前算出的先行带
+    pub senkou_a: f64,
+    pub senkou_b: f64,
+}
+
+impl IndicatorValue for IchimokuValue {
+    fn to_vec(self) -> Vec<f64> {
+        vec![self.tenkan, self.kijun, self.senkou_a, self.senkou_b]
+    }
+}
+
+// 最近 period 根 bar 的 (最高价 + 最低价) / 2
+fn midpoint<'a>(window: impl Iterator<Item = &'a (f64, f64)> + Clone) -> f64 {
+    let highest = window
+        .clone()
+        .map(|b| b.0)
+        .fold(f64::NEG_INFINITY, f64::max);
+    let lowest = window.map(|b| b.1).fold(f64::INFINITY, f64::min);
+    (highest + lowest) / 2.0
+}
+
+// 一目均衡表。迟行带只是把收盘价往回平移，流式计算拿不到，不作为输出。
+pub struct IchimokuIndicator {
+    tenkan_period: usize,
+    kijun_period: usize,
+    senkou_b_period: usize,
+    displacement: usize,
+    window: VecDeque<(f64, f64)>,
+    lines: Option<(f64, f64)>,
+    projected: VecDeque<(Option<f64>, Option<f64>)>,
+}
+
+impl IchimokuIndicator {
+    pub fn new(
+        tenkan_period: usize,
+        kijun_period: usize,
+        senkou_b_period: usize,
+        displacement: usize,
+    ) -> Self {
+        Self {
+            tenkan_period: tenkan_period.max(1),
+            kijun_period: kijun_period.max(1),
+            senkou_b_period: senkou_b_period.max(1),
+            displacement,
+            window: VecDeque::new(),
+            lines: None,
+            projected: VecDeque::with_capacity(displacement + 1),
+        }
+    }
+
+    fn lookback(&self) -> usize {
+        self.tenkan_period
+            .max(self.kijun_period)
+            .max(self.senkou_b_period)
+    }
+
+    // 当前 bar 的 (转换线, 基准线, 先行带 A, 先行带 B)，均未平移
+    fn lines_at(
+        &self,
+        window: &[(f64, f64)],
+    ) -> (Option<f64>, Option<f64>, Option<f64>, Option<f64>) {
+        let line = |period: usize| {
+            let start = window.len().checked_sub(period)?;
+            Some(midpoint(window[start..].iter()))
+        };
+        let tenkan = line(self.tenkan_period);
+        let kijun = line(self.kijun_period);
+        let senkou_a = tenkan.zip(kijun).map(|(t, k)| (t + k) / 2.0);
+        (tenkan, kijun, senkou_a, line(self.senkou_b_period))
+    }
+
+    pub fn output(&self) -> Option<IchimokuValue> {
+        let (tenkan, kijun) = self.lines?;
+        if self.projected.len() <= self.displacement {
+            return None;
+        }
+        let (senkou_a, senkou_b) = self.projected.front()?;
+        Some(IchimokuValue {
+            tenkan,
+            kijun,
+            senkou_a: (*senkou_a)?,
+            senkou_b: (*senkou_b)?,
+        })
+    }
+
+    pub fn compute(&self, bars: &[MarketData]) -> Vec<Option<IchimokuValue>> {
+        let hl: Vec<(f64, f64)> = bars.iter().map(|b| (b.high, b.low)).collect();
+        let lines: Vec<_> = (0..hl.len())
+            .map(|i| {
+                let start = (i + 1).saturating_sub(self.lookback());
+                self.lines_at(&hl[start..=i])
+            })
+            .collect();
+        lines
+            .iter()
+            .enumerate()
+            .map(|(i, &(tenkan, kijun, _, _))| {
+                let (_, _, senkou_a, senkou_b) = lines[i.checked_sub(self.displacement)?];
+                Some(IchimokuValue {
+                    tenkan: tenkan?,
+                    kijun: kijun?,
+                    senkou_a: senkou_a?,
+                    senkou_b: senkou_b?,
+                })
+            })
+            .collect()
+    }
+}
+
+impl Indicator for IchimokuIndicator {
+    fn update(&mut self, price: f64) {
+        self.update_bar(&MarketData::from_close(Default::default(), price));
+    }
+
+    fn update_bar(&mut self, bar: &MarketData) {
+        self.window.push_back((bar.high, bar.low));
+        if self.window.len() > self.lookback() {
+            self.window.pop_front();
+        }
+        self.window.make_contiguous();
+        let (tenkan, kijun, senkou_a, senkou_b) = self.lines_at(self.window.as_slices().0);
+        self.lines = tenkan.zip(kijun);
+        self.projected.push_back((senkou_a, senkou_b));
+        if self.projected.len() > self.displacement + 1 {
+            self.projected.pop_front();
+        }
+    }
+
+    fn value(&self) -> Option<f64> {
+        self.lines.map(|(tenkan, _)| tenkan)
+    }
+
+    fn values(&self) -> Option<Vec<f64>> {
+        self.output().map(IndicatorValue::to_vec)
+    }
+
+    fn outputs(&self) -> &'static [&'static str] {
+        &["tenkan", "kijun", "senkouA", "senkouB"]
+    }
+
+    fn warmup_period(&self) -> usize {
+        self.lookback() + self.displacement
+    }
+
+    fn reset(&mut self) {
+        self.window.clear();
+        self.lines = None;
+        self.projected.clear();
+    }
+
+    fn name(&self) -> &'static str {
+        "ichimoku"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::indicators::indicator::testing::{assert_stream_matches, sample_bars};
+
+    #[test]
+    fn test_ichimoku_batch_matches_stream() {
+        let bars = sample_bars(120);
+        let mut ichimoku = IchimokuIndicator::new(9, 26, 52, 26);
+        let batch = ichimoku.compute(&bars);
+        assert_stream_matches(&mut ichimoku, &bars, &batch);
+    }
+
+    #[test]
+    fn test_cloud_is_displaced() {
+        let mut ichimoku = IchimokuIndicator::new(1, 2, 2, 2);
+        for price in [10.0, 12.0, 20.0, 30.0] {
+            ichimoku.update(price);
+        }
+        let out = ichimoku.output().unwrap();
+        assert_eq!(out.tenkan, 30.0);
+        assert_eq!(out.kijun, 25.0);
+        // 两根 bar 之前（价格 12）：转换线 12，基准线与 B 带 (10 + 12) / 2
+        assert_eq!(out.senkou_a, 11.5);
+        assert_eq!(out.senkou_b, 11.0);
+    }
+}
@@ -1,5 +1,154 @@
+use serde::{Serialize, de::DeserializeOwned};
+use serde_json::Value;
+
+use crate::strategy::market_data::MarketData;
+
+/// 流式指标：每根 bar 调一次 `update_bar`，预热结束前 `value()` 为 None。
+/// 多输出指标（MACD 三线、通道上中下轨）的 `values()` 按 `outputs()` 的顺序给出全部输出，
+/// `value()` 是其中第一个输出。
 pub trait Indicator {
+    /// 只有收盘价时按 high = low = close、成交量为 0 的 bar 处理
     fn update(&mut self, price: f64);
+
+    /// 需要高低价或成交量的指标覆盖此方法，其余只看收盘价
+    fn update_bar(&mut self, bar: &MarketData) {
+        self.update(bar.close_price);
+    }
+
     fn value(&self) -> Option<f64>;
+
+    /// 所有输出都就绪后才有值
+    fn values(&self) -> Option<Vec<f64>> {
+        self.value().map(|v| vec![v])
+    }
+
+    fn outputs(&self) -> &'static [&'static str] {
+        &["value"]
+    }
+
+    /// 第一次产出完整 `values()` 需要的 bar 数
+    fn warmup_period(&self) -> usize;
+
+    fn is_ready(&self) -> bool {
+        self.values().is_some()
+    }
+
+    /// 回到刚创建时的状态
+    fn reset(&mut self);
+
+    /// 断点续跑用的内部状态（窗口、平滑值）；不支持的指标返回 None
+    fn save_state(&self) -> Option<Value> {
+        None
+    }
+
+    /// 恢复 save_state 导出的状态；格式不对或不支持时返回 false，指标不变
+    fn load_state(&mut self, _state: &Value) -> bool {
+        false
+    }
+
     fn name(&self) -> &'static str;
 }
+
+/// 整个结构可序列化的指标用这两个函数实现 save_state / load_state
+pub fn serialize_state<T: Serialize>(indicator: &T) -> Option<Value> {
+    serde_json::to_value(indicator).ok()
+}
+
+pub fn deserialize_state<T: DeserializeOwned>(indicator: &mut T, state: &Value) -> bool {
+    match T::deserialize(state) {
+        Ok(restored) => {
+            *indicator = restored;
+            true
+        }
+        Err(_) => false,
+    }
+}
+
+/// 指标在一根 bar 上的输出，按 `Indicator::outputs()` 的顺序展开
+pub trait IndicatorValue: Copy {
+    fn to_vec(self) -> Vec<f64>;
+}
+
+impl IndicatorValue for f64 {
+    fn to_vec(self) -> Vec<f64> {
+        vec![self]
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod testing {
+    use chrono::{Duration, TimeZone, Utc};
+
+    use super::*;
+
+    /// 带跳空、平盘和单边行情的小时线，跨越多个自然日
+    pub fn sample_bars(n: usize) -> Vec<MarketData> {
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let mut close = 100.0;
+        (0..n)
+            .map(|i| {
+                let t = i as f64;
+                let open = close;
+                close = open + (t * 0.7).sin() * 2.0 + (t * 0.13).cos() * 0.8;
+                if i % 17 == 5 {
+                    close += 6.0;
+                }
+                let (high, low) = if i % 11 == 3 {
+                    (open, open)
+                } else {
+                    (
+                        open.max(close) + (t * 1.3).sin().abs() * 1.5,
+                        open.min(close) - (t * 0.9).cos().abs() * 1.5,
+                    )
+                };
+                let close = if i % 11 == 3 { open } else { close };
+                MarketData {
+                    symbol: "TEST".to_string(),
+                    timestamp: start + Duration::hours(i as i64),
+                    open,
+                    high,
+                    low,
+                    close_price: close,
+                    volume: 1000.0 + (t * 0.5).sin().abs() * 500.0,
+                }
+            })
+            .collect()
+    }
+
+    /// 逐根喂入的 `values()` 必须与批量结果逐位相等；reset 之后再喂一遍结果不变
+    pub fn assert_stream_matches<T: IndicatorValue>(
+        indicator: &mut dyn Indicator,
+        bars: &[MarketData],
+        batch: &[Option<T>],
+    ) {
+        assert_eq!(bars.len(), batch.len());
+        for pass in 0..2 {
+            for (i, (bar, expected)) in bars.iter().zip(batch).enumerate() {
+                indicator.update_bar(bar);
+                assert_eq!(
+                    indicator.values(),
+                    expected.map(IndicatorValue::to_vec),
+                    "{} diverges from batch at bar {} (pass {})",
+                    indicator.name(),
+                    i,
+                    pass
+                );
+                assert_eq!(indicator.is_ready(), expected.is_some());
+            }
+            indicator.reset();
+            assert!(!indicator.is_ready());
+        }
+
+        let warmup = indicator.warmup_period();
+        assert_eq!(
+            batch.iter().position(Option::is_some),
+            Some(warmup - 1),
+            "{} warmup period",
+            indicator.name()
+        );
+        assert_eq!(
+            indicator.outputs().len(),
+            batch[warmup - 1].unwrap().to_vec().len()
+        );
+    }
+}
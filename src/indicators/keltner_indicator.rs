@@ -0,0 +1,115 @@
+use crate::strategy::market_data::MarketData;
+
+use super::{
+    atr_indicator::AtrIndicator,
+    indicator::{Indicator, IndicatorValue},
+    moving_average::ExponentialMovingAverage,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct KeltnerValue {
+    pub upper: f64,
+    pub middle: f64,
+    pub lower: f64,
+}
+
+impl IndicatorValue for KeltnerValue {
+    fn to_vec(self) -> Vec<f64> {
+        vec![self.middle, self.upper, self.lower]
+    }
+}
+
+fn bands(middle: f64, atr: f64, multiplier: f64) -> KeltnerValue {
+    KeltnerValue {
+        upper: middle + multiplier * atr,
+        middle,
+        lower: middle - multiplier * atr,
+    }
+}
+
+// Keltner 通道：收盘价 EMA 为中轨，上下各 multiplier 倍 ATR
+pub struct KeltnerIndicator {
+    middle: ExponentialMovingAverage,
+    atr: AtrIndicator,
+    multiplier: f64,
+}
+
+impl KeltnerIndicator {
+    pub fn new(ema_period: usize, atr_period: usize, multiplier: f64) -> Self {
+        Self {
+            middle: ExponentialMovingAverage::new(ema_period.max(1)),
+            atr: AtrIndicator::new(atr_period),
+            multiplier,
+        }
+    }
+
+    pub fn output(&self) -> Option<KeltnerValue> {
+        Some(bands(
+            self.middle.value()?,
+            self.atr.value()?,
+            self.multiplier,
+        ))
+    }
+
+    pub fn compute(&self, bars: &[MarketData]) -> Vec<Option<KeltnerValue>> {
+        self.middle
+            .compute(bars)
+            .into_iter()
+            .zip(self.atr.compute(bars))
+            .map(|(middle, atr)| Some(bands(middle?, atr?, self.multiplier)))
+            .collect()
+    }
+}
+
+impl Indicator for KeltnerIndicator {
+    fn update(&mut self, price: f64) {
+        self.update_bar(&MarketData::from_close(Default::default(), price));
+    }
+
+    fn update_bar(&mut self, bar: &MarketData) {
+        self.middle.update_bar(bar);
+        self.atr.update_bar(bar);
+    }
+
+    fn value(&self) -> Option<f64> {
+        self.middle.value()
+    }
+
+    fn values(&self) -> Option<Vec<f64>> {
+        self.output().map(IndicatorValue::to_vec)
+    }
+
+    fn outputs(&self) -> &'static [&'static str] {
+        &["middle", "upper", "lower"]
+    }
+
+    fn warmup_period(&self) -> usize {
+        self.middle.period().max(self.atr.period())
+    }
+
+    fn reset(&mut self) {
+        self.middle.reset();
+        self.atr.reset();
+    }
+
+    fn name(&self) -> &'static str {
+        "keltner"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::indicators::indicator::testing::{assert_stream_matches, sample_bars};
+
+    #[test]
+    fn test_keltner_batch_matches_stream() {
+        let bars = sample_bars(60);
+        let mut keltner = KeltnerIndicator::new(20, 10, 2.0);
+        let batch = keltner.compute(&bars);
+        assert_stream_matches(&mut keltner, &bars, &batch);
+
+        let last = batch.last().unwrap().unwrap();
+        assert!(last.lower < last.middle && last.middle < last.upper);
+    }
+}
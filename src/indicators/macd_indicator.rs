@@ -0,0 +1,171 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::strategy::market_data::MarketData;
+
+use super::{
+    indicator::{Indicator, IndicatorValue, deserialize_state, serialize_state},
+    moving_average::ExponentialMovingAverage,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct MacdValue {
+    pub macd: f64,
+    pub signal: f64,
+    pub histogram: f64,
+}
+
+impl IndicatorValue for MacdValue {
+    fn to_vec(self) -> Vec<f64> {
+        vec![self.macd, self.signal, self.histogram]
+    }
+}
+
+// MACD line = EMA(fast) - EMA(slow); the signal line is an EMA of the MACD
+// line and only starts once the slow EMA is ready.
+#[derive(Serialize, Deserialize)]
+pub struct MacdIndicator {
+    fast: ExponentialMovingAverage,
+    slow: ExponentialMovingAverage,
+    signal: ExponentialMovingAverage,
+    macd: Option<f64>,
+}
+
+impl MacdIndicator {
+    pub fn new(fast_period: usize, slow_period: usize, signal_period: usize) -> Self {
+        Self {
+            fast: ExponentialMovingAverage::new(fast_period),
+            slow: ExponentialMovingAverage::new(slow_period),
+            signal: ExponentialMovingAverage::new(signal_period),
+            macd: None,
+        }
+    }
+
+    pub fn output(&self) -> Option<MacdValue> {
+        let macd = self.macd?;
+        let signal = self.signal.value()?;
+        Some(MacdValue {
+            macd,
+            signal,
+            histogram: macd - signal,
+        })
+    }
+
+    pub fn compute(&self, bars: &[MarketData]) -> Vec<Option<MacdValue>> {
+        let fast = self.fast.compute(bars);
+        let slow = self.slow.compute(bars);
+        let line: Vec<Option<f64>> = fast
+            .iter()
+            .zip(&slow)
+            .map(|(f, s)| Some((*f)? - (*s)?))
+            .collect();
+        // 信号线只在 MACD 线就绪后开始累积
+        let ready: Vec<f64> = line.iter().flatten().copied().collect();
+        let mut signal = self.signal.compute_values(&ready).into_iter();
+        line.iter()
+            .map(|macd| {
+                let macd = (*macd)?;
+                let signal = signal.next().flatten()?;
+                Some(MacdValue {
+                    macd,
+                    signal,
+                    histogram: macd - signal,
+                })
+            })
+            .collect()
+    }
+}
+
+impl Indicator for MacdIndicator {
+    fn update(&mut self, price: f64) {
+        self.fast.update(price);
+        self.slow.update(price);
+        if let (Some(fast), Some(slow)) = (self.fast.value(), self.slow.value()) {
+            let macd = fast - slow;
+            self.macd = Some(macd);
+            self.signal.update(macd);
+        }
+    }
+
+    fn value(&self) -> Option<f64> {
+        self.macd
+    }
+
+    fn values(&self) -> Option<Vec<f64>> {
+        self.output().map(IndicatorValue::to_vec)
+    }
+
+    fn outputs(&self) -> &'static [&'static str] {
+        &["macd", "signal", "histogram"]
+    }
+
+    fn warmup_period(&self) -> usize {
+        self.fast.period().max(self.slow.period()) + self.signal.period() - 1
+    }
+
+    fn reset(&mut self) {
+        self.fast.reset();
+        self.slow.reset();
+        self.signal.reset();
+        self.macd = None;
+    }
+
+    fn save_state(&self) -> Option<Value> {
+        serialize_state(self)
+    }
+
+    fn load_state(&mut self, state: &Value) -> bool {
+        deserialize_state(self, state)
+    }
+
+    fn name(&self) -> &'static str {
+        "macd"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::indicators::indicator::testing::{assert_stream_matches, sample_bars};
+
+    #[test]
+    fn test_macd_matches_ema_difference() {
+        let prices: Vec<f64> = (0..40)
+            .map(|i| 100.0 + (i as f64 * 0.4).sin() * 5.0)
+            .collect();
+        let mut macd = MacdIndicator::new(3, 6, 4);
+        let mut fast = ExponentialMovingAverage::new(3);
+        let mut slow = ExponentialMovingAverage::new(6);
+        let mut signal = ExponentialMovingAverage::new(4);
+
+        for (i, &p) in prices.iter().enumerate() {
+            macd.update(p);
+            fast.update(p);
+            slow.update(p);
+            if i < 5 {
+                assert_eq!(macd.value(), None);
+                continue;
+            }
+            let line = fast.value().unwrap() - slow.value().unwrap();
+            signal.update(line);
+            assert!((macd.value().unwrap() - line).abs() < 1e-12);
+            match (macd.output(), signal.value()) {
+                (Some(out), Some(sig)) => {
+                    assert!((out.signal - sig).abs() < 1e-12);
+                    assert!((out.histogram - (line - sig)).abs() < 1e-12);
+                }
+                (None, None) => assert!(i < 8),
+                other => panic!("signal line out of step at {}: {:?}", i, other),
+            }
+        }
+        assert!(macd.output().is_some());
+    }
+
+    #[test]
+    fn test_macd_batch_matches_stream() {
+        let bars = sample_bars(80);
+        let mut macd = MacdIndicator::new(12, 26, 9);
+        let batch = macd.compute(&bars);
+        assert_stream_matches(&mut macd, &bars, &batch);
+    }
+}
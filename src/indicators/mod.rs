@@ -1,6 +1,23 @@
+pub mod adx_indicator;
+pub mod atr_indicator;
 pub mod calculator;
+pub mod cci_indicator;
+pub mod composite;
+pub mod donchian_indicator;
+pub mod hedge_ratio;
+pub mod ichimoku_indicator;
 pub mod indicator;
+pub mod keltner_indicator;
+pub mod macd_indicator;
 pub mod moving_average;
+pub mod obv_indicator;
+pub mod overlay;
+pub mod parabolic_sar_indicator;
+pub mod registry;
+pub mod rsi_indicator;
+pub mod spec;
 pub mod std_dev_indicator;
+pub mod stochastic_indicator;
+pub mod vwap_indicator;
 
 pub use calculator::*;
@@ -1,6 +1,12 @@
-use super::MovingAverage;
+use crate::{
+    indicators::indicator::{Indicator, deserialize_state, serialize_state},
+    strategy::market_data::MarketData,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use std::collections::VecDeque;
 /// Exponential Moving Average implementation
+#[derive(Serialize, Deserialize)]
 pub struct ExponentialMovingAverage {
     period: usize,
     alpha: f64,
@@ -36,9 +42,39 @@ impl ExponentialMovingAverage {
             initialization_values: VecDeque::new(),
         }
     }
+
+    pub fn period(&self) -> usize {
+        self.period
+    }
+
+    /// Batch version of `update` over the closes of `bars`
+    pub fn compute(&self, bars: &[MarketData]) -> Vec<Option<f64>> {
+        let closes: Vec<f64> = bars.iter().map(|b| b.close_price).collect();
+        self.compute_values(&closes)
+    }
+
+    /// Batch version of `update` over an arbitrary series, seeded with the SMA
+    /// of the first `period` values
+    pub fn compute_values(&self, values: &[f64]) -> Vec<Option<f64>> {
+        let mut ema: Option<f64> = None;
+        values
+            .iter()
+            .enumerate()
+            .map(|(i, &value)| {
+                ema = match ema {
+                    Some(prev) => Some(value * self.alpha + prev * (1.0 - self.alpha)),
+                    None if i + 1 == self.period => {
+                        Some(values[..self.period].iter().sum::<f64>() / self.period as f64)
+                    }
+                    None => None,
+                };
+                ema
+            })
+            .collect()
+    }
 }
 
-impl MovingAverage for ExponentialMovingAverage {
+impl Indicator for ExponentialMovingAverage {
     fn update(&mut self, value: f64) {
         match self.current_value {
             None => {
@@ -67,7 +103,7 @@ impl MovingAverage for ExponentialMovingAverage {
         self.current_value
     }
 
-    fn period(&self) -> usize {
+    fn warmup_period(&self) -> usize {
         self.period
     }
 
@@ -76,14 +112,23 @@ impl MovingAverage for ExponentialMovingAverage {
         self.initialization_values.clear();
     }
 
-    fn name(&self) -> &str {
-        "EMA"
+    fn save_state(&self) -> Option<Value> {
+        serialize_state(self)
+    }
+
+    fn load_state(&mut self, state: &Value) -> bool {
+        deserialize_state(self, state)
+    }
+
+    fn name(&self) -> &'static str {
+        "ema"
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::indicators::indicator::testing::{assert_stream_matches, sample_bars};
 
     #[test]
     fn test_exponential_moving_average() {
@@ -139,4 +184,12 @@ mod tests {
     fn test_zero_period() {
         ExponentialMovingAverage::new(0);
     }
+
+    #[test]
+    fn test_batch_matches_stream() {
+        let bars = sample_bars(60);
+        let mut ema = ExponentialMovingAverage::new(9);
+        let batch = ema.compute(&bars);
+        assert_stream_matches(&mut ema, &bars, &batch);
+    }
 }
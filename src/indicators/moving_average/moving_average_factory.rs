@@ -1,7 +1,9 @@
 use std::fmt;
 
+use crate::indicators::indicator::Indicator;
+
 use super::{
-    ExponentialMovingAverage, MovingAverage, SimpleMovingAverage, WeightedMovingAverage,
+    ExponentialMovingAverage, SimpleMovingAverage, WeightedMovingAverage,
     ma_type::MovingAverageType,
 };
 
@@ -19,7 +21,7 @@ impl fmt::Display for MovingAverageType {
 
 impl MovingAverageFactory {
     // Define a factory function to create different moving averages
-    pub fn create_moving_average(ma_type: MovingAverageType) -> Box<dyn MovingAverage> {
+    pub fn create_moving_average(ma_type: MovingAverageType) -> Box<dyn Indicator> {
         match ma_type {
             MovingAverageType::SMA(period) => Box::new(SimpleMovingAverage::new(period)),
             MovingAverageType::EMA(period) => Box::new(ExponentialMovingAverage::new(period)),
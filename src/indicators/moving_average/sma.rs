@@ -1,7 +1,13 @@
-use super::MovingAverage;
+use crate::{
+    indicators::indicator::{Indicator, deserialize_state, serialize_state},
+    strategy::market_data::MarketData,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use std::collections::VecDeque;
 
 /// Simple Moving Average implementation
+#[derive(Serialize, Deserialize)]
 pub struct SimpleMovingAverage {
     period: usize,
     values: VecDeque<f64>,
@@ -23,9 +29,36 @@ impl SimpleMovingAverage {
             current_value: None,
         }
     }
+
+    pub fn period(&self) -> usize {
+        self.period
+    }
+
+    /// Batch version of `update` over the closes of `bars`
+    pub fn compute(&self, bars: &[MarketData]) -> Vec<Option<f64>> {
+        let closes: Vec<f64> = bars.iter().map(|b| b.close_price).collect();
+        self.compute_values(&closes)
+    }
+
+    /// Batch version of `update` over an arbitrary series
+    pub fn compute_values(&self, values: &[f64]) -> Vec<Option<f64>> {
+        let mut sum = 0.0;
+        values
+            .iter()
+            .enumerate()
+            .map(|(i, &value)| {
+                // 与流式版本相同的累加顺序，保证结果逐位一致
+                sum += value;
+                if i >= self.period {
+                    sum -= values[i - self.period];
+                }
+                (i + 1 >= self.period).then(|| sum / self.period as f64)
+            })
+            .collect()
+    }
 }
 
-impl MovingAverage for SimpleMovingAverage {
+impl Indicator for SimpleMovingAverage {
     fn update(&mut self, value: f64) {
         // Add new value
         self.sum += value;
@@ -48,7 +81,7 @@ impl MovingAverage for SimpleMovingAverage {
         self.current_value
     }
 
-    fn period(&self) -> usize {
+    fn warmup_period(&self) -> usize {
         self.period
     }
 
@@ -58,14 +91,23 @@ impl MovingAverage for SimpleMovingAverage {
         self.current_value = None;
     }
 
-    fn name(&self) -> &str {
-        "SMA"
+    fn save_state(&self) -> Option<Value> {
+        serialize_state(self)
+    }
+
+    fn load_state(&mut self, state: &Value) -> bool {
+        deserialize_state(self, state)
+    }
+
+    fn name(&self) -> &'static str {
+        "sma"
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::indicators::indicator::testing::{assert_stream_matches, sample_bars};
 
     #[test]
     fn test_simple_moving_average() {
@@ -108,4 +150,12 @@ mod tests {
     fn test_zero_period() {
         SimpleMovingAverage::new(0);
     }
+
+    #[test]
+    fn test_batch_matches_stream() {
+        let bars = sample_bars(60);
+        let mut sma = SimpleMovingAverage::new(7);
+        let batch = sma.compute(&bars);
+        assert_stream_matches(&mut sma, &bars, &batch);
+    }
 }
@@ -1,7 +1,13 @@
-use super::MovingAverage;
+use crate::{
+    indicators::indicator::{Indicator, deserialize_state, serialize_state},
+    strategy::market_data::MarketData,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use std::collections::VecDeque;
 
 /// Weighted Moving Average implementation
+#[derive(Serialize, Deserialize)]
 pub struct WeightedMovingAverage {
     period: usize,
     values: VecDeque<f64>,
@@ -26,9 +32,36 @@ impl WeightedMovingAverage {
             current_value: None,
         }
     }
+
+    pub fn period(&self) -> usize {
+        self.period
+    }
+
+    /// Batch version of `update` over the closes of `bars`
+    pub fn compute(&self, bars: &[MarketData]) -> Vec<Option<f64>> {
+        let closes: Vec<f64> = bars.iter().map(|b| b.close_price).collect();
+        self.compute_values(&closes)
+    }
+
+    /// Batch version of `update` over an arbitrary series
+    pub fn compute_values(&self, values: &[f64]) -> Vec<Option<f64>> {
+        (0..values.len())
+            .map(|i| {
+                if i + 1 < self.period {
+                    return None;
+                }
+                let window = &values[i + 1 - self.period..=i];
+                let mut weighted_sum = 0.0;
+                for (w, &price) in window.iter().enumerate() {
+                    weighted_sum += price * (w + 1) as f64;
+                }
+                Some(weighted_sum / self.weight_sum as f64)
+            })
+            .collect()
+    }
 }
 
-impl MovingAverage for WeightedMovingAverage {
+impl Indicator for WeightedMovingAverage {
     fn update(&mut self, value: f64) {
         // Add new value
         self.values.push_back(value);
@@ -55,7 +88,7 @@ impl MovingAverage for WeightedMovingAverage {
         self.current_value
     }
 
-    fn period(&self) -> usize {
+    fn warmup_period(&self) -> usize {
         self.period
     }
 
@@ -64,14 +97,23 @@ impl MovingAverage for WeightedMovingAverage {
         self.current_value = None;
     }
 
-    fn name(&self) -> &str {
-        "WMA"
+    fn save_state(&self) -> Option<Value> {
+        serialize_state(self)
+    }
+
+    fn load_state(&mut self, state: &Value) -> bool {
+        deserialize_state(self, state)
+    }
+
+    fn name(&self) -> &'static str {
+        "wma"
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::indicators::indicator::testing::{assert_stream_matches, sample_bars};
 
     #[test]
     fn test_weighted_moving_average() {
@@ -113,4 +155,12 @@ mod tests {
     fn test_zero_period() {
         WeightedMovingAverage::new(0);
     }
+
+    #[test]
+    fn test_batch_matches_stream() {
+        let bars = sample_bars(60);
+        let mut wma = WeightedMovingAverage::new(5);
+        let batch = wma.compute(&bars);
+        assert_stream_matches(&mut wma, &bars, &batch);
+    }
 }
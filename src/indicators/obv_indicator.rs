@@ -0,0 +1,103 @@
+use crate::strategy::market_data::MarketData;
+
+use super::indicator::Indicator;
+
+// 能量潮：收涨加上当根成交量，收跌减去，平收不变；从第一根 bar 的 0 开始累计
+pub struct ObvIndicator {
+    prev_close: Option<f64>,
+    obv: Option<f64>,
+}
+
+fn signed_volume(close: f64, prev_close: f64, volume: f64) -> f64 {
+    if close > prev_close {
+        volume
+    } else if close < prev_close {
+        -volume
+    } else {
+        0.0
+    }
+}
+
+impl ObvIndicator {
+    pub fn new() -> Self {
+        Self {
+            prev_close: None,
+            obv: None,
+        }
+    }
+
+    pub fn compute(&self, bars: &[MarketData]) -> Vec<Option<f64>> {
+        let mut obv = 0.0;
+        bars.iter()
+            .enumerate()
+            .map(|(i, bar)| {
+                if i > 0 {
+                    obv += signed_volume(bar.close_price, bars[i - 1].close_price, bar.volume);
+                }
+                Some(obv)
+            })
+            .collect()
+    }
+}
+
+impl Default for ObvIndicator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Indicator for ObvIndicator {
+    fn update(&mut self, price: f64) {
+        self.update_bar(&MarketData::from_close(Default::default(), price));
+    }
+
+    fn update_bar(&mut self, bar: &MarketData) {
+        self.obv = Some(match (self.obv, self.prev_close) {
+            (Some(obv), Some(prev)) => obv + signed_volume(bar.close_price, prev, bar.volume),
+            _ => 0.0,
+        });
+        self.prev_close = Some(bar.close_price);
+    }
+
+    fn value(&self) -> Option<f64> {
+        self.obv
+    }
+
+    fn warmup_period(&self) -> usize {
+        1
+    }
+
+    fn reset(&mut self) {
+        *self = Self::new();
+    }
+
+    fn name(&self) -> &'static str {
+        "obv"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::indicators::indicator::testing::{assert_stream_matches, sample_bars};
+
+    #[test]
+    fn test_obv_batch_matches_stream() {
+        let bars = sample_bars(60);
+        let mut obv = ObvIndicator::new();
+        let batch = obv.compute(&bars);
+        assert_stream_matches(&mut obv, &bars, &batch);
+    }
+
+    #[test]
+    fn test_obv_accumulates_signed_volume() {
+        let mut obv = ObvIndicator::new();
+        for (close, volume) in [(10.0, 100.0), (11.0, 50.0), (11.0, 70.0), (9.0, 30.0)] {
+            obv.update_bar(&MarketData {
+                volume,
+                ..MarketData::from_close(Default::default(), close)
+            });
+        }
+        assert_eq!(obv.value(), Some(50.0 - 30.0));
+    }
+}
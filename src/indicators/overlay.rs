@@ -0,0 +1,84 @@
+use serde::{Deserialize, Serialize};
+
+use crate::strategy::market_data::MarketData;
+
+use super::indicator::Indicator;
+
+/// 图表上的一条线，values 与 K 线一一对应，未就绪为 null
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChartSeries {
+    pub name: String,
+    pub values: Vec<Option<f64>>,
+}
+
+/// 把 bars 逐根喂给指标，每个输出一条线；from 之前的 bar 只用于预热，不输出
+pub fn compute_series(
+    indicator: &mut dyn Indicator,
+    bars: &[MarketData],
+    from: usize,
+) -> Vec<ChartSeries> {
+    let mut series: Vec<ChartSeries> = indicator
+        .outputs()
+        .iter()
+        .map(|name| ChartSeries {
+            name: name.to_string(),
+            values: Vec::with_capacity(bars.len().saturating_sub(from)),
+        })
+        .collect();
+    for (i, bar) in bars.iter().enumerate() {
+        indicator.update_bar(bar);
+        if i < from {
+            continue;
+        }
+        let values = indicator.values();
+        for (k, line) in series.iter_mut().enumerate() {
+            line.values
+                .push(values.as_ref().and_then(|v| v.get(k).copied()));
+        }
+    }
+    series
+}
+
+/// 策略 chart_values 用：单输出指标记为 prefix，多输出记为 prefix.输出名（prefix 为空时只用输出名）
+pub fn named_values(prefix: &str, indicator: &dyn Indicator) -> Vec<(String, Option<f64>)> {
+    let outputs = indicator.outputs();
+    let values = indicator.values();
+    outputs
+        .iter()
+        .enumerate()
+        .map(|(k, output)| {
+            let name = match (prefix, outputs.len()) {
+                ("", _) => output.to_string(),
+                (_, 1) => prefix.to_string(),
+                _ => format!("{}.{}", prefix, output),
+            };
+            (name, values.as_ref().and_then(|v| v.get(k).copied()))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::indicators::{
+        indicator::testing::sample_bars, macd_indicator::MacdIndicator,
+        moving_average::SimpleMovingAverage,
+    };
+
+    #[test]
+    fn test_series_skip_warmup_window() {
+        let bars = sample_bars(40);
+        let full = compute_series(&mut SimpleMovingAverage::new(5), &bars, 0);
+        let tail = compute_series(&mut SimpleMovingAverage::new(5), &bars, 10);
+        assert_eq!(full[0].name, "value");
+        assert_eq!(full[0].values[..4], [None; 4]);
+        // 预热期在窗口之外，窗口内第一根就有值
+        assert_eq!(tail[0].values.len(), 30);
+        assert_eq!(tail[0].values, full[0].values[10..]);
+
+        let macd = compute_series(&mut MacdIndicator::new(12, 26, 9), &bars, 0);
+        let names: Vec<&str> = macd.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, ["macd", "signal", "histogram"]);
+        assert!(macd.iter().all(|s| s.values.len() == 40));
+    }
+}
@@ -0,0 +1,202 @@
+use crate::strategy::market_data::MarketData;
+
+use super::indicator::Indicator;
+
+// (high, low, close)
+type Hlc = (f64, f64, f64);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct SarState {
+    long: bool,
+    sar: f64,
+    extreme: f64,
+    af: f64,
+}
+
+// Wilder 抛物线转向：SAR 以加速因子 af 追向极值点，每创新极值 af 加 step（上限 max_af），
+// 价格穿过 SAR 时反转，新 SAR 取上一段趋势的极值点。
+pub struct ParabolicSarIndicator {
+    step: f64,
+    max_af: f64,
+    // 最近两根 bar，[1] 为上一根
+    prev: [Option<Hlc>; 2],
+    state: Option<SarState>,
+}
+
+impl ParabolicSarIndicator {
+    pub fn new(step: f64, max_af: f64) -> Self {
+        Self {
+            step,
+            max_af: max_af.max(step),
+            prev: [None, None],
+            state: None,
+        }
+    }
+
+    /// 当前是否处于上升趋势（SAR 在价格下方）
+    pub fn is_long(&self) -> Option<bool> {
+        self.state.map(|s| s.long)
+    }
+
+    // 第二根 bar 上按收盘方向定初始趋势
+    fn start(&self, first: Hlc, second: Hlc) -> SarState {
+        let long = second.2 >= first.2;
+        let (sar, extreme) = if long {
+            (first.1.min(second.1), first.0.max(second.0))
+        } else {
+            (first.0.max(second.0), first.1.min(second.1))
+        };
+        SarState {
+            long,
+            sar,
+            extreme,
+            af: self.step,
+        }
+    }
+
+    fn advance(&self, s: SarState, (high, low, _): Hlc, prev: [Hlc; 2]) -> SarState {
+        let next = s.sar + s.af * (s.extreme - s.sar);
+        if s.long {
+            let next = next.min(prev[0].1).min(prev[1].1);
+            if low < next {
+                return SarState {
+                    long: false,
+                    sar: s.extreme,
+                    extreme: low,
+                    af: self.step,
+                };
+            }
+            let (extreme, af) = if high > s.extreme {
+                (high, (s.af + self.step).min(self.max_af))
+            } else {
+                (s.extreme, s.af)
+            };
+            SarState {
+                long: true,
+                sar: next,
+                extreme,
+                af,
+            }
+        } else {
+            let next = next.max(prev[0].0).max(prev[1].0);
+            if high > next {
+                return SarState {
+                    long: true,
+                    sar: s.extreme,
+                    extreme: high,
+                    af: self.step,
+                };
+            }
+            let (extreme, af) = if low < s.extreme {
+                (low, (s.af + self.step).min(self.max_af))
+            } else {
+                (s.extreme, s.af)
+            };
+            SarState {
+                long: false,
+                sar: next,
+                extreme,
+                af,
+            }
+        }
+    }
+
+    pub fn compute(&self, bars: &[MarketData]) -> Vec<Option<f64>> {
+        let hlc: Vec<Hlc> = bars
+            .iter()
+            .map(|b| (b.high, b.low, b.close_price))
+            .collect();
+        let mut out = vec![None; bars.len()];
+        let mut state: Option<SarState> = None;
+        for i in 1..hlc.len() {
+            let next = match state {
+                None => self.start(hlc[0], hlc[1]),
+                Some(s) => self.advance(s, hlc[i], [hlc[i - 2], hlc[i - 1]]),
+            };
+            out[i] = Some(next.sar);
+            state = Some(next);
+        }
+        out
+    }
+}
+
+impl Default for ParabolicSarIndicator {
+    fn default() -> Self {
+        Self::new(0.02, 0.2)
+    }
+}
+
+impl Indicator for ParabolicSarIndicator {
+    fn update(&mut self, price: f64) {
+        self.update_bar(&MarketData::from_close(Default::default(), price));
+    }
+
+    fn update_bar(&mut self, bar: &MarketData) {
+        let current = (bar.high, bar.low, bar.close_price);
+        self.state = match (self.state, self.prev) {
+            (Some(s), [Some(older), Some(last)]) => Some(self.advance(s, current, [older, last])),
+            (None, [_, Some(last)]) => Some(self.start(last, current)),
+            _ => None,
+        };
+        self.prev = [self.prev[1], Some(current)];
+    }
+
+    fn value(&self) -> Option<f64> {
+        self.state.map(|s| s.sar)
+    }
+
+    fn warmup_period(&self) -> usize {
+        2
+    }
+
+    fn reset(&mut self) {
+        self.prev = [None, None];
+        self.state = None;
+    }
+
+    fn name(&self) -> &'static str {
+        "psar"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::indicators::indicator::testing::{assert_stream_matches, sample_bars};
+
+    #[test]
+    fn test_psar_batch_matches_stream() {
+        let bars = sample_bars(80);
+        let mut psar = ParabolicSarIndicator::default();
+        let batch = psar.compute(&bars);
+        assert_stream_matches(&mut psar, &bars, &batch);
+    }
+
+    #[test]
+    fn test_psar_trails_trend_and_flips() {
+        let mut psar = ParabolicSarIndicator::new(0.02, 0.2);
+        let mut sars = Vec::new();
+        for i in 0..20 {
+            let close = 100.0 + i as f64;
+            psar.update_bar(&MarketData {
+                high: close + 1.0,
+                low: close - 1.0,
+                ..MarketData::from_close(Default::default(), close)
+            });
+            sars.push(psar.value());
+        }
+        assert_eq!(psar.is_long(), Some(true));
+        // 上升趋势中 SAR 单调抬升且始终在最低价下方
+        let sars: Vec<f64> = sars.into_iter().flatten().collect();
+        assert!(sars.windows(2).all(|w| w[1] >= w[0]));
+        assert!(sars.last().unwrap() < &(119.0 - 1.0));
+
+        psar.update_bar(&MarketData {
+            high: 105.0,
+            low: 95.0,
+            ..MarketData::from_close(Default::default(), 96.0)
+        });
+        assert_eq!(psar.is_long(), Some(false));
+        assert_eq!(psar.value(), Some(120.0));
+    }
+}
@@ -0,0 +1,467 @@
+use std::collections::{BTreeMap, HashMap};
+
+use serde::Serialize;
+
+use super::{
+    adx_indicator::AdxIndicator,
+    atr_indicator::AtrIndicator,
+    cci_indicator::CciIndicator,
+    composite::{Arithmetic, Chained, Constant, OutputSelect, PriceSeries},
+    donchian_indicator::DonchianIndicator,
+    ichimoku_indicator::IchimokuIndicator,
+    indicator::Indicator,
+    keltner_indicator::KeltnerIndicator,
+    macd_indicator::MacdIndicator,
+    moving_average::{ExponentialMovingAverage, SimpleMovingAverage, WeightedMovingAverage},
+    obv_indicator::ObvIndicator,
+    parabolic_sar_indicator::ParabolicSarIndicator,
+    rsi_indicator::RsiIndicator,
+    spec::{BinaryOp, IndicatorSpec, PriceSource, SpecError, join_path},
+    std_dev_indicator::StdDevIndicator,
+    stochastic_indicator::StochasticIndicator,
+    vwap_indicator::VwapIndicator,
+};
+
+type IndicatorBuilder = fn(&[f64]) -> Box<dyn Indicator>;
+
+/// 指标参数：都必须 > 0，integer 为 true 时还必须是整数（周期类参数）
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct ParamDef {
+    pub name: &'static str,
+    pub default: f64,
+    pub integer: bool,
+}
+
+impl ParamDef {
+    pub fn int(name: &'static str, default: usize) -> Self {
+        Self {
+            name,
+            default: default as f64,
+            integer: true,
+        }
+    }
+
+    pub fn float(name: &'static str, default: f64) -> Self {
+        Self {
+            name,
+            default,
+            integer: false,
+        }
+    }
+
+    fn check(&self, indicator: &str, value: f64) -> Result<f64, String> {
+        if value <= 0.0 || !value.is_finite() {
+            return Err(format!(
+                "`{}` parameter `{}` must be > 0",
+                indicator, self.name
+            ));
+        }
+        if self.integer && value.fract() != 0.0 {
+            return Err(format!(
+                "`{}` parameter `{}` must be an integer",
+                indicator, self.name
+            ));
+        }
+        Ok(value)
+    }
+}
+
+/// 一个可按名字创建的指标：参数按位置或名字给出，没给的取默认值
+pub struct IndicatorDef {
+    pub params: Vec<ParamDef>,
+    pub build: IndicatorBuilder,
+}
+
+/// 给前端的指标目录
+#[derive(Debug, Clone, Serialize)]
+pub struct IndicatorInfo {
+    pub name: String,
+    pub params: Vec<ParamDef>,
+    pub outputs: Vec<&'static str>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct IndicatorCatalog {
+    pub indicators: Vec<IndicatorInfo>,
+    pub sources: Vec<&'static str>,
+    pub operators: Vec<&'static str>,
+}
+
+pub struct IndicatorRegistry {
+    registry: HashMap<String, IndicatorDef>,
+}
+
+impl IndicatorRegistry {
+    pub fn new() -> Self {
+        let mut registry = IndicatorRegistry {
+            registry: HashMap::new(),
+        };
+
+        let period = |default| vec![ParamDef::int("period", default)];
+        registry.registry("sma", period(20), |p| {
+            Box::new(SimpleMovingAverage::new(p[0] as usize))
+        });
+        registry.registry("ema", period(20), |p| {
+            Box::new(ExponentialMovingAverage::new(p[0] as usize))
+        });
+        registry.registry("wma", period(20), |p| {
+            Box::new(WeightedMovingAverage::new(p[0] as usize))
+        });
+        registry.registry("rsi", period(14), |p| {
+            Box::new(RsiIndicator::new(p[0] as usize))
+        });
+        registry.registry(
+            "macd",
+            vec![
+                ParamDef::int("fast", 12),
+                ParamDef::int("slow", 26),
+                ParamDef::int("signal", 9),
+            ],
+            |p| {
+                Box::new(MacdIndicator::new(
+                    p[0] as usize,
+                    p[1] as usize,
+                    p[2] as usize,
+                ))
+            },
+        );
+        registry.registry("stddev", period(20), |p| {
+            Box::new(StdDevIndicator::new(p[0] as usize))
+        });
+        registry.registry("atr", period(14), |p| {
+            Box::new(AtrIndicator::new(p[0] as usize))
+        });
+        registry.registry("donchian", period(20), |p| {
+            Box::new(DonchianIndicator::new(p[0] as usize))
+        });
+        registry.registry("adx", period(14), |p| {
+            Box::new(AdxIndicator::new(p[0] as usize))
+        });
+        registry.registry(
+            "stochastic",
+            vec![
+                ParamDef::int("kPeriod", 14),
+                ParamDef::int("smooth", 3),
+                ParamDef::int("dPeriod", 3),
+            ],
+            |p| {
+                Box::new(StochasticIndicator::new(
+                    p[0] as usize,
+                    p[1] as usize,
+                    p[2] as usize,
+                ))
+            },
+        );
+        registry.registry("cci", period(20), |p| {
+            Box::new(CciIndicator::new(p[0] as usize))
+        });
+        registry.registry("obv", vec![], |_| Box::new(ObvIndicator::new()));
+        registry.registry("vwap", vec![], |_| Box::new(VwapIndicator::new()));
+        registry.registry(
+            "keltner",
+            vec![
+                ParamDef::int("emaPeriod", 20),
+                ParamDef::int("atrPeriod", 10),
+                ParamDef::float("multiplier", 2.0),
+            ],
+            |p| Box::new(KeltnerIndicator::new(p[0] as usize, p[1] as usize, p[2])),
+        );
+        registry.registry(
+            "ichimoku",
+            vec![
+                ParamDef::int("tenkan", 9),
+                ParamDef::int("kijun", 26),
+                ParamDef::int("senkouB", 52),
+                ParamDef::int("displacement", 26),
+            ],
+            |p| {
+                Box::new(IchimokuIndicator::new(
+                    p[0] as usize,
+                    p[1] as usize,
+                    p[2] as usize,
+                    p[3] as usize,
+                ))
+            },
+        );
+        registry.registry(
+            "psar",
+            vec![
+                ParamDef::float("step", 0.02),
+                ParamDef::float("maxStep", 0.2),
+            ],
+            |p| Box::new(ParabolicSarIndicator::new(p[0], p[1])),
+        );
+
+        registry
+    }
+
+    pub fn registry(&mut self, name: &str, params: Vec<ParamDef>, build: IndicatorBuilder) {
+        self.registry
+            .insert(name.to_string(), IndicatorDef { params, build });
+    }
+
+    pub fn get(&self, name: &str) -> Option<&IndicatorDef> {
+        self.registry.get(name)
+    }
+
+    pub fn names(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = self.registry.keys().map(String::as_str).collect();
+        names.sort();
+        names
+    }
+
+    pub fn catalog(&self) -> IndicatorCatalog {
+        let indicators = self
+            .names()
+            .into_iter()
+            .map(|name| IndicatorInfo {
+                name: name.to_string(),
+                params: self.registry[name].params.clone(),
+                outputs: self.build(name, &[]).unwrap().outputs().to_vec(),
+            })
+            .collect();
+        IndicatorCatalog {
+            indicators,
+            sources: PriceSource::ALL.iter().map(PriceSource::name).collect(),
+            operators: BinaryOp::ALL.iter().map(BinaryOp::name).collect(),
+        }
+    }
+
+    /// 按位置补齐默认参数；参数个数超出或取值不合法时返回错误信息
+    pub fn resolve(&self, name: &str, params: &[f64]) -> Result<Vec<f64>, String> {
+        let def = self
+            .get(name)
+            .ok_or_else(|| format!("unknown indicator `{}`", name))?;
+        if params.len() > def.params.len() {
+            return Err(format!(
+                "`{}` takes at most {} parameter(s) ({}), got {}",
+                name,
+                def.params.len(),
+                def.params
+                    .iter()
+                    .map(|p| p.name)
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                params.len()
+            ));
+        }
+        def.params
+            .iter()
+            .enumerate()
+            .map(|(i, p)| p.check(name, params.get(i).copied().unwrap_or(p.default)))
+            .collect()
+    }
+
+    /// 按名字补齐默认参数，出错时返回 (出错的参数名, 错误信息)
+    pub fn resolve_named(
+        &self,
+        name: &str,
+        params: &BTreeMap<String, f64>,
+    ) -> Result<Vec<f64>, (String, String)> {
+        let def = self
+            .get(name)
+            .ok_or_else(|| ("type".to_string(), format!("unknown indicator `{}`", name)))?;
+        if let Some(unknown) = params
+            .keys()
+            .find(|k| def.params.iter().all(|p| p.name != k.as_str()))
+        {
+            return Err((
+                unknown.clone(),
+                format!("`{}` has no parameter `{}`", name, unknown),
+            ));
+        }
+        def.params
+            .iter()
+            .map(|p| {
+                p.check(name, params.get(p.name).copied().unwrap_or(p.default))
+                    .map_err(|e| (p.name.to_string(), e))
+            })
+            .collect()
+    }
+
+    pub fn build(&self, name: &str, params: &[f64]) -> Result<Box<dyn Indicator>, String> {
+        let resolved = self.resolve(name, params)?;
+        Ok((self.registry[name].build)(&resolved))
+    }
+
+    /// 策略、规则 DSL 和 API 共用的构建入口
+    pub fn build_spec(&self, spec: &IndicatorSpec) -> Result<Box<dyn Indicator>, SpecError> {
+        self.build_at(spec, "")
+    }
+
+    fn build_at(&self, spec: &IndicatorSpec, path: &str) -> Result<Box<dyn Indicator>, SpecError> {
+        let indicator: Box<dyn Indicator> = match spec {
+            IndicatorSpec::Const(v) => Box::new(Constant(*v)),
+            IndicatorSpec::Price(source) => Box::new(PriceSeries::new(*source)),
+            IndicatorSpec::Binary { op, left, right } => Box::new(Arithmetic::new(
+                *op,
+                self.build_at(left, &join_path(path, "left"))?,
+                self.build_at(right, &join_path(path, "right"))?,
+            )),
+            IndicatorSpec::Indicator {
+                kind,
+                params,
+                source,
+                output,
+            } => {
+                let resolved = self
+                    .resolve_named(kind, params)
+                    .map_err(|(key, e)| SpecError::new(&join_path(path, &key), e))?;
+                let mut indicator = (self.registry[kind.as_str()].build)(&resolved);
+                if let Some(output) = output {
+                    let outputs = indicator.outputs();
+                    let index = outputs.iter().position(|o| o == output).ok_or_else(|| {
+                        SpecError::new(
+                            &join_path(path, "output"),
+                            format!(
+                                "`{}` has no output `{}`, expected one of {}",
+                                kind,
+                                output,
+                                outputs.join(", ")
+                            ),
+                        )
+                    })?;
+                    indicator = Box::new(OutputSelect::new(indicator, index));
+                }
+                match source {
+                    Some(source) => Box::new(Chained::new(
+                        self.build_at(source, &join_path(path, "source"))?,
+                        indicator,
+                    )),
+                    None => indicator,
+                }
+            }
+        };
+        Ok(indicator)
+    }
+}
+
+impl Default for IndicatorRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::indicators::indicator::testing::sample_bars;
+
+    #[test]
+    fn test_registry_fills_defaults_and_rejects_bad_params() {
+        let registry = IndicatorRegistry::new();
+        assert_eq!(registry.resolve("macd", &[5.0]), Ok(vec![5.0, 26.0, 9.0]));
+        assert!(registry.resolve("ema", &[12.0, 26.0]).is_err());
+        assert!(registry.resolve("rsi", &[0.0]).is_err());
+        assert!(registry.resolve("rsi", &[14.5]).is_err());
+        assert_eq!(
+            registry.resolve("keltner", &[20.0, 10.0, 1.5]),
+            Ok(vec![20.0, 10.0, 1.5])
+        );
+        assert!(registry.build("nope", &[]).is_err());
+
+        let mut ema = registry.build("ema", &[3.0]).unwrap();
+        for price in [10.0, 20.0, 30.0] {
+            ema.update(price);
+        }
+        assert_eq!(ema.name(), "ema");
+        assert_eq!(ema.value(), Some(20.0));
+
+        // 每个注册的指标名与实例的 name() 一致
+        for name in registry.names() {
+            assert_eq!(registry.build(name, &[]).unwrap().name(), name);
+        }
+    }
+
+    #[test]
+    fn test_build_spec_composes_sources_outputs_and_arithmetic() {
+        let registry = IndicatorRegistry::new();
+        let bars = sample_bars(80);
+        let spec = |v| IndicatorSpec::parse(&v).unwrap();
+
+        // EMA(hlc3) 与直接对典型价做 EMA 一致
+        let mut ema = registry
+            .build_spec(&spec(
+                json!({ "type": "ema", "period": 10, "source": "hlc3" }),
+            ))
+            .unwrap();
+        let mut direct = ExponentialMovingAverage::new(10);
+        // 平滑后的 RSI：RSI 就绪后才开始喂 SMA
+        let mut smoothed = registry
+            .build_spec(&spec(json!({
+                "type": "sma", "period": 3, "source": { "type": "rsi", "period": 5 }
+            })))
+            .unwrap();
+        let mut rsi = RsiIndicator::new(5);
+        let mut rsi_sma = SimpleMovingAverage::new(3);
+        // MACD 柱 = 线 - 信号线
+        let mut histogram = registry
+            .build_spec(&spec(json!({
+                "type": "sub",
+                "left": { "type": "macd", "fast": 3, "slow": 6, "signal": 4 },
+                "right": { "type": "macd", "fast": 3, "slow": 6, "signal": 4, "output": "signal" }
+            })))
+            .unwrap();
+        let mut macd = MacdIndicator::new(3, 6, 4);
+
+        for bar in &bars {
+            ema.update_bar(bar);
+            direct.update(bar.typical_price());
+            assert_eq!(ema.value(), direct.value());
+
+            smoothed.update_bar(bar);
+            rsi.update(bar.close_price);
+            if let Some(v) = rsi.value() {
+                rsi_sma.update(v);
+            }
+            assert_eq!(smoothed.value(), rsi_sma.value());
+
+            histogram.update_bar(bar);
+            macd.update(bar.close_price);
+            if let Some(out) = macd.output() {
+                assert!((histogram.value().unwrap() - out.histogram).abs() < 1e-12);
+            }
+        }
+        assert!(smoothed.is_ready() && histogram.is_ready());
+        assert_eq!(smoothed.warmup_period(), 6 + 3 - 1);
+    }
+
+    #[test]
+    fn test_build_spec_errors_point_at_the_key() {
+        let registry = IndicatorRegistry::new();
+        let err = |v| {
+            registry
+                .build_spec(&IndicatorSpec::parse(&v).unwrap())
+                .err()
+                .unwrap()
+        };
+        assert_eq!(
+            err(json!({ "type": "sma", "source": { "type": "kdj" } })).path,
+            "source.type"
+        );
+        assert_eq!(
+            err(json!({ "type": "add", "left": 1, "right": { "type": "ema", "length": 3 } })).path,
+            "right.length"
+        );
+        assert_eq!(
+            err(json!({ "type": "rsi", "period": 2.5 })).message,
+            "`rsi` parameter `period` must be an integer"
+        );
+        assert_eq!(
+            err(json!({ "type": "macd", "output": "hist" })).path,
+            "output"
+        );
+
+        let catalog = registry.catalog();
+        let keltner = catalog
+            .indicators
+            .iter()
+            .find(|i| i.name == "keltner")
+            .unwrap();
+        assert_eq!(keltner.outputs, vec!["middle", "upper", "lower"]);
+        assert!(!keltner.params[2].integer);
+        assert!(catalog.sources.contains(&"hlc3"));
+    }
+}
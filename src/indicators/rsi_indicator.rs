@@ -0,0 +1,174 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::strategy::market_data::MarketData;
+
+use super::indicator::{Indicator, deserialize_state, serialize_state};
+
+// Wilder's RSI: the first average gain/loss is a plain mean over `period`
+// changes, after that both are smoothed with alpha = 1 / period.
+#[derive(Serialize, Deserialize)]
+pub struct RsiIndicator {
+    period: usize,
+    prev_price: Option<f64>,
+    seed_gain: f64,
+    seed_loss: f64,
+    seen: usize,
+    avg_gain: Option<f64>,
+    avg_loss: Option<f64>,
+}
+
+impl RsiIndicator {
+    pub fn new(period: usize) -> Self {
+        Self {
+            period: period.max(1),
+            prev_price: None,
+            seed_gain: 0.0,
+            seed_loss: 0.0,
+            seen: 0,
+            avg_gain: None,
+            avg_loss: None,
+        }
+    }
+
+    pub fn period(&self) -> usize {
+        self.period
+    }
+
+    pub fn compute(&self, bars: &[MarketData]) -> Vec<Option<f64>> {
+        let n = self.period as f64;
+        let mut seed = (0.0, 0.0);
+        let mut avg: Option<(f64, f64)> = None;
+        let mut out = vec![None; bars.len()];
+        for i in 1..bars.len() {
+            let change = bars[i].close_price - bars[i - 1].close_price;
+            let (gain, loss) = (change.max(0.0), (-change).max(0.0));
+            avg = match avg {
+                Some((g, l)) => Some(((g * (n - 1.0) + gain) / n, (l * (n - 1.0) + loss) / n)),
+                None => {
+                    seed.0 += gain;
+                    seed.1 += loss;
+                    (i == self.period).then(|| (seed.0 / n, seed.1 / n))
+                }
+            };
+            out[i] = avg.map(|(g, l)| rsi(g, l));
+        }
+        out
+    }
+}
+
+fn rsi(gain: f64, loss: f64) -> f64 {
+    if loss == 0.0 {
+        return if gain == 0.0 { 50.0 } else { 100.0 };
+    }
+    100.0 - 100.0 / (1.0 + gain / loss)
+}
+
+impl Indicator for RsiIndicator {
+    fn update(&mut self, price: f64) {
+        let Some(prev) = self.prev_price.replace(price) else {
+            return;
+        };
+        let change = price - prev;
+        let gain = change.max(0.0);
+        let loss = (-change).max(0.0);
+        let n = self.period as f64;
+
+        match (self.avg_gain, self.avg_loss) {
+            (Some(g), Some(l)) => {
+                self.avg_gain = Some((g * (n - 1.0) + gain) / n);
+                self.avg_loss = Some((l * (n - 1.0) + loss) / n);
+            }
+            _ => {
+                self.seed_gain += gain;
+                self.seed_loss += loss;
+                self.seen += 1;
+                if self.seen == self.period {
+                    self.avg_gain = Some(self.seed_gain / n);
+                    self.avg_loss = Some(self.seed_loss / n);
+                }
+            }
+        }
+    }
+
+    fn value(&self) -> Option<f64> {
+        Some(rsi(self.avg_gain?, self.avg_loss?))
+    }
+
+    fn warmup_period(&self) -> usize {
+        self.period + 1
+    }
+
+    fn reset(&mut self) {
+        *self = Self::new(self.period);
+    }
+
+    fn save_state(&self) -> Option<Value> {
+        serialize_state(self)
+    }
+
+    fn load_state(&mut self, state: &Value) -> bool {
+        deserialize_state(self, state)
+    }
+
+    fn name(&self) -> &'static str {
+        "rsi"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::indicators::indicator::testing::{assert_stream_matches, sample_bars};
+
+    // Wilder's 14-period worked example.
+    const CLOSES: [f64; 33] = [
+        44.34, 44.09, 44.15, 43.61, 44.33, 44.83, 45.10, 45.42, 45.84, 46.08, 45.89, 46.03, 45.61,
+        46.28, 46.28, 46.00, 46.03, 46.41, 46.22, 45.64, 46.21, 46.25, 45.71, 46.45, 45.78, 45.35,
+        44.03, 44.18, 44.22, 44.57, 43.42, 42.66, 43.13,
+    ];
+
+    const EXPECTED: [f64; 19] = [
+        70.46, 66.25, 66.48, 69.35, 66.29, 57.92, 62.88, 63.21, 56.01, 62.34, 54.67, 50.39, 40.02,
+        41.49, 41.90, 45.50, 37.32, 33.09, 37.79,
+    ];
+
+    #[test]
+    fn test_rsi_matches_reference_series() {
+        let mut rsi = RsiIndicator::new(14);
+        let mut values = Vec::new();
+        for (i, &close) in CLOSES.iter().enumerate() {
+            rsi.update(close);
+            if i < 14 {
+                assert_eq!(rsi.value(), None, "rsi ready too early at {}", i);
+            } else {
+                values.push(rsi.value().unwrap());
+            }
+        }
+
+        assert_eq!(values.len(), EXPECTED.len());
+        for (got, want) in values.iter().zip(EXPECTED.iter()) {
+            assert!((got - want).abs() < 0.01, "got {:.4}, want {}", got, want);
+        }
+    }
+
+    #[test]
+    fn test_rsi_flat_and_one_sided_series() {
+        let mut flat = RsiIndicator::new(3);
+        let mut rising = RsiIndicator::new(3);
+        for i in 0..5 {
+            flat.update(10.0);
+            rising.update(10.0 + i as f64);
+        }
+        assert_eq!(flat.value(), Some(50.0));
+        assert_eq!(rising.value(), Some(100.0));
+    }
+
+    #[test]
+    fn test_rsi_batch_matches_stream() {
+        let bars = sample_bars(80);
+        let mut rsi = RsiIndicator::new(14);
+        let batch = rsi.compute(&bars);
+        assert_stream_matches(&mut rsi, &bars, &batch);
+    }
+}
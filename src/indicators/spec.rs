@@ -0,0 +1,348 @@
+use std::{collections::BTreeMap, fmt};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer, de};
+use serde_json::{Map, Value, json};
+
+use crate::strategy::market_data::MarketData;
+
+/// 指标的输入序列
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PriceSource {
+    Open,
+    High,
+    Low,
+    Close,
+    Volume,
+    Hl2,
+    Hlc3,
+    Ohlc4,
+}
+
+impl PriceSource {
+    pub const ALL: [PriceSource; 8] = [
+        PriceSource::Open,
+        PriceSource::High,
+        PriceSource::Low,
+        PriceSource::Close,
+        PriceSource::Volume,
+        PriceSource::Hl2,
+        PriceSource::Hlc3,
+        PriceSource::Ohlc4,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            PriceSource::Open => "open",
+            PriceSource::High => "high",
+            PriceSource::Low => "low",
+            PriceSource::Close => "close",
+            PriceSource::Volume => "volume",
+            PriceSource::Hl2 => "hl2",
+            PriceSource::Hlc3 => "hlc3",
+            PriceSource::Ohlc4 => "ohlc4",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|source| source.name() == s)
+    }
+
+    pub fn of(&self, bar: &MarketData) -> f64 {
+        match self {
+            PriceSource::Open => bar.open,
+            PriceSource::High => bar.high,
+            PriceSource::Low => bar.low,
+            PriceSource::Close => bar.close_price,
+            PriceSource::Volume => bar.volume,
+            PriceSource::Hl2 => (bar.high + bar.low) / 2.0,
+            PriceSource::Hlc3 => bar.typical_price(),
+            PriceSource::Ohlc4 => (bar.open + bar.high + bar.low + bar.close_price) / 4.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BinaryOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+}
+
+impl BinaryOp {
+    pub const ALL: [BinaryOp; 4] = [BinaryOp::Add, BinaryOp::Sub, BinaryOp::Mul, BinaryOp::Div];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            BinaryOp::Add => "add",
+            BinaryOp::Sub => "sub",
+            BinaryOp::Mul => "mul",
+            BinaryOp::Div => "div",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|op| op.name() == s)
+    }
+
+    /// 除数为 0 时没有值
+    pub fn apply(&self, left: f64, right: f64) -> Option<f64> {
+        match self {
+            BinaryOp::Add => Some(left + right),
+            BinaryOp::Sub => Some(left - right),
+            BinaryOp::Mul => Some(left * right),
+            BinaryOp::Div => (right != 0.0).then(|| left / right),
+        }
+    }
+}
+
+/// 序列化的指标描述，可以嵌套：
+/// - `20`：常数序列
+/// - `"hlc3"`：K 线价格
+/// - `{"type": "ema", "period": 20, "source": "hlc3"}`：指标，其余数字键为参数，
+///   `source` 可以是任意描述（指标的指标），`output` 选择多输出指标的某一路
+/// - `{"type": "sub", "left": ..., "right": ...}`：两条序列的 add/sub/mul/div
+#[derive(Debug, Clone, PartialEq)]
+pub enum IndicatorSpec {
+    Const(f64),
+    Price(PriceSource),
+    Indicator {
+        kind: String,
+        params: BTreeMap<String, f64>,
+        source: Option<Box<IndicatorSpec>>,
+        output: Option<String>,
+    },
+    Binary {
+        op: BinaryOp,
+        left: Box<IndicatorSpec>,
+        right: Box<IndicatorSpec>,
+    },
+}
+
+/// 描述解析/构建错误，path 指向出错的键，如 `left.source.period`；根节点为空串
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpecError {
+    pub path: String,
+    pub message: String,
+}
+
+impl SpecError {
+    pub fn new(path: &str, message: impl Into<String>) -> Self {
+        Self {
+            path: path.to_string(),
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for SpecError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.path.is_empty() {
+            write!(f, "{}", self.message)
+        } else {
+            write!(f, "{}: {}", self.path, self.message)
+        }
+    }
+}
+
+impl std::error::Error for SpecError {}
+
+pub fn join_path(path: &str, key: &str) -> String {
+    if path.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", path, key)
+    }
+}
+
+impl IndicatorSpec {
+    /// 不带参数和 source 的指标，参数全部取默认值
+    pub fn indicator(kind: &str) -> Self {
+        IndicatorSpec::Indicator {
+            kind: kind.to_string(),
+            params: BTreeMap::new(),
+            source: None,
+            output: None,
+        }
+    }
+
+    pub fn with_param(mut self, name: &str, value: f64) -> Self {
+        if let IndicatorSpec::Indicator { params, .. } = &mut self {
+            params.insert(name.to_string(), value);
+        }
+        self
+    }
+
+    pub fn parse(value: &Value) -> Result<Self, SpecError> {
+        Self::parse_at(value, "")
+    }
+
+    fn parse_at(value: &Value, path: &str) -> Result<Self, SpecError> {
+        if let Some(n) = value.as_f64() {
+            return Ok(IndicatorSpec::Const(n));
+        }
+        if let Some(s) = value.as_str() {
+            return PriceSource::parse(s)
+                .map(IndicatorSpec::Price)
+                .ok_or_else(|| {
+                    SpecError::new(
+                        path,
+                        format!(
+                            "unknown price source `{}`, expected one of {}",
+                            s,
+                            PriceSource::ALL.map(|p| p.name()).join(", ")
+                        ),
+                    )
+                });
+        }
+        let Some(obj) = value.as_object() else {
+            return Err(SpecError::new(
+                path,
+                "expected a number, a price source or {\"type\": ...}",
+            ));
+        };
+        let kind = obj
+            .get("type")
+            .ok_or_else(|| SpecError::new(path, "missing `type`"))?
+            .as_str()
+            .ok_or_else(|| SpecError::new(&join_path(path, "type"), "expected a name"))?;
+
+        if let Some(op) = BinaryOp::parse(kind) {
+            let side = |key: &str| -> Result<Box<IndicatorSpec>, SpecError> {
+                let side_path = join_path(path, key);
+                let v = obj
+                    .get(key)
+                    .ok_or_else(|| SpecError::new(path, format!("missing `{}`", key)))?;
+                Ok(Box::new(Self::parse_at(v, &side_path)?))
+            };
+            return Ok(IndicatorSpec::Binary {
+                op,
+                left: side("left")?,
+                right: side("right")?,
+            });
+        }
+
+        let mut params = BTreeMap::new();
+        let mut source = None;
+        let mut output = None;
+        for (key, v) in obj {
+            let key_path = join_path(path, key);
+            match key.as_str() {
+                "type" => {}
+                "source" => source = Some(Box::new(Self::parse_at(v, &key_path)?)),
+                "output" => {
+                    let name = v
+                        .as_str()
+                        .ok_or_else(|| SpecError::new(&key_path, "expected an output name"))?;
+                    output = Some(name.to_string());
+                }
+                _ => {
+                    let n = v
+                        .as_f64()
+                        .ok_or_else(|| SpecError::new(&key_path, "expected a number"))?;
+                    params.insert(key.clone(), n);
+                }
+            }
+        }
+        Ok(IndicatorSpec::Indicator {
+            kind: kind.to_string(),
+            params,
+            source,
+            output,
+        })
+    }
+
+    pub fn to_value(&self) -> Value {
+        match self {
+            IndicatorSpec::Const(n) => json!(n),
+            IndicatorSpec::Price(source) => json!(source.name()),
+            IndicatorSpec::Indicator {
+                kind,
+                params,
+                source,
+                output,
+            } => {
+                let mut obj = Map::new();
+                obj.insert("type".to_string(), json!(kind));
+                for (name, v) in params {
+                    obj.insert(name.clone(), json!(v));
+                }
+                if let Some(source) = source {
+                    obj.insert("source".to_string(), source.to_value());
+                }
+                if let Some(output) = output {
+                    obj.insert("output".to_string(), json!(output));
+                }
+                Value::Object(obj)
+            }
+            IndicatorSpec::Binary { op, left, right } => json!({
+                "type": op.name(),
+                "left": left.to_value(),
+                "right": right.to_value(),
+            }),
+        }
+    }
+}
+
+impl Serialize for IndicatorSpec {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.to_value().serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for IndicatorSpec {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = Value::deserialize(deserializer)?;
+        IndicatorSpec::parse(&value).map_err(de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spec_parses_nested_sources_and_round_trips() {
+        let raw = json!({
+            "type": "sub",
+            "left": { "type": "ema", "period": 20, "source": "hlc3" },
+            "right": { "type": "sma", "period": 5, "source": { "type": "rsi", "period": 14 } }
+        });
+        let spec: IndicatorSpec = serde_json::from_value(raw.clone()).unwrap();
+        let IndicatorSpec::Binary { op, left, right } = &spec else {
+            panic!("expected a binary spec, got {:?}", spec);
+        };
+        assert_eq!(*op, BinaryOp::Sub);
+        assert_eq!(
+            **left,
+            IndicatorSpec::Indicator {
+                kind: "ema".to_string(),
+                params: BTreeMap::from([("period".to_string(), 20.0)]),
+                source: Some(Box::new(IndicatorSpec::Price(PriceSource::Hlc3))),
+                output: None,
+            }
+        );
+        assert!(matches!(**right, IndicatorSpec::Indicator { ref source, .. } if source.is_some()));
+        assert_eq!(
+            serde_json::from_value::<IndicatorSpec>(serde_json::to_value(&spec).unwrap()).unwrap(),
+            spec
+        );
+    }
+
+    #[test]
+    fn test_spec_errors_point_at_the_key() {
+        let err = |v: Value| IndicatorSpec::parse(&v).unwrap_err();
+        assert_eq!(
+            err(json!({ "type": "ema", "source": "hlc4" })).path,
+            "source"
+        );
+        assert_eq!(
+            err(json!({ "type": "div", "left": 1, "right": { "type": "sma", "period": "x" } }))
+                .path,
+            "right.period"
+        );
+        assert_eq!(err(json!({ "period": 3 })).message, "missing `type`");
+    }
+}
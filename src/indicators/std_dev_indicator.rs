@@ -1,7 +1,14 @@
 use std::collections::VecDeque;
 
-use super::indicator::Indicator;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
 
+use crate::strategy::market_data::MarketData;
+
+use super::indicator::{Indicator, deserialize_state, serialize_state};
+
+// 总体标准差（除以 period）
+#[derive(Serialize, Deserialize)]
 pub struct StdDevIndicator {
     window: VecDeque<f64>,
     period: usize,
@@ -9,10 +16,25 @@ pub struct StdDevIndicator {
 impl StdDevIndicator {
     pub fn new(period: usize) -> Self {
         Self {
-            period,
+            period: period.max(1),
             window: VecDeque::with_capacity(period),
         }
     }
+
+    pub fn compute(&self, bars: &[MarketData]) -> Vec<Option<f64>> {
+        let closes: Vec<f64> = bars.iter().map(|b| b.close_price).collect();
+        (0..closes.len())
+            .map(|i| {
+                (i + 1 >= self.period).then(|| {
+                    let window = &closes[i + 1 - self.period..=i];
+                    let mean = window.iter().sum::<f64>() / self.period as f64;
+                    let var =
+                        window.iter().map(|p| (p - mean).powi(2)).sum::<f64>() / self.period as f64;
+                    var.sqrt()
+                })
+            })
+            .collect()
+    }
 }
 impl Indicator for StdDevIndicator {
     fn update(&mut self, price: f64) {
@@ -31,7 +53,41 @@ impl Indicator for StdDevIndicator {
             None
         }
     }
+    fn warmup_period(&self) -> usize {
+        self.period
+    }
+    fn reset(&mut self) {
+        self.window.clear();
+    }
+    fn save_state(&self) -> Option<Value> {
+        serialize_state(self)
+    }
+
+    fn load_state(&mut self, state: &Value) -> bool {
+        deserialize_state(self, state)
+    }
+
     fn name(&self) -> &'static str {
         "stddev"
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::indicators::indicator::testing::{assert_stream_matches, sample_bars};
+
+    #[test]
+    fn test_stddev_batch_matches_stream() {
+        let bars = sample_bars(50);
+        let mut stddev = StdDevIndicator::new(10);
+        let batch = stddev.compute(&bars);
+        assert_stream_matches(&mut stddev, &bars, &batch);
+
+        let mut flat = StdDevIndicator::new(3);
+        for _ in 0..3 {
+            flat.update(5.0);
+        }
+        assert_eq!(flat.value(), Some(0.0));
+    }
+}
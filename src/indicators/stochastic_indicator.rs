@@ -0,0 +1,173 @@
+use std::collections::VecDeque;
+
+use crate::strategy::market_data::MarketData;
+
+use super::{
+    indicator::{Indicator, IndicatorValue},
+    moving_average::SimpleMovingAverage,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StochasticValue {
+    pub k: f64,
+    pub d: f64,
+}
+
+impl IndicatorValue for StochasticValue {
+    fn to_vec(self) -> Vec<f64> {
+        vec![self.k, self.d]
+    }
+}
+
+// 最近 period 根 bar 的区间内收盘价所处的位置；区间为 0 时取 50
+fn raw_k(close: f64, highest: f64, lowest: f64) -> f64 {
+    if highest == lowest {
+        50.0
+    } else {
+        100.0 * (close - lowest) / (highest - lowest)
+    }
+}
+
+// 慢速随机指标：%K = SMA(原始 %K, smooth)，%D = SMA(%K, d_period)
+pub struct StochasticIndicator {
+    k_period: usize,
+    window: VecDeque<(f64, f64)>,
+    k: SimpleMovingAverage,
+    d: SimpleMovingAverage,
+}
+
+impl StochasticIndicator {
+    pub fn new(k_period: usize, smooth: usize, d_period: usize) -> Self {
+        let k_period = k_period.max(1);
+        Self {
+            k_period,
+            window: VecDeque::with_capacity(k_period),
+            k: SimpleMovingAverage::new(smooth.max(1)),
+            d: SimpleMovingAverage::new(d_period.max(1)),
+        }
+    }
+
+    pub fn output(&self) -> Option<StochasticValue> {
+        Some(StochasticValue {
+            k: self.k.value()?,
+            d: self.d.value()?,
+        })
+    }
+
+    pub fn compute(&self, bars: &[MarketData]) -> Vec<Option<StochasticValue>> {
+        let raw: Vec<f64> = (self.k_period.saturating_sub(1)..bars.len())
+            .map(|i| {
+                let window = &bars[i + 1 - self.k_period..=i];
+                let highest = window
+                    .iter()
+                    .map(|b| b.high)
+                    .fold(f64::NEG_INFINITY, f64::max);
+                let lowest = window.iter().map(|b| b.low).fold(f64::INFINITY, f64::min);
+                raw_k(bars[i].close_price, highest, lowest)
+            })
+            .collect();
+        let k = self.k.compute_values(&raw);
+        let ready: Vec<f64> = k.iter().flatten().copied().collect();
+        let mut d = self.d.compute_values(&ready).into_iter();
+
+        let mut out = vec![None; bars.len().min(self.k_period - 1)];
+        out.extend(k.iter().map(|k| {
+            let k = (*k)?;
+            Some(StochasticValue {
+                k,
+                d: d.next().flatten()?,
+            })
+        }));
+        out
+    }
+}
+
+impl Indicator for StochasticIndicator {
+    fn update(&mut self, price: f64) {
+        self.update_bar(&MarketData::from_close(Default::default(), price));
+    }
+
+    fn update_bar(&mut self, bar: &MarketData) {
+        self.window.push_back((bar.high, bar.low));
+        if self.window.len() > self.k_period {
+            self.window.pop_front();
+        }
+        if self.window.len() < self.k_period {
+            return;
+        }
+        let highest = self
+            .window
+            .iter()
+            .map(|b| b.0)
+            .fold(f64::NEG_INFINITY, f64::max);
+        let lowest = self
+            .window
+            .iter()
+            .map(|b| b.1)
+            .fold(f64::INFINITY, f64::min);
+        self.k.update(raw_k(bar.close_price, highest, lowest));
+        if let Some(k) = self.k.value() {
+            self.d.update(k);
+        }
+    }
+
+    fn value(&self) -> Option<f64> {
+        self.k.value()
+    }
+
+    fn values(&self) -> Option<Vec<f64>> {
+        self.output().map(IndicatorValue::to_vec)
+    }
+
+    fn outputs(&self) -> &'static [&'static str] {
+        &["k", "d"]
+    }
+
+    fn warmup_period(&self) -> usize {
+        self.k_period + self.k.period() + self.d.period() - 2
+    }
+
+    fn reset(&mut self) {
+        self.window.clear();
+        self.k.reset();
+        self.d.reset();
+    }
+
+    fn name(&self) -> &'static str {
+        "stochastic"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::indicators::indicator::testing::{assert_stream_matches, sample_bars};
+
+    #[test]
+    fn test_stochastic_batch_matches_stream() {
+        let bars = sample_bars(60);
+        let mut stochastic = StochasticIndicator::new(14, 3, 3);
+        let batch = stochastic.compute(&bars);
+        assert_stream_matches(&mut stochastic, &bars, &batch);
+    }
+
+    #[test]
+    fn test_fast_stochastic_range() {
+        let mut stochastic = StochasticIndicator::new(3, 1, 1);
+        for (high, low, close) in [(10.0, 8.0, 9.0), (12.0, 9.0, 11.0), (11.0, 7.0, 7.0)] {
+            stochastic.update_bar(&MarketData {
+                high,
+                low,
+                ..MarketData::from_close(Default::default(), close)
+            });
+        }
+        // 收在区间最低价
+        assert_eq!(stochastic.output().map(|v| v.k), Some(0.0));
+        stochastic.update_bar(&MarketData {
+            high: 12.0,
+            low: 9.0,
+            ..MarketData::from_close(Default::default(), 12.0)
+        });
+        assert_eq!(stochastic.value(), Some(100.0));
+    }
+}
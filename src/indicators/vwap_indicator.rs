@@ -0,0 +1,116 @@
+use chrono::NaiveDate;
+
+use crate::strategy::market_data::MarketData;
+
+use super::indicator::Indicator;
+
+// 成交量加权均价：按典型价加权，每个 UTC 自然日重新累计。
+// 累计成交量为 0（例如只有收盘价的数据）时没有值。
+pub struct VwapIndicator {
+    session: Option<NaiveDate>,
+    price_volume: f64,
+    volume: f64,
+}
+
+impl VwapIndicator {
+    pub fn new() -> Self {
+        Self {
+            session: None,
+            price_volume: 0.0,
+            volume: 0.0,
+        }
+    }
+
+    pub fn compute(&self, bars: &[MarketData]) -> Vec<Option<f64>> {
+        let mut out = Vec::with_capacity(bars.len());
+        let mut start = 0;
+        for (i, bar) in bars.iter().enumerate() {
+            if bars[start].timestamp.date_naive() != bar.timestamp.date_naive() {
+                start = i;
+            }
+            let session = &bars[start..=i];
+            let (price_volume, volume) = session.iter().fold((0.0, 0.0), |(pv, v), b| {
+                (pv + b.typical_price() * b.volume, v + b.volume)
+            });
+            out.push((volume > 0.0).then(|| price_volume / volume));
+        }
+        out
+    }
+}
+
+impl Default for VwapIndicator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Indicator for VwapIndicator {
+    fn update(&mut self, price: f64) {
+        self.update_bar(&MarketData::from_close(Default::default(), price));
+    }
+
+    fn update_bar(&mut self, bar: &MarketData) {
+        let day = bar.timestamp.date_naive();
+        if self.session != Some(day) {
+            self.session = Some(day);
+            self.price_volume = 0.0;
+            self.volume = 0.0;
+        }
+        self.price_volume += bar.typical_price() * bar.volume;
+        self.volume += bar.volume;
+    }
+
+    fn value(&self) -> Option<f64> {
+        (self.volume > 0.0).then(|| self.price_volume / self.volume)
+    }
+
+    fn warmup_period(&self) -> usize {
+        1
+    }
+
+    fn reset(&mut self) {
+        *self = Self::new();
+    }
+
+    fn name(&self) -> &'static str {
+        "vwap"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, TimeZone, Utc};
+
+    use super::*;
+    use crate::indicators::indicator::testing::{assert_stream_matches, sample_bars};
+
+    #[test]
+    fn test_vwap_batch_matches_stream() {
+        // 小时线跨越三个交易日
+        let bars = sample_bars(60);
+        let mut vwap = VwapIndicator::new();
+        let batch = vwap.compute(&bars);
+        assert_stream_matches(&mut vwap, &bars, &batch);
+    }
+
+    #[test]
+    fn test_vwap_restarts_each_day() {
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 22, 0, 0).unwrap();
+        let bar = |hours: i64, price: f64, volume: f64| MarketData {
+            volume,
+            ..MarketData::from_close(start + Duration::hours(hours), price)
+        };
+        let mut vwap = VwapIndicator::new();
+        vwap.update_bar(&bar(0, 10.0, 100.0));
+        vwap.update_bar(&bar(1, 13.0, 300.0));
+        assert!((vwap.value().unwrap() - 12.25).abs() < 1e-9);
+
+        vwap.update_bar(&bar(2, 20.0, 50.0));
+        assert!((vwap.value().unwrap() - 20.0).abs() < 1e-9);
+
+        // 没有成交量的收盘价不构成 VWAP
+        vwap.reset();
+        vwap.update(25.0);
+        assert_eq!(vwap.value(), None);
+    }
+}
@@ -7,6 +7,7 @@ pub mod indicators;
 pub mod matcher;
 pub mod models;
 pub mod processor;
+pub mod regime;
 pub mod risk;
 pub mod service;
 pub mod sizer;
@@ -1,9 +1,15 @@
 use quantedge_x::{
     api::create_router,
     matcher::{
-        book::orderbook::OrderBook, domain::qty_lots::QtyLots, engine::engine::Engine,
-        policy::price_level::fifo::FifoPriceLevel, runtime::actor::BookActor,
-        storage::localfile_storage::LocalFileStorage, strategies::simple_mm::SimpleMarketMaker,
+        book::orderbook::OrderBook,
+        domain::qty_lots::QtyLots,
+        engine::engine::Engine,
+        fix::{config::FixGatewayConfig, gateway::FixGateway},
+        policy::price_level::fifo::FifoPriceLevel,
+        runtime::actor::BookActor,
+        simulation::{scenario::ScenarioConfig, simulator::Simulator},
+        storage::localfile_storage::LocalFileStorage,
+        strategies::simple_mm::SimpleMarketMaker,
     },
     ws::push_stream::start_ws_server,
 };
@@ -22,14 +28,31 @@ async fn main() {
         LocalFileStorage,                                  // S
     >::actor(1024, 300, engine.engine);
 
-    SimpleMarketMaker::new(
-        client.clone(),
-        "BTC/USDT".to_string(),
-        20000,
-        -4,
-        QtyLots(1),
-    )
-    .start();
+    // SIM_SCENARIO points at an agent simulation config (see scenarios/)
+    match env::var("SIM_SCENARIO") {
+        Ok(path) => {
+            let scenario = ScenarioConfig::from_file(&path).expect("invalid simulation scenario");
+            Simulator::new(scenario).start(client.clone());
+        }
+        Err(_) => {
+            SimpleMarketMaker::new(
+                client.clone(),
+                "BTC/USDT".to_string(),
+                20000,
+                -4,
+                QtyLots(1),
+            )
+            .start();
+        }
+    }
+
+    // FIX_GATEWAY points at a FIX acceptor config (see fix/gateway.json)
+    if let Ok(path) = env::var("FIX_GATEWAY") {
+        let config = FixGatewayConfig::from_file(&path).expect("invalid FIX gateway config");
+        let gateway = FixGateway::new(config, client.clone()).expect("failed to open FIX store");
+        let (addr, _fix) = gateway.start().await.expect("failed to bind FIX gateway");
+        println!("FIX gateway listening on {}", addr);
+    }
 
     // Get server configuration from environment or use defaults
     let host = env::var("API_HOST").unwrap_or_else(|_| "0.0.0.0".to_string());
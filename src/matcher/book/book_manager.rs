@@ -1,13 +1,16 @@
 use anyhow::{Ok, Result};
 use bincode::{Decode, Encode, config::standard, encode_into_std_write};
 use std::{
-    collections::{BTreeMap, HashMap},
+    collections::{BTreeMap, HashMap, VecDeque},
     marker::PhantomData,
 };
 
 use crate::matcher::{
     book::orderbook::OrderBook,
-    domain::{order::OrderSide, price_ticks::PriceTicks},
+    domain::{
+        execution_sequence::ExecutionSequence, order::OrderSide, order_record::OrderRecord,
+        price_ticks::PriceTicks,
+    },
     policy::price_level::price_level::PriceLevelPolicy,
     storage::Storage,
 };
@@ -42,7 +45,8 @@ where
         FF: Fn() -> LL + Clone,
     {
         let data = book.snapshot();
-        let mut buf = Vec::new();
+        let mut buf = SNAPSHOT_MAGIC.to_vec();
+        buf.push(SNAPSHOT_VERSION);
         encode_into_std_write(&data, &mut buf, standard())?;
         self.storage.save_snapshot(&buf)
     }
@@ -52,25 +56,55 @@ where
             .storage
             .load_latest_snapshot()?
             .ok_or_else(|| anyhow::anyhow!("No snapshot found"))?;
+        self.decode(&bytes)
+    }
+
+    /// 只有没有任何快照时才新建空盘口；快照存在但解不出来时报错，不能丢掉已有挂单
+    pub fn load_or_create(&self) -> Result<OrderBook<L, F>> {
+        match self.storage.load_latest_snapshot()? {
+            Some(bytes) => self.decode(&bytes),
+            None => Ok(OrderBook::new(self.new_level.clone())),
+        }
+    }
 
-        let (data, _): (OrderBookData<L>, _) =
-            bincode::decode_from_slice(&bytes, bincode::config::standard())?;
+    fn decode(&self, bytes: &[u8]) -> Result<OrderBook<L, F>> {
+        let data = match bytes.strip_prefix(&SNAPSHOT_MAGIC) {
+            Some([SNAPSHOT_VERSION, rest @ ..]) => decode_all::<OrderBookData<L>>(rest)?,
+            Some([version, ..]) => {
+                anyhow::bail!("unsupported order book snapshot version {}", version)
+            }
+            Some([]) => anyhow::bail!("order book snapshot is truncated"),
+            // 没有版本头的旧快照只有盘口和索引，订单状态和成交序号从头开始
+            None => decode_all::<LegacyOrderBookData<L>>(bytes)?.into(),
+        };
 
         Ok(OrderBook::build(
             data.bids,
             data.asks,
             data.id_index,
+            data.orders,
+            data.closed_orders,
+            data.sequence,
             self.new_level.clone(),
             data.last_update_id,
         ))
     }
+}
 
-    pub fn load_or_create(&self) -> Result<OrderBook<L, F>> {
-        match self.load() {
-            Result::Ok(book) => Ok(book),
-            Result::Err(_) => Ok(OrderBook::new(self.new_level.clone())),
-        }
+/// 快照开头的标记和格式版本，之后才是 bincode 编码的 OrderBookData
+const SNAPSHOT_MAGIC: [u8; 4] = *b"OBSN";
+const SNAPSHOT_VERSION: u8 = 1;
+
+// 必须正好用完全部字节，避免把别的格式误解成功
+fn decode_all<T: Decode<()>>(bytes: &[u8]) -> Result<T> {
+    let (data, read) = bincode::decode_from_slice(bytes, standard())?;
+    if read != bytes.len() {
+        anyhow::bail!(
+            "order book snapshot has {} trailing bytes",
+            bytes.len() - read
+        );
     }
+    Ok(data)
 }
 
 #[derive(Encode, Decode)]
@@ -81,5 +115,127 @@ where
     pub bids: BTreeMap<PriceTicks, L>,
     pub asks: BTreeMap<PriceTicks, L>,
     pub id_index: HashMap<u64, (OrderSide, PriceTicks)>,
+    pub orders: HashMap<u64, OrderRecord>,
+    pub closed_orders: VecDeque<u64>,
+    pub sequence: ExecutionSequence,
     pub last_update_id: u64,
 }
+
+/// 加入订单状态之前的快照格式
+#[derive(Encode, Decode)]
+struct LegacyOrderBookData<L>
+where
+    L: PriceLevelPolicy + Encode + Decode<()>,
+{
+    bids: BTreeMap<PriceTicks, L>,
+    asks: BTreeMap<PriceTicks, L>,
+    id_index: HashMap<u64, (OrderSide, PriceTicks)>,
+    last_update_id: u64,
+}
+
+impl<L> From<LegacyOrderBookData<L>> for OrderBookData<L>
+where
+    L: PriceLevelPolicy + Encode + Decode<()>,
+{
+    fn from(legacy: LegacyOrderBookData<L>) -> Self {
+        Self {
+            bids: legacy.bids,
+            asks: legacy.asks,
+            id_index: legacy.id_index,
+            orders: HashMap::new(),
+            closed_orders: VecDeque::new(),
+            sequence: ExecutionSequence::default(),
+            last_update_id: legacy.last_update_id,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+    use crate::matcher::{
+        book::book_ops::OrderBookOps,
+        domain::{
+            order::{Order, OrderType},
+            qty_lots::QtyLots,
+            time_in_force::TimeInForce,
+        },
+        executor::{limit_executor::LimitExecutor, order_executor::OrderTypeExecutor},
+        policy::{price_level::fifo::FifoPriceLevel, tif::tif_policy_factory::obtain_tif_policy},
+        storage::localfile_storage::LocalFileStorage,
+    };
+
+    type Book = OrderBook<FifoPriceLevel, fn() -> FifoPriceLevel>;
+
+    fn storage(name: &str) -> (PathBuf, LocalFileStorage) {
+        let root =
+            std::env::temp_dir().join(format!("book_manager_{}_{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        (root.clone(), LocalFileStorage::new(root, 3, "book"))
+    }
+
+    fn resting_book() -> Book {
+        let mut book: Book = OrderBook::new(FifoPriceLevel::new);
+        let order = Order {
+            id: 7,
+            order_type: OrderType::Limit,
+            tif: TimeInForce::GTC,
+            side: OrderSide::Sell,
+            px: PriceTicks(100),
+            qty: QtyLots(5),
+        };
+        let executor = LimitExecutor::new(obtain_tif_policy(order.tif));
+        let result = executor.execute(order, &mut book).unwrap();
+        book.record_execution(&result).unwrap();
+        book
+    }
+
+    #[test]
+    fn test_load_versioned_and_legacy_snapshots() {
+        let (root, storage) = storage("formats");
+        let manager = OrderBookManager::new(storage, FifoPriceLevel::new as fn() -> FifoPriceLevel);
+        assert_eq!(0, manager.load_or_create().unwrap().size());
+
+        let book = resting_book();
+        manager.save(&book).unwrap();
+        let loaded = manager.load_or_create().unwrap();
+        assert_eq!(1, loaded.size());
+        assert!(loaded.order_state(7).unwrap().is_some());
+
+        // 没有版本头的旧快照仍然能恢复盘口
+        let data = book.snapshot();
+        let legacy = LegacyOrderBookData {
+            bids: data.bids,
+            asks: data.asks,
+            id_index: data.id_index,
+            last_update_id: data.last_update_id,
+        };
+        let mut buf = Vec::new();
+        encode_into_std_write(&legacy, &mut buf, standard()).unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(2));
+        manager.storage.save_snapshot(&buf).unwrap();
+        let loaded = manager.load_or_create().unwrap();
+        assert_eq!(1, loaded.size());
+        assert_eq!(data.last_update_id, loaded.snapshot().last_update_id);
+
+        let _ = std::fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn test_unreadable_snapshot_is_an_error() {
+        let (root, storage) = storage("corrupt");
+        let manager = OrderBookManager::new(storage, FifoPriceLevel::new as fn() -> FifoPriceLevel);
+        manager.storage.save_snapshot(&[0xff; 16]).unwrap();
+        assert!(manager.load_or_create().is_err());
+
+        let mut unknown = SNAPSHOT_MAGIC.to_vec();
+        unknown.push(SNAPSHOT_VERSION + 1);
+        std::thread::sleep(std::time::Duration::from_millis(2));
+        manager.storage.save_snapshot(&unknown).unwrap();
+        assert!(manager.load_or_create().is_err());
+
+        let _ = std::fs::remove_dir_all(root);
+    }
+}
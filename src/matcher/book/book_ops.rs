@@ -7,7 +7,16 @@ use crate::{
     matcher::{
         book::orderbook::OrderBook,
         domain::{
-            order::Order, price_ticks::PriceTicks, qty_lots::QtyLots, sweep_result::SweepResult,
+            book_depth::BookDepth,
+            book_top::BookTop,
+            execution_result::ExecutionResult,
+            execution_sequence::ExecutionSequence,
+            fill_estimate::FillEstimate,
+            order::{Order, OrderSide},
+            order_state::OrderState,
+            price_ticks::PriceTicks,
+            qty_lots::QtyLots,
+            sweep_result::SweepResult,
         },
         policy::price_level::price_level::PriceLevelPolicy,
     },
@@ -40,4 +49,18 @@ pub trait OrderBookOps {
     fn level_update(&self, prices: HashMap<Side, Vec<PriceTicks>>) -> anyhow::Result<LevelChange>;
 
     fn get_orderbook(&self) -> anyhow::Result<&OrderBook<Self::Level, Self::Factory>>;
+
+    fn execution_sequence(&mut self) -> &mut ExecutionSequence;
+
+    fn record_execution(&mut self, result: &ExecutionResult) -> anyhow::Result<()>;
+
+    fn order_state(&self, id: u64) -> anyhow::Result<Option<OrderState>>;
+
+    fn open_orders(&self, side: Option<OrderSide>) -> anyhow::Result<Vec<OrderState>>;
+
+    fn depth(&self, levels: usize) -> anyhow::Result<BookDepth>;
+
+    fn top_of_book(&self) -> anyhow::Result<BookTop>;
+
+    fn fill_estimate(&self, side: OrderSide, want: QtyLots) -> anyhow::Result<FillEstimate>;
 }
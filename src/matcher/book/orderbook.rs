@@ -1,5 +1,5 @@
 use std::{
-    collections::{BTreeMap, HashMap, HashSet},
+    collections::{BTreeMap, HashMap, HashSet, VecDeque},
     i64,
 };
 
@@ -11,16 +11,29 @@ use crate::{
     matcher::{
         book::{book_manager::OrderBookData, book_ops::OrderBookOps},
         domain::{
+            book_depth::{BookDepth, DepthLevel},
+            book_top::BookTop,
+            execution_event::ExecutionEvent,
+            execution_result::ExecutionResult,
+            execution_sequence::ExecutionSequence,
+            fill_estimate::FillEstimate,
             order::{Order, OrderSide},
+            order_record::OrderRecord,
+            order_state::OrderState,
+            order_status::OrderStatus,
             price_ticks::PriceTicks,
             qty_lots::QtyLots,
+            reject_reason::RejectReason,
             sweep_result::SweepResult,
         },
         policy::price_level::price_level::PriceLevelPolicy,
     },
     models::level_update::{LevelChange, LevelUpdate},
+    utils::time::now_millis,
 };
 
+const MAX_CLOSED_ORDERS: usize = 100_000;
+
 pub struct OrderBook<L, F>
 where
     L: PriceLevelPolicy + Encode + Decode<()>,
@@ -30,6 +43,9 @@ where
     asks: BTreeMap<PriceTicks, L>,
     new_level: F,
     id_index: HashMap<u64, (OrderSide, PriceTicks)>,
+    orders: HashMap<u64, OrderRecord>,
+    closed_orders: VecDeque<u64>,
+    sequence: ExecutionSequence,
     last_update_id: u64,
 }
 
@@ -44,6 +60,9 @@ where
             asks: BTreeMap::<PriceTicks, L>::new(),
             new_level: factory,
             id_index: HashMap::new(),
+            orders: HashMap::new(),
+            closed_orders: VecDeque::new(),
+            sequence: ExecutionSequence::default(),
             last_update_id: 0,
         }
     }
@@ -52,6 +71,9 @@ where
         bids: BTreeMap<PriceTicks, L>,
         asks: BTreeMap<PriceTicks, L>,
         id_index: HashMap<u64, (OrderSide, PriceTicks)>,
+        orders: HashMap<u64, OrderRecord>,
+        closed_orders: VecDeque<u64>,
+        sequence: ExecutionSequence,
         factory: F,
         last_update_id: u64,
     ) -> Self {
@@ -60,6 +82,9 @@ where
             asks,
             new_level: factory,
             id_index,
+            orders,
+            closed_orders,
+            sequence,
             last_update_id,
         }
     }
@@ -69,6 +94,9 @@ where
             bids: self.bids().clone(),
             asks: self.asks().clone(),
             id_index: self.id_index().clone(),
+            orders: self.orders.clone(),
+            closed_orders: self.closed_orders.clone(),
+            sequence: self.sequence,
             last_update_id: self.last_update_id,
         }
     }
@@ -93,6 +121,10 @@ where
         self.last_update_id
     }
 
+    pub fn sequence(&self) -> ExecutionSequence {
+        self.sequence
+    }
+
     pub fn increase_update_id(&mut self) {
         self.last_update_id += 1
     }
@@ -108,6 +140,60 @@ where
             Ok(None)
         }
     }
+
+    fn queue_position(&self, id: u64) -> anyhow::Result<Option<usize>> {
+        let Some((side, px)) = self.id_index.get(&id) else {
+            return Ok(None);
+        };
+        let level = match side {
+            OrderSide::Buy => self.bids.get(px),
+            OrderSide::Sell => self.asks.get(px),
+        };
+        match level {
+            Some(level) => level.position(id),
+            None => Ok(None),
+        }
+    }
+
+    fn collect_depth<'a, I>(levels: I, n: usize) -> anyhow::Result<Vec<DepthLevel>>
+    where
+        I: Iterator<Item = (&'a PriceTicks, &'a L)>,
+        L: 'a,
+    {
+        let mut out = Vec::with_capacity(n);
+        let mut cumulative = QtyLots::zero();
+        for (price, level) in levels.take(n) {
+            let qty = level.total()?;
+            cumulative += qty;
+            out.push(DepthLevel {
+                price: *price,
+                qty,
+                cumulative_qty: cumulative,
+                order_count: level.order_count()?,
+            });
+        }
+        Ok(out)
+    }
+
+    // 吃完的挂单已经离开盘口；它们的订单状态在 record_execution 里按撮合时间更新
+    fn unindex(&mut self, completed_ids: &[u64]) {
+        for id in completed_ids {
+            self.id_index.remove(id);
+        }
+    }
+
+    fn retire(&mut self, id: u64, status: OrderStatus, now: i64) {
+        self.id_index.remove(&id);
+        if let Some(record) = self.orders.get_mut(&id) {
+            record.close(status, now);
+            self.closed_orders.push_back(id);
+        }
+        while self.closed_orders.len() > MAX_CLOSED_ORDERS {
+            if let Some(old) = self.closed_orders.pop_front() {
+                self.orders.remove(&old);
+            }
+        }
+    }
 }
 
 impl<L, F> OrderBookOps for OrderBook<L, F>
@@ -157,6 +243,7 @@ where
 
         let filled = QtyLots(fills.iter().map(|f| f.qty.0).sum());
         debug_assert_eq!(filled, init_want - want);
+        self.unindex(&completed_order_ids);
 
         self.increase_update_id();
         Result::Ok(SweepResult::build(
@@ -208,6 +295,7 @@ where
         }
         let filled = QtyLots(fills.iter().map(|f| f.qty.0).sum());
         debug_assert_eq!(filled, init_want - want);
+        self.unindex(&completed_order_ids);
 
         self.increase_update_id();
         Result::Ok(SweepResult::build(
@@ -245,6 +333,7 @@ where
 
         let filled = QtyLots(fills.iter().map(|f| f.qty.0).sum());
         debug_assert_eq!(filled, init_want - want);
+        self.unindex(&completed_order_ids);
         Result::Ok(SweepResult::build(
             fills,
             filled,
@@ -266,6 +355,9 @@ where
             factory()
         });
         self.id_index.insert(id, (side, px));
+        self.orders
+            .entry(id)
+            .or_insert_with(|| OrderRecord::new(&o, now_millis()));
         lvl.add(o)
     }
 
@@ -282,6 +374,9 @@ where
             if removed && level.total()?.0 == 0 {
                 side_map.remove(&px);
             }
+            if removed {
+                self.retire(id, OrderStatus::Cancelled, now_millis());
+            }
             return Ok(removed);
         }
         Ok(false)
@@ -333,4 +428,382 @@ where
         let level_change = LevelChange::new(update_id, level_updates);
         Ok(level_change)
     }
+
+    fn execution_sequence(&mut self) -> &mut ExecutionSequence {
+        &mut self.sequence
+    }
+
+    fn record_execution(&mut self, result: &ExecutionResult) -> anyhow::Result<()> {
+        let order = &result.order;
+        let now = result.ts;
+        let mut filled = QtyLots::zero();
+        let mut closed_as = None;
+        for event in &result.events {
+            match event {
+                ExecutionEvent::Traded {
+                    taker_order_id,
+                    qty,
+                    ..
+                } if *taker_order_id == order.id => filled += *qty,
+                ExecutionEvent::Cancelled { order_id, .. } if *order_id == order.id => {
+                    closed_as = Some(OrderStatus::Cancelled)
+                }
+                ExecutionEvent::Rejected { order_id, reason } if *order_id == order.id => {
+                    closed_as = Some(match reason {
+                        RejectReason::Expired => OrderStatus::Expired,
+                        _ => OrderStatus::Cancelled,
+                    })
+                }
+                _ => {}
+            }
+        }
+
+        let record = self
+            .orders
+            .entry(order.id)
+            .or_insert_with(|| OrderRecord::new(order, now));
+        record.orig_qty = order.qty;
+        record.filled_qty = QtyLots::zero();
+        if !filled.is_zero() {
+            record.apply_fill(filled, now);
+        }
+
+        let status = closed_as.or_else(|| (!record.status.is_open()).then_some(record.status));
+        if let Some(status) = status {
+            self.retire(order.id, status, now);
+        }
+
+        // 被吃的挂单按这次撮合的时间记录成交
+        let maker_fills = result.events.iter().filter_map(|event| match event {
+            ExecutionEvent::Traded {
+                maker_order_id,
+                qty,
+                ..
+            } => Some((*maker_order_id, *qty)),
+            _ => None,
+        });
+        for (id, qty) in maker_fills {
+            let Some(maker) = self.orders.get_mut(&id) else {
+                continue;
+            };
+            maker.apply_fill(qty, now);
+            if !maker.status.is_open() {
+                self.retire(id, OrderStatus::Filled, now);
+            }
+        }
+        Ok(())
+    }
+
+    fn order_state(&self, id: u64) -> anyhow::Result<Option<OrderState>> {
+        let Some(record) = self.orders.get(&id) else {
+            return Ok(None);
+        };
+        let position = self.queue_position(id)?;
+        Ok(Some(OrderState::from_record(record, position)))
+    }
+
+    fn open_orders(&self, side: Option<OrderSide>) -> anyhow::Result<Vec<OrderState>> {
+        let mut states = Vec::new();
+        for (id, (order_side, _)) in &self.id_index {
+            if side.is_some_and(|s| s != *order_side) {
+                continue;
+            }
+            if let Some(record) = self.orders.get(id) {
+                let position = self.queue_position(*id)?;
+                states.push(OrderState::from_record(record, position));
+            }
+        }
+        states.sort_by_key(|s| (s.submitted_at, s.id));
+        Ok(states)
+    }
+
+    fn depth(&self, levels: usize) -> anyhow::Result<BookDepth> {
+        Ok(BookDepth {
+            bids: Self::collect_depth(self.bids.iter().rev(), levels)?,
+            asks: Self::collect_depth(self.asks.iter(), levels)?,
+            last_update_id: self.last_update_id,
+        })
+    }
+
+    fn top_of_book(&self) -> anyhow::Result<BookTop> {
+        Ok(BookTop {
+            best_bid: Self::collect_depth(self.bids.iter().rev(), 1)?.pop(),
+            best_ask: Self::collect_depth(self.asks.iter(), 1)?.pop(),
+            last_update_id: self.last_update_id,
+        })
+    }
+
+    fn fill_estimate(&self, side: OrderSide, want: QtyLots) -> anyhow::Result<FillEstimate> {
+        let levels: Box<dyn Iterator<Item = (&PriceTicks, &L)>> = match side {
+            OrderSide::Buy => Box::new(self.asks.iter()),
+            OrderSide::Sell => Box::new(self.bids.iter().rev()),
+        };
+
+        let mut fillable = QtyLots::zero();
+        let mut notional: i128 = 0;
+        let mut worst_price = None;
+        let mut consumed = 0;
+        for (price, level) in levels {
+            if fillable >= want {
+                break;
+            }
+            let take = QtyLots(level.total()?.0.min(want.0 - fillable.0));
+            fillable += take;
+            notional += price.0 as i128 * take.0 as i128;
+            worst_price = Some(*price);
+            consumed += 1;
+        }
+
+        let vwap = (!fillable.is_zero()).then(|| notional as f64 / fillable.0 as f64);
+        Ok(FillEstimate {
+            side,
+            requested: want,
+            fillable,
+            vwap,
+            worst_price,
+            levels: consumed,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::matcher::{
+        book::{book_ops::OrderBookOps, orderbook::OrderBook},
+        domain::{
+            execution_event::ExecutionEvent,
+            order::{Order, OrderSide, OrderType},
+            order_status::OrderStatus,
+            price_ticks::PriceTicks,
+            qty_lots::QtyLots,
+            scales::Scales,
+            time_in_force::TimeInForce,
+        },
+        executor::{limit_executor::LimitExecutor, order_executor::OrderTypeExecutor},
+        policy::{price_level::fifo::FifoPriceLevel, tif::tif_policy_factory::obtain_tif_policy},
+    };
+
+    fn limit(id: u64, side: OrderSide, px: i64, qty: i64, tif: TimeInForce) -> Order {
+        Order {
+            id,
+            order_type: OrderType::Limit,
+            tif,
+            side,
+            px: PriceTicks(px),
+            qty: QtyLots(qty),
+        }
+    }
+
+    fn submit(book: &mut OrderBook<FifoPriceLevel, fn() -> FifoPriceLevel>, order: Order) {
+        let executor = LimitExecutor::new(obtain_tif_policy(order.tif));
+        let result = executor.execute(order, book).unwrap();
+        book.record_execution(&result).unwrap();
+    }
+
+    #[test]
+    fn order_state_tracks_fills_and_queue_position() {
+        let mut book: OrderBook<FifoPriceLevel, fn() -> FifoPriceLevel> =
+            OrderBook::new(FifoPriceLevel::new);
+        submit(
+            &mut book,
+            limit(1, OrderSide::Sell, 100, 10, TimeInForce::GTC),
+        );
+        submit(
+            &mut book,
+            limit(2, OrderSide::Sell, 100, 5, TimeInForce::GTC),
+        );
+
+        let second = book.order_state(2).unwrap().unwrap();
+        assert_eq!(OrderStatus::New, second.status);
+        assert_eq!(Some(1), second.queue_position);
+
+        submit(
+            &mut book,
+            limit(3, OrderSide::Buy, 101, 12, TimeInForce::GTC),
+        );
+
+        let first = book.order_state(1).unwrap().unwrap();
+        assert_eq!(OrderStatus::Filled, first.status);
+        assert_eq!(QtyLots(10), first.filled_qty);
+        assert_eq!(QtyLots(0), first.remaining_qty);
+        assert_eq!(None, first.queue_position);
+
+        let second = book.order_state(2).unwrap().unwrap();
+        assert_eq!(OrderStatus::PartiallyFilled, second.status);
+        assert_eq!(QtyLots(2), second.filled_qty);
+        assert_eq!(QtyLots(3), second.remaining_qty);
+        assert_eq!(Some(0), second.queue_position);
+
+        let taker = book.order_state(3).unwrap().unwrap();
+        assert_eq!(OrderStatus::Filled, taker.status);
+        assert_eq!(QtyLots(12), taker.filled_qty);
+
+        let open = book.open_orders(None).unwrap();
+        assert_eq!(vec![2], open.iter().map(|s| s.id).collect::<Vec<_>>());
+        assert!(book.open_orders(Some(OrderSide::Buy)).unwrap().is_empty());
+    }
+
+    #[test]
+    fn order_state_reports_cancel_and_partial_rest() {
+        let mut book: OrderBook<FifoPriceLevel, fn() -> FifoPriceLevel> =
+            OrderBook::new(FifoPriceLevel::new);
+        submit(
+            &mut book,
+            limit(1, OrderSide::Sell, 100, 4, TimeInForce::GTC),
+        );
+        submit(
+            &mut book,
+            limit(2, OrderSide::Buy, 101, 10, TimeInForce::GTC),
+        );
+
+        let rested = book.order_state(2).unwrap().unwrap();
+        assert_eq!(OrderStatus::PartiallyFilled, rested.status);
+        assert_eq!(QtyLots(10), rested.orig_qty);
+        assert_eq!(QtyLots(4), rested.filled_qty);
+        assert_eq!(QtyLots(6), rested.remaining_qty);
+
+        assert!(book.cancel(2).unwrap());
+        let cancelled = book.order_state(2).unwrap().unwrap();
+        assert_eq!(OrderStatus::Cancelled, cancelled.status);
+        assert_eq!(QtyLots(0), cancelled.remaining_qty);
+        assert_eq!(0, book.size());
+
+        submit(
+            &mut book,
+            limit(3, OrderSide::Buy, 100, 1, TimeInForce::IOC),
+        );
+        let killed = book.order_state(3).unwrap().unwrap();
+        assert_eq!(OrderStatus::Cancelled, killed.status);
+        assert_eq!(QtyLots(0), killed.filled_qty);
+    }
+
+    #[test]
+    fn depth_top_and_fill_estimate() {
+        let mut book: OrderBook<FifoPriceLevel, fn() -> FifoPriceLevel> =
+            OrderBook::new(FifoPriceLevel::new);
+        book.add_order(limit(1, OrderSide::Buy, 99, 5, TimeInForce::GTC))
+            .unwrap();
+        book.add_order(limit(2, OrderSide::Buy, 99, 3, TimeInForce::GTC))
+            .unwrap();
+        book.add_order(limit(3, OrderSide::Buy, 98, 10, TimeInForce::GTC))
+            .unwrap();
+        book.add_order(limit(4, OrderSide::Sell, 101, 4, TimeInForce::GTC))
+            .unwrap();
+        book.add_order(limit(5, OrderSide::Sell, 103, 6, TimeInForce::GTC))
+            .unwrap();
+
+        let depth = book.depth(1).unwrap();
+        assert_eq!(1, depth.bids.len());
+        assert_eq!(PriceTicks(99), depth.bids[0].price);
+        assert_eq!(2, depth.bids[0].order_count);
+
+        let depth = book.depth(10).unwrap();
+        assert_eq!(QtyLots(18), depth.bids[1].cumulative_qty);
+        assert_eq!(QtyLots(10), depth.asks[1].cumulative_qty);
+
+        let scales = Scales::new(10, 100);
+        let top = book.top_of_book().unwrap();
+        assert_eq!(Some(PriceTicks(2)), top.spread());
+        let top = top.to_f64(&scales);
+        assert_eq!(Some(10.0), top.mid);
+        assert_eq!(Some(0.2), top.spread);
+
+        let buy = book.fill_estimate(OrderSide::Buy, QtyLots(6)).unwrap();
+        assert!(buy.is_complete());
+        assert_eq!(Some(PriceTicks(103)), buy.worst_price);
+        assert_eq!(2, buy.levels);
+        let expected = (101.0 * 4.0 + 103.0 * 2.0) / 6.0;
+        assert!((buy.vwap.unwrap() - expected).abs() < 1e-9);
+        assert!((buy.to_f64(&scales).vwap.unwrap() - expected / 10.0).abs() < 1e-9);
+
+        let sell = book.fill_estimate(OrderSide::Sell, QtyLots(30)).unwrap();
+        assert!(!sell.is_complete());
+        assert_eq!(QtyLots(18), sell.fillable);
+    }
+
+    #[test]
+    fn trade_ids_and_exec_seq_increase_per_book() {
+        let mut book: OrderBook<FifoPriceLevel, fn() -> FifoPriceLevel> =
+            OrderBook::new(FifoPriceLevel::new);
+        submit(
+            &mut book,
+            limit(1, OrderSide::Sell, 100, 5, TimeInForce::GTC),
+        );
+        submit(
+            &mut book,
+            limit(2, OrderSide::Sell, 100, 5, TimeInForce::GTC),
+        );
+
+        let executor = LimitExecutor::new(obtain_tif_policy(TimeInForce::GTC));
+        let result = executor
+            .execute(
+                limit(3, OrderSide::Buy, 101, 8, TimeInForce::GTC),
+                &mut book,
+            )
+            .unwrap();
+        assert_eq!(3, result.exec_seq);
+        let trade_ids: Vec<u64> = result
+            .events
+            .iter()
+            .filter_map(|e| match e {
+                ExecutionEvent::Traded { trade_id, .. } => Some(*trade_id),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(vec![1, 2], trade_ids);
+
+        let batch = result
+            .build_trade_event()
+            .to_trade_batch("BTC/USDT", 0.1, 0.01)
+            .unwrap();
+        assert!(batch.trades.iter().all(|t| t.exec_seq == Some(3)));
+        assert!(batch.trades.iter().all(|t| t.ts == result.ts));
+
+        let result = executor
+            .execute(
+                limit(4, OrderSide::Buy, 101, 2, TimeInForce::GTC),
+                &mut book,
+            )
+            .unwrap();
+        assert_eq!(4, result.exec_seq);
+        assert!(matches!(
+            result.events[0],
+            ExecutionEvent::Traded { trade_id: 3, .. }
+        ));
+        assert_eq!(3, book.sequence().last_trade_id);
+    }
+
+    #[test]
+    fn maker_fills_use_the_match_timestamp() {
+        let mut book: OrderBook<FifoPriceLevel, fn() -> FifoPriceLevel> =
+            OrderBook::new(FifoPriceLevel::new);
+        submit(
+            &mut book,
+            limit(1, OrderSide::Sell, 100, 4, TimeInForce::GTC),
+        );
+        submit(
+            &mut book,
+            limit(2, OrderSide::Sell, 100, 4, TimeInForce::GTC),
+        );
+
+        let executor = LimitExecutor::new(obtain_tif_policy(TimeInForce::GTC));
+        let mut result = executor
+            .execute(
+                limit(3, OrderSide::Buy, 101, 6, TimeInForce::GTC),
+                &mut book,
+            )
+            .unwrap();
+        // 撮合时间和记录时的墙钟时间不同，挂单的更新时间应跟成交一致
+        result.ts -= 60_000;
+        book.record_execution(&result).unwrap();
+
+        let filled = book.order_state(1).unwrap().unwrap();
+        assert_eq!(OrderStatus::Filled, filled.status);
+        assert_eq!(result.ts, filled.updated_at);
+        let partial = book.order_state(2).unwrap().unwrap();
+        assert_eq!(OrderStatus::PartiallyFilled, partial.status);
+        assert_eq!(QtyLots(2), partial.filled_qty);
+        assert_eq!(result.ts, partial.updated_at);
+        assert_eq!(1, book.size());
+    }
 }
@@ -0,0 +1,54 @@
+use serde::{Deserialize, Serialize};
+
+use crate::matcher::domain::{price_ticks::PriceTicks, qty_lots::QtyLots, scales::Scales};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DepthLevel {
+    pub price: PriceTicks,
+    pub qty: QtyLots,
+    pub cumulative_qty: QtyLots,
+    pub order_count: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DepthLevelF64 {
+    pub price: f64,
+    pub qty: f64,
+    pub cumulative_qty: f64,
+    pub order_count: usize,
+}
+
+impl DepthLevel {
+    pub fn to_f64(&self, scales: &Scales) -> DepthLevelF64 {
+        DepthLevelF64 {
+            price: scales.ticks_to_f64(self.price),
+            qty: scales.lots_to_f64(self.qty),
+            cumulative_qty: scales.lots_to_f64(self.cumulative_qty),
+            order_count: self.order_count,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BookDepth {
+    pub bids: Vec<DepthLevel>,
+    pub asks: Vec<DepthLevel>,
+    pub last_update_id: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BookDepthF64 {
+    pub bids: Vec<DepthLevelF64>,
+    pub asks: Vec<DepthLevelF64>,
+    pub last_update_id: u64,
+}
+
+impl BookDepth {
+    pub fn to_f64(&self, scales: &Scales) -> BookDepthF64 {
+        BookDepthF64 {
+            bids: self.bids.iter().map(|l| l.to_f64(scales)).collect(),
+            asks: self.asks.iter().map(|l| l.to_f64(scales)).collect(),
+            last_update_id: self.last_update_id,
+        }
+    }
+}
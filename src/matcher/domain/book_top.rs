@@ -0,0 +1,51 @@
+use serde::{Deserialize, Serialize};
+
+use crate::matcher::domain::{
+    book_depth::{DepthLevel, DepthLevelF64},
+    price_ticks::PriceTicks,
+    scales::Scales,
+};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BookTop {
+    pub best_bid: Option<DepthLevel>,
+    pub best_ask: Option<DepthLevel>,
+    pub last_update_id: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BookTopF64 {
+    pub best_bid: Option<DepthLevelF64>,
+    pub best_ask: Option<DepthLevelF64>,
+    pub spread: Option<f64>,
+    pub mid: Option<f64>,
+    pub last_update_id: u64,
+}
+
+impl BookTop {
+    pub fn spread(&self) -> Option<PriceTicks> {
+        match (&self.best_bid, &self.best_ask) {
+            (Some(bid), Some(ask)) => Some(PriceTicks(ask.price.0 - bid.price.0)),
+            _ => None,
+        }
+    }
+
+    pub fn mid(&self, scales: &Scales) -> Option<f64> {
+        match (&self.best_bid, &self.best_ask) {
+            (Some(bid), Some(ask)) => {
+                Some((scales.ticks_to_f64(bid.price) + scales.ticks_to_f64(ask.price)) / 2.0)
+            }
+            _ => None,
+        }
+    }
+
+    pub fn to_f64(&self, scales: &Scales) -> BookTopF64 {
+        BookTopF64 {
+            best_bid: self.best_bid.as_ref().map(|l| l.to_f64(scales)),
+            best_ask: self.best_ask.as_ref().map(|l| l.to_f64(scales)),
+            spread: self.spread().map(|s| scales.ticks_to_f64(s)),
+            mid: self.mid(scales),
+            last_update_id: self.last_update_id,
+        }
+    }
+}
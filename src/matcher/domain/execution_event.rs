@@ -1,7 +1,6 @@
 use crate::{
     matcher::domain::{price_ticks::PriceTicks, qty_lots::QtyLots, reject_reason::RejectReason},
     models::trade_tick::TradeTickInternal,
-    utils::time::now_millis,
 };
 
 #[derive(PartialEq, Eq, PartialOrd, Ord, Debug, Clone)]
@@ -14,6 +13,7 @@ pub enum ExecutionEvent {
     },
 
     Traded {
+        trade_id: u64,
         taker_order_id: u64,
         maker_order_id: u64,
         qty: QtyLots,
@@ -35,13 +35,26 @@ pub enum ExecutionEvent {
 }
 
 impl ExecutionEvent {
-    pub fn build_trade_tick(&self, symbol: &str) -> Option<TradeTickInternal> {
-        if let ExecutionEvent::Traded { qty, price, .. } = self {
+    pub fn build_trade_tick(
+        &self,
+        symbol: &str,
+        exec_seq: u64,
+        ts: i64,
+    ) -> Option<TradeTickInternal> {
+        if let ExecutionEvent::Traded {
+            trade_id,
+            qty,
+            price,
+            ..
+        } = self
+        {
             let tick = TradeTickInternal {
                 symbol: symbol.to_string(),
+                trade_id: *trade_id,
+                exec_seq,
                 price: *price,
                 qty: *qty,
-                ts: now_millis(),
+                ts,
             };
             return Some(tick);
         }
@@ -4,6 +4,7 @@ use crate::{
     domain::order::Side,
     matcher::domain::{
         execution_event::ExecutionEvent,
+        execution_sequence::ExecutionSequence,
         order::{Order, OrderSide, OrderType},
         price_ticks::PriceTicks,
         rest_on_book::RestOnBookType,
@@ -11,6 +12,7 @@ use crate::{
         trade_batch::TradeBatch,
     },
     models::trade_tick::TradeTick,
+    utils::time::now_millis,
 };
 
 #[derive(Debug, Clone)]
@@ -18,12 +20,16 @@ pub struct ExecutionResult {
     pub events: Vec<ExecutionEvent>,
     pub prices: HashMap<Side, Vec<PriceTicks>>,
     pub order: Order,
+    pub exec_seq: u64,
+    pub ts: i64,
 }
 
 #[derive(Debug, Clone)]
 pub struct TradeEventResult {
     pub order: Order,
     pub events: Vec<ExecutionEvent>,
+    pub exec_seq: u64,
+    pub ts: i64,
 }
 
 impl TradeEventResult {
@@ -36,7 +42,7 @@ impl TradeEventResult {
         let trades: Vec<TradeTick> = self
             .events
             .iter()
-            .filter_map(|event| event.build_trade_tick(symbol))
+            .filter_map(|event| event.build_trade_tick(symbol, self.exec_seq, self.ts))
             .map(|internal| internal.to_f64(tick_size, lot_size))
             .collect();
 
@@ -58,9 +64,17 @@ impl ExecutionResult {
         TradeEventResult {
             order: self.order.clone(),
             events,
+            exec_seq: self.exec_seq,
+            ts: self.ts,
         }
     }
-    pub fn from_tif_result(order: Order, tif_result: TifPolicyResult) -> Self {
+    pub fn from_tif_result(
+        order: Order,
+        tif_result: TifPolicyResult,
+        sequence: &mut ExecutionSequence,
+    ) -> Self {
+        let ts = now_millis();
+        let exec_seq = sequence.next_exec_seq();
         let mut two_way_prices = HashMap::new();
         let mut events = Vec::new();
         let mut prices = Vec::new();
@@ -74,6 +88,7 @@ impl ExecutionResult {
                 for fill in fills {
                     prices.push(fill.price);
                     events.push(ExecutionEvent::Traded {
+                        trade_id: sequence.next_trade_id(),
                         taker_order_id: order_id,
                         taker_completed: true,
 
@@ -97,6 +112,7 @@ impl ExecutionResult {
                 for fill in fills {
                     prices.push(fill.price);
                     events.push(ExecutionEvent::Traded {
+                        trade_id: sequence.next_trade_id(),
                         taker_order_id: order_id,
                         taker_completed: false,
                         maker_order_id: fill.order_id,
@@ -124,6 +140,7 @@ impl ExecutionResult {
             } => {
                 for fill in fills {
                     events.push(ExecutionEvent::Traded {
+                        trade_id: sequence.next_trade_id(),
                         taker_order_id: order_id,
                         taker_completed: false,
                         maker_order_id: fill.order_id,
@@ -166,6 +183,8 @@ impl ExecutionResult {
             events,
             order,
             prices: two_way_prices,
+            exec_seq,
+            ts,
         }
     }
 }
@@ -0,0 +1,19 @@
+use bincode::{Decode, Encode};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Decode, Encode)]
+pub struct ExecutionSequence {
+    pub last_trade_id: u64,
+    pub last_exec_seq: u64,
+}
+
+impl ExecutionSequence {
+    pub fn next_trade_id(&mut self) -> u64 {
+        self.last_trade_id += 1;
+        self.last_trade_id
+    }
+
+    pub fn next_exec_seq(&mut self) -> u64 {
+        self.last_exec_seq += 1;
+        self.last_exec_seq
+    }
+}
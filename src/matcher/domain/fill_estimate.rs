@@ -0,0 +1,42 @@
+use serde::{Deserialize, Serialize};
+
+use crate::matcher::domain::{
+    order::OrderSide, price_ticks::PriceTicks, qty_lots::QtyLots, scales::Scales,
+};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FillEstimate {
+    pub side: OrderSide,
+    pub requested: QtyLots,
+    pub fillable: QtyLots,
+    pub vwap: Option<f64>,
+    pub worst_price: Option<PriceTicks>,
+    pub levels: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FillEstimateF64 {
+    pub side: OrderSide,
+    pub requested: f64,
+    pub fillable: f64,
+    pub vwap: Option<f64>,
+    pub worst_price: Option<f64>,
+    pub levels: usize,
+}
+
+impl FillEstimate {
+    pub fn is_complete(&self) -> bool {
+        self.fillable >= self.requested
+    }
+
+    pub fn to_f64(&self, scales: &Scales) -> FillEstimateF64 {
+        FillEstimateF64 {
+            side: self.side,
+            requested: scales.lots_to_f64(self.requested),
+            fillable: scales.lots_to_f64(self.fillable),
+            vwap: self.vwap.map(|v| v / scales.tick_size as f64),
+            worst_price: self.worst_price.map(|p| scales.ticks_to_f64(p)),
+            levels: self.levels,
+        }
+    }
+}
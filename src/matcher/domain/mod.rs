@@ -1,11 +1,18 @@
 pub mod allocation_result;
+pub mod book_depth;
 pub mod book_info;
+pub mod book_top;
 pub mod execution_event;
 pub mod execution_result;
+pub mod execution_sequence;
 pub mod fill;
+pub mod fill_estimate;
 pub mod match_output;
 pub mod order;
 pub mod order_book;
+pub mod order_record;
+pub mod order_state;
+pub mod order_status;
 pub mod price_ticks;
 pub mod qty_lots;
 pub mod reject_reason;
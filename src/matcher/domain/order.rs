@@ -42,7 +42,7 @@ pub enum OrderEvent {
     Cancel(u64),
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, Decode, Encode)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Decode, Encode)]
 pub enum OrderSide {
     Buy,
     Sell,
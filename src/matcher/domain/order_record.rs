@@ -0,0 +1,61 @@
+use bincode::{Decode, Encode};
+
+use crate::matcher::domain::{
+    order::{Order, OrderSide},
+    order_status::OrderStatus,
+    price_ticks::PriceTicks,
+    qty_lots::QtyLots,
+    time_in_force::TimeInForce,
+};
+
+#[derive(Debug, Clone, Decode, Encode)]
+pub struct OrderRecord {
+    pub id: u64,
+    pub side: OrderSide,
+    pub px: PriceTicks,
+    pub tif: TimeInForce,
+    pub orig_qty: QtyLots,
+    pub filled_qty: QtyLots,
+    pub status: OrderStatus,
+    pub submitted_at: i64,
+    pub updated_at: i64,
+}
+
+impl OrderRecord {
+    pub fn new(order: &Order, now: i64) -> Self {
+        Self {
+            id: order.id,
+            side: order.side,
+            px: order.px,
+            tif: order.tif,
+            orig_qty: order.qty,
+            filled_qty: QtyLots::zero(),
+            status: OrderStatus::New,
+            submitted_at: now,
+            updated_at: now,
+        }
+    }
+
+    pub fn remaining_qty(&self) -> QtyLots {
+        if self.status.is_open() {
+            self.orig_qty - self.filled_qty
+        } else {
+            QtyLots::zero()
+        }
+    }
+
+    pub fn apply_fill(&mut self, qty: QtyLots, now: i64) {
+        self.filled_qty += qty;
+        self.status = if self.filled_qty >= self.orig_qty {
+            OrderStatus::Filled
+        } else {
+            OrderStatus::PartiallyFilled
+        };
+        self.updated_at = now;
+    }
+
+    pub fn close(&mut self, status: OrderStatus, now: i64) {
+        self.status = status;
+        self.updated_at = now;
+    }
+}
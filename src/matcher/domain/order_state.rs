@@ -0,0 +1,39 @@
+use serde::{Deserialize, Serialize};
+
+use crate::matcher::domain::{
+    order::OrderSide, order_record::OrderRecord, order_status::OrderStatus,
+    price_ticks::PriceTicks, qty_lots::QtyLots, time_in_force::TimeInForce,
+};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrderState {
+    pub id: u64,
+    pub side: OrderSide,
+    pub px: PriceTicks,
+    pub tif: TimeInForce,
+    pub status: OrderStatus,
+    pub orig_qty: QtyLots,
+    pub filled_qty: QtyLots,
+    pub remaining_qty: QtyLots,
+    pub queue_position: Option<usize>,
+    pub submitted_at: i64,
+    pub updated_at: i64,
+}
+
+impl OrderState {
+    pub fn from_record(record: &OrderRecord, queue_position: Option<usize>) -> Self {
+        Self {
+            id: record.id,
+            side: record.side,
+            px: record.px,
+            tif: record.tif,
+            status: record.status,
+            orig_qty: record.orig_qty,
+            filled_qty: record.filled_qty,
+            remaining_qty: record.remaining_qty(),
+            queue_position,
+            submitted_at: record.submitted_at,
+            updated_at: record.updated_at,
+        }
+    }
+}
@@ -235,6 +235,7 @@ impl Engine {
                 limit_executor.execute(order, book)
             }
         }?;
+        book.record_execution(&result)?;
         let prices = result.prices.clone();
         let level_updates = book.level_update(prices)?;
         let events = result.build_trade_event();
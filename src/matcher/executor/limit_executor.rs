@@ -34,6 +34,10 @@ impl<P: TifPolicy, T: OrderBookOps> OrderTypeExecutor<T> for LimitExecutor<P> {
             book.add_order(rest_order)?;
         }
 
-        Ok(ExecutionResult::from_tif_result(order, resp))
+        Ok(ExecutionResult::from_tif_result(
+            order,
+            resp,
+            book.execution_sequence(),
+        ))
     }
 }
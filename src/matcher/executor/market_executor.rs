@@ -36,6 +36,10 @@ impl<T: OrderBookOps> OrderTypeExecutor<T> for MarketExecutor {
                 completed_order_ids,
             } => TifPolicyResult::accepted(fills, filled, Some(completed_order_ids)),
         };
-        Ok(ExecutionResult::from_tif_result(order, resp))
+        Ok(ExecutionResult::from_tif_result(
+            order,
+            resp,
+            book.execution_sequence(),
+        ))
     }
 }
@@ -0,0 +1,65 @@
+use std::{fs, path::Path, path::PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::matcher::domain::scales::Scales;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FixGatewayConfig {
+    pub bind: String,
+    pub sender_comp_id: String,
+    pub target_comp_ids: Vec<String>,
+    pub symbol: String,
+    pub tick_size: i64,
+    pub lot_size: i64,
+    #[serde(default = "default_store_path")]
+    pub store_path: PathBuf,
+    #[serde(default = "default_heartbeat_secs")]
+    pub heartbeat_secs: u64,
+    #[serde(default = "default_reconcile_ms")]
+    pub reconcile_ms: u64,
+    #[serde(default = "default_first_order_id")]
+    pub first_order_id: u64,
+}
+
+fn default_store_path() -> PathBuf {
+    PathBuf::from(".fix_store/sessions.json")
+}
+
+fn default_heartbeat_secs() -> u64 {
+    30
+}
+
+fn default_reconcile_ms() -> u64 {
+    250
+}
+
+fn default_first_order_id() -> u64 {
+    5_000_000_000
+}
+
+impl FixGatewayConfig {
+    pub fn from_file<P: AsRef<Path>>(path: P) -> anyhow::Result<Self> {
+        let raw = fs::read_to_string(path.as_ref())?;
+        Self::from_json(&raw)
+    }
+
+    pub fn from_json(raw: &str) -> anyhow::Result<Self> {
+        let config: FixGatewayConfig = serde_json::from_str(raw)?;
+        if config.tick_size <= 0 || config.lot_size <= 0 {
+            anyhow::bail!(
+                "tick_size and lot_size must be positive, got {} and {}",
+                config.tick_size,
+                config.lot_size
+            );
+        }
+        if config.target_comp_ids.is_empty() {
+            anyhow::bail!("at least one target_comp_id is required");
+        }
+        Ok(config)
+    }
+
+    pub fn scales(&self) -> Scales {
+        Scales::new(self.tick_size, self.lot_size)
+    }
+}
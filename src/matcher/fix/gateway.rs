@@ -0,0 +1,987 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use anyhow::{anyhow, bail};
+use log::{info, warn};
+use tokio::{net::TcpListener, sync::mpsc, task::JoinHandle};
+
+use crate::matcher::{
+    domain::{
+        execution_event::ExecutionEvent,
+        execution_result::ExecutionResult,
+        order::{Order, OrderSide, OrderType},
+        order_state::OrderState,
+        price_ticks::PriceTicks,
+        qty_lots::QtyLots,
+        scales::Scales,
+    },
+    fix::{
+        config::FixGatewayConfig,
+        message::FixMessage,
+        registry::{FixOrdStatus, FixOrder, OrderRegistry},
+        reports::{
+            ExecType, cancel_reject, execution_report, fill_report, parse_ord_type, parse_side,
+            parse_tif, reject_report,
+        },
+        session::FixSession,
+        store::{FixStore, SeqNums},
+        tags::{self, msg_type},
+    },
+    runtime::book_client::BookClient,
+};
+
+const MAX_SENT_MESSAGES: usize = 10_000;
+
+#[derive(Debug, Clone)]
+pub struct SentMessage {
+    pub message: FixMessage,
+    pub sending_time: String,
+}
+
+#[derive(Default)]
+struct SessionSlot {
+    live: Option<mpsc::UnboundedSender<FixMessage>>,
+    outbox: Vec<FixMessage>,
+    sent: BTreeMap<u64, SentMessage>,
+}
+
+type Outgoing = Vec<(String, FixMessage)>;
+
+pub struct FixGateway {
+    config: FixGatewayConfig,
+    scales: Scales,
+    client: BookClient,
+    store: Mutex<FixStore>,
+    registry: Mutex<OrderRegistry>,
+    sessions: Mutex<HashMap<String, SessionSlot>>,
+}
+
+impl FixGateway {
+    pub fn new(config: FixGatewayConfig, client: BookClient) -> anyhow::Result<Self> {
+        let store = FixStore::open(&config.store_path)?;
+        Ok(Self {
+            scales: config.scales(),
+            config,
+            client,
+            store: Mutex::new(store),
+            registry: Mutex::new(OrderRegistry::default()),
+            sessions: Mutex::new(HashMap::new()),
+        })
+    }
+
+    pub async fn start(self) -> anyhow::Result<(SocketAddr, JoinHandle<()>)> {
+        let listener = TcpListener::bind(&self.config.bind).await?;
+        let addr = listener.local_addr()?;
+        let gateway = Arc::new(self);
+        info!(
+            "[fix] {} accepting on {}",
+            gateway.config.sender_comp_id, addr
+        );
+
+        let handle = tokio::spawn(async move {
+            let mut reconcile =
+                tokio::time::interval(Duration::from_millis(gateway.config.reconcile_ms));
+            loop {
+                tokio::select! {
+                    accepted = listener.accept() => match accepted {
+                        Ok((stream, peer)) => {
+                            let gateway = gateway.clone();
+                            tokio::spawn(async move {
+                                if let Err(err) = FixSession::new(gateway, stream).run().await {
+                                    warn!("[fix] session from {} ended: {:#}", peer, err);
+                                }
+                            });
+                        }
+                        Err(err) => warn!("[fix] accept failed: {}", err),
+                    },
+                    _ = reconcile.tick() => {
+                        let ids = gateway.registry.lock().unwrap().open_book_ids();
+                        gateway.reconcile(ids).await;
+                    }
+                }
+            }
+        });
+        Ok((addr, handle))
+    }
+
+    pub fn config(&self) -> &FixGatewayConfig {
+        &self.config
+    }
+
+    pub fn seq_nums(&self, session: &str) -> SeqNums {
+        self.store.lock().unwrap().seq_nums(session)
+    }
+
+    pub fn save_seq_nums(&self, session: &str, seq: SeqNums) -> anyhow::Result<()> {
+        self.store.lock().unwrap().set_seq_nums(session, seq)
+    }
+
+    // Registers a logged-on session and hands back anything queued while it
+    // was away. Fails when the counterparty is already connected.
+    pub fn attach(
+        &self,
+        session: &str,
+        tx: mpsc::UnboundedSender<FixMessage>,
+    ) -> anyhow::Result<Vec<FixMessage>> {
+        let mut sessions = self.sessions.lock().unwrap();
+        let slot = sessions.entry(session.to_string()).or_default();
+        if slot.live.as_ref().is_some_and(|live| !live.is_closed()) {
+            bail!("session {} is already logged on", session);
+        }
+        slot.live = Some(tx);
+        Ok(std::mem::take(&mut slot.outbox))
+    }
+
+    pub fn detach(&self, session: &str) {
+        if let Some(slot) = self.sessions.lock().unwrap().get_mut(session) {
+            slot.live = None;
+        }
+    }
+
+    pub fn reset_session(&self, session: &str) {
+        if let Some(slot) = self.sessions.lock().unwrap().get_mut(session) {
+            slot.sent.clear();
+        }
+    }
+
+    pub fn record_sent(&self, session: &str, seq: u64, message: FixMessage, sending_time: String) {
+        let mut sessions = self.sessions.lock().unwrap();
+        let slot = sessions.entry(session.to_string()).or_default();
+        slot.sent.insert(
+            seq,
+            SentMessage {
+                message,
+                sending_time,
+            },
+        );
+        while slot.sent.len() > MAX_SENT_MESSAGES {
+            slot.sent.pop_first();
+        }
+    }
+
+    pub fn sent_range(&self, session: &str, begin: u64, end: u64) -> BTreeMap<u64, SentMessage> {
+        let sessions = self.sessions.lock().unwrap();
+        sessions
+            .get(session)
+            .map(|slot| {
+                slot.sent
+                    .range(begin..=end)
+                    .map(|(seq, sent)| (*seq, sent.clone()))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    fn deliver(&self, outgoing: Outgoing) {
+        let mut sessions = self.sessions.lock().unwrap();
+        for (session, message) in outgoing {
+            let slot = sessions.entry(session).or_default();
+            let message = match &slot.live {
+                Some(live) => match live.send(message) {
+                    Ok(()) => continue,
+                    Err(err) => err.0,
+                },
+                None => message,
+            };
+            slot.outbox.push(message);
+        }
+    }
+
+    pub async fn on_application(&self, session: &str, msg: FixMessage) {
+        let result = match msg.msg_type() {
+            msg_type::NEW_ORDER_SINGLE => self.new_order(session, &msg).await,
+            msg_type::ORDER_CANCEL_REQUEST => self.cancel_order(session, &msg).await,
+            msg_type::ORDER_CANCEL_REPLACE_REQUEST => self.replace_order(session, &msg).await,
+            other => Err(anyhow!("unsupported MsgType {}", other)),
+        };
+        if let Err(err) = result {
+            let reply = match msg.msg_type() {
+                msg_type::NEW_ORDER_SINGLE => reject_report(&msg, &err.to_string()),
+                _ => cancel_reject(&msg, None, &err.to_string()),
+            };
+            self.deliver(vec![(session.to_string(), reply)]);
+        }
+    }
+
+    fn allocate_order_id(&self) -> anyhow::Result<u64> {
+        self.store
+            .lock()
+            .unwrap()
+            .allocate_order_id(self.config.first_order_id)
+    }
+
+    fn parse_qty(&self, msg: &FixMessage) -> anyhow::Result<QtyLots> {
+        let qty = self
+            .scales
+            .to_lots_strict_str(msg.require(tags::ORDER_QTY)?)
+            .map_err(|e| anyhow!(e))?;
+        if qty.0 <= 0 {
+            bail!("OrderQty must be positive");
+        }
+        Ok(qty)
+    }
+
+    fn parse_px(&self, msg: &FixMessage, order_type: OrderType) -> anyhow::Result<PriceTicks> {
+        match order_type {
+            OrderType::Market => Ok(PriceTicks(0)),
+            OrderType::Limit => {
+                let px = self
+                    .scales
+                    .to_ticks_strict_str(msg.require(tags::PRICE)?)
+                    .map_err(|e| anyhow!(e))?;
+                if px.0 <= 0 {
+                    bail!("Price must be positive");
+                }
+                Ok(px)
+            }
+        }
+    }
+
+    async fn new_order(&self, session: &str, msg: &FixMessage) -> anyhow::Result<()> {
+        let cl_ord_id = msg.require(tags::CL_ORD_ID)?;
+        if self
+            .registry
+            .lock()
+            .unwrap()
+            .contains_cl_ord_id(session, cl_ord_id)
+        {
+            bail!("duplicate ClOrdID {}", cl_ord_id);
+        }
+        let symbol = msg.require(tags::SYMBOL)?;
+        if symbol != self.config.symbol {
+            bail!("unknown symbol {}", symbol);
+        }
+        let side = parse_side(msg.require(tags::SIDE)?)?;
+        let order_type = parse_ord_type(msg.require(tags::ORD_TYPE)?)?;
+        // MarketExecutor only sweeps the ask side.
+        if let (OrderType::Market, OrderSide::Sell) = (order_type, side) {
+            bail!("market sell orders are not supported");
+        }
+        let tif = parse_tif(msg.get(tags::TIME_IN_FORCE))?;
+        let qty = self.parse_qty(msg)?;
+        let px = self.parse_px(msg, order_type)?;
+
+        let order_id = self.allocate_order_id()?;
+        self.registry.lock().unwrap().insert(FixOrder {
+            order_id,
+            book_id: order_id,
+            session: session.to_string(),
+            cl_ord_id: cl_ord_id.to_string(),
+            symbol: symbol.to_string(),
+            side,
+            order_type,
+            tif,
+            px,
+            qty,
+            cum_qty: QtyLots::zero(),
+            book_filled: QtyLots::zero(),
+            notional: 0,
+            status: FixOrdStatus::New,
+            pending: true,
+            exec_count: 0,
+        });
+
+        let result = self
+            .client
+            .place_order(Order {
+                id: order_id,
+                order_type,
+                tif,
+                side,
+                px,
+                qty,
+            })
+            .await;
+        let result = match result {
+            Ok(result) => result,
+            Err(err) => {
+                self.close_order(order_id, FixOrdStatus::Rejected, &err.to_string());
+                return Ok(());
+            }
+        };
+        let makers = self.apply_result(order_id, &result, ExecType::New, None);
+        self.clear_pending(order_id);
+        self.reconcile(makers).await;
+        Ok(())
+    }
+
+    async fn cancel_order(&self, session: &str, msg: &FixMessage) -> anyhow::Result<()> {
+        let cl_ord_id = msg.require(tags::CL_ORD_ID)?;
+        let orig = msg.require(tags::ORIG_CL_ORD_ID)?;
+        let Some((order_id, book_id)) = self.open_order(session, msg, orig) else {
+            return Ok(());
+        };
+        let result = self
+            .cancel_pending(session, msg, cl_ord_id, orig, order_id, book_id)
+            .await;
+        self.clear_pending(order_id);
+        result
+    }
+
+    async fn cancel_pending(
+        &self,
+        session: &str,
+        msg: &FixMessage,
+        cl_ord_id: &str,
+        orig: &str,
+        order_id: u64,
+        book_id: u64,
+    ) -> anyhow::Result<()> {
+        if !self.client.cancel_order(book_id).await? {
+            self.clear_pending(order_id);
+            self.reconcile(vec![book_id]).await;
+            self.reject_cancel(order_id, msg, "too late to cancel");
+            return Ok(());
+        }
+
+        let state = self.client.query_order(book_id).await?;
+        let mut outgoing = Outgoing::new();
+        {
+            let mut registry = self.registry.lock().unwrap();
+            registry.alias(session, cl_ord_id, order_id);
+            let order = registry
+                .get_mut(order_id)
+                .ok_or_else(|| anyhow!("order {} vanished", order_id))?;
+            self.sync_fills(order, state.as_ref(), &mut outgoing);
+            order.status = FixOrdStatus::Cancelled;
+            order.cl_ord_id = cl_ord_id.to_string();
+            let report = execution_report(order, ExecType::Cancelled, &self.scales)
+                .with(tags::ORIG_CL_ORD_ID, orig);
+            outgoing.push((order.session.clone(), report));
+        }
+        self.deliver(outgoing);
+        Ok(())
+    }
+
+    async fn replace_order(&self, session: &str, msg: &FixMessage) -> anyhow::Result<()> {
+        let cl_ord_id = msg.require(tags::CL_ORD_ID)?;
+        let orig = msg.require(tags::ORIG_CL_ORD_ID)?;
+        let Some((order_id, book_id)) = self.open_order(session, msg, orig) else {
+            return Ok(());
+        };
+        let result = self
+            .replace_pending(session, msg, cl_ord_id, orig, order_id, book_id)
+            .await;
+        self.clear_pending(order_id);
+        result
+    }
+
+    async fn replace_pending(
+        &self,
+        session: &str,
+        msg: &FixMessage,
+        cl_ord_id: &str,
+        orig: &str,
+        order_id: u64,
+        book_id: u64,
+    ) -> anyhow::Result<()> {
+        let (side, order_type, tif, cum_qty) = {
+            let registry = self.registry.lock().unwrap();
+            let order = registry
+                .get(order_id)
+                .ok_or_else(|| anyhow!("order {} vanished", order_id))?;
+            (order.side, order.order_type, order.tif, order.cum_qty)
+        };
+        let new_qty = self.parse_qty(msg)?;
+        let new_px = self.parse_px(msg, order_type)?;
+        if parse_side(msg.require(tags::SIDE)?)? != side {
+            self.reject_cancel(order_id, msg, "side cannot be replaced");
+            return Ok(());
+        }
+        if new_qty <= cum_qty {
+            self.reject_cancel(order_id, msg, "OrderQty must exceed CumQty");
+            return Ok(());
+        }
+
+        if !self.client.cancel_order(book_id).await? {
+            self.clear_pending(order_id);
+            self.reconcile(vec![book_id]).await;
+            self.reject_cancel(order_id, msg, "too late to replace");
+            return Ok(());
+        }
+        let state = self.client.query_order(book_id).await?;
+        let new_book_id = self.allocate_order_id()?;
+
+        let mut outgoing = Outgoing::new();
+        let remaining = {
+            let mut registry = self.registry.lock().unwrap();
+            registry.alias(session, cl_ord_id, order_id);
+            let order = registry
+                .get_mut(order_id)
+                .ok_or_else(|| anyhow!("order {} vanished", order_id))?;
+            self.sync_fills(order, state.as_ref(), &mut outgoing);
+            order.cl_ord_id = cl_ord_id.to_string();
+            let remaining = new_qty - order.cum_qty;
+            if remaining.0 <= 0 {
+                order.status = FixOrdStatus::Cancelled;
+                let report = execution_report(order, ExecType::Cancelled, &self.scales)
+                    .with(tags::ORIG_CL_ORD_ID, orig)
+                    .with(tags::TEXT, "filled past the replacement quantity");
+                outgoing.push((order.session.clone(), report));
+                None
+            } else {
+                order.qty = new_qty;
+                order.px = new_px;
+                order.status = if order.cum_qty.is_zero() {
+                    FixOrdStatus::New
+                } else {
+                    FixOrdStatus::PartiallyFilled
+                };
+                registry.rebook(order_id, new_book_id);
+                Some(remaining)
+            }
+        };
+        self.deliver(outgoing);
+        let Some(remaining) = remaining else {
+            return Ok(());
+        };
+
+        let result = self
+            .client
+            .place_order(Order {
+                id: new_book_id,
+                order_type,
+                tif,
+                side,
+                px: new_px,
+                qty: remaining,
+            })
+            .await;
+        let result = match result {
+            Ok(result) => result,
+            Err(err) => {
+                self.close_order(order_id, FixOrdStatus::Cancelled, &err.to_string());
+                return Ok(());
+            }
+        };
+        let makers = self.apply_result(order_id, &result, ExecType::Replaced, Some(orig));
+        self.reconcile(makers).await;
+        Ok(())
+    }
+
+    // Looks up an open order for a cancel or replace and marks it pending so
+    // reconciliation leaves it alone, replying with an OrderCancelReject when
+    // there is nothing to act on.
+    fn open_order(&self, session: &str, msg: &FixMessage, orig: &str) -> Option<(u64, u64)> {
+        let mut registry = self.registry.lock().unwrap();
+        let found = registry
+            .by_cl_ord_id(session, orig)
+            .and_then(|id| registry.get_mut(id));
+        let reply = match found {
+            Some(order) if order.pending => cancel_reject(msg, Some(order), "request pending"),
+            Some(order) if order.status.is_open() => {
+                order.pending = true;
+                return Some((order.order_id, order.book_id));
+            }
+            Some(order) => cancel_reject(msg, Some(order), "order is not open"),
+            None => cancel_reject(msg, None, "unknown order"),
+        };
+        drop(registry);
+        self.deliver(vec![(session.to_string(), reply)]);
+        None
+    }
+
+    fn clear_pending(&self, order_id: u64) {
+        if let Some(order) = self.registry.lock().unwrap().get_mut(order_id) {
+            order.pending = false;
+        }
+    }
+
+    fn reject_cancel(&self, order_id: u64, msg: &FixMessage, reason: &str) {
+        let registry = self.registry.lock().unwrap();
+        let Some(order) = registry.get(order_id) else {
+            return;
+        };
+        let reply = (
+            order.session.clone(),
+            cancel_reject(msg, Some(order), reason),
+        );
+        drop(registry);
+        self.deliver(vec![reply]);
+    }
+
+    fn close_order(&self, order_id: u64, status: FixOrdStatus, reason: &str) {
+        let mut registry = self.registry.lock().unwrap();
+        let Some(order) = registry.get_mut(order_id) else {
+            return;
+        };
+        order.status = status;
+        let exec_type = match status {
+            FixOrdStatus::Rejected => ExecType::Rejected,
+            _ => ExecType::Cancelled,
+        };
+        let report = execution_report(order, exec_type, &self.scales).with(tags::TEXT, reason);
+        let reply = (order.session.clone(), report);
+        drop(registry);
+        self.deliver(vec![reply]);
+    }
+
+    // Turns the taker side of a placement into reports and returns the book
+    // ids of resting gateway orders it traded against.
+    fn apply_result(
+        &self,
+        order_id: u64,
+        result: &ExecutionResult,
+        first: ExecType,
+        orig_cl_ord_id: Option<&str>,
+    ) -> Vec<u64> {
+        let mut outgoing = Outgoing::new();
+        let mut makers = Vec::new();
+        {
+            let mut registry = self.registry.lock().unwrap();
+            let maker_ids: Vec<u64> = result
+                .events
+                .iter()
+                .filter_map(|event| match event {
+                    ExecutionEvent::Traded { maker_order_id, .. } => Some(*maker_order_id),
+                    _ => None,
+                })
+                .filter(|id| registry.by_book_id(*id).is_some())
+                .collect();
+            makers.extend(maker_ids);
+
+            let Some(order) = registry.get_mut(order_id) else {
+                return makers;
+            };
+            let book_id = order.book_id;
+            let rejected = result.events.iter().find_map(|event| match event {
+                ExecutionEvent::Rejected { order_id, reason } if *order_id == book_id => {
+                    Some(reason)
+                }
+                _ => None,
+            });
+
+            if let Some(reason) = rejected {
+                let exec_type = match first {
+                    ExecType::New => {
+                        order.status = FixOrdStatus::Rejected;
+                        ExecType::Rejected
+                    }
+                    _ => {
+                        order.status = FixOrdStatus::Cancelled;
+                        ExecType::Cancelled
+                    }
+                };
+                let mut report = execution_report(order, exec_type, &self.scales)
+                    .with(tags::TEXT, format!("{:?}", reason));
+                if let ExecType::Rejected = exec_type {
+                    report.set(tags::ORD_REJ_REASON, 99);
+                }
+                if let Some(orig) = orig_cl_ord_id {
+                    report.set(tags::ORIG_CL_ORD_ID, orig);
+                }
+                outgoing.push((order.session.clone(), report));
+            } else {
+                let mut report = execution_report(order, first, &self.scales);
+                if let Some(orig) = orig_cl_ord_id {
+                    report.set(tags::ORIG_CL_ORD_ID, orig);
+                }
+                outgoing.push((order.session.clone(), report));
+
+                for event in &result.events {
+                    match event {
+                        ExecutionEvent::Traded {
+                            trade_id,
+                            taker_order_id,
+                            qty,
+                            price,
+                            ..
+                        } if *taker_order_id == book_id => {
+                            order.apply_fill(*qty, *price);
+                            let report =
+                                fill_report(order, *qty, *price, Some(*trade_id), &self.scales);
+                            outgoing.push((order.session.clone(), report));
+                        }
+                        ExecutionEvent::Cancelled { order_id, .. }
+                            if *order_id == book_id && order.status.is_open() =>
+                        {
+                            order.status = FixOrdStatus::Cancelled;
+                            let report = execution_report(order, ExecType::Cancelled, &self.scales);
+                            outgoing.push((order.session.clone(), report));
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        }
+        self.deliver(outgoing);
+        makers
+    }
+
+    fn sync_fills(&self, order: &mut FixOrder, state: Option<&OrderState>, out: &mut Outgoing) {
+        let Some(state) = state else {
+            return;
+        };
+        if state.filled_qty > order.book_filled {
+            let delta = state.filled_qty - order.book_filled;
+            // Resting orders always fill at their own limit.
+            let px = order.px;
+            order.apply_fill(delta, px);
+            let report = fill_report(order, delta, px, None, &self.scales);
+            out.push((order.session.clone(), report));
+        }
+    }
+
+    // Resting orders fill through flow that never passes the gateway, so their
+    // progress is read back from the book.
+    pub async fn reconcile(&self, book_ids: Vec<u64>) {
+        for book_id in book_ids {
+            let state = match self.client.query_order(book_id).await {
+                Ok(state) => state,
+                Err(err) => {
+                    warn!("[fix] query_order {} failed: {}", book_id, err);
+                    continue;
+                }
+            };
+            let mut outgoing = Outgoing::new();
+            {
+                let mut registry = self.registry.lock().unwrap();
+                let Some(order_id) = registry.by_book_id(book_id) else {
+                    continue;
+                };
+                let Some(order) = registry.get_mut(order_id) else {
+                    continue;
+                };
+                if order.book_id != book_id || !order.status.is_open() || order.pending {
+                    continue;
+                }
+                self.sync_fills(order, state.as_ref(), &mut outgoing);
+                let closed = state.as_ref().is_some_and(|s| !s.status.is_open());
+                if closed && order.status.is_open() {
+                    order.status = FixOrdStatus::Cancelled;
+                    let report = execution_report(order, ExecType::Cancelled, &self.scales);
+                    outgoing.push((order.session.clone(), report));
+                }
+            }
+            self.deliver(outgoing);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{net::SocketAddr, path::Path, sync::Arc, time::Duration};
+
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpStream,
+        task::JoinHandle,
+    };
+
+    use crate::matcher::{
+        book::orderbook::OrderBook,
+        domain::{
+            order::{Order, OrderSide, OrderType},
+            price_ticks::PriceTicks,
+            qty_lots::QtyLots,
+            time_in_force::TimeInForce,
+        },
+        engine::engine::Engine,
+        fix::{
+            config::FixGatewayConfig,
+            gateway::FixGateway,
+            message::{FixMessage, utc_timestamp},
+            tags::{self, msg_type},
+        },
+        policy::price_level::fifo::FifoPriceLevel,
+        runtime::{actor::BookActor, book_client::BookClient},
+        storage::localfile_storage::LocalFileStorage,
+    };
+
+    struct Initiator {
+        stream: TcpStream,
+        buf: Vec<u8>,
+        next_seq: u64,
+    }
+
+    impl Initiator {
+        async fn connect(addr: SocketAddr, next_seq: u64) -> Self {
+            Self {
+                stream: TcpStream::connect(addr).await.unwrap(),
+                buf: Vec::new(),
+                next_seq,
+            }
+        }
+
+        async fn send(&mut self, msg: FixMessage) {
+            let seq = self.next_seq;
+            self.next_seq += 1;
+            self.send_as(msg, seq).await;
+        }
+
+        async fn send_as(&mut self, msg: FixMessage, seq: u64) {
+            let bytes = msg.stamped("DESK", "QEX", seq, &utc_timestamp()).encode();
+            self.stream.write_all(&bytes).await.unwrap();
+        }
+
+        async fn recv(&mut self) -> Option<FixMessage> {
+            loop {
+                if let Some((msg, used)) = FixMessage::decode(&self.buf).unwrap() {
+                    self.buf.drain(..used);
+                    return Some(msg);
+                }
+                let mut chunk = [0u8; 1024];
+                let read =
+                    tokio::time::timeout(Duration::from_secs(5), self.stream.read(&mut chunk))
+                        .await
+                        .expect("timed out waiting for the gateway")
+                        .unwrap();
+                if read == 0 {
+                    return None;
+                }
+                self.buf.extend_from_slice(&chunk[..read]);
+            }
+        }
+
+        async fn expect(&mut self, msg_type: &str) -> FixMessage {
+            let msg = self.recv().await.expect("gateway closed the connection");
+            assert_eq!(msg_type, msg.msg_type(), "unexpected message {}", msg);
+            msg
+        }
+
+        async fn logon(&mut self) -> FixMessage {
+            let logon = FixMessage::new(msg_type::LOGON)
+                .with(tags::ENCRYPT_METHOD, 0)
+                .with(tags::HEART_BT_INT, 30);
+            self.send(logon).await;
+            self.expect(msg_type::LOGON).await
+        }
+
+        async fn new_order(&mut self, cl_ord_id: &str, side: &str, px: &str, qty: &str) {
+            let order = FixMessage::new(msg_type::NEW_ORDER_SINGLE)
+                .with(tags::CL_ORD_ID, cl_ord_id)
+                .with(tags::SYMBOL, "BTC/USDT")
+                .with(tags::SIDE, side)
+                .with(tags::ORD_TYPE, "2")
+                .with(tags::TIME_IN_FORCE, "1")
+                .with(tags::PRICE, px)
+                .with(tags::ORDER_QTY, qty)
+                .with(tags::TRANSACT_TIME, utc_timestamp());
+            self.send(order).await;
+        }
+    }
+
+    fn fresh_client() -> BookClient {
+        let order_book: OrderBook<FifoPriceLevel, fn() -> FifoPriceLevel> =
+            OrderBook::new(FifoPriceLevel::new);
+        let engine = Engine::new(Arc::new(|_| {}), Arc::new(|_| {}));
+        let (client, _jh) = BookActor::<
+            OrderBook<FifoPriceLevel, fn() -> FifoPriceLevel>,
+            FifoPriceLevel,
+            fn() -> FifoPriceLevel,
+            LocalFileStorage,
+        >::build_actor(order_book, 1024, 3600, engine);
+        client
+    }
+
+    async fn start_gateway(store: &Path, client: BookClient) -> (SocketAddr, JoinHandle<()>) {
+        let config = FixGatewayConfig::from_json(&format!(
+            r#"{{
+                "bind": "127.0.0.1:0",
+                "sender_comp_id": "QEX",
+                "target_comp_ids": ["DESK"],
+                "symbol": "BTC/USDT",
+                "tick_size": 10,
+                "lot_size": 100,
+                "store_path": "{}",
+                "reconcile_ms": 50
+            }}"#,
+            store.join("sessions.json").display()
+        ))
+        .unwrap();
+        FixGateway::new(config, client)
+            .unwrap()
+            .start()
+            .await
+            .unwrap()
+    }
+
+    fn field(msg: &FixMessage, tag: u32) -> &str {
+        msg.get(tag).unwrap_or_default()
+    }
+
+    #[tokio::test]
+    async fn orders_fill_replace_and_cancel_over_fix() {
+        let dir = tempfile::tempdir().unwrap();
+        let client = fresh_client();
+        let (addr, _gateway) = start_gateway(dir.path(), client.clone()).await;
+        let mut desk = Initiator::connect(addr, 1).await;
+        desk.logon().await;
+
+        desk.new_order("s1", "2", "100", "1").await;
+        let ack = desk.expect(msg_type::EXECUTION_REPORT).await;
+        assert_eq!("0", field(&ack, tags::EXEC_TYPE));
+        assert_eq!("s1", field(&ack, tags::CL_ORD_ID));
+        assert_eq!("1", field(&ack, tags::LEAVES_QTY));
+
+        desk.new_order("b1", "1", "100.5", "0.4").await;
+        let mut reports = Vec::new();
+        for _ in 0..3 {
+            reports.push(desk.expect(msg_type::EXECUTION_REPORT).await);
+        }
+        // The maker fill comes from reconciliation and may overtake the taker.
+        let report = |cl_ord_id: &str, exec_type: &str| {
+            reports
+                .iter()
+                .find(|r| {
+                    field(r, tags::CL_ORD_ID) == cl_ord_id && field(r, tags::EXEC_TYPE) == exec_type
+                })
+                .cloned()
+                .unwrap()
+        };
+        let ack = report("b1", "0");
+        let taker = report("b1", "F");
+        assert_eq!("2", field(&taker, tags::ORD_STATUS));
+        assert_eq!("100", field(&taker, tags::LAST_PX));
+        assert_eq!("0.4", field(&taker, tags::LAST_QTY));
+        assert!(taker.get(tags::TRD_MATCH_ID).is_some());
+        let maker = report("s1", "F");
+        assert_eq!("1", field(&maker, tags::ORD_STATUS));
+        assert_eq!("0.6", field(&maker, tags::LEAVES_QTY));
+
+        let replace = FixMessage::new(msg_type::ORDER_CANCEL_REPLACE_REQUEST)
+            .with(tags::ORIG_CL_ORD_ID, "s1")
+            .with(tags::CL_ORD_ID, "s2")
+            .with(tags::SYMBOL, "BTC/USDT")
+            .with(tags::SIDE, "2")
+            .with(tags::ORD_TYPE, "2")
+            .with(tags::PRICE, "100.2")
+            .with(tags::ORDER_QTY, "2");
+        desk.send(replace).await;
+        let replaced = desk.expect(msg_type::EXECUTION_REPORT).await;
+        assert_eq!("5", field(&replaced, tags::EXEC_TYPE));
+        assert_eq!("s1", field(&replaced, tags::ORIG_CL_ORD_ID));
+        assert_eq!(field(&ack, tags::ORDER_ID), field(&taker, tags::ORDER_ID));
+        assert_eq!(
+            field(&maker, tags::ORDER_ID),
+            field(&replaced, tags::ORDER_ID)
+        );
+        assert_eq!("0.4", field(&replaced, tags::CUM_QTY));
+        assert_eq!("1.6", field(&replaced, tags::LEAVES_QTY));
+        assert_eq!("100.2", field(&replaced, tags::PRICE));
+
+        // Flow that bypasses the gateway is picked up by reconciliation.
+        let outside = Order {
+            id: 1,
+            order_type: OrderType::Limit,
+            tif: TimeInForce::IOC,
+            side: OrderSide::Buy,
+            px: PriceTicks(1005),
+            qty: QtyLots(60),
+        };
+        client.place_order(outside).await.unwrap();
+        let fill = desk.expect(msg_type::EXECUTION_REPORT).await;
+        assert_eq!(
+            ("s2", "F"),
+            (field(&fill, tags::CL_ORD_ID), field(&fill, tags::EXEC_TYPE))
+        );
+        assert_eq!("100.2", field(&fill, tags::LAST_PX));
+        assert_eq!("1", field(&fill, tags::CUM_QTY));
+
+        let cancel = FixMessage::new(msg_type::ORDER_CANCEL_REQUEST)
+            .with(tags::ORIG_CL_ORD_ID, "s2")
+            .with(tags::CL_ORD_ID, "s3")
+            .with(tags::SYMBOL, "BTC/USDT")
+            .with(tags::SIDE, "2");
+        desk.send(cancel.clone()).await;
+        let cancelled = desk.expect(msg_type::EXECUTION_REPORT).await;
+        assert_eq!("4", field(&cancelled, tags::EXEC_TYPE));
+        assert_eq!("4", field(&cancelled, tags::ORD_STATUS));
+        assert_eq!("0", field(&cancelled, tags::LEAVES_QTY));
+        assert!(client.list_open_orders(None).await.unwrap().is_empty());
+
+        desk.send(cancel).await;
+        let reject = desk.expect(msg_type::ORDER_CANCEL_REJECT).await;
+        assert_eq!("1", field(&reject, tags::CXL_REJ_RESPONSE_TO));
+
+        desk.new_order("x1", "1", "100.05", "1").await;
+        let bad = desk.expect(msg_type::EXECUTION_REPORT).await;
+        assert_eq!("8", field(&bad, tags::EXEC_TYPE));
+        assert_eq!("NONE", field(&bad, tags::ORDER_ID));
+    }
+
+    #[tokio::test]
+    async fn session_answers_test_and_resend_requests() {
+        let dir = tempfile::tempdir().unwrap();
+        let (addr, _gateway) = start_gateway(dir.path(), fresh_client()).await;
+        let mut desk = Initiator::connect(addr, 1).await;
+        desk.logon().await;
+
+        desk.send(FixMessage::new(msg_type::TEST_REQUEST).with(tags::TEST_REQ_ID, "ping"))
+            .await;
+        let heartbeat = desk.expect(msg_type::HEARTBEAT).await;
+        assert_eq!("ping", field(&heartbeat, tags::TEST_REQ_ID));
+
+        desk.new_order("a1", "1", "99", "1").await;
+        let ack = desk.expect(msg_type::EXECUTION_REPORT).await;
+        assert_eq!("3", field(&ack, tags::MSG_SEQ_NUM));
+
+        let resend = FixMessage::new(msg_type::RESEND_REQUEST)
+            .with(tags::BEGIN_SEQ_NO, 1)
+            .with(tags::END_SEQ_NO, 0);
+        desk.send(resend).await;
+        let gap = desk.expect(msg_type::SEQUENCE_RESET).await;
+        assert_eq!("1", field(&gap, tags::MSG_SEQ_NUM));
+        assert_eq!("3", field(&gap, tags::NEW_SEQ_NO));
+        assert_eq!("Y", field(&gap, tags::GAP_FILL_FLAG));
+        let replay = desk.expect(msg_type::EXECUTION_REPORT).await;
+        assert_eq!("3", field(&replay, tags::MSG_SEQ_NUM));
+        assert_eq!("Y", field(&replay, tags::POSS_DUP_FLAG));
+        assert_eq!(field(&ack, tags::EXEC_ID), field(&replay, tags::EXEC_ID));
+        assert_eq!(
+            field(&ack, tags::SENDING_TIME),
+            field(&replay, tags::ORIG_SENDING_TIME)
+        );
+
+        // Skip ahead: the gateway asks for the missing range.
+        let expected = desk.next_seq;
+        desk.send_as(FixMessage::new(msg_type::HEARTBEAT), expected + 3)
+            .await;
+        let request = desk.expect(msg_type::RESEND_REQUEST).await;
+        assert_eq!(expected.to_string(), field(&request, tags::BEGIN_SEQ_NO));
+        assert_eq!("0", field(&request, tags::END_SEQ_NO));
+
+        desk.send_as(FixMessage::new(msg_type::HEARTBEAT), 1).await;
+        let logout = desk.expect(msg_type::LOGOUT).await;
+        assert!(field(&logout, tags::TEXT).contains("too low"));
+        assert!(desk.recv().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn sequence_numbers_survive_gateway_restart() {
+        let dir = tempfile::tempdir().unwrap();
+        let client = fresh_client();
+        let (addr, gateway) = start_gateway(dir.path(), client.clone()).await;
+        let mut desk = Initiator::connect(addr, 1).await;
+        desk.logon().await;
+        desk.new_order("r1", "1", "99", "1").await;
+        let first = desk.expect(msg_type::EXECUTION_REPORT).await;
+        desk.send(FixMessage::new(msg_type::LOGOUT)).await;
+        let logout = desk.expect(msg_type::LOGOUT).await;
+        assert!(desk.recv().await.is_none());
+        gateway.abort();
+
+        let (addr, _gateway) = start_gateway(dir.path(), client).await;
+        let mut stale = Initiator::connect(addr, 1).await;
+        stale
+            .send(FixMessage::new(msg_type::LOGON).with(tags::HEART_BT_INT, 30))
+            .await;
+        assert!(stale.recv().await.is_none());
+
+        let mut desk = Initiator::connect(addr, desk.next_seq).await;
+        let logon = desk.logon().await;
+        let resumed: u64 = field(&logon, tags::MSG_SEQ_NUM).parse().unwrap();
+        let last: u64 = field(&logout, tags::MSG_SEQ_NUM).parse().unwrap();
+        assert_eq!(last + 1, resumed);
+
+        desk.new_order("r2", "1", "98", "1").await;
+        let second = desk.expect(msg_type::EXECUTION_REPORT).await;
+        let first_id: u64 = field(&first, tags::ORDER_ID).parse().unwrap();
+        let second_id: u64 = field(&second, tags::ORDER_ID).parse().unwrap();
+        assert!(second_id > first_id);
+    }
+}
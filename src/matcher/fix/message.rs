@@ -0,0 +1,268 @@
+use std::fmt::{self, Display};
+
+use anyhow::{Context, anyhow, bail};
+use chrono::Utc;
+
+use crate::matcher::fix::tags;
+
+pub const SOH: u8 = 0x01;
+pub const BEGIN_STRING_VALUE: &str = "FIX.4.4";
+
+const TRAILER_LEN: usize = 7; // "10=XXX<SOH>"
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FixMessage {
+    fields: Vec<(u32, String)>,
+}
+
+impl FixMessage {
+    pub fn new(msg_type: &str) -> Self {
+        Self {
+            fields: vec![(tags::MSG_TYPE, msg_type.to_string())],
+        }
+    }
+
+    pub fn with(mut self, tag: u32, value: impl ToString) -> Self {
+        self.set(tag, value);
+        self
+    }
+
+    pub fn set(&mut self, tag: u32, value: impl ToString) {
+        let value = value.to_string();
+        match self.fields.iter_mut().find(|(t, _)| *t == tag) {
+            Some(field) => field.1 = value,
+            None => self.fields.push((tag, value)),
+        }
+    }
+
+    pub fn remove(&mut self, tag: u32) {
+        self.fields.retain(|(t, _)| *t != tag);
+    }
+
+    pub fn get(&self, tag: u32) -> Option<&str> {
+        self.fields
+            .iter()
+            .find(|(t, _)| *t == tag)
+            .map(|(_, v)| v.as_str())
+    }
+
+    pub fn require(&self, tag: u32) -> anyhow::Result<&str> {
+        self.get(tag)
+            .ok_or_else(|| anyhow!("missing required tag {}", tag))
+    }
+
+    pub fn get_u64(&self, tag: u32) -> anyhow::Result<Option<u64>> {
+        self.get(tag)
+            .map(|v| {
+                v.parse::<u64>()
+                    .with_context(|| format!("bad tag {}: {}", tag, v))
+            })
+            .transpose()
+    }
+
+    pub fn flag(&self, tag: u32) -> bool {
+        self.get(tag) == Some("Y")
+    }
+
+    pub fn msg_type(&self) -> &str {
+        self.get(tags::MSG_TYPE).unwrap_or_default()
+    }
+
+    pub fn seq_num(&self) -> anyhow::Result<u64> {
+        self.get_u64(tags::MSG_SEQ_NUM)?
+            .ok_or_else(|| anyhow!("missing MsgSeqNum"))
+    }
+
+    // Copies the message with the standard header in front of the body, keeping
+    // PossDupFlag/OrigSendingTime when the caller already set them.
+    pub fn stamped(&self, sender: &str, target: &str, seq: u64, sending_time: &str) -> FixMessage {
+        let mut out = FixMessage::new(self.msg_type())
+            .with(tags::SENDER_COMP_ID, sender)
+            .with(tags::TARGET_COMP_ID, target)
+            .with(tags::MSG_SEQ_NUM, seq)
+            .with(tags::SENDING_TIME, sending_time);
+        for tag in [tags::POSS_DUP_FLAG, tags::ORIG_SENDING_TIME] {
+            if let Some(value) = self.get(tag) {
+                out.set(tag, value);
+            }
+        }
+        for (tag, value) in &self.fields {
+            if !matches!(
+                *tag,
+                tags::MSG_TYPE
+                    | tags::SENDER_COMP_ID
+                    | tags::TARGET_COMP_ID
+                    | tags::MSG_SEQ_NUM
+                    | tags::SENDING_TIME
+                    | tags::POSS_DUP_FLAG
+                    | tags::ORIG_SENDING_TIME
+            ) {
+                out.fields.push((*tag, value.clone()));
+            }
+        }
+        out
+    }
+
+    pub fn encode(&self) -> Vec<u8> {
+        let mut body = Vec::with_capacity(128);
+        for (tag, value) in &self.fields {
+            if matches!(
+                *tag,
+                tags::BEGIN_STRING | tags::BODY_LENGTH | tags::CHECK_SUM
+            ) {
+                continue;
+            }
+            body.extend_from_slice(format!("{}={}", tag, value).as_bytes());
+            body.push(SOH);
+        }
+
+        let mut out = Vec::with_capacity(body.len() + 32);
+        out.extend_from_slice(format!("8={}", BEGIN_STRING_VALUE).as_bytes());
+        out.push(SOH);
+        out.extend_from_slice(format!("9={}", body.len()).as_bytes());
+        out.push(SOH);
+        out.extend_from_slice(&body);
+        let sum = checksum(&out);
+        out.extend_from_slice(format!("10={:03}", sum).as_bytes());
+        out.push(SOH);
+        out
+    }
+
+    // Returns the first complete frame in `buf` and how many bytes it used, or
+    // None when more bytes are needed.
+    pub fn decode(buf: &[u8]) -> anyhow::Result<Option<(FixMessage, usize)>> {
+        let begin = format!("8={}", BEGIN_STRING_VALUE);
+        let prefix_len = begin.len() + 1;
+        if buf.len() < prefix_len {
+            return Ok(None);
+        }
+        if &buf[..begin.len()] != begin.as_bytes() || buf[begin.len()] != SOH {
+            bail!(
+                "frame does not start with BeginString {}",
+                BEGIN_STRING_VALUE
+            );
+        }
+
+        let rest = &buf[prefix_len..];
+        let Some(len_end) = rest.iter().position(|b| *b == SOH) else {
+            return Ok(None);
+        };
+        let len_field = std::str::from_utf8(&rest[..len_end])?;
+        let body_len: usize = len_field
+            .strip_prefix("9=")
+            .ok_or_else(|| anyhow!("BodyLength must follow BeginString"))?
+            .parse()
+            .with_context(|| format!("bad BodyLength {}", len_field))?;
+
+        let body_start = prefix_len + len_end + 1;
+        let body_end = body_start + body_len;
+        let frame_len = body_end + TRAILER_LEN;
+        if buf.len() < frame_len {
+            return Ok(None);
+        }
+
+        let trailer = &buf[body_end..frame_len];
+        if &trailer[..3] != b"10=" || trailer[TRAILER_LEN - 1] != SOH {
+            bail!("BodyLength {} does not end at CheckSum", body_len);
+        }
+        let expected: u32 = std::str::from_utf8(&trailer[3..6])?.parse()?;
+        let actual = checksum(&buf[..body_end]);
+        if expected != actual {
+            bail!(
+                "CheckSum mismatch: got {:03}, computed {:03}",
+                expected,
+                actual
+            );
+        }
+
+        let mut fields = Vec::new();
+        for raw in buf[body_start..body_end].split(|b| *b == SOH) {
+            if raw.is_empty() {
+                continue;
+            }
+            let raw = std::str::from_utf8(raw)?;
+            let (tag, value) = raw
+                .split_once('=')
+                .ok_or_else(|| anyhow!("malformed field {}", raw))?;
+            let tag: u32 = tag.parse().with_context(|| format!("bad tag {}", tag))?;
+            fields.push((tag, value.to_string()));
+        }
+        let message = FixMessage { fields };
+        if message.get(tags::MSG_TYPE).is_none() {
+            bail!("message without MsgType");
+        }
+        Ok(Some((message, frame_len)))
+    }
+}
+
+impl Display for FixMessage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let text: Vec<String> = self
+            .fields
+            .iter()
+            .map(|(tag, value)| format!("{}={}", tag, value))
+            .collect();
+        write!(f, "{}", text.join("|"))
+    }
+}
+
+pub fn checksum(bytes: &[u8]) -> u32 {
+    bytes.iter().map(|b| *b as u32).sum::<u32>() % 256
+}
+
+pub fn utc_timestamp() -> String {
+    Utc::now().format("%Y%m%d-%H:%M:%S%.3f").to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::matcher::fix::{
+        message::{FixMessage, SOH, checksum},
+        tags::{self, msg_type},
+    };
+
+    #[test]
+    fn encode_decode_round_trip() {
+        let msg = FixMessage::new(msg_type::NEW_ORDER_SINGLE)
+            .with(tags::SENDER_COMP_ID, "DESK")
+            .with(tags::TARGET_COMP_ID, "QEX")
+            .with(tags::MSG_SEQ_NUM, 7)
+            .with(tags::CL_ORD_ID, "abc-1")
+            .with(tags::PRICE, "101.5");
+        let mut bytes = msg.encode();
+        let len = bytes.len();
+        bytes.extend_from_slice(b"8=FIX.4.4");
+
+        let (decoded, used) = FixMessage::decode(&bytes).unwrap().unwrap();
+        assert_eq!(len, used);
+        assert_eq!(msg, decoded);
+        assert_eq!(7, decoded.seq_num().unwrap());
+        assert!(FixMessage::decode(&bytes[used..]).unwrap().is_none());
+
+        let body_end = len - 7;
+        let sum: u32 = std::str::from_utf8(&bytes[body_end + 3..body_end + 6])
+            .unwrap()
+            .parse()
+            .unwrap();
+        assert_eq!(checksum(&bytes[..body_end]), sum);
+    }
+
+    #[test]
+    fn decode_waits_for_full_frame_and_rejects_bad_checksum() {
+        let bytes = FixMessage::new(msg_type::HEARTBEAT)
+            .with(tags::MSG_SEQ_NUM, 1)
+            .encode();
+        for cut in 0..bytes.len() {
+            assert!(FixMessage::decode(&bytes[..cut]).unwrap().is_none());
+        }
+
+        let mut corrupt = bytes.clone();
+        let pos = corrupt.windows(3).position(|w| w == b"35=").unwrap() + 3;
+        corrupt[pos] = b'1';
+        assert!(FixMessage::decode(&corrupt).is_err());
+
+        let garbage = [b'x'; 16];
+        assert!(FixMessage::decode(&garbage).is_err());
+        assert_eq!(SOH, *bytes.last().unwrap());
+    }
+}
@@ -0,0 +1,8 @@
+pub mod config;
+pub mod gateway;
+pub mod message;
+pub mod registry;
+pub mod reports;
+pub mod session;
+pub mod store;
+pub mod tags;
@@ -0,0 +1,156 @@
+use std::collections::HashMap;
+
+use crate::matcher::domain::{
+    order::{OrderSide, OrderType},
+    price_ticks::PriceTicks,
+    qty_lots::QtyLots,
+    time_in_force::TimeInForce,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FixOrdStatus {
+    New,
+    PartiallyFilled,
+    Filled,
+    Cancelled,
+    Rejected,
+}
+
+impl FixOrdStatus {
+    pub fn code(&self) -> &'static str {
+        match self {
+            FixOrdStatus::New => "0",
+            FixOrdStatus::PartiallyFilled => "1",
+            FixOrdStatus::Filled => "2",
+            FixOrdStatus::Cancelled => "4",
+            FixOrdStatus::Rejected => "8",
+        }
+    }
+
+    pub fn is_open(&self) -> bool {
+        matches!(self, FixOrdStatus::New | FixOrdStatus::PartiallyFilled)
+    }
+}
+
+// One FIX order as the counterparty sees it. A replace moves the order onto a
+// new book id while `order_id` (tag 37) stays stable. `pending` is set while a
+// placement, cancel or replace is in flight and keeps reconciliation away.
+#[derive(Debug, Clone)]
+pub struct FixOrder {
+    pub order_id: u64,
+    pub book_id: u64,
+    pub session: String,
+    pub cl_ord_id: String,
+    pub symbol: String,
+    pub side: OrderSide,
+    pub order_type: OrderType,
+    pub tif: TimeInForce,
+    pub px: PriceTicks,
+    pub qty: QtyLots,
+    pub cum_qty: QtyLots,
+    pub book_filled: QtyLots,
+    pub notional: i128,
+    pub status: FixOrdStatus,
+    pub pending: bool,
+    pub exec_count: u64,
+}
+
+impl FixOrder {
+    pub fn leaves_qty(&self) -> QtyLots {
+        if self.status.is_open() {
+            self.qty - self.cum_qty
+        } else {
+            QtyLots::zero()
+        }
+    }
+
+    pub fn avg_px_ticks(&self) -> f64 {
+        if self.cum_qty.is_zero() {
+            0.0
+        } else {
+            self.notional as f64 / self.cum_qty.0 as f64
+        }
+    }
+
+    pub fn next_exec_id(&mut self) -> String {
+        self.exec_count += 1;
+        format!("{}-{}", self.order_id, self.exec_count)
+    }
+
+    pub fn apply_fill(&mut self, qty: QtyLots, px: PriceTicks) {
+        self.cum_qty += qty;
+        self.book_filled += qty;
+        self.notional += qty.0 as i128 * px.0 as i128;
+        self.status = if self.cum_qty >= self.qty {
+            FixOrdStatus::Filled
+        } else {
+            FixOrdStatus::PartiallyFilled
+        };
+    }
+}
+
+#[derive(Default)]
+pub struct OrderRegistry {
+    orders: HashMap<u64, FixOrder>,
+    by_cl_ord_id: HashMap<(String, String), u64>,
+    by_book_id: HashMap<u64, u64>,
+}
+
+impl OrderRegistry {
+    pub fn contains_cl_ord_id(&self, session: &str, cl_ord_id: &str) -> bool {
+        self.by_cl_ord_id
+            .contains_key(&(session.to_string(), cl_ord_id.to_string()))
+    }
+
+    pub fn insert(&mut self, order: FixOrder) {
+        self.by_cl_ord_id.insert(
+            (order.session.clone(), order.cl_ord_id.clone()),
+            order.order_id,
+        );
+        self.by_book_id.insert(order.book_id, order.order_id);
+        self.orders.insert(order.order_id, order);
+    }
+
+    pub fn alias(&mut self, session: &str, cl_ord_id: &str, order_id: u64) {
+        self.by_cl_ord_id
+            .insert((session.to_string(), cl_ord_id.to_string()), order_id);
+    }
+
+    pub fn rebook(&mut self, order_id: u64, book_id: u64) {
+        if let Some(order) = self.orders.get_mut(&order_id) {
+            self.by_book_id.remove(&order.book_id);
+            order.book_id = book_id;
+            order.book_filled = QtyLots::zero();
+            self.by_book_id.insert(book_id, order_id);
+        }
+    }
+
+    pub fn by_cl_ord_id(&self, session: &str, cl_ord_id: &str) -> Option<u64> {
+        self.by_cl_ord_id
+            .get(&(session.to_string(), cl_ord_id.to_string()))
+            .copied()
+    }
+
+    pub fn by_book_id(&self, book_id: u64) -> Option<u64> {
+        self.by_book_id.get(&book_id).copied()
+    }
+
+    pub fn get(&self, order_id: u64) -> Option<&FixOrder> {
+        self.orders.get(&order_id)
+    }
+
+    pub fn get_mut(&mut self, order_id: u64) -> Option<&mut FixOrder> {
+        self.orders.get_mut(&order_id)
+    }
+
+    pub fn open_book_ids(&self) -> Vec<u64> {
+        let mut ids: Vec<u64> = self
+            .orders
+            .values()
+            .filter(|o| o.status.is_open())
+            .map(|o| o.book_id)
+            .collect();
+        ids.sort_unstable();
+        ids
+    }
+}
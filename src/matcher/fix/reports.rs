@@ -0,0 +1,175 @@
+use anyhow::bail;
+use uuid::Uuid;
+
+use crate::matcher::{
+    domain::{
+        order::{OrderSide, OrderType},
+        price_ticks::PriceTicks,
+        qty_lots::QtyLots,
+        scales::Scales,
+        time_in_force::TimeInForce,
+    },
+    fix::{
+        message::{FixMessage, utc_timestamp},
+        registry::{FixOrdStatus, FixOrder},
+        tags::{self, msg_type},
+    },
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExecType {
+    New,
+    Trade,
+    Cancelled,
+    Replaced,
+    Rejected,
+}
+
+impl ExecType {
+    pub fn code(&self) -> &'static str {
+        match self {
+            ExecType::New => "0",
+            ExecType::Trade => "F",
+            ExecType::Cancelled => "4",
+            ExecType::Replaced => "5",
+            ExecType::Rejected => "8",
+        }
+    }
+}
+
+pub fn side_code(side: OrderSide) -> &'static str {
+    match side {
+        OrderSide::Buy => "1",
+        OrderSide::Sell => "2",
+    }
+}
+
+pub fn parse_side(code: &str) -> anyhow::Result<OrderSide> {
+    match code {
+        "1" => Ok(OrderSide::Buy),
+        "2" => Ok(OrderSide::Sell),
+        other => bail!("unsupported Side {}", other),
+    }
+}
+
+pub fn ord_type_code(order_type: OrderType) -> &'static str {
+    match order_type {
+        OrderType::Market => "1",
+        OrderType::Limit => "2",
+    }
+}
+
+pub fn parse_ord_type(code: &str) -> anyhow::Result<OrderType> {
+    match code {
+        "1" => Ok(OrderType::Market),
+        "2" => Ok(OrderType::Limit),
+        other => bail!("unsupported OrdType {}", other),
+    }
+}
+
+pub fn tif_code(tif: TimeInForce) -> &'static str {
+    match tif {
+        TimeInForce::GTC => "1",
+        TimeInForce::IOC => "3",
+        TimeInForce::FOK => "4",
+        TimeInForce::GTT(_) => "6",
+    }
+}
+
+// Day orders rest until cancelled; the book has no session close.
+pub fn parse_tif(code: Option<&str>) -> anyhow::Result<TimeInForce> {
+    match code.unwrap_or("0") {
+        "0" | "1" => Ok(TimeInForce::GTC),
+        "3" => Ok(TimeInForce::IOC),
+        "4" => Ok(TimeInForce::FOK),
+        other => bail!("unsupported TimeInForce {}", other),
+    }
+}
+
+pub fn execution_report(order: &mut FixOrder, exec_type: ExecType, scales: &Scales) -> FixMessage {
+    let mut msg = FixMessage::new(msg_type::EXECUTION_REPORT)
+        .with(tags::ORDER_ID, order.order_id)
+        .with(tags::CL_ORD_ID, &order.cl_ord_id)
+        .with(tags::EXEC_ID, order.next_exec_id())
+        .with(tags::EXEC_TYPE, exec_type.code())
+        .with(tags::ORD_STATUS, order.status.code())
+        .with(tags::SYMBOL, &order.symbol)
+        .with(tags::SIDE, side_code(order.side))
+        .with(tags::ORD_TYPE, ord_type_code(order.order_type))
+        .with(tags::TIME_IN_FORCE, tif_code(order.tif))
+        .with(tags::ORDER_QTY, scales.lots_to_f64(order.qty))
+        .with(tags::LEAVES_QTY, scales.lots_to_f64(order.leaves_qty()))
+        .with(tags::CUM_QTY, scales.lots_to_f64(order.cum_qty))
+        .with(tags::AVG_PX, order.avg_px_ticks() / scales.tick_size as f64)
+        .with(tags::TRANSACT_TIME, utc_timestamp());
+    if let OrderType::Limit = order.order_type {
+        msg.set(tags::PRICE, scales.ticks_to_f64(order.px));
+    }
+    msg
+}
+
+pub fn fill_report(
+    order: &mut FixOrder,
+    last_qty: QtyLots,
+    last_px: PriceTicks,
+    trade_id: Option<u64>,
+    scales: &Scales,
+) -> FixMessage {
+    let mut msg = execution_report(order, ExecType::Trade, scales)
+        .with(tags::LAST_QTY, scales.lots_to_f64(last_qty))
+        .with(tags::LAST_PX, scales.ticks_to_f64(last_px));
+    if let Some(trade_id) = trade_id {
+        msg.set(tags::TRD_MATCH_ID, trade_id);
+    }
+    msg
+}
+
+// For orders that never made it into the registry (bad fields, duplicate ids).
+pub fn reject_report(request: &FixMessage, reason: &str) -> FixMessage {
+    let mut msg = FixMessage::new(msg_type::EXECUTION_REPORT)
+        .with(tags::ORDER_ID, "NONE")
+        .with(
+            tags::CL_ORD_ID,
+            request.get(tags::CL_ORD_ID).unwrap_or_default(),
+        )
+        .with(tags::EXEC_ID, Uuid::new_v4())
+        .with(tags::EXEC_TYPE, ExecType::Rejected.code())
+        .with(tags::ORD_STATUS, FixOrdStatus::Rejected.code())
+        .with(tags::ORD_REJ_REASON, 99)
+        .with(tags::LEAVES_QTY, 0)
+        .with(tags::CUM_QTY, 0)
+        .with(tags::AVG_PX, 0)
+        .with(tags::TRANSACT_TIME, utc_timestamp())
+        .with(tags::TEXT, reason);
+    for tag in [tags::SYMBOL, tags::SIDE, tags::ORDER_QTY, tags::ORD_TYPE] {
+        if let Some(value) = request.get(tag) {
+            msg.set(tag, value);
+        }
+    }
+    msg
+}
+
+pub fn cancel_reject(request: &FixMessage, order: Option<&FixOrder>, reason: &str) -> FixMessage {
+    let response_to = match request.msg_type() {
+        msg_type::ORDER_CANCEL_REPLACE_REQUEST => "2",
+        _ => "1",
+    };
+    let (order_id, status) = match order {
+        Some(order) => (order.order_id.to_string(), order.status.code()),
+        None => ("NONE".to_string(), FixOrdStatus::Rejected.code()),
+    };
+    FixMessage::new(msg_type::ORDER_CANCEL_REJECT)
+        .with(tags::ORDER_ID, order_id)
+        .with(
+            tags::CL_ORD_ID,
+            request.get(tags::CL_ORD_ID).unwrap_or_default(),
+        )
+        .with(
+            tags::ORIG_CL_ORD_ID,
+            request.get(tags::ORIG_CL_ORD_ID).unwrap_or_default(),
+        )
+        .with(tags::ORD_STATUS, status)
+        .with(tags::CXL_REJ_RESPONSE_TO, response_to)
+        .with(tags::CXL_REJ_REASON, if order.is_some() { 0 } else { 1 })
+        .with(tags::TEXT, reason)
+}
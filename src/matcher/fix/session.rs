@@ -0,0 +1,390 @@
+use std::sync::Arc;
+
+use anyhow::bail;
+use log::{info, warn};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpStream, tcp::OwnedWriteHalf},
+    sync::mpsc,
+    time::{Duration, Instant},
+};
+
+use crate::matcher::fix::{
+    gateway::FixGateway,
+    message::{FixMessage, utc_timestamp},
+    store::SeqNums,
+    tags::{self, msg_type},
+};
+
+const READ_CHUNK: usize = 4096;
+
+pub struct FixSession {
+    gateway: Arc<FixGateway>,
+    stream: Option<TcpStream>,
+    writer: Option<OwnedWriteHalf>,
+    counterparty: Option<String>,
+    seq: SeqNums,
+    heartbeat: Duration,
+    connected_at: Instant,
+    last_recv: Instant,
+    last_sent: Instant,
+    test_request_sent: Option<Instant>,
+    resend_until: Option<u64>,
+}
+
+impl FixSession {
+    pub fn new(gateway: Arc<FixGateway>, stream: TcpStream) -> Self {
+        let now = Instant::now();
+        let heartbeat = Duration::from_secs(gateway.config().heartbeat_secs.max(1));
+        Self {
+            gateway,
+            stream: Some(stream),
+            writer: None,
+            counterparty: None,
+            seq: SeqNums::default(),
+            heartbeat,
+            connected_at: now,
+            last_recv: now,
+            last_sent: now,
+            test_request_sent: None,
+            resend_until: None,
+        }
+    }
+
+    pub async fn run(mut self) -> anyhow::Result<()> {
+        let result = self.run_loop().await;
+        if let Some(counterparty) = &self.counterparty {
+            self.gateway.detach(counterparty);
+            info!("[fix] {} disconnected", counterparty);
+        }
+        result
+    }
+
+    async fn run_loop(&mut self) -> anyhow::Result<()> {
+        let Some(stream) = self.stream.take() else {
+            bail!("session already ran");
+        };
+        let (mut reader, writer) = stream.into_split();
+        self.writer = Some(writer);
+
+        let mut buf = Vec::with_capacity(READ_CHUNK);
+        let mut chunk = [0u8; READ_CHUNK];
+        let (tx, mut outbound) = mpsc::unbounded_channel::<FixMessage>();
+        let mut tx = Some(tx);
+        let mut ticker = tokio::time::interval(Duration::from_secs(1));
+
+        loop {
+            tokio::select! {
+                read = reader.read(&mut chunk) => {
+                    let n = read?;
+                    if n == 0 {
+                        return Ok(());
+                    }
+                    buf.extend_from_slice(&chunk[..n]);
+                    while let Some((msg, used)) = FixMessage::decode(&buf)? {
+                        buf.drain(..used);
+                        if !self.on_message(msg, &mut tx).await? {
+                            return Ok(());
+                        }
+                    }
+                }
+                Some(msg) = outbound.recv(), if self.counterparty.is_some() => {
+                    self.send(msg).await?;
+                }
+                _ = ticker.tick() => {
+                    if !self.on_timer().await? {
+                        return Ok(());
+                    }
+                }
+            }
+        }
+    }
+
+    fn counterparty(&self) -> &str {
+        self.counterparty.as_deref().unwrap_or_default()
+    }
+
+    async fn on_message(
+        &mut self,
+        msg: FixMessage,
+        tx: &mut Option<mpsc::UnboundedSender<FixMessage>>,
+    ) -> anyhow::Result<bool> {
+        self.last_recv = Instant::now();
+        self.test_request_sent = None;
+
+        if self.counterparty.is_none() {
+            return match tx.take() {
+                Some(tx) if msg.msg_type() == msg_type::LOGON => self.on_logon(msg, tx).await,
+                _ => {
+                    warn!("[fix] first message was not a Logon: {}", msg);
+                    Ok(false)
+                }
+            };
+        }
+
+        if msg.get(tags::SENDER_COMP_ID) != Some(self.counterparty())
+            || msg.get(tags::TARGET_COMP_ID) != Some(self.gateway.config().sender_comp_id.as_str())
+        {
+            self.logout("CompID problem").await?;
+            return Ok(false);
+        }
+
+        if msg.msg_type() == msg_type::SEQUENCE_RESET && !msg.flag(tags::GAP_FILL_FLAG) {
+            let new_seq = msg.require(tags::NEW_SEQ_NO)?.parse::<u64>()?;
+            if new_seq > self.seq.next_in {
+                self.seq.next_in = new_seq;
+                self.save_seq()?;
+            }
+            return Ok(true);
+        }
+
+        let seq = msg.seq_num()?;
+        if seq > self.seq.next_in {
+            if self.resend_until.is_none() {
+                self.request_resend(seq).await?;
+            }
+            return Ok(true);
+        }
+        if seq < self.seq.next_in {
+            if msg.flag(tags::POSS_DUP_FLAG) {
+                return Ok(true);
+            }
+            let text = format!(
+                "MsgSeqNum too low, expecting {} but received {}",
+                self.seq.next_in, seq
+            );
+            self.logout(&text).await?;
+            return Ok(false);
+        }
+
+        self.seq.next_in += 1;
+        if self
+            .resend_until
+            .is_some_and(|until| self.seq.next_in > until)
+        {
+            self.resend_until = None;
+        }
+        self.save_seq()?;
+
+        match msg.msg_type() {
+            msg_type::HEARTBEAT | msg_type::REJECT | msg_type::LOGON => {}
+            msg_type::TEST_REQUEST => {
+                let mut heartbeat = FixMessage::new(msg_type::HEARTBEAT);
+                if let Some(id) = msg.get(tags::TEST_REQ_ID) {
+                    heartbeat.set(tags::TEST_REQ_ID, id);
+                }
+                self.send(heartbeat).await?;
+            }
+            msg_type::RESEND_REQUEST => self.on_resend_request(&msg).await?,
+            msg_type::SEQUENCE_RESET => {
+                let new_seq = msg.require(tags::NEW_SEQ_NO)?.parse::<u64>()?;
+                if new_seq > self.seq.next_in {
+                    self.seq.next_in = new_seq;
+                    self.save_seq()?;
+                }
+            }
+            msg_type::LOGOUT => {
+                self.send(FixMessage::new(msg_type::LOGOUT)).await?;
+                return Ok(false);
+            }
+            msg_type::NEW_ORDER_SINGLE
+            | msg_type::ORDER_CANCEL_REQUEST
+            | msg_type::ORDER_CANCEL_REPLACE_REQUEST => {
+                let counterparty = self.counterparty().to_string();
+                self.gateway.on_application(&counterparty, msg).await;
+            }
+            other => {
+                let reject = FixMessage::new(msg_type::REJECT)
+                    .with(tags::REF_SEQ_NUM, seq)
+                    .with(tags::SESSION_REJECT_REASON, 11)
+                    .with(tags::TEXT, format!("unsupported MsgType {}", other));
+                self.send(reject).await?;
+            }
+        }
+        Ok(true)
+    }
+
+    async fn on_logon(
+        &mut self,
+        msg: FixMessage,
+        tx: mpsc::UnboundedSender<FixMessage>,
+    ) -> anyhow::Result<bool> {
+        let config = self.gateway.config();
+        let counterparty = msg.require(tags::SENDER_COMP_ID)?.to_string();
+        if msg.get(tags::TARGET_COMP_ID) != Some(config.sender_comp_id.as_str())
+            || !config.target_comp_ids.contains(&counterparty)
+        {
+            warn!("[fix] rejected Logon from {}", counterparty);
+            return Ok(false);
+        }
+
+        let reset = msg.flag(tags::RESET_SEQ_NUM_FLAG);
+        let seq = msg.seq_num()?;
+        let stored = self.gateway.seq_nums(&counterparty);
+        if !reset && seq < stored.next_in {
+            warn!(
+                "[fix] Logon from {} with MsgSeqNum {} below expected {}",
+                counterparty, seq, stored.next_in
+            );
+            return Ok(false);
+        }
+        let outbox = match self.gateway.attach(&counterparty, tx) {
+            Ok(outbox) => outbox,
+            Err(err) => {
+                warn!("[fix] {}", err);
+                return Ok(false);
+            }
+        };
+
+        self.counterparty = Some(counterparty.clone());
+        self.seq = stored;
+        if reset {
+            self.seq = SeqNums::default();
+            self.gateway.reset_session(&counterparty);
+        }
+        if let Some(secs) = msg.get_u64(tags::HEART_BT_INT)? {
+            self.heartbeat = Duration::from_secs(secs.max(1));
+        }
+
+        let mut logon = FixMessage::new(msg_type::LOGON)
+            .with(tags::ENCRYPT_METHOD, 0)
+            .with(tags::HEART_BT_INT, self.heartbeat.as_secs());
+        if reset {
+            logon.set(tags::RESET_SEQ_NUM_FLAG, "Y");
+        }
+        self.send(logon).await?;
+        info!("[fix] {} logged on", counterparty);
+
+        if seq > self.seq.next_in {
+            self.request_resend(seq).await?;
+        } else {
+            self.seq.next_in += 1;
+            self.save_seq()?;
+        }
+
+        for msg in outbox {
+            self.send(msg).await?;
+        }
+        Ok(true)
+    }
+
+    async fn on_timer(&mut self) -> anyhow::Result<bool> {
+        if self.counterparty.is_none() {
+            return Ok(self.connected_at.elapsed() < self.heartbeat);
+        }
+        if self.last_sent.elapsed() >= self.heartbeat {
+            self.send(FixMessage::new(msg_type::HEARTBEAT)).await?;
+        }
+        let grace = self.heartbeat + self.heartbeat / 5;
+        match self.test_request_sent {
+            None if self.last_recv.elapsed() >= grace => {
+                let request = FixMessage::new(msg_type::TEST_REQUEST)
+                    .with(tags::TEST_REQ_ID, utc_timestamp());
+                self.send(request).await?;
+                self.test_request_sent = Some(Instant::now());
+            }
+            Some(sent) if sent.elapsed() >= self.heartbeat => {
+                self.logout("heartbeat timeout").await?;
+                return Ok(false);
+            }
+            _ => {}
+        }
+        Ok(true)
+    }
+
+    async fn request_resend(&mut self, received: u64) -> anyhow::Result<()> {
+        let request = FixMessage::new(msg_type::RESEND_REQUEST)
+            .with(tags::BEGIN_SEQ_NO, self.seq.next_in)
+            .with(tags::END_SEQ_NO, 0);
+        self.resend_until = Some(received);
+        self.send(request).await
+    }
+
+    // Application messages still in memory are replayed as PossDup; admin
+    // messages and anything older are covered by SequenceReset-GapFill.
+    async fn on_resend_request(&mut self, msg: &FixMessage) -> anyhow::Result<()> {
+        let last = self.seq.next_out - 1;
+        let begin = msg.get_u64(tags::BEGIN_SEQ_NO)?.unwrap_or(1).max(1);
+        let end = match msg.get_u64(tags::END_SEQ_NO)?.unwrap_or(0) {
+            0 => last,
+            end => end.min(last),
+        };
+        if begin > end {
+            return Ok(());
+        }
+
+        let sent = self.gateway.sent_range(self.counterparty(), begin, end);
+        let mut gap_start: Option<u64> = None;
+        for seq in begin..=end {
+            match sent.get(&seq) {
+                Some(original) => {
+                    if let Some(start) = gap_start.take() {
+                        self.gap_fill(start, seq).await?;
+                    }
+                    let replay = original
+                        .message
+                        .clone()
+                        .with(tags::POSS_DUP_FLAG, "Y")
+                        .with(tags::ORIG_SENDING_TIME, &original.sending_time);
+                    self.write(replay, seq).await?;
+                }
+                None => {
+                    gap_start.get_or_insert(seq);
+                }
+            }
+        }
+        if let Some(start) = gap_start {
+            self.gap_fill(start, end + 1).await?;
+        }
+        Ok(())
+    }
+
+    async fn gap_fill(&mut self, seq: u64, new_seq: u64) -> anyhow::Result<()> {
+        let fill = FixMessage::new(msg_type::SEQUENCE_RESET)
+            .with(tags::POSS_DUP_FLAG, "Y")
+            .with(tags::GAP_FILL_FLAG, "Y")
+            .with(tags::NEW_SEQ_NO, new_seq);
+        self.write(fill, seq).await?;
+        Ok(())
+    }
+
+    async fn logout(&mut self, text: &str) -> anyhow::Result<()> {
+        warn!("[fix] logout {}: {}", self.counterparty(), text);
+        self.send(FixMessage::new(msg_type::LOGOUT).with(tags::TEXT, text))
+            .await
+    }
+
+    async fn send(&mut self, msg: FixMessage) -> anyhow::Result<()> {
+        let seq = self.seq.next_out;
+        let sending_time = self.write(msg.clone(), seq).await?;
+        if !msg_type::is_admin(msg.msg_type()) {
+            self.gateway
+                .record_sent(self.counterparty(), seq, msg, sending_time);
+        }
+        self.seq.next_out += 1;
+        self.save_seq()
+    }
+
+    async fn write(&mut self, msg: FixMessage, seq: u64) -> anyhow::Result<String> {
+        let sending_time = utc_timestamp();
+        let stamped = msg.stamped(
+            &self.gateway.config().sender_comp_id,
+            self.counterparty(),
+            seq,
+            &sending_time,
+        );
+        let Some(writer) = self.writer.as_mut() else {
+            bail!("session is not connected");
+        };
+        writer.write_all(&stamped.encode()).await?;
+        self.last_sent = Instant::now();
+        Ok(sending_time)
+    }
+
+    fn save_seq(&self) -> anyhow::Result<()> {
+        match &self.counterparty {
+            Some(counterparty) => self.gateway.save_seq_nums(counterparty, self.seq),
+            None => Ok(()),
+        }
+    }
+}
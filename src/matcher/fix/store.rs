@@ -0,0 +1,76 @@
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SeqNums {
+    pub next_in: u64,
+    pub next_out: u64,
+}
+
+impl Default for SeqNums {
+    fn default() -> Self {
+        Self {
+            next_in: 1,
+            next_out: 1,
+        }
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct StoreData {
+    sessions: HashMap<String, SeqNums>,
+    next_order_id: Option<u64>,
+}
+
+// Sequence numbers per counterparty plus the gateway's order id cursor, written
+// through to a JSON file so a restarted gateway resumes the same sessions.
+pub struct FixStore {
+    path: PathBuf,
+    data: StoreData,
+}
+
+impl FixStore {
+    pub fn open<P: AsRef<Path>>(path: P) -> anyhow::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let data = match fs::read_to_string(&path) {
+            Ok(raw) => serde_json::from_str(&raw)?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => StoreData::default(),
+            Err(e) => return Err(e.into()),
+        };
+        Ok(Self { path, data })
+    }
+
+    pub fn seq_nums(&self, session: &str) -> SeqNums {
+        self.data.sessions.get(session).copied().unwrap_or_default()
+    }
+
+    pub fn set_seq_nums(&mut self, session: &str, seq: SeqNums) -> anyhow::Result<()> {
+        if self.data.sessions.get(session) == Some(&seq) {
+            return Ok(());
+        }
+        self.data.sessions.insert(session.to_string(), seq);
+        self.flush()
+    }
+
+    pub fn allocate_order_id(&mut self, first: u64) -> anyhow::Result<u64> {
+        let id = self.data.next_order_id.unwrap_or(first);
+        self.data.next_order_id = Some(id + 1);
+        self.flush()?;
+        Ok(id)
+    }
+
+    fn flush(&self) -> anyhow::Result<()> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let tmp = self.path.with_extension("tmp");
+        fs::write(&tmp, serde_json::to_vec_pretty(&self.data)?)?;
+        fs::rename(&tmp, &self.path)?;
+        Ok(())
+    }
+}
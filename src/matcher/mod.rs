@@ -2,7 +2,9 @@ pub mod book;
 pub mod domain;
 pub mod engine;
 pub mod executor;
+pub mod fix;
 pub mod policy;
 pub mod runtime;
+pub mod simulation;
 pub mod storage;
 pub mod strategies;
\ No newline at end of file
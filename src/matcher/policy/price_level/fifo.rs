@@ -78,4 +78,12 @@ impl PriceLevelPolicy for FifoPriceLevel {
         }
         Result::Ok(AllocationResult::new(out, filled, done_ids))
     }
+
+    fn position(&self, id: u64) -> anyhow::Result<Option<usize>> {
+        Ok(self.orders.iter().position(|x| x.id == id))
+    }
+
+    fn order_count(&self) -> anyhow::Result<usize> {
+        Ok(self.orders.len())
+    }
 }
@@ -39,4 +39,12 @@ impl PriceLevelPolicy for MakerPriceLevel {
     fn allocate(&mut self, want: QtyLots) -> anyhow::Result<AllocationResult> {
         self.inner.allocate(want)
     }
+
+    fn position(&self, id: u64) -> anyhow::Result<Option<usize>> {
+        self.inner.position(id)
+    }
+
+    fn order_count(&self) -> anyhow::Result<usize> {
+        self.inner.order_count()
+    }
 }
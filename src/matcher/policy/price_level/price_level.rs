@@ -7,4 +7,6 @@ pub trait PriceLevelPolicy: Clone {
     fn cancel(&mut self, id: u64) -> anyhow::Result<bool>;
     fn total(&self) -> anyhow::Result<QtyLots>;
     fn allocate(&mut self, want: QtyLots) -> anyhow::Result<AllocationResult>;
+    fn position(&self, id: u64) -> anyhow::Result<Option<usize>>;
+    fn order_count(&self) -> anyhow::Result<usize>;
 }
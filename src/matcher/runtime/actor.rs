@@ -241,6 +241,36 @@ where
                     let _ = tx.send(res);
                 }
             }
+            Cmd::QueryOrder { id, resp } => {
+                let res = self.book.order_state(id);
+                if let Some(tx) = resp {
+                    let _ = tx.send(res);
+                }
+            }
+            Cmd::ListOpenOrders { side, resp } => {
+                let res = self.book.open_orders(side);
+                if let Some(tx) = resp {
+                    let _ = tx.send(res);
+                }
+            }
+            Cmd::Depth { levels, resp } => {
+                let res = self.book.depth(levels);
+                if let Some(tx) = resp {
+                    let _ = tx.send(res);
+                }
+            }
+            Cmd::Top { resp } => {
+                let res = self.book.top_of_book();
+                if let Some(tx) = resp {
+                    let _ = tx.send(res);
+                }
+            }
+            Cmd::FillEstimate { side, qty, resp } => {
+                let res = self.book.fill_estimate(side, qty);
+                if let Some(tx) = resp {
+                    let _ = tx.send(res);
+                }
+            }
         }
         Result::Ok(())
     }
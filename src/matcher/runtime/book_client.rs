@@ -1,7 +1,16 @@
 use tokio::sync::{mpsc, oneshot};
 
 use crate::matcher::{
-    domain::{book_info::BookInfo, execution_result::ExecutionResult, order::Order},
+    domain::{
+        book_depth::BookDepth,
+        book_info::BookInfo,
+        book_top::BookTop,
+        execution_result::ExecutionResult,
+        fill_estimate::FillEstimate,
+        order::{Order, OrderSide},
+        order_state::OrderState,
+        qty_lots::QtyLots,
+    },
     runtime::cmd::Cmd,
 };
 
@@ -37,4 +46,57 @@ impl BookClient {
         self.tx.send(Cmd::Cancel { id, resp: Some(tx) }).await?;
         rx.await?
     }
+
+    pub async fn query_order(&self, id: u64) -> anyhow::Result<Option<OrderState>> {
+        let (tx, rx) = oneshot::channel();
+        self.tx.send(Cmd::QueryOrder { id, resp: Some(tx) }).await?;
+        rx.await?
+    }
+
+    pub async fn list_open_orders(
+        &self,
+        side: Option<OrderSide>,
+    ) -> anyhow::Result<Vec<OrderState>> {
+        let (tx, rx) = oneshot::channel();
+        self.tx
+            .send(Cmd::ListOpenOrders {
+                side,
+                resp: Some(tx),
+            })
+            .await?;
+        rx.await?
+    }
+
+    pub async fn depth(&self, levels: usize) -> anyhow::Result<BookDepth> {
+        let (tx, rx) = oneshot::channel();
+        self.tx
+            .send(Cmd::Depth {
+                levels,
+                resp: Some(tx),
+            })
+            .await?;
+        rx.await?
+    }
+
+    pub async fn top_of_book(&self) -> anyhow::Result<BookTop> {
+        let (tx, rx) = oneshot::channel();
+        self.tx.send(Cmd::Top { resp: Some(tx) }).await?;
+        rx.await?
+    }
+
+    pub async fn fill_estimate(
+        &self,
+        side: OrderSide,
+        qty: QtyLots,
+    ) -> anyhow::Result<FillEstimate> {
+        let (tx, rx) = oneshot::channel();
+        self.tx
+            .send(Cmd::FillEstimate {
+                side,
+                qty,
+                resp: Some(tx),
+            })
+            .await?;
+        rx.await?
+    }
 }
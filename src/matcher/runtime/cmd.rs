@@ -1,7 +1,14 @@
 use tokio::sync::oneshot;
 
 use crate::matcher::domain::{
-    book_info::BookInfo, execution_result::ExecutionResult, order::Order,
+    book_depth::BookDepth,
+    book_info::BookInfo,
+    book_top::BookTop,
+    execution_result::ExecutionResult,
+    fill_estimate::FillEstimate,
+    order::{Order, OrderSide},
+    order_state::OrderState,
+    qty_lots::QtyLots,
 };
 
 pub enum Cmd {
@@ -16,4 +23,24 @@ pub enum Cmd {
         id: u64,
         resp: Option<oneshot::Sender<anyhow::Result<bool>>>,
     },
+    QueryOrder {
+        id: u64,
+        resp: Option<oneshot::Sender<anyhow::Result<Option<OrderState>>>>,
+    },
+    ListOpenOrders {
+        side: Option<OrderSide>,
+        resp: Option<oneshot::Sender<anyhow::Result<Vec<OrderState>>>>,
+    },
+    Depth {
+        levels: usize,
+        resp: Option<oneshot::Sender<anyhow::Result<BookDepth>>>,
+    },
+    Top {
+        resp: Option<oneshot::Sender<anyhow::Result<BookTop>>>,
+    },
+    FillEstimate {
+        side: OrderSide,
+        qty: QtyLots,
+        resp: Option<oneshot::Sender<anyhow::Result<FillEstimate>>>,
+    },
 }
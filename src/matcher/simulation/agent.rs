@@ -0,0 +1,59 @@
+use crate::matcher::simulation::{
+    context::AgentContext, informed_trader::InformedTrader, market_maker::LayeredMarketMaker,
+    momentum_taker::MomentumTaker, noise_trader::NoiseTrader, scenario::AgentConfig,
+};
+
+pub enum SimAgent {
+    MarketMaker(LayeredMarketMaker),
+    NoiseTrader(NoiseTrader),
+    MomentumTaker(MomentumTaker),
+    InformedTrader(InformedTrader),
+}
+
+impl SimAgent {
+    pub fn from_config(config: &AgentConfig, mut next_seed: impl FnMut() -> u64) -> Vec<SimAgent> {
+        match config {
+            AgentConfig::MarketMaker { count, params } => (0..*count)
+                .map(|_| {
+                    SimAgent::MarketMaker(LayeredMarketMaker::new(params.clone(), next_seed()))
+                })
+                .collect(),
+            AgentConfig::NoiseTrader { count, params } => (0..*count)
+                .map(|_| SimAgent::NoiseTrader(NoiseTrader::new(params.clone(), next_seed())))
+                .collect(),
+            AgentConfig::MomentumTaker { count, params } => (0..*count)
+                .map(|_| SimAgent::MomentumTaker(MomentumTaker::new(params.clone())))
+                .collect(),
+            AgentConfig::InformedTrader { count, params } => (0..*count)
+                .map(|_| SimAgent::InformedTrader(InformedTrader::new(params.clone(), next_seed())))
+                .collect(),
+        }
+    }
+
+    pub fn kind(&self) -> &'static str {
+        match self {
+            SimAgent::MarketMaker(_) => "market_maker",
+            SimAgent::NoiseTrader(_) => "noise_trader",
+            SimAgent::MomentumTaker(_) => "momentum_taker",
+            SimAgent::InformedTrader(_) => "informed_trader",
+        }
+    }
+
+    pub fn position(&self) -> i64 {
+        match self {
+            SimAgent::MarketMaker(a) => a.inventory(),
+            SimAgent::NoiseTrader(a) => a.position(),
+            SimAgent::MomentumTaker(a) => a.position(),
+            SimAgent::InformedTrader(a) => a.position(),
+        }
+    }
+
+    pub async fn step(&mut self, ctx: &mut AgentContext<'_>) -> anyhow::Result<()> {
+        match self {
+            SimAgent::MarketMaker(a) => a.step(ctx).await,
+            SimAgent::NoiseTrader(a) => a.step(ctx).await,
+            SimAgent::MomentumTaker(a) => a.step(ctx).await,
+            SimAgent::InformedTrader(a) => a.step(ctx).await,
+        }
+    }
+}
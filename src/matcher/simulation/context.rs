@@ -0,0 +1,142 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use serde::{Deserialize, Serialize};
+
+use crate::matcher::{
+    domain::{
+        book_top::BookTop,
+        execution_event::ExecutionEvent,
+        execution_result::ExecutionResult,
+        order::{Order, OrderSide, OrderType},
+        price_ticks::PriceTicks,
+        qty_lots::QtyLots,
+        time_in_force::TimeInForce,
+    },
+    runtime::book_client::BookClient,
+};
+
+pub struct OrderIdGen {
+    next: AtomicU64,
+}
+
+impl OrderIdGen {
+    pub fn new(first: u64) -> Self {
+        Self {
+            next: AtomicU64::new(first),
+        }
+    }
+
+    pub fn next_id(&self) -> u64 {
+        self.next.fetch_add(1, Ordering::Relaxed)
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SimStats {
+    pub orders_placed: u64,
+    pub orders_cancelled: u64,
+    pub orders_rejected: u64,
+    pub trades: u64,
+    pub traded_qty: i64,
+}
+
+pub struct AgentContext<'a> {
+    pub client: &'a BookClient,
+    pub ids: &'a OrderIdGen,
+    pub stats: &'a mut SimStats,
+    pub top: &'a BookTop,
+    pub mid: f64,
+    pub fundamental: f64,
+    pub step: u64,
+}
+
+impl AgentContext<'_> {
+    pub fn best_bid(&self) -> Option<PriceTicks> {
+        self.top.best_bid.as_ref().map(|l| l.price)
+    }
+
+    pub fn best_ask(&self) -> Option<PriceTicks> {
+        self.top.best_ask.as_ref().map(|l| l.price)
+    }
+
+    pub async fn place_limit(
+        &mut self,
+        side: OrderSide,
+        px: i64,
+        qty: i64,
+        tif: TimeInForce,
+    ) -> anyhow::Result<(u64, ExecutionResult)> {
+        let id = self.ids.next_id();
+        let order = Order {
+            id,
+            order_type: OrderType::Limit,
+            tif,
+            side,
+            px: PriceTicks(px.max(1)),
+            qty: QtyLots(qty),
+        };
+        let result = self.client.place_order(order).await?;
+        self.stats.orders_placed += 1;
+        for event in &result.events {
+            match event {
+                ExecutionEvent::Traded { qty, .. } => {
+                    self.stats.trades += 1;
+                    self.stats.traded_qty += qty.0;
+                }
+                ExecutionEvent::Rejected { .. } => self.stats.orders_rejected += 1,
+                _ => {}
+            }
+        }
+        Ok((id, result))
+    }
+
+    pub async fn take(
+        &mut self,
+        side: OrderSide,
+        qty: i64,
+        slippage_ticks: i64,
+    ) -> anyhow::Result<QtyLots> {
+        let px = match side {
+            OrderSide::Buy => match self.best_ask() {
+                Some(ask) => ask.0 + slippage_ticks.max(1),
+                None => return Ok(QtyLots::zero()),
+            },
+            OrderSide::Sell => match self.best_bid() {
+                Some(bid) => bid.0 - slippage_ticks.max(1),
+                None => return Ok(QtyLots::zero()),
+            },
+        };
+        let (_, result) = self.place_limit(side, px, qty, TimeInForce::IOC).await?;
+        Ok(taker_filled(&result))
+    }
+
+    pub async fn cancel(&mut self, id: u64) -> anyhow::Result<bool> {
+        let removed = self.client.cancel_order(id).await?;
+        if removed {
+            self.stats.orders_cancelled += 1;
+        }
+        Ok(removed)
+    }
+}
+
+pub fn taker_filled(result: &ExecutionResult) -> QtyLots {
+    let mut filled = QtyLots::zero();
+    for event in &result.events {
+        match event {
+            ExecutionEvent::Traded {
+                taker_order_id,
+                qty,
+                ..
+            } if *taker_order_id == result.order.id => filled += *qty,
+            _ => {}
+        }
+    }
+    filled
+}
+
+pub fn signed(side: OrderSide, qty: QtyLots) -> i64 {
+    match side {
+        OrderSide::Buy => qty.0,
+        OrderSide::Sell => -qty.0,
+    }
+}
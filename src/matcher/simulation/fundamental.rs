@@ -0,0 +1,40 @@
+use rand::{Rng, SeedableRng, rngs::StdRng};
+
+use crate::matcher::simulation::scenario::FundamentalConfig;
+
+pub struct FundamentalProcess {
+    config: FundamentalConfig,
+    value: f64,
+    rng: StdRng,
+}
+
+impl FundamentalProcess {
+    pub fn new(config: FundamentalConfig, initial: f64, seed: u64) -> Self {
+        Self {
+            config,
+            value: initial,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    pub fn value(&self) -> f64 {
+        self.value
+    }
+
+    pub fn advance(&mut self) -> f64 {
+        let mut delta = self.config.drift_ticks + self.config.volatility_ticks * self.gaussian();
+        if self.config.jump_prob > 0.0 && self.rng.gen_bool(self.config.jump_prob.min(1.0)) {
+            let sign = if self.rng.gen_bool(0.5) { 1.0 } else { -1.0 };
+            delta += sign * self.config.jump_ticks;
+        }
+        self.value = (self.value + delta).max(1.0);
+        self.value
+    }
+
+    fn gaussian(&mut self) -> f64 {
+        // Box-Muller; rand 0.8 ships no normal distribution without rand_distr.
+        let u1: f64 = self.rng.gen_range(f64::EPSILON..1.0);
+        let u2: f64 = self.rng.gen_range(0.0..1.0);
+        (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos()
+    }
+}
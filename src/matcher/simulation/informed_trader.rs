@@ -0,0 +1,59 @@
+use rand::{Rng, SeedableRng, rngs::StdRng};
+
+use crate::matcher::{
+    domain::{order::OrderSide, time_in_force::TimeInForce},
+    simulation::{
+        context::{AgentContext, signed, taker_filled},
+        scenario::InformedTraderConfig,
+    },
+};
+
+pub struct InformedTrader {
+    config: InformedTraderConfig,
+    rng: StdRng,
+    position: i64,
+}
+
+impl InformedTrader {
+    pub fn new(config: InformedTraderConfig, seed: u64) -> Self {
+        Self {
+            config,
+            rng: StdRng::seed_from_u64(seed),
+            position: 0,
+        }
+    }
+
+    pub fn position(&self) -> i64 {
+        self.position
+    }
+
+    pub async fn step(&mut self, ctx: &mut AgentContext<'_>) -> anyhow::Result<()> {
+        if !self.rng.gen_bool(self.config.activity.clamp(0.0, 1.0)) {
+            return Ok(());
+        }
+
+        // Only trade against quotes that are mispriced relative to the fundamental,
+        // and never pay through it.
+        let (side, px) = match (ctx.best_bid(), ctx.best_ask()) {
+            (_, Some(ask))
+                if ctx.fundamental - ask.0 as f64 >= self.config.threshold_ticks
+                    && self.position < self.config.max_position =>
+            {
+                (OrderSide::Buy, ctx.fundamental.floor() as i64 + 1)
+            }
+            (Some(bid), _)
+                if bid.0 as f64 - ctx.fundamental >= self.config.threshold_ticks
+                    && self.position > -self.config.max_position =>
+            {
+                (OrderSide::Sell, ctx.fundamental.ceil() as i64)
+            }
+            _ => return Ok(()),
+        };
+
+        let (_, result) = ctx
+            .place_limit(side, px, self.config.qty, TimeInForce::IOC)
+            .await?;
+        self.position += signed(side, taker_filled(&result));
+        Ok(())
+    }
+}
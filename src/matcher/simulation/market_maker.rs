@@ -0,0 +1,108 @@
+use rand::{Rng, SeedableRng, rngs::StdRng};
+
+use crate::matcher::{
+    domain::{order::OrderSide, qty_lots::QtyLots, time_in_force::TimeInForce},
+    simulation::{
+        context::{AgentContext, signed, taker_filled},
+        scenario::MarketMakerConfig,
+    },
+};
+
+struct Quote {
+    id: u64,
+    side: OrderSide,
+    seen_filled: QtyLots,
+}
+
+pub struct LayeredMarketMaker {
+    config: MarketMakerConfig,
+    rng: StdRng,
+    inventory: i64,
+    quotes: Vec<Quote>,
+    next_refresh: u64,
+}
+
+impl LayeredMarketMaker {
+    pub fn new(config: MarketMakerConfig, seed: u64) -> Self {
+        Self {
+            config,
+            rng: StdRng::seed_from_u64(seed),
+            inventory: 0,
+            quotes: Vec::new(),
+            next_refresh: 0,
+        }
+    }
+
+    pub fn inventory(&self) -> i64 {
+        self.inventory
+    }
+
+    pub async fn step(&mut self, ctx: &mut AgentContext<'_>) -> anyhow::Result<()> {
+        self.reconcile(ctx).await?;
+        if ctx.step < self.next_refresh {
+            return Ok(());
+        }
+        self.next_refresh = ctx.step + self.config.refresh_steps.max(1);
+
+        for quote in std::mem::take(&mut self.quotes) {
+            ctx.cancel(quote.id).await?;
+        }
+
+        let reservation = ctx.mid - self.inventory as f64 * self.config.skew_ticks_per_lot;
+        for level in 0..self.config.levels {
+            let offset = (self.config.half_spread_ticks
+                + level as i64 * self.config.level_step_ticks) as f64;
+            let jitter = if self.config.qty_jitter > 0 {
+                self.rng.gen_range(0..=self.config.qty_jitter)
+            } else {
+                0
+            };
+            let qty = self.config.base_qty + level as i64 * self.config.qty_step + jitter;
+
+            if self.inventory < self.config.max_inventory {
+                let px = (reservation - offset).floor() as i64;
+                self.quote(ctx, OrderSide::Buy, px, qty).await?;
+            }
+            if self.inventory > -self.config.max_inventory {
+                let px = (reservation + offset).ceil() as i64;
+                self.quote(ctx, OrderSide::Sell, px, qty).await?;
+            }
+        }
+        Ok(())
+    }
+
+    async fn quote(
+        &mut self,
+        ctx: &mut AgentContext<'_>,
+        side: OrderSide,
+        px: i64,
+        qty: i64,
+    ) -> anyhow::Result<()> {
+        let (id, result) = ctx.place_limit(side, px, qty, TimeInForce::GTC).await?;
+        let filled = taker_filled(&result);
+        self.inventory += signed(side, filled);
+        self.quotes.push(Quote {
+            id,
+            side,
+            seen_filled: filled,
+        });
+        Ok(())
+    }
+
+    async fn reconcile(&mut self, ctx: &mut AgentContext<'_>) -> anyhow::Result<()> {
+        let mut open = Vec::with_capacity(self.quotes.len());
+        for mut quote in std::mem::take(&mut self.quotes) {
+            let Some(state) = ctx.client.query_order(quote.id).await? else {
+                continue;
+            };
+            let delta = state.filled_qty - quote.seen_filled;
+            self.inventory += signed(quote.side, delta);
+            quote.seen_filled = state.filled_qty;
+            if state.status.is_open() {
+                open.push(quote);
+            }
+        }
+        self.quotes = open;
+        Ok(())
+    }
+}
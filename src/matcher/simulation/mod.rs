@@ -0,0 +1,9 @@
+pub mod agent;
+pub mod context;
+pub mod fundamental;
+pub mod informed_trader;
+pub mod market_maker;
+pub mod momentum_taker;
+pub mod noise_trader;
+pub mod scenario;
+pub mod simulator;
@@ -0,0 +1,68 @@
+use std::collections::VecDeque;
+
+use crate::matcher::{
+    domain::order::OrderSide,
+    simulation::{
+        context::{AgentContext, signed},
+        scenario::MomentumTakerConfig,
+    },
+};
+
+pub struct MomentumTaker {
+    config: MomentumTakerConfig,
+    mids: VecDeque<f64>,
+    position: i64,
+    last_trade_step: Option<u64>,
+}
+
+impl MomentumTaker {
+    pub fn new(config: MomentumTakerConfig) -> Self {
+        Self {
+            mids: VecDeque::with_capacity(config.lookback + 1),
+            config,
+            position: 0,
+            last_trade_step: None,
+        }
+    }
+
+    pub fn position(&self) -> i64 {
+        self.position
+    }
+
+    pub async fn step(&mut self, ctx: &mut AgentContext<'_>) -> anyhow::Result<()> {
+        self.mids.push_back(ctx.mid);
+        if self.mids.len() > self.config.lookback {
+            self.mids.pop_front();
+        }
+        if self.mids.len() < self.config.lookback {
+            return Ok(());
+        }
+        if self
+            .last_trade_step
+            .is_some_and(|last| ctx.step < last + self.config.cooldown_steps)
+        {
+            return Ok(());
+        }
+
+        let change = ctx.mid - self.mids.front().copied().unwrap_or(ctx.mid);
+        let side =
+            if change >= self.config.threshold_ticks && self.position < self.config.max_position {
+                OrderSide::Buy
+            } else if change <= -self.config.threshold_ticks
+                && self.position > -self.config.max_position
+            {
+                OrderSide::Sell
+            } else {
+                return Ok(());
+            };
+
+        let filled = ctx
+            .take(side, self.config.qty, self.config.slippage_ticks)
+            .await?;
+        if !filled.is_zero() {
+            self.position += signed(side, filled);
+            self.last_trade_step = Some(ctx.step);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,102 @@
+use rand::{Rng, SeedableRng, rngs::StdRng};
+
+use crate::matcher::{
+    domain::{order::OrderSide, qty_lots::QtyLots, time_in_force::TimeInForce},
+    simulation::{
+        context::{AgentContext, signed, taker_filled},
+        scenario::NoiseTraderConfig,
+    },
+};
+
+struct RestingOrder {
+    id: u64,
+    side: OrderSide,
+    expires: u64,
+    seen_filled: QtyLots,
+}
+
+pub struct NoiseTrader {
+    config: NoiseTraderConfig,
+    rng: StdRng,
+    position: i64,
+    resting: Vec<RestingOrder>,
+}
+
+impl NoiseTrader {
+    pub fn new(config: NoiseTraderConfig, seed: u64) -> Self {
+        Self {
+            config,
+            rng: StdRng::seed_from_u64(seed),
+            position: 0,
+            resting: Vec::new(),
+        }
+    }
+
+    pub fn position(&self) -> i64 {
+        self.position
+    }
+
+    pub async fn step(&mut self, ctx: &mut AgentContext<'_>) -> anyhow::Result<()> {
+        self.reconcile(ctx).await?;
+
+        if !self.rng.gen_bool(self.config.activity.clamp(0.0, 1.0)) {
+            return Ok(());
+        }
+
+        let side = if self.rng.gen_bool(0.5) {
+            OrderSide::Buy
+        } else {
+            OrderSide::Sell
+        };
+        let qty = self
+            .rng
+            .gen_range(self.config.min_qty..=self.config.max_qty.max(self.config.min_qty));
+        let offset = self.rng.gen_range(1..=self.config.max_offset_ticks.max(1));
+
+        if self
+            .rng
+            .gen_bool(self.config.aggressive_prob.clamp(0.0, 1.0))
+        {
+            let filled = ctx.take(side, qty, offset).await?;
+            self.position += signed(side, filled);
+        } else {
+            let px = match side {
+                OrderSide::Buy => ctx.mid.floor() as i64 - offset,
+                OrderSide::Sell => ctx.mid.ceil() as i64 + offset,
+            };
+            let (id, result) = ctx.place_limit(side, px, qty, TimeInForce::GTC).await?;
+            let filled = taker_filled(&result);
+            self.position += signed(side, filled);
+            self.resting.push(RestingOrder {
+                id,
+                side,
+                expires: ctx.step + self.config.order_ttl_steps,
+                seen_filled: filled,
+            });
+        }
+        Ok(())
+    }
+
+    // 和做市商一样按订单状态补记挂单被动成交的部分，再撤掉到期的挂单
+    async fn reconcile(&mut self, ctx: &mut AgentContext<'_>) -> anyhow::Result<()> {
+        let mut open = Vec::with_capacity(self.resting.len());
+        for mut order in std::mem::take(&mut self.resting) {
+            let Some(state) = ctx.client.query_order(order.id).await? else {
+                continue;
+            };
+            let delta = state.filled_qty - order.seen_filled;
+            self.position += signed(order.side, delta);
+            order.seen_filled = state.filled_qty;
+            if !state.status.is_open() {
+                continue;
+            }
+            if order.expires <= ctx.step {
+                ctx.cancel(order.id).await?;
+            } else {
+                open.push(order);
+            }
+        }
+        self.resting = open;
+        Ok(())
+    }
+}
@@ -0,0 +1,139 @@
+use std::{fs, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScenarioConfig {
+    pub name: String,
+    pub seed: u64,
+    pub steps: Option<u64>,
+    #[serde(default)]
+    pub step_interval_ms: u64,
+    pub initial_mid: i64,
+    #[serde(default = "default_first_order_id")]
+    pub first_order_id: u64,
+    pub fundamental: FundamentalConfig,
+    pub agents: Vec<AgentConfig>,
+}
+
+fn default_first_order_id() -> u64 {
+    1_000_000
+}
+
+impl ScenarioConfig {
+    pub fn from_file<P: AsRef<Path>>(path: P) -> anyhow::Result<Self> {
+        let raw = fs::read_to_string(path.as_ref())?;
+        Self::from_json(&raw)
+    }
+
+    pub fn from_json(raw: &str) -> anyhow::Result<Self> {
+        let config: ScenarioConfig = serde_json::from_str(raw)?;
+        if config.initial_mid <= 0 {
+            anyhow::bail!("initial_mid must be positive, got {}", config.initial_mid);
+        }
+        Ok(config)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FundamentalConfig {
+    pub volatility_ticks: f64,
+    #[serde(default)]
+    pub drift_ticks: f64,
+    #[serde(default)]
+    pub jump_prob: f64,
+    #[serde(default)]
+    pub jump_ticks: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AgentConfig {
+    MarketMaker {
+        #[serde(default = "default_count")]
+        count: usize,
+        #[serde(flatten)]
+        params: MarketMakerConfig,
+    },
+    NoiseTrader {
+        #[serde(default = "default_count")]
+        count: usize,
+        #[serde(flatten)]
+        params: NoiseTraderConfig,
+    },
+    MomentumTaker {
+        #[serde(default = "default_count")]
+        count: usize,
+        #[serde(flatten)]
+        params: MomentumTakerConfig,
+    },
+    InformedTrader {
+        #[serde(default = "default_count")]
+        count: usize,
+        #[serde(flatten)]
+        params: InformedTraderConfig,
+    },
+}
+
+fn default_count() -> usize {
+    1
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MarketMakerConfig {
+    pub levels: usize,
+    pub half_spread_ticks: i64,
+    pub level_step_ticks: i64,
+    pub base_qty: i64,
+    #[serde(default)]
+    pub qty_step: i64,
+    #[serde(default)]
+    pub qty_jitter: i64,
+    pub skew_ticks_per_lot: f64,
+    pub max_inventory: i64,
+    #[serde(default = "default_refresh_steps")]
+    pub refresh_steps: u64,
+}
+
+fn default_refresh_steps() -> u64 {
+    1
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NoiseTraderConfig {
+    pub activity: f64,
+    pub min_qty: i64,
+    pub max_qty: i64,
+    pub aggressive_prob: f64,
+    pub max_offset_ticks: i64,
+    #[serde(default = "default_order_ttl_steps")]
+    pub order_ttl_steps: u64,
+}
+
+fn default_order_ttl_steps() -> u64 {
+    50
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MomentumTakerConfig {
+    pub lookback: usize,
+    pub threshold_ticks: f64,
+    pub qty: i64,
+    pub max_position: i64,
+    #[serde(default)]
+    pub cooldown_steps: u64,
+    #[serde(default = "default_slippage_ticks")]
+    pub slippage_ticks: i64,
+}
+
+fn default_slippage_ticks() -> i64 {
+    5
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InformedTraderConfig {
+    pub activity: f64,
+    pub threshold_ticks: f64,
+    pub qty: i64,
+    pub max_position: i64,
+}
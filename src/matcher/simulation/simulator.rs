@@ -0,0 +1,195 @@
+use rand::{Rng, SeedableRng, rngs::StdRng, seq::SliceRandom};
+use serde::{Deserialize, Serialize};
+use tokio::{
+    task::JoinHandle,
+    time::{Duration, sleep},
+};
+
+use crate::matcher::{
+    domain::book_top::BookTop,
+    runtime::book_client::BookClient,
+    simulation::{
+        agent::SimAgent,
+        context::{AgentContext, OrderIdGen, SimStats},
+        fundamental::FundamentalProcess,
+        scenario::ScenarioConfig,
+    },
+};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgentSummary {
+    pub kind: String,
+    pub position: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SimulationReport {
+    pub scenario: String,
+    pub steps: u64,
+    pub stats: SimStats,
+    pub agents: Vec<AgentSummary>,
+    pub final_top: Option<BookTop>,
+    pub final_fundamental: f64,
+}
+
+pub struct Simulator {
+    config: ScenarioConfig,
+    agents: Vec<SimAgent>,
+    fundamental: FundamentalProcess,
+    ids: OrderIdGen,
+    rng: StdRng,
+    stats: SimStats,
+}
+
+impl Simulator {
+    pub fn new(config: ScenarioConfig) -> Self {
+        let mut seeds = StdRng::seed_from_u64(config.seed);
+        let mut next_seed = move || seeds.gen_range(0..u64::MAX);
+
+        let fundamental = FundamentalProcess::new(
+            config.fundamental.clone(),
+            config.initial_mid as f64,
+            next_seed(),
+        );
+        let rng = StdRng::seed_from_u64(next_seed());
+        let agents = config
+            .agents
+            .iter()
+            .flat_map(|agent| SimAgent::from_config(agent, &mut next_seed))
+            .collect();
+
+        Self {
+            ids: OrderIdGen::new(config.first_order_id),
+            config,
+            agents,
+            fundamental,
+            rng,
+            stats: SimStats::default(),
+        }
+    }
+
+    pub fn start(self, client: BookClient) -> JoinHandle<anyhow::Result<SimulationReport>> {
+        tokio::spawn(async move { self.run(&client).await })
+    }
+
+    pub async fn run(mut self, client: &BookClient) -> anyhow::Result<SimulationReport> {
+        let mut order: Vec<usize> = (0..self.agents.len()).collect();
+        let mut last_mid = self.config.initial_mid as f64;
+        let mut step = 0;
+
+        while self.config.steps.is_none_or(|steps| step < steps) {
+            let top = client.top_of_book().await?;
+            if let (Some(bid), Some(ask)) = (&top.best_bid, &top.best_ask) {
+                last_mid = (bid.price.0 + ask.price.0) as f64 / 2.0;
+            }
+
+            order.shuffle(&mut self.rng);
+            for &idx in &order {
+                let mut ctx = AgentContext {
+                    client,
+                    ids: &self.ids,
+                    stats: &mut self.stats,
+                    top: &top,
+                    mid: last_mid,
+                    fundamental: self.fundamental.value(),
+                    step,
+                };
+                self.agents[idx].step(&mut ctx).await?;
+            }
+
+            self.fundamental.advance();
+            step += 1;
+            if self.config.step_interval_ms > 0 {
+                sleep(Duration::from_millis(self.config.step_interval_ms)).await;
+            }
+        }
+
+        Ok(SimulationReport {
+            scenario: self.config.name.clone(),
+            steps: step,
+            stats: self.stats.clone(),
+            agents: self
+                .agents
+                .iter()
+                .map(|a| AgentSummary {
+                    kind: a.kind().to_string(),
+                    position: a.position(),
+                })
+                .collect(),
+            final_top: client.top_of_book().await.ok(),
+            final_fundamental: self.fundamental.value(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::matcher::{
+        book::orderbook::OrderBook,
+        engine::engine::Engine,
+        policy::price_level::fifo::FifoPriceLevel,
+        runtime::{actor::BookActor, book_client::BookClient},
+        simulation::{scenario::ScenarioConfig, simulator::Simulator},
+        storage::localfile_storage::LocalFileStorage,
+    };
+
+    fn fresh_client() -> BookClient {
+        let order_book: OrderBook<FifoPriceLevel, fn() -> FifoPriceLevel> =
+            OrderBook::new(FifoPriceLevel::new);
+        let engine = Engine::new(Arc::new(|_| {}), Arc::new(|_| {}));
+        let (client, _jh) = BookActor::<
+            OrderBook<FifoPriceLevel, fn() -> FifoPriceLevel>,
+            FifoPriceLevel,
+            fn() -> FifoPriceLevel,
+            LocalFileStorage,
+        >::build_actor(order_book, 1024, 3600, engine);
+        client
+    }
+
+    fn scenario(steps: u64) -> ScenarioConfig {
+        let mut config =
+            ScenarioConfig::from_json(include_str!("../../../scenarios/default.json")).unwrap();
+        config.steps = Some(steps);
+        config
+    }
+
+    #[tokio::test]
+    async fn default_scenario_builds_depth_and_trades() {
+        let client = fresh_client();
+        let report = Simulator::new(scenario(300)).run(&client).await.unwrap();
+
+        assert_eq!(300, report.steps);
+        assert!(report.stats.trades > 0);
+        let depth = client.depth(20).await.unwrap();
+        assert!(depth.bids.len() >= 5);
+        assert!(depth.asks.len() >= 5);
+        // 散户的主动成交和挂单被动成交都计入持仓
+        assert!(
+            report
+                .agents
+                .iter()
+                .any(|a| a.kind == "noise_trader" && a.position != 0)
+        );
+    }
+
+    #[tokio::test]
+    async fn same_seed_replays_identically() {
+        let first = Simulator::new(scenario(200))
+            .run(&fresh_client())
+            .await
+            .unwrap();
+        let second = Simulator::new(scenario(200))
+            .run(&fresh_client())
+            .await
+            .unwrap();
+
+        assert_eq!(first.stats.trades, second.stats.trades);
+        assert_eq!(first.stats.traded_qty, second.stats.traded_qty);
+        assert_eq!(
+            first.agents.iter().map(|a| a.position).collect::<Vec<_>>(),
+            second.agents.iter().map(|a| a.position).collect::<Vec<_>>()
+        );
+    }
+}
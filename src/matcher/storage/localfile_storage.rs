@@ -44,6 +44,10 @@ impl LocalFileStorage {
     }
 
     fn list_snapshots_desc(&self) -> anyhow::Result<Vec<PathBuf>> {
+        // 还没保存过快照时目录不存在
+        if !self.root.exists() {
+            return Ok(Vec::new());
+        }
         let mut entries = fs::read_dir(&self.root)?;
 
         let mut files = Vec::new();
@@ -110,7 +114,9 @@ mod tests {
     use crate::matcher::{
         book::{book_manager::OrderBookManager, book_ops::OrderBookOps, orderbook::OrderBook},
         domain::{
+            execution_sequence::ExecutionSequence,
             order::{Order, OrderSide, OrderType},
+            order_status::OrderStatus,
             price_ticks::PriceTicks,
             qty_lots::QtyLots,
             scales::Scales,
@@ -183,4 +189,60 @@ mod tests {
 
         assert_eq!(save_book_info, load_book_info)
     }
+
+    #[test]
+    fn test_order_state_round_trips_snapshot() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = LocalFileStorage::new(dir.path(), 1, "btc-usdt");
+        let factory = || FifoPriceLevel::new();
+        let mut order_book = OrderBook::new(factory);
+        let scales = Scales::new(100, 1000);
+        for id in 0..100 {
+            let order = random_order(id, &scales);
+            order_book.add_order(order).unwrap();
+        }
+        order_book.cancel(7).unwrap();
+
+        let book_manager = OrderBookManager::new(storage, factory);
+        book_manager.save(&order_book).unwrap();
+        let loaded = book_manager.load().unwrap();
+
+        for id in 0..100 {
+            let before = order_book.order_state(id).unwrap().unwrap();
+            let after = loaded.order_state(id).unwrap().unwrap();
+            assert_eq!(before.status, after.status);
+            assert_eq!(before.filled_qty, after.filled_qty);
+            assert_eq!(before.queue_position, after.queue_position);
+            assert_eq!(before.submitted_at, after.submitted_at);
+        }
+        assert_eq!(
+            OrderStatus::Cancelled,
+            loaded.order_state(7).unwrap().unwrap().status
+        );
+    }
+
+    #[test]
+    fn test_execution_sequence_survives_snapshot() {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = LocalFileStorage::new(dir.path(), 1, "btc-usdt");
+        let factory = || FifoPriceLevel::new();
+        let mut order_book = OrderBook::new(factory);
+        let scales = Scales::new(100, 1000);
+        for id in 0..100 {
+            let order = random_order(id, &scales);
+            order_book.add_order(order).unwrap();
+        }
+        *order_book.execution_sequence() = ExecutionSequence {
+            last_trade_id: 42,
+            last_exec_seq: 17,
+        };
+
+        let book_manager = OrderBookManager::new(storage, factory);
+        book_manager.save(&order_book).unwrap();
+        let mut loaded = book_manager.load().unwrap();
+
+        assert_eq!(order_book.sequence(), loaded.sequence());
+        assert_eq!(43, loaded.execution_sequence().next_trade_id());
+        assert_eq!(18, loaded.execution_sequence().next_exec_seq());
+    }
 }
@@ -7,6 +7,10 @@ use crate::matcher::domain::{price_ticks::PriceTicks, qty_lots::QtyLots};
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct TradeTick {
     pub symbol: String,
+    #[serde(default)]
+    pub trade_id: Option<u64>,
+    #[serde(default)]
+    pub exec_seq: Option<u64>,
     pub price: f64,
     pub qty: f64,
     pub ts: i64, // exchange timestamp - ms
@@ -21,6 +25,8 @@ impl Display for TradeTick {
 #[derive(Debug, Clone)]
 pub struct TradeTickInternal {
     pub symbol: String,
+    pub trade_id: u64,
+    pub exec_seq: u64,
     pub price: PriceTicks,
     pub qty: QtyLots,
     pub ts: i64, // exchange timestamp - ms
@@ -30,6 +36,8 @@ impl TradeTickInternal {
     pub fn to_f64(&self, tick_size: f64, lot_size: f64) -> TradeTick {
         TradeTick {
             symbol: self.symbol.clone(),
+            trade_id: Some(self.trade_id),
+            exec_seq: Some(self.exec_seq),
             price: self.price.to_f64(tick_size),
             qty: self.qty.to_f64(lot_size),
             ts: self.ts,
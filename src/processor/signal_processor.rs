@@ -1,14 +1,38 @@
+use std::error::Error;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
 use crate::{
     domain::{
-        OrderRequest, OrderSide, PositionSizer, TradeObserver, executor::OrderExecutor,
-        trade_observer::TradeRecord,
+        OrderRequest, OrderSide, OrderType, PositionSizer, TradeObserver, executor::OrderExecutor,
+        order::OrderResponse, trade_observer::TradeRecord,
     },
     strategy::{
-        direction::Direction, market_data::MarketData, signal::Signal,
-        strategy_context::StrategyContext,
+        direction::Direction,
+        market_data::MarketData,
+        signal::{Entry, Leg, Order, Signal},
+        strategy_context::{Holding, LegFill, LotEntry, PairEntry, StrategyContext},
+        strategy_trait::Strategy,
     },
 };
 
+/// 执行过程中产生、处理完信号后回调给策略的事件
+enum OrderEvent {
+    Filled(OrderRequest, OrderResponse),
+    Rejected(OrderRequest),
+    Closed(TradeRecord),
+}
+
+/// 断点续跑时要带上的处理器状态：挂单和模拟账户
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProcessorState {
+    pub marking: bool,
+    pub pending: Vec<Signal>,
+    /// OrderExecutor::save_state 的结果，实盘为 None
+    pub executor: Option<Value>,
+}
+
 pub struct SignalProcessor<EX>
 where
     EX: OrderExecutor,
@@ -16,6 +40,11 @@ where
     executor: EX,
     sizer: Box<dyn PositionSizer>,
     observers: Vec<Box<dyn TradeObserver>>,
+    // 建立过组合持仓后，之后每根 bar 都推送净值（清仓后即为现金）
+    marking: bool,
+    events: Vec<OrderEvent>,
+    // 还没触价的限价/止损单（Enter 或 Reduce）
+    pending: Vec<Signal>,
 }
 
 impl<EX> SignalProcessor<EX>
@@ -27,6 +56,9 @@ where
             executor,
             sizer,
             observers: Vec::new(),
+            marking: false,
+            events: Vec::new(),
+            pending: Vec::new(),
         }
     }
 
@@ -39,94 +71,1100 @@ where
         self.executor.sync_positions()
     }
 
-    /// 核心：把 Signal “落地”成下单、状态更新、日志广播
-    pub fn process(&mut self, sig: Signal, ctx: &mut StrategyContext, data: &MarketData) {
-        // ctx.reconcile_entry();
+    /// 导出挂单和模拟账户，事件队列在每根 bar 处理完时已清空
+    pub fn save_state(&self) -> ProcessorState {
+        ProcessorState {
+            marking: self.marking,
+            pending: self.pending.clone(),
+            executor: self.executor.save_state(),
+        }
+    }
+
+    pub fn load_state(&mut self, state: ProcessorState) -> Result<(), Box<dyn Error>> {
+        if let Some(executor) = &state.executor {
+            self.executor.load_state(executor)?;
+        }
+        self.marking = state.marking;
+        self.pending = state.pending;
+        Ok(())
+    }
+
+    /// 回测或实盘开始前通知策略
+    pub fn start(&mut self, strategy: &mut dyn Strategy, ctx: &StrategyContext) {
+        strategy.on_start(ctx);
+    }
+
+    /// 数据源耗尽后通知策略
+    pub fn finish(&mut self, strategy: &mut dyn Strategy, ctx: &StrategyContext) {
+        strategy.on_finish(ctx);
+    }
+
+    /// 多品种入口：执行信号，再把这期间的成交、拒单和平仓按发生顺序回调给策略
+    pub fn process_bars(
+        &mut self,
+        sig: Signal,
+        ctx: &mut StrategyContext,
+        bars: &[MarketData],
+        strategy: &mut dyn Strategy,
+    ) {
+        self.route_bars(sig, ctx, bars);
+        self.notify(strategy);
+    }
+
+    fn notify(&mut self, strategy: &mut dyn Strategy) {
+        for event in std::mem::take(&mut self.events) {
+            match event {
+                OrderEvent::Filled(req, resp) => strategy.on_fill(&req, &resp),
+                OrderEvent::Rejected(req) => strategy.on_order_rejected(&req),
+                OrderEvent::Closed(record) => strategy.on_position_closed(&record),
+            }
+        }
+    }
+
+    // 所有订单都经由这里发给 executor，没有任何成交视为拒单
+    fn send(&mut self, req: OrderRequest) -> OrderResponse {
+        let resp = self.executor.execute(req.clone());
+        if resp.filled_qty > 0.0 {
+            self.events.push(OrderEvent::Filled(req, resp.clone()));
+        } else {
+            self.events.push(OrderEvent::Rejected(req));
+        }
+        resp
+    }
+
+    // 一笔往返结束：广播给观察者，并记下回调给策略
+    fn report(&mut self, record: TradeRecord) {
+        for o in &mut self.observers {
+            o.on_trade(&record);
+        }
+        self.events.push(OrderEvent::Closed(record));
+    }
+
+    // 配对信号在这里展开成每条腿的订单，其余信号交给第一个品种
+    fn route_bars(&mut self, sig: Signal, ctx: &mut StrategyContext, bars: &[MarketData]) {
         match sig {
-            Signal::EnterLong(price) if ctx.current_entry.is_none() && ctx.position <= 0.0 => {
-                let qty = self.sizer.calc(price, ctx);
-                if qty <= 0.0 {
-                    return;
+            Signal::EnterPair(legs) if ctx.pair_entry.is_none() && ctx.current_entry.is_none() => {
+                self.enter_pair(legs, ctx, bars)
+            }
+            Signal::Exit if ctx.pair_entry.is_some() => self.exit_pair(ctx, bars),
+            Signal::Batch(signals) => {
+                for sig in signals {
+                    self.route_bars(sig, ctx, bars);
                 }
-                let req = OrderRequest {
-                    side: OrderSide::Buy,
-                    price,
-                    quantity: qty,
-                    timestamp: data.timestamp.to_string(),
+            }
+            Signal::Invest { index, quote } => self.invest(index, quote, ctx, bars),
+            Signal::Rebalance(weights) => self.rebalance(&weights, ctx, bars),
+            Signal::Exit if !ctx.holdings.is_empty() => {
+                if let Some(data) = bars.first() {
+                    self.process(Signal::Exit, ctx, data);
+                }
+                for holding in std::mem::take(&mut ctx.holdings) {
+                    self.sell_holding(holding, ctx, bars);
+                }
+            }
+            other => {
+                if let Some(data) = bars.first() {
+                    self.process(other, ctx, data);
+                }
+            }
+        }
+    }
+
+    fn enter_pair(&mut self, legs: [Leg; 2], ctx: &mut StrategyContext, bars: &[MarketData]) {
+        // sizer 按组合一份的总名义价值计算份数
+        let gross: f64 = legs.iter().map(|l| l.ratio.abs() * l.price).sum();
+        let units = self.sizer.calc(gross, ctx);
+        if units <= 0.0 {
+            return;
+        }
+        let Some(first) = bars.first() else {
+            return;
+        };
+        let timestamp = first.timestamp.to_rfc3339();
+
+        let mut fills = Vec::with_capacity(legs.len());
+        for leg in legs {
+            let qty = units * leg.ratio.abs();
+            if qty <= 0.0 {
+                continue;
+            }
+            let symbol = bars
+                .get(leg.index)
+                .map(|b| b.symbol.clone())
+                .unwrap_or_default();
+            let side = if leg.ratio > 0.0 {
+                OrderSide::Buy
+            } else {
+                OrderSide::Sell
+            };
+            let resp = self.send(OrderRequest {
+                symbol: symbol.clone(),
+                side,
+                order_type: OrderType::Market,
+                price: leg.price,
+                quantity: qty,
+                timestamp: timestamp.clone(),
+            });
+            fills.push(LegFill {
+                index: leg.index,
+                symbol,
+                quantity: resp.filled_qty.copysign(leg.ratio),
+                price: resp.filled_price,
+            });
+        }
+
+        let direction = if legs[0].ratio > 0.0 {
+            Direction::Long
+        } else {
+            Direction::Short
+        };
+        ctx.pair_entry = Some(PairEntry {
+            entry_time: timestamp,
+            units,
+            direction,
+            legs: fills,
+        });
+    }
+
+    fn exit_pair(&mut self, ctx: &mut StrategyContext, bars: &[MarketData]) {
+        let Some(entry) = ctx.pair_entry.take() else {
+            return;
+        };
+        let timestamp = bars
+            .first()
+            .map(|b| b.timestamp.to_rfc3339())
+            .unwrap_or_default();
+
+        let mut pnl = 0.0;
+        let mut entry_value = 0.0;
+        let mut exit_value = 0.0;
+        // 按开仓价计的名义价值：全部 / 已平，用来折算平掉的份数
+        let mut gross = 0.0;
+        let mut closed = 0.0;
+        let mut remaining = Vec::new();
+        for leg in &entry.legs {
+            let price = bars.get(leg.index).map_or(leg.price, |b| b.close_price);
+            let side = if leg.quantity > 0.0 {
+                OrderSide::Sell
+            } else {
+                OrderSide::Buy
+            };
+            let resp = self.send(OrderRequest {
+                symbol: leg.symbol.clone(),
+                side,
+                order_type: OrderType::Market,
+                price,
+                quantity: leg.quantity.abs(),
+                timestamp: timestamp.clone(),
+            });
+            // 按实际成交数量结算，没成交的部分留在组合里
+            let filled = resp
+                .filled_qty
+                .min(leg.quantity.abs())
+                .copysign(leg.quantity);
+            pnl += (resp.filled_price - leg.price) * filled;
+            entry_value += leg.price * filled;
+            exit_value += resp.filled_price * filled;
+            gross += leg.price * leg.quantity.abs();
+            closed += leg.price * filled.abs();
+            if filled != leg.quantity {
+                remaining.push(LegFill {
+                    quantity: leg.quantity - filled,
+                    ..leg.clone()
+                });
+            }
+        }
+        let closed_units = if gross > 0.0 {
+            entry.units * closed / gross
+        } else {
+            entry.units
+        };
+        if !remaining.is_empty() {
+            ctx.pair_entry = Some(PairEntry {
+                entry_time: entry.entry_time.clone(),
+                units: entry.units - closed_units,
+                direction: entry.direction.clone(),
+                legs: remaining,
+            });
+        }
+        if closed_units <= 0.0 {
+            return;
+        }
+
+        // 组合按份计价：每份的价值 = Σ 带符号数量 * 价格 / 份数
+        let symbol = entry
+            .legs
+            .iter()
+            .map(|l| l.symbol.as_str())
+            .collect::<Vec<_>>()
+            .join("/");
+        let record = TradeRecord {
+            symbol,
+            entry_time: entry.entry_time,
+            exit_time: timestamp,
+            entry_price: entry_value / closed_units,
+            exit_price: exit_value / closed_units,
+            quantity: closed_units,
+            direction: entry.direction,
+            pnl,
+            lot: None,
+            holding_time: "".to_string(),
+        };
+        self.report(record);
+    }
+
+    /// 持有组合时按收盘价给观察者推送净值，驱动余额曲线
+    pub fn mark_to_market(&mut self, ctx: &mut StrategyContext, bars: &[MarketData]) {
+        self.marking |= !ctx.holdings.is_empty();
+        if !self.marking {
+            return;
+        }
+        let Some(first) = bars.first() else {
+            return;
+        };
+        ctx.account_equity = ctx.portfolio_value(bars);
+        let time = first.timestamp.to_rfc3339();
+        for o in &mut self.observers {
+            o.on_mark(&time, ctx.account_equity);
+        }
+    }
+
+    fn invest(&mut self, index: usize, quote: f64, ctx: &mut StrategyContext, bars: &[MarketData]) {
+        let Some(bar) = bars.get(index) else {
+            return;
+        };
+        let amount = quote.min(ctx.cash);
+        if amount <= 0.0 || bar.close_price <= 0.0 {
+            return;
+        }
+        self.buy_holding(index, amount / bar.close_price, ctx, bars);
+    }
+
+    // 先卖后买，卖出释放的现金用于补足低配品种
+    fn rebalance(&mut self, weights: &[f64], ctx: &mut StrategyContext, bars: &[MarketData]) {
+        let equity = ctx.portfolio_value(bars);
+        let deltas: Vec<(usize, f64)> = weights
+            .iter()
+            .enumerate()
+            .filter_map(|(index, &w)| {
+                let price = bars.get(index)?.close_price;
+                (price > 0.0).then(|| (index, w.max(0.0) * equity / price - ctx.holding(index)))
+            })
+            .collect();
+
+        for &(index, delta) in deltas.iter().filter(|(_, d)| *d < 0.0) {
+            self.reduce_holding(index, -delta, ctx, bars);
+        }
+        for &(index, delta) in deltas.iter().filter(|(_, d)| *d > 0.0) {
+            let price = bars[index].close_price;
+            let qty = delta.min(ctx.cash / price);
+            if qty > 0.0 {
+                self.buy_holding(index, qty, ctx, bars);
+            }
+        }
+    }
+
+    fn buy_holding(
+        &mut self,
+        index: usize,
+        qty: f64,
+        ctx: &mut StrategyContext,
+        bars: &[MarketData],
+    ) {
+        let bar = &bars[index];
+        let resp = self.send(OrderRequest {
+            symbol: bar.symbol.clone(),
+            side: OrderSide::Buy,
+            order_type: OrderType::Market,
+            price: bar.close_price,
+            quantity: qty,
+            timestamp: bar.timestamp.to_rfc3339(),
+        });
+        ctx.cash -= resp.filled_price * resp.filled_qty;
+        match ctx.holdings.iter_mut().find(|h| h.index == index) {
+            Some(h) => {
+                let total = h.quantity + resp.filled_qty;
+                h.cost = (h.cost * h.quantity + resp.filled_price * resp.filled_qty) / total;
+                h.quantity = total;
+            }
+            None => ctx.holdings.push(Holding {
+                index,
+                symbol: bar.symbol.clone(),
+                quantity: resp.filled_qty,
+                cost: resp.filled_price,
+                entry_time: resp.timestamp,
+            }),
+        }
+    }
+
+    fn reduce_holding(
+        &mut self,
+        index: usize,
+        qty: f64,
+        ctx: &mut StrategyContext,
+        bars: &[MarketData],
+    ) {
+        let Some(i) = ctx.holdings.iter().position(|h| h.index == index) else {
+            return;
+        };
+        let mut holding = ctx.holdings.remove(i);
+        if qty < holding.quantity {
+            let mut sold = holding.clone();
+            sold.quantity = qty;
+            holding.quantity -= qty;
+            ctx.holdings.insert(i, holding);
+            holding = sold;
+        }
+        self.sell_holding(holding, ctx, bars);
+    }
+
+    // 卖出（部分）持仓，按均价结算已实现盈亏
+    fn sell_holding(&mut self, holding: Holding, ctx: &mut StrategyContext, bars: &[MarketData]) {
+        let Some(bar) = bars.get(holding.index) else {
+            return;
+        };
+        let resp = self.send(OrderRequest {
+            symbol: holding.symbol.clone(),
+            side: OrderSide::Sell,
+            order_type: OrderType::Market,
+            price: bar.close_price,
+            quantity: holding.quantity,
+            timestamp: bar.timestamp.to_rfc3339(),
+        });
+        ctx.cash += resp.filled_price * resp.filled_qty;
+        let record = TradeRecord {
+            symbol: holding.symbol,
+            entry_time: holding.entry_time,
+            exit_time: resp.timestamp,
+            entry_price: holding.cost,
+            exit_price: resp.filled_price,
+            quantity: resp.filled_qty,
+            direction: Direction::Long,
+            pnl: (resp.filled_price - holding.cost) * resp.filled_qty,
+            lot: None,
+            holding_time: "".to_string(),
+        };
+        self.report(record);
+    }
+
+    /// 平掉一笔 lot，并把这一次往返报告给观察者
+    fn close_lot(
+        &mut self,
+        entry: LotEntry,
+        price: f64,
+        ctx: &mut StrategyContext,
+        data: &MarketData,
+    ) {
+        let side = if entry.direction == Direction::Long {
+            OrderSide::Sell
+        } else {
+            OrderSide::Buy
+        };
+        let resp = self.send(OrderRequest {
+            symbol: data.symbol.clone(),
+            side,
+            order_type: OrderType::Market,
+            price,
+            quantity: entry.quantity,
+            timestamp: data.timestamp.to_rfc3339(),
+        });
+        // 按实际成交数量结算，没成交的部分仍留在这个 lot 上
+        let filled = resp.filled_qty.min(entry.quantity);
+        if filled < entry.quantity {
+            ctx.lots.push(LotEntry {
+                quantity: entry.quantity - filled,
+                ..entry.clone()
+            });
+        }
+        if filled <= 0.0 {
+            return;
+        }
+        let pnl = if entry.direction == Direction::Long {
+            ctx.position -= filled;
+            (resp.filled_price - entry.entry_price) * filled
+        } else {
+            ctx.position += filled;
+            (entry.entry_price - resp.filled_price) * filled
+        };
+        let record = TradeRecord {
+            symbol: data.symbol.clone(),
+            entry_time: entry.entry_time,
+            exit_time: resp.timestamp,
+            entry_price: entry.entry_price,
+            exit_price: resp.filled_price,
+            quantity: filled,
+            direction: entry.direction,
+            pnl,
+            lot: Some(entry.id),
+            holding_time: "".to_string(),
+        };
+        self.report(record);
+    }
+
+    /// 新 bar 开始时先撮合挂单和持仓附带的止损/止盈，在风控和策略之前调用
+    pub fn fill_resting(
+        &mut self,
+        ctx: &mut StrategyContext,
+        bars: &[MarketData],
+        strategy: &mut dyn Strategy,
+    ) {
+        if let Some(data) = bars.first() {
+            self.check_bracket(ctx, data);
+            for sig in std::mem::take(&mut self.pending) {
+                let triggered = match &sig {
+                    Signal::Enter(entry) => entry
+                        .order
+                        .touched(entry.direction == Direction::Long, data),
+                    Signal::Reduce { order, .. } => match &ctx.current_entry {
+                        Some((.., dir)) => order.touched(*dir == Direction::Short, data),
+                        // 持仓已经没了，平仓挂单作废
+                        None => continue,
+                    },
+                    _ => None,
+                };
+                let Some(price) = triggered else {
+                    self.pending.push(sig);
+                    continue;
                 };
+                match sig {
+                    Signal::Enter(entry) => {
+                        let order = Order {
+                            price,
+                            ..entry.order
+                        };
+                        let qty = self.sizer.calc(price, ctx);
+                        self.open(&entry, qty, order, ctx, data);
+                    }
+                    Signal::Reduce { fraction, order } => {
+                        self.close_entry(fraction, Order { price, ..order }, ctx, data);
+                    }
+                    _ => {}
+                }
+            }
+        }
+        self.notify(strategy);
+    }
 
-                let resp = self.executor.execute(req);
+    // 止损优先于止盈：同一根 bar 两者都触及时按止损处理
+    fn check_bracket(&mut self, ctx: &mut StrategyContext, data: &MarketData) {
+        let Some((.., dir)) = &ctx.current_entry else {
+            return;
+        };
+        let long = *dir == Direction::Long;
+        let stop = ctx
+            .stop_loss
+            .and_then(|price| Order::stop(price).touched(!long, data))
+            .map(Order::stop);
+        let take = ctx
+            .take_profit
+            .and_then(|price| Order::limit(price).touched(!long, data))
+            .map(Order::limit);
+        if let Some(order) = stop.or(take) {
+            self.close_entry(1.0, order, ctx, data);
+        }
+    }
 
-                ctx.position += resp.filled_qty;
-                ctx.current_entry = Some((
-                    resp.timestamp,
-                    resp.filled_price,
-                    resp.filled_qty,
-                    Direction::Long,
-                ));
+    // 空仓时开仓，带上止损/止盈
+    fn open(
+        &mut self,
+        entry: &Entry,
+        qty: f64,
+        order: Order,
+        ctx: &mut StrategyContext,
+        data: &MarketData,
+    ) {
+        let (side, sign) = match entry.direction {
+            Direction::Long if ctx.position <= 0.0 => (OrderSide::Buy, 1.0),
+            Direction::Short if ctx.position >= 0.0 => (OrderSide::Sell, -1.0),
+            _ => return,
+        };
+        if ctx.current_entry.is_some() || qty <= 0.0 {
+            return;
+        }
+        let resp = self.send(OrderRequest {
+            symbol: data.symbol.clone(),
+            side,
+            order_type: order.order_type,
+            price: order.price,
+            quantity: qty,
+            timestamp: data.timestamp.to_rfc3339(),
+        });
+        if resp.filled_qty <= 0.0 {
+            return;
+        }
+        ctx.position += sign * resp.filled_qty;
+        ctx.current_entry = Some((
+            resp.timestamp,
+            resp.filled_price,
+            resp.filled_qty,
+            entry.direction.clone(),
+        ));
+        ctx.stop_loss = entry.stop_loss;
+        ctx.take_profit = entry.take_profit;
+    }
+
+    fn scale_in(&mut self, qty: f64, price: f64, ctx: &mut StrategyContext, data: &MarketData) {
+        if qty <= 0.0 {
+            return;
+        }
+        let Some((et, entry_price, held, dir)) = ctx.current_entry.take() else {
+            return;
+        };
+        let side = if dir == Direction::Long {
+            OrderSide::Buy
+        } else {
+            OrderSide::Sell
+        };
+        let resp = self.send(OrderRequest {
+            symbol: data.symbol.clone(),
+            side,
+            order_type: OrderType::Market,
+            price,
+            quantity: qty,
+            timestamp: data.timestamp.to_rfc3339(),
+        });
+        ctx.position += if dir == Direction::Long {
+            resp.filled_qty
+        } else {
+            -resp.filled_qty
+        };
+
+        // 加仓后按成交量加权更新均价，平仓时整体结算
+        let total = held + resp.filled_qty;
+        let avg_price = (entry_price * held + resp.filled_price * resp.filled_qty) / total;
+        ctx.current_entry = Some((et, avg_price, total, dir));
+    }
+
+    // 按比例平掉 current_entry，只结算实际成交的部分，没成交的留在持仓里
+    fn close_entry(
+        &mut self,
+        fraction: f64,
+        order: Order,
+        ctx: &mut StrategyContext,
+        data: &MarketData,
+    ) {
+        let Some((et, entry_price, held, dir)) = ctx.current_entry.take() else {
+            return;
+        };
+        let qty = held * fraction.clamp(0.0, 1.0);
+        let side = if dir == Direction::Long {
+            OrderSide::Sell
+        } else {
+            OrderSide::Buy
+        };
+        let resp = if qty > 0.0 {
+            self.send(OrderRequest {
+                symbol: data.symbol.clone(),
+                side,
+                order_type: order.order_type,
+                price: order.price,
+                quantity: qty,
+                timestamp: data.timestamp.to_rfc3339(),
+            })
+        } else {
+            ctx.current_entry = Some((et, entry_price, held, dir));
+            return;
+        };
+        let filled = resp.filled_qty.min(qty);
+        ctx.position += if dir == Direction::Long {
+            -filled
+        } else {
+            filled
+        };
+        if filled < held {
+            ctx.current_entry = Some((et.clone(), entry_price, held - filled, dir.clone()));
+        } else {
+            ctx.stop_loss = None;
+            ctx.take_profit = None;
+        }
+        if filled <= 0.0 {
+            return;
+        }
+        let pnl = if dir == Direction::Long {
+            (resp.filled_price - entry_price) * filled
+        } else {
+            (entry_price - resp.filled_price) * filled
+        };
+        self.report(TradeRecord {
+            symbol: data.symbol.clone(),
+            entry_time: et,
+            exit_time: resp.timestamp,
+            entry_price,
+            exit_price: resp.filled_price,
+            quantity: filled,
+            direction: dir,
+            pnl,
+            lot: None,
+            holding_time: "".to_string(),
+        });
+    }
+
+    // 目标仓位按 account_equity 计算（与 sizer 相同口径），方向相反时先平后开
+    fn target(&mut self, percent: f64, ctx: &mut StrategyContext, data: &MarketData) {
+        let price = data.close_price;
+        if price <= 0.0 {
+            return;
+        }
+        let target = percent / 100.0 * ctx.account_equity / price;
+        let held = |ctx: &StrategyContext| match &ctx.current_entry {
+            Some((_, _, qty, Direction::Long)) => *qty,
+            Some((_, _, qty, Direction::Short)) => -*qty,
+            None => 0.0,
+        };
+        let current = held(ctx);
+        if current != 0.0 && target * current <= 0.0 {
+            self.close_entry(1.0, Order::market(price), ctx, data);
+        }
+        let current = held(ctx);
+        if current == 0.0 {
+            if target != 0.0 {
+                let direction = if target > 0.0 {
+                    Direction::Long
+                } else {
+                    Direction::Short
+                };
+                let entry = Entry::new(direction, Order::market(price));
+                self.open(&entry, target.abs(), entry.order, ctx, data);
+            }
+        } else if target.abs() > current.abs() {
+            self.scale_in(target.abs() - current.abs(), price, ctx, data);
+        } else if target.abs() < current.abs() {
+            let fraction = (current.abs() - target.abs()) / current.abs();
+            self.close_entry(fraction, Order::market(price), ctx, data);
+        }
+    }
+
+    /// 核心：把 Signal “落地”成下单、状态更新、日志广播
+    fn process(&mut self, sig: Signal, ctx: &mut StrategyContext, data: &MarketData) {
+        // ctx.reconcile_entry();
+        match sig {
+            Signal::EnterLong(price) => self.process(
+                Signal::Enter(Entry::new(Direction::Long, Order::market(price))),
+                ctx,
+                data,
+            ),
+            Signal::EnterShort(price) => self.process(
+                Signal::Enter(Entry::new(Direction::Short, Order::market(price))),
+                ctx,
+                data,
+            ),
+            // 限价/止损单从下一根 bar 开始挂单
+            Signal::Enter(entry) if entry.order.order_type != OrderType::Market => {
+                self.pending.push(Signal::Enter(entry));
+            }
+            Signal::Reduce { fraction, order } if order.order_type != OrderType::Market => {
+                self.pending.push(Signal::Reduce { fraction, order });
+            }
+            Signal::Enter(entry) => {
+                let qty = self.sizer.calc(entry.order.price, ctx);
+                self.open(&entry, qty, entry.order, ctx, data);
             }
-            Signal::EnterShort(price) if ctx.current_entry.is_none() && ctx.position >= 0.0 => {
+            Signal::ScaleIn(price) if ctx.current_entry.is_some() => {
                 let qty = self.sizer.calc(price, ctx);
+                self.scale_in(qty, price, ctx, data);
+            }
+            Signal::Reduce { fraction, order } => self.close_entry(fraction, order, ctx, data),
+            Signal::TargetPercent(percent) => self.target(percent, ctx, data),
+            Signal::Exit => {
+                self.pending.clear();
+                self.close_entry(1.0, Order::market(data.close_price), ctx, data);
+                // 剩余的 lot 按收盘价一并平掉
+                for entry in std::mem::take(&mut ctx.lots) {
+                    self.close_lot(entry, data.close_price, ctx, data);
+                }
+            }
+            Signal::OpenLot(lot) if !ctx.lots.iter().any(|l| l.id == lot.id) => {
+                let qty = self.sizer.calc(lot.price, ctx);
                 if qty <= 0.0 {
                     return;
                 }
-                let req = OrderRequest {
-                    side: OrderSide::Sell,
-                    price,
+                let side = if lot.direction == Direction::Long {
+                    OrderSide::Buy
+                } else {
+                    OrderSide::Sell
+                };
+                let resp = self.send(OrderRequest {
+                    symbol: data.symbol.clone(),
+                    side,
+                    order_type: OrderType::Market,
+                    price: lot.price,
                     quantity: qty,
-                    timestamp: data.timestamp.to_string(),
+                    timestamp: data.timestamp.to_rfc3339(),
+                });
+                if resp.filled_qty <= 0.0 {
+                    return;
+                }
+                ctx.position += if lot.direction == Direction::Long {
+                    resp.filled_qty
+                } else {
+                    -resp.filled_qty
                 };
-
-                let resp = self.executor.execute(req);
-                ctx.position -= resp.filled_qty;
-                ctx.current_entry = Some((
-                    resp.timestamp.clone(),
-                    resp.filled_price,
-                    resp.filled_qty,
-                    Direction::Short,
-                ));
+                ctx.lots.push(LotEntry {
+                    id: lot.id,
+                    entry_time: resp.timestamp,
+                    entry_price: resp.filled_price,
+                    quantity: resp.filled_qty,
+                    direction: lot.direction,
+                });
             }
-            Signal::Exit => {
-                if let Some((et, entry_price, qty, dir)) = ctx.current_entry.take() {
-                    let (side, signed_qty) = if dir == Direction::Long {
-                        (OrderSide::Sell, qty)
-                    } else {
-                        (OrderSide::Buy, qty)
-                    };
-                    let resp = self.executor.execute(OrderRequest {
-                        side,
-                        price: data.close_price,
-                        quantity: signed_qty,
-                        timestamp: data.timestamp.to_string(),
-                    });
-                    ctx.position += if dir == Direction::Long {
-                        -resp.filled_qty
-                    } else {
-                        resp.filled_qty
-                    };
-
-                    let pnl = if dir == Direction::Long {
-                        (resp.filled_price - entry_price) * qty
-                    } else {
-                        (entry_price - resp.filled_price) * qty
-                    };
-                    let record = TradeRecord {
-                        entry_time: et.clone(),
-                        exit_time: resp.timestamp.clone(),
-                        entry_price,
-                        exit_price: resp.filled_price,
-                        quantity: qty,
-                        direction: dir.clone(),
-                        pnl,
-                        holding_time: "".to_string(),
-                    };
-                    // 广播给所有观察者
-                    for o in &mut self.observers {
-                        o.on_trade(&record);
-                    }
+            Signal::CloseLot(lot) => {
+                if let Some(i) = ctx.lots.iter().position(|l| l.id == lot.id) {
+                    let entry = ctx.lots.remove(i);
+                    self.close_lot(entry, lot.price, ctx, data);
+                }
+            }
+            Signal::Batch(signals) => {
+                for sig in signals {
+                    self.process(sig, ctx, data);
                 }
             }
             _ => {}
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use chrono::{TimeZone, Utc};
+
+    use super::*;
+    use crate::{
+        executor::backtest_executor::BacktestExecutor,
+        sizer::fixed_size_sizer::FixedSizeSizer,
+        strategy::{
+            position::{PositionType, TradePosition},
+            signal::Lot,
+        },
+    };
+
+    // 每单最多成交 fill_limit，为 0 时拒单
+    struct LimitedExecutor {
+        fill_limit: Rc<RefCell<f64>>,
+    }
+
+    impl OrderExecutor for LimitedExecutor {
+        fn execute(&mut self, order: OrderRequest) -> OrderResponse {
+            OrderResponse {
+                order_id: "test".to_string(),
+                side: order.side,
+                filled_price: order.price + 0.5,
+                filled_qty: order.quantity.min(*self.fill_limit.borrow()),
+                timestamp: order.timestamp,
+            }
+        }
+
+        fn sync_positions(&mut self) -> f64 {
+            0.0
+        }
+    }
+
+    #[derive(Default)]
+    struct Recorder {
+        events: Vec<String>,
+    }
+
+    impl Strategy for Recorder {
+        fn generate_signal(&mut self, _price: f64, _position: f64) -> Signal {
+            Signal::Hold
+        }
+        fn update(&mut self, _market_data: &MarketData, _current_position: &Option<TradePosition>) {
+        }
+        fn name(&self) -> &str {
+            "recorder"
+        }
+        fn apply_parameters(&mut self, _entry: Option<f64>, _exit: Option<f64>) {}
+        fn position_type(&self) -> &PositionType {
+            &PositionType::Both
+        }
+        fn on_fill(&mut self, request: &OrderRequest, response: &OrderResponse) {
+            self.events.push(format!(
+                "fill {}/{} @ {}",
+                response.filled_qty, request.quantity, response.filled_price
+            ));
+        }
+        fn on_order_rejected(&mut self, request: &OrderRequest) {
+            self.events.push(format!("rejected {}", request.quantity));
+        }
+        fn on_position_closed(&mut self, trade: &TradeRecord) {
+            self.events
+                .push(format!("closed {} pnl {:.2}", trade.quantity, trade.pnl));
+        }
+    }
+
+    #[test]
+    fn test_strategy_hears_fills_rejections_and_closes() {
+        let fill_limit = Rc::new(RefCell::new(0.0));
+        let executor = LimitedExecutor {
+            fill_limit: fill_limit.clone(),
+        };
+        let mut processor = SignalProcessor::new(executor, Box::new(FixedSizeSizer::new(2.0)));
+        let mut strategy = Recorder::default();
+        let mut ctx = StrategyContext::new(10_000.0);
+        let bar = |price: f64| {
+            vec![MarketData::from_close(
+                Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap(),
+                price,
+            )]
+        };
+
+        // 拒单不留下持仓，之后仍可再次入场
+        processor.process_bars(
+            Signal::EnterLong(100.0),
+            &mut ctx,
+            &bar(100.0),
+            &mut strategy,
+        );
+        assert!(ctx.current_entry.is_none());
+        *fill_limit.borrow_mut() = 2.0;
+        processor.process_bars(
+            Signal::EnterLong(100.0),
+            &mut ctx,
+            &bar(100.0),
+            &mut strategy,
+        );
+        assert_eq!(ctx.position, 2.0);
+
+        // 平仓只成交一半，剩下的仍是持仓
+        *fill_limit.borrow_mut() = 1.0;
+        processor.process_bars(Signal::Exit, &mut ctx, &bar(110.0), &mut strategy);
+        assert_eq!(ctx.position, 1.0);
+        assert_eq!(ctx.current_entry.as_ref().map(|e| e.2), Some(1.0));
+        processor.process_bars(Signal::Exit, &mut ctx, &bar(110.0), &mut strategy);
+        assert!(ctx.current_entry.is_none());
+
+        assert_eq!(
+            strategy.events,
+            vec![
+                "rejected 2",
+                "fill 2/2 @ 100.5",
+                "fill 1/2 @ 110.5",
+                "closed 1 pnl 10.00",
+                "fill 1/1 @ 110.5",
+                "closed 1 pnl 10.00",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_lot_close_settles_on_filled_quantity() {
+        let fill_limit = Rc::new(RefCell::new(2.0));
+        let executor = LimitedExecutor {
+            fill_limit: fill_limit.clone(),
+        };
+        let mut processor = SignalProcessor::new(executor, Box::new(FixedSizeSizer::new(2.0)));
+        let mut strategy = Recorder::default();
+        let mut ctx = StrategyContext::new(10_000.0);
+        let bar = |price: f64| {
+            vec![MarketData::from_close(
+                Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap(),
+                price,
+            )]
+        };
+        let lot = |price: f64| Lot {
+            id: 7,
+            price,
+            direction: Direction::Long,
+        };
+
+        processor.process_bars(
+            Signal::OpenLot(lot(100.0)),
+            &mut ctx,
+            &bar(100.0),
+            &mut strategy,
+        );
+        assert_eq!(ctx.position, 2.0);
+
+        // 只成交 1，剩下的 1 仍挂在同一个 lot 上
+        *fill_limit.borrow_mut() = 1.0;
+        let close = Signal::CloseLot(lot(110.0));
+        processor.process_bars(close.clone(), &mut ctx, &bar(110.0), &mut strategy);
+        assert_eq!(ctx.position, 1.0);
+        assert_eq!(ctx.lots.len(), 1);
+        assert_eq!((ctx.lots[0].id, ctx.lots[0].quantity), (7, 1.0));
+
+        // 拒单时 lot 原样保留
+        *fill_limit.borrow_mut() = 0.0;
+        processor.process_bars(Signal::Exit, &mut ctx, &bar(110.0), &mut strategy);
+        assert_eq!(ctx.lots.len(), 1);
+
+        *fill_limit.borrow_mut() = 1.0;
+        processor.process_bars(close, &mut ctx, &bar(110.0), &mut strategy);
+        assert!(ctx.lots.is_empty());
+        assert_eq!(ctx.position, 0.0);
+
+        let closes: Vec<_> = strategy
+            .events
+            .iter()
+            .filter(|e| e.starts_with("closed"))
+            .collect();
+        assert_eq!(closes, vec!["closed 1 pnl 10.00", "closed 1 pnl 10.00"]);
+    }
+
+    struct Trades(Rc<RefCell<Vec<TradeRecord>>>);
+
+    impl TradeObserver for Trades {
+        fn on_trade(&mut self, record: &TradeRecord) {
+            self.0.borrow_mut().push(record.clone());
+        }
+    }
+
+    #[test]
+    fn test_pair_exit_settles_on_filled_legs() {
+        let fill_limit = Rc::new(RefCell::new(10.0));
+        let executor = LimitedExecutor {
+            fill_limit: fill_limit.clone(),
+        };
+        let mut processor = SignalProcessor::new(executor, Box::new(FixedSizeSizer::new(2.0)));
+        let trades = Rc::new(RefCell::new(Vec::new()));
+        processor.add_observer(Box::new(Trades(trades.clone())));
+        let mut strategy = Recorder::default();
+        let mut ctx = StrategyContext::new(10_000.0);
+        let bars = |a: f64, b: f64| {
+            let time = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+            vec![
+                MarketData::from_close(time, a).with_symbol("AAA"),
+                MarketData::from_close(time, b).with_symbol("BBB"),
+            ]
+        };
+        let legs = [
+            Leg {
+                index: 0,
+                price: 100.0,
+                ratio: 1.0,
+            },
+            Leg {
+                index: 1,
+                price: 50.0,
+                ratio: -2.0,
+            },
+        ];
+        let enter = Signal::EnterPair(legs);
+        processor.process_bars(enter, &mut ctx, &bars(100.0, 50.0), &mut strategy);
+
+        // 每条腿只平掉 1，盈亏按成交数量算，剩余的腿留在组合里
+        *fill_limit.borrow_mut() = 1.0;
+        processor.process_bars(Signal::Exit, &mut ctx, &bars(110.0, 45.0), &mut strategy);
+        let left: Vec<f64> = ctx
+            .pair_entry
+            .as_ref()
+            .unwrap()
+            .legs
+            .iter()
+            .map(|l| l.quantity)
+            .collect();
+        assert_eq!(left, vec![1.0, -3.0]);
+        assert_eq!(trades.borrow()[0].pnl, 15.0);
+
+        *fill_limit.borrow_mut() = 10.0;
+        processor.process_bars(Signal::Exit, &mut ctx, &bars(110.0, 45.0), &mut strategy);
+        assert!(ctx.pair_entry.is_none());
+        let trades = trades.borrow();
+        assert_eq!(trades[1].pnl, 25.0);
+        let units: f64 = trades.iter().map(|t| t.quantity).sum();
+        assert!((units - 2.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_holdings_report_their_first_buy_time() {
+        let executor = BacktestExecutor::new(10_000.0);
+        let mut processor = SignalProcessor::new(executor, Box::new(FixedSizeSizer::new(1.0)));
+        let trades = Rc::new(RefCell::new(Vec::new()));
+        processor.add_observer(Box::new(Trades(trades.clone())));
+        let mut strategy = Recorder::default();
+        let mut ctx = StrategyContext::new(10_000.0);
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let bar = |day: i64| {
+            vec![MarketData::from_close(
+                start + chrono::Duration::days(day),
+                100.0,
+            )]
+        };
+
+        for day in 0..3 {
+            let invest = Signal::Invest {
+                index: 0,
+                quote: 1_000.0,
+            };
+            processor.process_bars(invest, &mut ctx, &bar(day), &mut strategy);
+        }
+        processor.process_bars(Signal::Exit, &mut ctx, &bar(3), &mut strategy);
+
+        let trades = trades.borrow();
+        assert_eq!(trades.len(), 1);
+        assert_eq!(trades[0].entry_time, start.to_rfc3339());
+        assert_eq!(trades[0].quantity, 30.0);
+    }
+
+    #[test]
+    fn test_resting_orders_brackets_and_targets() {
+        let executor = BacktestExecutor::new(10_000.0).with_slippage(0.01);
+        let mut processor = SignalProcessor::new(executor, Box::new(FixedSizeSizer::new(2.0)));
+        let mut strategy = Recorder::default();
+        let mut ctx = StrategyContext::new(10_000.0);
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let bar = |day: i64, open: f64, high: f64, low: f64, close: f64| {
+            vec![MarketData {
+                open,
+                high,
+                low,
+                ..MarketData::from_close(start + chrono::Duration::days(day), close)
+            }]
+        };
+
+        let entry = Entry::new(Direction::Long, Order::limit(95.0))
+            .with_stop_loss(90.0)
+            .with_take_profit(110.0);
+        let b0 = bar(0, 100.0, 101.0, 99.0, 100.0);
+        processor.process_bars(Signal::Enter(entry), &mut ctx, &b0, &mut strategy);
+        assert!(ctx.current_entry.is_none());
+
+        // 没有触及 95 的 bar 不成交，挂单继续有效
+        processor.fill_resting(&mut ctx, &bar(1, 100.0, 101.0, 96.0, 98.0), &mut strategy);
+        assert!(ctx.current_entry.is_none());
+        let b2 = bar(2, 97.0, 99.0, 94.0, 95.0);
+        processor.fill_resting(&mut ctx, &b2, &mut strategy);
+        // 限价单按挂单价成交，不计滑点
+        assert_eq!(
+            ctx.current_entry.as_ref().map(|e| (e.1, e.2)),
+            Some((95.0, 2.0))
+        );
+        assert_eq!(ctx.stop_loss, Some(90.0));
+
+        let half = Signal::Reduce {
+            fraction: 0.5,
+            order: Order::market(100.0),
+        };
+        processor.process_bars(half, &mut ctx, &b2, &mut strategy);
+        assert_eq!(ctx.position, 1.0);
+
+        // 跳空低开越过止损价，按开盘价触发并计滑点
+        processor.fill_resting(&mut ctx, &bar(3, 88.0, 89.0, 85.0, 86.0), &mut strategy);
+        assert!(ctx.current_entry.is_none());
+        assert_eq!(ctx.stop_loss, None);
+        assert_eq!(ctx.position, 0.0);
+
+        // 目标仓位按 account_equity 计算：-50% 即 50 个单位空头，卖出按 99 成交、买回按 101
+        let b4 = bar(4, 100.0, 100.0, 100.0, 100.0);
+        for percent in [-50.0, -20.0, 30.0] {
+            processor.process_bars(Signal::TargetPercent(percent), &mut ctx, &b4, &mut strategy);
+            assert!((ctx.position - percent).abs() < 1e-9, "{}", percent);
+        }
+
+        let closes: Vec<&String> = strategy
+            .events
+            .iter()
+            .filter(|e| e.starts_with("closed"))
+            .collect();
+        assert_eq!(
+            closes,
+            [
+                "closed 1 pnl 4.00",
+                "closed 1 pnl -7.88",
+                "closed 30 pnl -60.00",
+                "closed 20 pnl -40.00"
+            ]
+        );
+    }
+}
@@ -0,0 +1,510 @@
+use std::{collections::VecDeque, error::Error};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{
+    data::coin_market::CoinsMarket, domain::RegimeClassifier,
+    indicators::indicator::serialize_state, strategy::market_data::MarketData,
+};
+
+use super::{Regime, Trend, Volatility};
+
+/// 效率比窗口和 Baum-Welch 迭代次数
+pub const HMM_PERIOD: usize = 10;
+pub const HMM_ITERATIONS: usize = 50;
+
+// 每根 bar 的观测：对数收益率和 period 根 bar 的效率比（净变动 / 路径长度，0~1）
+type Observation = [f64; 2];
+
+fn observation(closes: &[f64]) -> Option<Observation> {
+    let (&last, rest) = closes.split_last()?;
+    let &prev = rest.last()?;
+    if prev <= 0.0 || last <= 0.0 {
+        return None;
+    }
+    let path: f64 = closes.windows(2).map(|w| (w[1] - w[0]).abs()).sum();
+    let efficiency = if path > 0.0 {
+        (last - closes[0]).abs() / path
+    } else {
+        0.0
+    };
+    Some([(last / prev).ln(), efficiency])
+}
+
+fn observations(bars: &[MarketData], period: usize) -> Vec<Observation> {
+    let closes: Vec<f64> = bars.iter().map(|b| b.close_price).collect();
+    closes.windows(period + 1).filter_map(observation).collect()
+}
+
+fn normalize(values: &mut [f64]) {
+    let sum: f64 = values.iter().sum();
+    if sum > 0.0 {
+        values.iter_mut().for_each(|v| *v /= sum);
+    } else {
+        let n = values.len() as f64;
+        values.iter_mut().for_each(|v| *v = 1.0 / n);
+    }
+}
+
+/// 对角高斯隐马尔可夫模型，离线用历史 OHLCV 拟合，可序列化保存后复用
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HmmModel {
+    /// 计算效率比的 bar 数
+    pub period: usize,
+    pub initial: Vec<f64>,
+    pub transition: Vec<Vec<f64>>,
+    pub means: Vec<Observation>,
+    pub variances: Vec<Observation>,
+    /// 每个隐状态对应的市场状态：收益方差和效率比均值分别排在上半的为高波动、趋势
+    pub regimes: Vec<Regime>,
+    /// 训练数据的品种和最后一根 bar 的时间，模型只能用在这之后的行情上
+    pub symbol: String,
+    pub trained_until: DateTime<Utc>,
+}
+
+impl HmmModel {
+    /// Baum-Welch 拟合；初始状态用 k-means 划分，结果是确定的
+    pub fn fit(
+        bars: &[MarketData],
+        states: usize,
+        period: usize,
+        iterations: usize,
+    ) -> Result<Self, Box<dyn Error>> {
+        let states = states.max(1);
+        let period = period.max(2);
+        let obs = observations(bars, period);
+        if obs.len() < states * 10 {
+            return Err(format!(
+                "need at least {} bars to fit {} states, got {}",
+                states * 10 + period,
+                states,
+                bars.len()
+            )
+            .into());
+        }
+
+        let mut model = Self::initialize(&obs, states, period);
+        let floor = model.variance_floor(&obs);
+        for _ in 0..iterations {
+            model.reestimate(&obs, floor);
+        }
+        model.label();
+        if let Some(last) = bars.last() {
+            model.symbol = last.symbol.clone();
+            model.trained_until = last.timestamp;
+        }
+        Ok(model)
+    }
+
+    /// 数据库里存的日线中早于 before 的部分，用于离线拟合
+    pub fn stored_bars(
+        coin: &str,
+        before: DateTime<Utc>,
+    ) -> Result<Vec<MarketData>, Box<dyn Error>> {
+        let bars = CoinsMarket::get_coin_ohlcv(coin)?
+            .iter()
+            .filter(|ohlc| ohlc.timestamp.0 < before)
+            .map(|ohlc| MarketData {
+                symbol: ohlc.symbol.clone(),
+                timestamp: ohlc.timestamp.0,
+                open: ohlc.open,
+                high: ohlc.high,
+                low: ohlc.low,
+                close_price: ohlc.close,
+                volume: ohlc.volume,
+            })
+            .collect();
+        Ok(bars)
+    }
+
+    pub fn states(&self) -> usize {
+        self.initial.len()
+    }
+
+    // 标准化后做 k-means：第一个中心取离均值最近的点，之后依次取离已有中心最远的点
+    fn initialize(obs: &[Observation], states: usize, period: usize) -> Self {
+        let n = obs.len() as f64;
+        let mean = |d: usize| obs.iter().map(|o| o[d]).sum::<f64>() / n;
+        let center = [mean(0), mean(1)];
+        let scale = |d: usize| {
+            let var = obs.iter().map(|o| (o[d] - center[d]).powi(2)).sum::<f64>() / n;
+            if var > 0.0 { var.sqrt() } else { 1.0 }
+        };
+        let scale = [scale(0), scale(1)];
+        let z: Vec<Observation> = obs
+            .iter()
+            .map(|o| [(o[0] - center[0]) / scale[0], (o[1] - center[1]) / scale[1]])
+            .collect();
+        let dist = |a: &Observation, b: &Observation| (a[0] - b[0]).powi(2) + (a[1] - b[1]).powi(2);
+        let nearest = |point: &Observation, centers: &[Observation]| {
+            (0..centers.len())
+                .min_by(|&i, &j| dist(point, &centers[i]).total_cmp(&dist(point, &centers[j])))
+                .unwrap_or(0)
+        };
+
+        let origin = [0.0, 0.0];
+        let mut centers: Vec<Observation> = Vec::with_capacity(states);
+        centers.push(z[nearest(&origin, &z)]);
+        while centers.len() < states {
+            let farthest = z
+                .iter()
+                .max_by(|a, b| {
+                    let da = centers.iter().map(|c| dist(a, c)).fold(f64::MAX, f64::min);
+                    let db = centers.iter().map(|c| dist(b, c)).fold(f64::MAX, f64::min);
+                    da.total_cmp(&db)
+                })
+                .copied()
+                .unwrap_or(origin);
+            centers.push(farthest);
+        }
+
+        let mut assignment = vec![0; z.len()];
+        for _ in 0..20 {
+            for (a, point) in assignment.iter_mut().zip(&z) {
+                *a = nearest(point, &centers);
+            }
+            for (k, c) in centers.iter_mut().enumerate() {
+                let members: Vec<&Observation> = z
+                    .iter()
+                    .zip(&assignment)
+                    .filter(|(_, a)| **a == k)
+                    .map(|(p, _)| p)
+                    .collect();
+                if !members.is_empty() {
+                    let m = members.len() as f64;
+                    *c = [
+                        members.iter().map(|p| p[0]).sum::<f64>() / m,
+                        members.iter().map(|p| p[1]).sum::<f64>() / m,
+                    ];
+                }
+            }
+        }
+
+        // 每个簇的均值方差作为发射分布的初值，转移矩阵偏向停留在原状态
+        let stay = if states > 1 { 0.9 } else { 1.0 };
+        let moves = if states > 1 {
+            0.1 / (states - 1) as f64
+        } else {
+            0.0
+        };
+        let mut model = Self {
+            period,
+            initial: vec![1.0 / states as f64; states],
+            transition: (0..states)
+                .map(|i| {
+                    (0..states)
+                        .map(|j| if i == j { stay } else { moves })
+                        .collect()
+                })
+                .collect(),
+            means: vec![center; states],
+            variances: vec![[scale[0].powi(2), scale[1].powi(2)]; states],
+            regimes: Vec::new(),
+            symbol: String::new(),
+            trained_until: DateTime::default(),
+        };
+        let weights: Vec<Vec<f64>> = assignment
+            .iter()
+            .map(|&a| {
+                (0..states)
+                    .map(|k| if k == a { 1.0 } else { 0.0 })
+                    .collect()
+            })
+            .collect();
+        let floor = model.variance_floor(obs);
+        model.update_emissions(obs, &weights, floor);
+        model
+    }
+
+    fn variance_floor(&self, obs: &[Observation]) -> Observation {
+        let n = obs.len() as f64;
+        let mut floor = [0.0; 2];
+        for (d, f) in floor.iter_mut().enumerate() {
+            let mean = obs.iter().map(|o| o[d]).sum::<f64>() / n;
+            let var = obs.iter().map(|o| (o[d] - mean).powi(2)).sum::<f64>() / n;
+            *f = (var * 1e-3).max(1e-12);
+        }
+        floor
+    }
+
+    fn update_emissions(&mut self, obs: &[Observation], weights: &[Vec<f64>], floor: Observation) {
+        for k in 0..self.states() {
+            let total: f64 = weights.iter().map(|w| w[k]).sum();
+            if total <= 0.0 {
+                continue;
+            }
+            for d in 0..2 {
+                let mean = obs
+                    .iter()
+                    .zip(weights)
+                    .map(|(o, w)| w[k] * o[d])
+                    .sum::<f64>()
+                    / total;
+                let var = obs
+                    .iter()
+                    .zip(weights)
+                    .map(|(o, w)| w[k] * (o[d] - mean).powi(2))
+                    .sum::<f64>()
+                    / total;
+                self.means[k][d] = mean;
+                self.variances[k][d] = var.max(floor[d]);
+            }
+        }
+    }
+
+    /// 各状态的发射概率，按最大值缩放，只用于归一化后的递推
+    fn emissions(&self, o: &Observation) -> Vec<f64> {
+        let log: Vec<f64> = (0..self.states())
+            .map(|k| {
+                (0..2)
+                    .map(|d| {
+                        let var = self.variances[k][d];
+                        -0.5 * ((o[d] - self.means[k][d]).powi(2) / var
+                            + var.ln()
+                            + std::f64::consts::TAU.ln())
+                    })
+                    .sum::<f64>()
+            })
+            .collect();
+        let max = log.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        log.iter().map(|l| (l - max).exp()).collect()
+    }
+
+    /// 前向一步：prior 为上一根 bar 的后验，None 表示第一根
+    fn filter(&self, prior: Option<&[f64]>, o: &Observation) -> Vec<f64> {
+        let b = self.emissions(o);
+        let mut alpha: Vec<f64> = (0..self.states())
+            .map(|j| {
+                let p = match prior {
+                    Some(prior) => (0..self.states())
+                        .map(|i| prior[i] * self.transition[i][j])
+                        .sum(),
+                    None => self.initial[j],
+                };
+                p * b[j]
+            })
+            .collect();
+        normalize(&mut alpha);
+        alpha
+    }
+
+    // 一轮 Baum-Welch，前后向每步归一化
+    fn reestimate(&mut self, obs: &[Observation], floor: Observation) {
+        let states = self.states();
+        let b: Vec<Vec<f64>> = obs.iter().map(|o| self.emissions(o)).collect();
+
+        let mut alpha: Vec<Vec<f64>> = Vec::with_capacity(obs.len());
+        for o in obs {
+            let next = self.filter(alpha.last().map(Vec::as_slice), o);
+            alpha.push(next);
+        }
+        let mut beta = vec![vec![1.0; states]; obs.len()];
+        for t in (0..obs.len() - 1).rev() {
+            let mut next: Vec<f64> = (0..states)
+                .map(|i| {
+                    (0..states)
+                        .map(|j| self.transition[i][j] * b[t + 1][j] * beta[t + 1][j])
+                        .sum()
+                })
+                .collect();
+            normalize(&mut next);
+            beta[t] = next;
+        }
+
+        let gamma: Vec<Vec<f64>> = alpha
+            .iter()
+            .zip(&beta)
+            .map(|(a, be)| {
+                let mut g: Vec<f64> = a.iter().zip(be).map(|(x, y)| x * y).collect();
+                normalize(&mut g);
+                g
+            })
+            .collect();
+
+        let mut transitions = vec![vec![0.0; states]; states];
+        for t in 0..obs.len() - 1 {
+            let mut xi = vec![vec![0.0; states]; states];
+            let mut sum = 0.0;
+            for (i, row) in xi.iter_mut().enumerate() {
+                for (j, x) in row.iter_mut().enumerate() {
+                    *x = alpha[t][i] * self.transition[i][j] * b[t + 1][j] * beta[t + 1][j];
+                    sum += *x;
+                }
+            }
+            if sum <= 0.0 {
+                continue;
+            }
+            for (acc, row) in transitions.iter_mut().zip(&xi) {
+                for (a, x) in acc.iter_mut().zip(row) {
+                    *a += x / sum;
+                }
+            }
+        }
+        for (i, row) in transitions.iter_mut().enumerate() {
+            if row.iter().sum::<f64>() > 0.0 {
+                normalize(row);
+                self.transition[i] = row.clone();
+            }
+        }
+
+        self.initial = gamma[0].clone();
+        self.update_emissions(obs, &gamma, floor);
+    }
+
+    // 收益方差、效率比均值排在上半的状态分别记为高波动、趋势
+    fn label(&mut self) {
+        let states = self.states();
+        let upper_half = |key: &dyn Fn(usize) -> f64| {
+            let mut order: Vec<usize> = (0..states).collect();
+            order.sort_by(|&a, &b| key(a).total_cmp(&key(b)));
+            let mut upper = vec![false; states];
+            for &k in &order[states - states / 2..] {
+                upper[k] = true;
+            }
+            upper
+        };
+        let volatile = upper_half(&|k| self.variances[k][0]);
+        let trending = upper_half(&|k| self.means[k][1]);
+        self.regimes = (0..states)
+            .map(|k| {
+                Regime::new(
+                    if trending[k] {
+                        Trend::Trending
+                    } else {
+                        Trend::Ranging
+                    },
+                    if volatile[k] {
+                        Volatility::High
+                    } else {
+                        Volatility::Low
+                    },
+                )
+            })
+            .collect();
+    }
+}
+
+/// 用拟合好的 HmmModel 逐 bar 前向滤波，取后验概率最大的隐状态
+#[derive(Serialize, Deserialize)]
+pub struct HmmClassifier {
+    model: HmmModel,
+    closes: VecDeque<f64>,
+    posterior: Option<Vec<f64>>,
+}
+
+impl HmmClassifier {
+    pub fn new(model: HmmModel) -> Self {
+        Self {
+            closes: VecDeque::with_capacity(model.period + 1),
+            model,
+            posterior: None,
+        }
+    }
+
+    pub fn posterior(&self) -> Option<&[f64]> {
+        self.posterior.as_deref()
+    }
+}
+
+impl RegimeClassifier for HmmClassifier {
+    fn update(&mut self, bar: &MarketData) -> Option<Regime> {
+        self.closes.push_back(bar.close_price);
+        if self.closes.len() > self.model.period + 1 {
+            self.closes.pop_front();
+        }
+        if self.closes.len() <= self.model.period {
+            return None;
+        }
+        let o = observation(self.closes.make_contiguous())?;
+        let posterior = self.model.filter(self.posterior.as_deref(), &o);
+        let state = (0..posterior.len()).max_by(|&i, &j| posterior[i].total_cmp(&posterior[j]))?;
+        self.posterior = Some(posterior);
+        self.model.regimes.get(state).copied()
+    }
+
+    fn trained_until(&self) -> Option<DateTime<Utc>> {
+        Some(self.model.trained_until)
+    }
+
+    fn save_state(&self) -> Option<Value> {
+        serialize_state(&(&self.closes, &self.posterior))
+    }
+
+    /// 只恢复窗口和后验，模型用构建时拟合的
+    fn load_state(&mut self, state: &Value) -> bool {
+        match <(VecDeque<f64>, Option<Vec<f64>>)>::deserialize(state) {
+            Ok((closes, posterior)) => {
+                self.closes = closes;
+                self.posterior = posterior;
+                true
+            }
+            Err(_) => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, TimeZone, Utc};
+
+    use super::*;
+
+    // 交替出现的平静震荡段和剧烈单边段
+    fn switching_bars() -> (Vec<MarketData>, Vec<bool>) {
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let mut close = 100.0;
+        let mut bars = Vec::new();
+        let mut trending = Vec::new();
+        for segment in 0..8 {
+            let trend = segment % 2 == 1;
+            for i in 0..60 {
+                close = if trend {
+                    close * (1.0 + 0.03 + 0.02 * ((i * 7) % 5) as f64 / 4.0)
+                } else {
+                    close * (1.0 + 0.002 * if i % 2 == 0 { 1.0 } else { -1.0 })
+                };
+                let t = start + Duration::days(bars.len() as i64);
+                bars.push(MarketData::from_close(t, close));
+                trending.push(trend);
+            }
+        }
+        (bars, trending)
+    }
+
+    #[test]
+    fn test_hmm_separates_regimes() {
+        let (bars, trending) = switching_bars();
+        let model = HmmModel::fit(&bars, 2, 10, 30).unwrap();
+        assert_eq!(model.states(), 2);
+        assert_eq!(model.trained_until, bars.last().unwrap().timestamp);
+        let sum: f64 = model.transition[0].iter().sum();
+        assert!((sum - 1.0).abs() < 1e-9);
+
+        let mut classifier = HmmClassifier::new(model);
+        let mut correct = 0;
+        let mut total = 0;
+        for (bar, &trend) in bars.iter().zip(&trending) {
+            if let Some(regime) = classifier.update(bar) {
+                total += 1;
+                let expected = if trend {
+                    Regime::new(Trend::Trending, Volatility::High)
+                } else {
+                    Regime::new(Trend::Ranging, Volatility::Low)
+                };
+                if regime == expected {
+                    correct += 1;
+                }
+            }
+        }
+        // 段首效率比还没跟上，允许少量误判
+        assert!(correct as f64 > 0.85 * total as f64, "{correct}/{total}");
+    }
+
+    #[test]
+    fn test_hmm_needs_enough_bars() {
+        let (bars, _) = switching_bars();
+        assert!(HmmModel::fit(&bars[..30], 4, 10, 5).is_err());
+    }
+}
@@ -0,0 +1,170 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::strategy::signal::Signal;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Trend {
+    Trending,
+    Ranging,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Volatility {
+    #[serde(rename = "highVol")]
+    High,
+    #[serde(rename = "lowVol")]
+    Low,
+}
+
+/// 市场状态：趋势 / 震荡 × 高波动 / 低波动
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Regime {
+    pub trend: Trend,
+    pub volatility: Volatility,
+}
+
+impl Regime {
+    pub fn new(trend: Trend, volatility: Volatility) -> Self {
+        Self { trend, volatility }
+    }
+}
+
+impl fmt::Display for Regime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let trend = match self.trend {
+            Trend::Trending => "trending",
+            Trend::Ranging => "ranging",
+        };
+        let volatility = match self.volatility {
+            Volatility::High => "highVol",
+            Volatility::Low => "lowVol",
+        };
+        write!(f, "{}/{}", trend, volatility)
+    }
+}
+
+/// 配置里 tradeRegimes 可用的标签
+pub const REGIME_LABELS: &[&str] = &["trending", "ranging", "highVol", "lowVol"];
+
+/// 只在指定市场状态下开仓，平仓不受限制。
+/// 同一维度的标签取或，不同维度取且：["trending", "lowVol"] 只在低波动趋势市开仓，
+/// ["trending", "ranging"] 等于不限趋势；状态未知（预热期）时不开仓
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RegimeGate {
+    trends: Vec<Trend>,
+    volatilities: Vec<Volatility>,
+}
+
+impl RegimeGate {
+    /// 无法识别的标签忽略，全部无效时返回 None
+    pub fn from_labels(labels: &[Value]) -> Option<Self> {
+        let mut gate = Self::default();
+        for label in labels {
+            match (
+                Trend::deserialize(label).ok(),
+                Volatility::deserialize(label).ok(),
+            ) {
+                (Some(trend), _) => gate.trends.push(trend),
+                (_, Some(volatility)) => gate.volatilities.push(volatility),
+                _ => {}
+            }
+        }
+        if gate.trends.is_empty() && gate.volatilities.is_empty() {
+            None
+        } else {
+            Some(gate)
+        }
+    }
+
+    pub fn allows(&self, regime: Option<Regime>) -> bool {
+        let Some(regime) = regime else {
+            return false;
+        };
+        (self.trends.is_empty() || self.trends.contains(&regime.trend))
+            && (self.volatilities.is_empty() || self.volatilities.contains(&regime.volatility))
+    }
+
+    /// 不允许开仓时把开仓类信号换成 Hold，平仓和减仓原样保留
+    pub fn filter(&self, regime: Option<Regime>, signal: Signal) -> Signal {
+        if self.allows(regime) {
+            return signal;
+        }
+        Self::drop_entries(signal)
+    }
+
+    fn drop_entries(signal: Signal) -> Signal {
+        match signal {
+            Signal::EnterShort(_)
+            | Signal::EnterLong(_)
+            | Signal::Enter(_)
+            | Signal::ScaleIn(_)
+            | Signal::EnterPair(_)
+            | Signal::OpenLot(_)
+            | Signal::Invest { .. }
+            | Signal::Rebalance(_) => Signal::Hold,
+            // 目标仓位为 0 就是平仓
+            Signal::TargetPercent(percent) if percent != 0.0 => Signal::Hold,
+            Signal::Batch(signals) => {
+                let kept: Vec<Signal> = signals
+                    .into_iter()
+                    .map(Self::drop_entries)
+                    .filter(|s| *s != Signal::Hold)
+                    .collect();
+                if kept.is_empty() {
+                    Signal::Hold
+                } else {
+                    Signal::Batch(kept)
+                }
+            }
+            other => other,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_gate_combines_dimensions() {
+        let gate = RegimeGate::from_labels(&[json!("trending"), json!("lowVol")]).unwrap();
+        assert!(gate.allows(Some(Regime::new(Trend::Trending, Volatility::Low))));
+        assert!(!gate.allows(Some(Regime::new(Trend::Trending, Volatility::High))));
+        assert!(!gate.allows(Some(Regime::new(Trend::Ranging, Volatility::Low))));
+        assert!(!gate.allows(None));
+
+        let either = RegimeGate::from_labels(&[json!("trending"), json!("ranging")]).unwrap();
+        assert!(either.allows(Some(Regime::new(Trend::Ranging, Volatility::High))));
+        assert_eq!(RegimeGate::from_labels(&[json!("sideways")]), None);
+    }
+
+    #[test]
+    fn test_gate_keeps_exits() {
+        let gate = RegimeGate::from_labels(&[json!("trending")]).unwrap();
+        let ranging = Some(Regime::new(Trend::Ranging, Volatility::Low));
+        assert_eq!(gate.filter(ranging, Signal::EnterLong(10.0)), Signal::Hold);
+        assert_eq!(gate.filter(ranging, Signal::Exit), Signal::Exit);
+        assert_eq!(
+            gate.filter(ranging, Signal::TargetPercent(0.0)),
+            Signal::TargetPercent(0.0)
+        );
+        assert_eq!(
+            gate.filter(
+                ranging,
+                Signal::Batch(vec![Signal::Exit, Signal::EnterShort(10.0)])
+            ),
+            Signal::Batch(vec![Signal::Exit])
+        );
+        let trending = Some(Regime::new(Trend::Trending, Volatility::High));
+        assert_eq!(
+            gate.filter(trending, Signal::EnterLong(10.0)),
+            Signal::EnterLong(10.0)
+        );
+    }
+}
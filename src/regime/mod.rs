@@ -0,0 +1,9 @@
+pub mod hmm_classifier;
+pub mod market_regime;
+pub mod regime_factory;
+pub mod rule_classifier;
+pub mod tracker;
+
+pub use market_regime::{REGIME_LABELS, Regime, RegimeGate, Trend, Volatility};
+pub use regime_factory::RegimeFactory;
+pub use tracker::RegimeTracker;
@@ -0,0 +1,41 @@
+use std::error::Error;
+
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::domain::RegimeClassifier;
+
+use super::{
+    RegimeGate, RegimeTracker,
+    hmm_classifier::{HmmClassifier, HmmModel},
+    rule_classifier::RuleClassifier,
+};
+
+pub struct RegimeFactory;
+
+impl RegimeFactory {
+    /// regimeMethod 和 tradeRegimes 都没给时不做状态识别；只给了 tradeRegimes 时用规则识别。
+    /// hmm 用服务按 regimeModelId 取出后放进 regimeModel 的离线模型，没有模型时返回错误
+    pub fn build(params: &Value) -> Result<Option<RegimeTracker>, Box<dyn Error>> {
+        let gate = params
+            .get("tradeRegimes")
+            .and_then(Value::as_array)
+            .and_then(|labels| RegimeGate::from_labels(labels));
+        let method = params.get("regimeMethod").and_then(Value::as_str);
+        if method.is_none() && gate.is_none() {
+            return Ok(None);
+        }
+
+        let classifier: Box<dyn RegimeClassifier> = match method {
+            Some("hmm") => {
+                let model = params
+                    .get("regimeModel")
+                    .filter(|model| !model.is_null())
+                    .ok_or("regimeModelId is required for the hmm method")?;
+                Box::new(HmmClassifier::new(HmmModel::deserialize(model)?))
+            }
+            _ => Box::new(RuleClassifier::from_params(params)),
+        };
+        Ok(Some(RegimeTracker::new(classifier).with_gate(gate)))
+    }
+}
@@ -0,0 +1,174 @@
+use std::collections::VecDeque;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{
+    domain::RegimeClassifier,
+    indicators::{
+        adx_indicator::AdxIndicator,
+        indicator::{Indicator, deserialize_state, serialize_state},
+    },
+    strategy::market_data::MarketData,
+};
+
+use super::{Regime, Trend, Volatility};
+
+/// 规则识别：ADX 不低于阈值为趋势市；近 vol_period 根 bar 对数收益率的标准差
+/// 在最近 vol_lookback 个取值中的百分位不低于 vol_percentile 为高波动
+#[derive(Serialize, Deserialize)]
+pub struct RuleClassifier {
+    adx: AdxIndicator,
+    adx_threshold: f64,
+    vol_period: usize,
+    vol_lookback: usize,
+    /// 0~100
+    vol_percentile: f64,
+    prev_close: Option<f64>,
+    returns: VecDeque<f64>,
+    vols: VecDeque<f64>,
+}
+
+impl RuleClassifier {
+    pub fn new(
+        adx_period: usize,
+        adx_threshold: f64,
+        vol_period: usize,
+        vol_lookback: usize,
+        vol_percentile: f64,
+    ) -> Self {
+        let vol_period = vol_period.max(2);
+        Self {
+            adx: AdxIndicator::new(adx_period),
+            adx_threshold,
+            vol_period,
+            vol_lookback: vol_lookback.max(vol_period),
+            vol_percentile,
+            prev_close: None,
+            returns: VecDeque::with_capacity(vol_period),
+            vols: VecDeque::new(),
+        }
+    }
+
+    pub fn from_params(params: &Value) -> Self {
+        let adx_period = params
+            .get("regimeAdxPeriod")
+            .and_then(Value::as_u64)
+            .unwrap_or(14) as usize;
+        let adx_threshold = params
+            .get("regimeAdxThreshold")
+            .and_then(Value::as_f64)
+            .unwrap_or(25.0);
+        let vol_period = params
+            .get("regimeVolPeriod")
+            .and_then(Value::as_u64)
+            .unwrap_or(20) as usize;
+        let vol_lookback = params
+            .get("regimeVolLookback")
+            .and_then(Value::as_u64)
+            .unwrap_or(100) as usize;
+        let vol_percentile = params
+            .get("regimeVolPercentile")
+            .and_then(Value::as_f64)
+            .unwrap_or(70.0);
+        Self::new(
+            adx_period,
+            adx_threshold,
+            vol_period,
+            vol_lookback,
+            vol_percentile,
+        )
+    }
+
+    // 当前波动率在历史中的百分位（严格小于它的占比）
+    fn vol_rank(&mut self, close: f64) -> Option<f64> {
+        let prev = self.prev_close.replace(close)?;
+        if prev <= 0.0 || close <= 0.0 {
+            return None;
+        }
+        self.returns.push_back((close / prev).ln());
+        if self.returns.len() > self.vol_period {
+            self.returns.pop_front();
+        }
+        if self.returns.len() < self.vol_period {
+            return None;
+        }
+
+        let n = self.returns.len() as f64;
+        let mean = self.returns.iter().sum::<f64>() / n;
+        let vol = (self.returns.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / n).sqrt();
+        self.vols.push_back(vol);
+        if self.vols.len() > self.vol_lookback {
+            self.vols.pop_front();
+        }
+        // 历史太短时百分位没有意义
+        if self.vols.len() < self.vol_period {
+            return None;
+        }
+        let below = self.vols.iter().filter(|&&v| v < vol).count();
+        Some(100.0 * below as f64 / self.vols.len() as f64)
+    }
+}
+
+impl RegimeClassifier for RuleClassifier {
+    fn update(&mut self, bar: &MarketData) -> Option<Regime> {
+        self.adx.update_bar(bar);
+        let percentile = self.vol_rank(bar.close_price)?;
+        let adx = self.adx.value()?;
+
+        let trend = if adx >= self.adx_threshold {
+            Trend::Trending
+        } else {
+            Trend::Ranging
+        };
+        let volatility = if percentile >= self.vol_percentile {
+            Volatility::High
+        } else {
+            Volatility::Low
+        };
+        Some(Regime::new(trend, volatility))
+    }
+
+    fn save_state(&self) -> Option<Value> {
+        serialize_state(self)
+    }
+
+    fn load_state(&mut self, state: &Value) -> bool {
+        deserialize_state(self, state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use super::*;
+
+    fn bar(close: f64, range: f64) -> MarketData {
+        MarketData {
+            high: close + range,
+            low: close - range,
+            ..MarketData::from_close(Utc::now(), close)
+        }
+    }
+
+    #[test]
+    fn test_rule_classifier() {
+        let mut classifier = RuleClassifier::new(14, 25.0, 10, 60, 70.0);
+        let mut close = 100.0;
+        let mut last = None;
+        // 窄幅震荡
+        for i in 0..80 {
+            close = 100.0 + if i % 2 == 0 { 0.2 } else { -0.2 };
+            last = classifier.update(&bar(close, 0.3));
+        }
+        assert_eq!(last, Some(Regime::new(Trend::Ranging, Volatility::Low)));
+
+        // 大起小落的单边上涨
+        for i in 0..20 {
+            close *= if i % 2 == 0 { 1.05 } else { 0.99 };
+            last = classifier.update(&bar(close, close * 0.01));
+        }
+        assert_eq!(last, Some(Regime::new(Trend::Trending, Volatility::High)));
+    }
+}
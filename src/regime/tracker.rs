@@ -0,0 +1,117 @@
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+
+use crate::{
+    domain::RegimeClassifier,
+    engine::backtest_result::{RegimePerformance, Trade, TradeResultType},
+    strategy::{market_data::MarketData, signal::Signal},
+};
+
+use super::{Regime, RegimeGate, Trend, Volatility};
+
+/// 引擎里的市场状态识别：逐 bar 更新 StrategyContext::regime，可选地按状态拦截开仓，
+/// 并记下每根 bar 的状态，回测结束后按入场状态汇总绩效
+pub struct RegimeTracker {
+    classifier: Box<dyn RegimeClassifier>,
+    gate: Option<RegimeGate>,
+    history: Vec<(DateTime<Utc>, Option<Regime>)>,
+}
+
+impl RegimeTracker {
+    pub fn new(classifier: Box<dyn RegimeClassifier>) -> Self {
+        Self {
+            classifier,
+            gate: None,
+            history: Vec::new(),
+        }
+    }
+
+    pub fn with_gate(mut self, gate: Option<RegimeGate>) -> Self {
+        self.gate = gate;
+        self
+    }
+
+    pub fn update(&mut self, bar: &MarketData) -> Option<Regime> {
+        let regime = self.classifier.update(bar);
+        self.history.push((bar.timestamp, regime));
+        regime
+    }
+
+    /// 没配置 tradeRegimes 时原样返回
+    pub fn gate(&self, regime: Option<Regime>, signal: Signal) -> Signal {
+        match &self.gate {
+            Some(gate) => gate.filter(regime, signal),
+            None => signal,
+        }
+    }
+
+    pub fn trained_until(&self) -> Option<DateTime<Utc>> {
+        self.classifier.trained_until()
+    }
+
+    pub fn save_state(&self) -> Option<Value> {
+        self.classifier.save_state()
+    }
+
+    pub fn load_state(&mut self, state: &Value) -> bool {
+        self.classifier.load_state(state)
+    }
+
+    /// 某个时间点所在 bar 的状态
+    pub fn regime_at(&self, time: DateTime<Utc>) -> Option<Regime> {
+        let idx = self.history.partition_point(|(t, _)| *t <= time);
+        idx.checked_sub(1).and_then(|i| self.history[i].1)
+    }
+
+    /// 按交易入场时的状态分组统计；没有识别出状态的 bar 和交易不计入
+    pub fn breakdown(&self, trades: &[Trade]) -> Vec<RegimePerformance> {
+        let known = self.history.iter().filter(|(_, r)| r.is_some()).count();
+        let mut stats = Vec::new();
+        for trend in [Trend::Trending, Trend::Ranging] {
+            for volatility in [Volatility::High, Volatility::Low] {
+                let regime = Regime::new(trend, volatility);
+                let bars = self
+                    .history
+                    .iter()
+                    .filter(|(_, r)| *r == Some(regime))
+                    .count();
+                let trades: Vec<&Trade> = trades
+                    .iter()
+                    .filter(|t| {
+                        DateTime::parse_from_rfc3339(&t.entry_date)
+                            .is_ok_and(|at| self.regime_at(at.with_timezone(&Utc)) == Some(regime))
+                    })
+                    .collect();
+                if bars == 0 && trades.is_empty() {
+                    continue;
+                }
+
+                let wins = trades
+                    .iter()
+                    .filter(|t| t.result == TradeResultType::Win)
+                    .count();
+                let profit: f64 = trades.iter().map(|t| t.profit).sum();
+                let ratio = |n: usize, total: usize| {
+                    if total > 0 {
+                        n as f64 / total as f64
+                    } else {
+                        0.0
+                    }
+                };
+                stats.push(RegimePerformance {
+                    regime,
+                    time_share: ratio(bars, known),
+                    trades: trades.len(),
+                    win_rate: ratio(wins, trades.len()),
+                    profit,
+                    avg_profit: if trades.is_empty() {
+                        0.0
+                    } else {
+                        profit / trades.len() as f64
+                    },
+                });
+            }
+        }
+        stats
+    }
+}
@@ -50,4 +50,64 @@ impl RiskManager for FixedRiskManager {
         }
         None
     }
+
+    fn levels(&self, position: f64, entry_price: f64) -> (Option<f64>, Option<f64>) {
+        if position > 0.0 {
+            (
+                Some(entry_price * (1.0 - self.stop_loss)),
+                Some(entry_price * (1.0 + self.take_profit)),
+            )
+        } else if position < 0.0 {
+            (
+                Some(entry_price * (1.0 + self.stop_loss)),
+                Some(entry_price * (1.0 - self.take_profit)),
+            )
+        } else {
+            (None, None)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use super::*;
+    use crate::strategy::{market_data::MarketData, signal::Order};
+
+    fn bar(open: f64, high: f64, low: f64, close: f64) -> MarketData {
+        MarketData {
+            open,
+            high,
+            low,
+            ..MarketData::from_close(Utc::now(), close)
+        }
+    }
+
+    fn exit_at(price: f64) -> Option<Signal> {
+        Some(Signal::Reduce {
+            fraction: 1.0,
+            order: Order::market(price),
+        })
+    }
+
+    #[test]
+    fn test_check_bar_fills_at_trigger_level() {
+        let risk = FixedRiskManager {
+            stop_loss: 0.05,
+            take_profit: 0.1,
+        };
+        // 盘中跌破止损后收回止损价之上，按止损价成交而不是收盘价
+        let pierce = bar(99.0, 100.0, 90.0, 98.0);
+        assert_eq!(risk.check_bar(&pierce, 1.0, Some(100.0)), exit_at(95.0));
+        // 跳空低开在止损价之下，按开盘价成交
+        let gap = bar(92.0, 93.0, 91.0, 92.5);
+        assert_eq!(risk.check_bar(&gap, 1.0, Some(100.0)), exit_at(92.0));
+        // 空头触及止盈
+        let take = bar(95.0, 96.0, 88.0, 94.0);
+        assert_eq!(risk.check_bar(&take, -1.0, Some(100.0)), exit_at(90.0));
+        // 都没触及
+        let quiet = bar(100.0, 104.0, 96.0, 101.0);
+        assert_eq!(risk.check_bar(&quiet, 1.0, Some(100.0)), None);
+    }
 }
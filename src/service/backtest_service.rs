@@ -7,11 +7,12 @@ use serde_json::Value;
 use crate::{
     api::handlers::{StrategyBacktestRunRequest, backtest::LabBacktestRunRequest},
     data::{
+        data_feed::DataFeed,
         duckdb::{
             OhlcvRepository,
             repository::{
-                LabRunHistoryRepository, StrategyBuildsRepository, StrategyTemplateRepository,
-                TradeStrategyRepository,
+                LabRunHistoryRepository, StrategyBuildsRepository, StrategyScriptRepository,
+                StrategyTemplateRepository, TradeStrategyRepository,
                 backtest_run_repository::BacktestRunHistoryRepository,
                 connection::{UserConnectionManager, get_user_connection_manager},
             },
@@ -19,19 +20,106 @@ use crate::{
             types::Timestamp,
         },
         market_data_feed::MarketDataFeed,
-        sleddb::ChartDB,
+        sleddb::{ChartDB, regime_model_store::RegimeModelStore},
     },
     engine::{
-        backtest_result::{Balance, Trade},
+        backtest_result::{Balance, RegimePerformance, Trade},
         backtester::BacktestDriver,
-        parameters::BacktestInput,
+        chart::StrategyChart,
+        parameters::{BacktestInput, StrategyRunParameters},
     },
     indicators::{
         DistributionData, calculate_daily_return_distribution, calculate_monthly_returns,
         calculator::MonthlyReturnData,
     },
+    strategy::{
+        param_schema::FieldError, script_strategy::ScriptLimits, strategy_factory::StrategyFactory,
+    },
 };
 
+/// 回测失败的原因：参数只有在取出脚本、模型或构建策略时才发现有误的按字段返回 400，
+/// 其余是数据库错误
+#[derive(Debug)]
+pub enum BacktestError {
+    Invalid(Vec<FieldError>),
+    Db(duckdb::Error),
+}
+
+impl BacktestError {
+    pub fn invalid(field: &str, message: impl ToString) -> Self {
+        Self::Invalid(vec![FieldError::new(field, message.to_string())])
+    }
+}
+
+impl From<duckdb::Error> for BacktestError {
+    fn from(e: duckdb::Error) -> Self {
+        Self::Db(e)
+    }
+}
+
+/// 脚本策略按 scriptId 从当前用户的 strategy_scripts 表取出源码，资源上限截断到服务端允许的范围
+fn attach_script(
+    user_id: Option<i64>,
+    build_params: &Value,
+    params: &mut Value,
+) -> Result<(), BacktestError> {
+    let Some(script_id) = build_params.get("scriptId").and_then(Value::as_i64) else {
+        return Ok(());
+    };
+    let field = "params.scriptId";
+    let Some(user_id) = user_id else {
+        return Err(BacktestError::invalid(
+            field,
+            "sign in to use a saved script",
+        ));
+    };
+    let script = StrategyScriptRepository::build(user_id)
+        .get_by_id(script_id)?
+        .ok_or_else(|| BacktestError::invalid(field, format!("script {} not found", script_id)))?;
+    params["script"] = Value::String(script.source);
+    ScriptLimits::from_params(build_params).write_params(params);
+    Ok(())
+}
+
+/// 实验室回测的运行参数：scriptId、regimeModelId 指向的脚本和模型从当前用户的库里取出注入
+fn lab_run_params(
+    user_id: Option<i64>,
+    params: &StrategyRunParameters,
+) -> Result<Value, BacktestError> {
+    let given = serde_json::to_value(params).unwrap();
+    let mut run_params = given.clone();
+    attach_script(user_id, &given, &mut run_params)?;
+    attach_regime_model(user_id, &mut run_params)?;
+    Ok(run_params)
+}
+
+/// regimeMethod 为 hmm 时按 regimeModelId 取出用户事先拟合保存的模型，放进 regimeModel 交给
+/// RegimeFactory；回测本身不再拟合，避免用到回测区间内的数据
+fn attach_regime_model(user_id: Option<i64>, params: &mut Value) -> Result<(), BacktestError> {
+    if params.get("regimeMethod").and_then(Value::as_str) != Some("hmm") {
+        return Ok(());
+    }
+    let field = "params.regimeModelId";
+    let Some(id) = params.get("regimeModelId").and_then(Value::as_u64) else {
+        return Err(BacktestError::invalid(
+            field,
+            "is required for the hmm method",
+        ));
+    };
+    let Some(user_id) = user_id else {
+        return Err(BacktestError::invalid(
+            field,
+            "sign in to use a saved regime model",
+        ));
+    };
+    let model = RegimeModelStore::build(user_id)
+        .load(id)
+        .map_err(|e| BacktestError::invalid(field, e))?
+        .ok_or_else(|| BacktestError::invalid(field, format!("regime model {} not found", id)))?;
+    params["regimeModel"] = serde_json::to_value(model).unwrap();
+    Ok(())
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct RunBacktestParameters {
@@ -151,6 +239,12 @@ pub struct RunBacktestData {
     pub return_distribution: Vec<DistributionData>,
     pub version: Option<i64>,
     pub date: Option<String>,
+    /// 策略指标线和信号标记，请求 includeChart 时随结果一起存进 ChartDB
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub chart: Option<StrategyChart>,
+    /// 按入场时市场状态拆分的绩效
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub regimes: Vec<RegimePerformance>,
 }
 
 pub struct BacktestService {
@@ -322,9 +416,10 @@ impl BacktestService {
         &self,
         user_id: i64,
         run_strategy_backtest: StrategyBacktestRunRequest,
-    ) -> Result<RunBacktestData, duckdb::Error> {
+    ) -> Result<RunBacktestData, BacktestError> {
         let strategy_id = run_strategy_backtest.strategy_id;
         let strategy_type = run_strategy_backtest.r#type;
+        let include_chart = run_strategy_backtest.include_chart;
 
         let strategy_builds_dao = StrategyBuildsRepository::build(user_id);
         let strategy_build = strategy_builds_dao.get_by_id(strategy_id)?;
@@ -332,6 +427,14 @@ impl BacktestService {
         let strategy_build = strategy_build.unwrap();
 
         let mut params = run_strategy_backtest.params;
+        // 规则策略的规则保存在 trade_strategy.parameters 中，运行请求里没带时从那里取
+        if params.rules.is_none() {
+            params.rules = strategy_build
+                .parameters
+                .as_ref()
+                .and_then(|p| p.get("rules"))
+                .cloned();
+        }
 
         let market_details = StrategyRunMarketDetails {
             timeframe: run_strategy_backtest.timeframe,
@@ -373,20 +476,74 @@ impl BacktestService {
         let direction = direction_opt.unwrap();
         params.position_type = Some(direction.to_string());
 
+        // 组合类策略（定投、再平衡）用到全部资产：按资产顺序对齐行情，并带上目标配比
+        let asset_list = assets
+            .as_ref()
+            .and_then(|v| v.as_array())
+            .cloned()
+            .unwrap_or_default();
+        let mut symbols = vec![symbol];
+        symbols.extend(
+            asset_list
+                .iter()
+                .filter_map(|a| a.get("symbol").and_then(|s| s.as_str()))
+                .filter(|s| *s != symbol),
+        );
+        let allocations: Vec<Value> = asset_list
+            .iter()
+            .filter_map(|a| {
+                Some(serde_json::json!({
+                    "symbol": a.get("symbol")?,
+                    "allocation": a.get("allocation")?,
+                }))
+            })
+            .collect();
+
+        let mut strategy_run_params = serde_json::to_value(&params).unwrap();
+        if !allocations.is_empty() {
+            strategy_run_params["allocations"] = Value::Array(allocations);
+        }
+
+        let build_params = strategy_build.parameters.clone().unwrap_or(Value::Null);
+        let attached = attach_script(Some(user_id), &build_params, &mut strategy_run_params)
+            .and_then(|_| attach_regime_model(Some(user_id), &mut strategy_run_params));
+        if let Err(e) = attached {
+            lab_running_backtest.status = "failed".to_string();
+            backtest_run_history_dao.update(&lab_running_backtest)?;
+            return Err(e);
+        }
+
         let backtest_input = BacktestInput {
             r#type: strategy_type,
             initial_capital: market_details.initial_capital,
-            strategy_run_params: serde_json::to_value(&params).unwrap(),
+            strategy_run_params,
         };
 
-        let datafeed = MarketDataFeed::from_coins_market(symbol);
+        // 只有组合和配对策略按资产对齐行情；单品种策略用第一个资产自己的完整历史，
+        // 不会被其他资产的缺失日期截掉
+        let datafeed: Result<Box<dyn DataFeed>, _> =
+            if StrategyFactory::is_multi_asset(&backtest_input.r#type) {
+                MarketDataFeed::aligned(&symbols).map(|feed| Box::new(feed) as Box<dyn DataFeed>)
+            } else {
+                MarketDataFeed::from_coins_market(symbol)
+                    .map(|feed| Box::new(feed) as Box<dyn DataFeed>)
+            };
 
         let backtest_result = match datafeed {
             Ok(ohlcv) => {
-                let backtest_driver = BacktestDriver::new(backtest_input, ohlcv);
-                let res = backtest_driver.build_and_run_backtest();
-                lab_running_backtest.status = "success".to_string();
-                Some(res)
+                let backtest_driver =
+                    BacktestDriver::new(backtest_input, ohlcv).with_chart(include_chart);
+                match backtest_driver.build_and_run_backtest() {
+                    Ok(res) => {
+                        lab_running_backtest.status = "success".to_string();
+                        Some(res)
+                    }
+                    Err(e) => {
+                        lab_running_backtest.status = "failed".to_string();
+                        backtest_run_history_dao.update(&lab_running_backtest)?;
+                        return Err(BacktestError::invalid("params", e));
+                    }
+                }
             }
             Err(_) => {
                 lab_running_backtest.status = "failed".to_string();
@@ -425,6 +582,8 @@ impl BacktestService {
             return_distribution: daily_returns,
             version: lab_running_backtest.id,
             date: Some(lab_running_backtest.start_time.0.to_rfc3339()),
+            chart: backtest_result.chart,
+            regimes: backtest_result.regimes,
         };
 
         let run_id = lab_running_backtest.id.unwrap();
@@ -441,10 +600,12 @@ impl BacktestService {
 
     pub fn run_lab_backtest(
         &self,
+        user_id: Option<i64>,
         run_lab_backtest: LabBacktestRunRequest,
-    ) -> Result<RunBacktestData, duckdb::Error> {
+    ) -> Result<RunBacktestData, BacktestError> {
         let template_id = run_lab_backtest.template_id;
         let strategy_type = run_lab_backtest.r#type;
+        let include_chart = run_lab_backtest.include_chart;
 
         let pairs = run_lab_backtest.pairs;
         let mut params = run_lab_backtest.params;
@@ -481,22 +642,33 @@ impl BacktestService {
             market_details: serde_json::to_value(&market_details).unwrap(),
         };
 
+        let strategy_run_params = lab_run_params(user_id, &params)?;
+
         let mut lab_running_backtest = run_history_repo.create(run_history)?;
 
         let backtest_input = BacktestInput {
             r#type: strategy_type,
             initial_capital: run_lab_backtest.initial_capital,
-            strategy_run_params: serde_json::to_value(&params).unwrap(),
+            strategy_run_params,
         };
 
         let datafeed = MarketDataFeed::from_coins_market(&pairs);
 
         let backtest_result = match datafeed {
             Ok(ohlcv) => {
-                let backtest_driver = BacktestDriver::new(backtest_input, ohlcv);
-                let res = backtest_driver.build_and_run_backtest();
-                lab_running_backtest.status = "success".to_string();
-                Some(res)
+                let backtest_driver =
+                    BacktestDriver::new(backtest_input, ohlcv).with_chart(include_chart);
+                match backtest_driver.build_and_run_backtest() {
+                    Ok(res) => {
+                        lab_running_backtest.status = "success".to_string();
+                        Some(res)
+                    }
+                    Err(e) => {
+                        lab_running_backtest.status = "failed".to_string();
+                        run_history_repo.update(&lab_running_backtest)?;
+                        return Err(BacktestError::invalid("params", e));
+                    }
+                }
             }
             Err(_) => {
                 lab_running_backtest.status = "failed".to_string();
@@ -538,6 +710,8 @@ impl BacktestService {
             return_distribution: daily_returns,
             version: lab_running_backtest.id,
             date: Some(lab_running_backtest.start_time.0.to_rfc3339()),
+            chart: backtest_result.chart,
+            regimes: backtest_result.regimes,
         };
 
         let run_id = lab_running_backtest.id.unwrap();
@@ -551,3 +725,58 @@ impl BacktestService {
         Ok(result)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn script_params(script_id: i64) -> StrategyRunParameters {
+        serde_json::from_value(json!({
+            "scriptId": script_id,
+            "maxOperations": u64::MAX,
+        }))
+        .unwrap()
+    }
+
+    fn error_field(result: Result<Value, BacktestError>) -> String {
+        match result {
+            Err(BacktestError::Invalid(errors)) => errors[0].field.clone(),
+            other => panic!("expected a field error, got {:?}", other.map(|_| ())),
+        }
+    }
+
+    #[test]
+    fn test_lab_params_need_a_user_for_saved_scripts() {
+        assert_eq!(
+            error_field(lab_run_params(None, &script_params(1))),
+            "params.scriptId"
+        );
+        // 没有 scriptId 时原样返回
+        let inline: StrategyRunParameters =
+            serde_json::from_value(json!({ "script": "\"hold\"" })).unwrap();
+        assert_eq!(lab_run_params(None, &inline).unwrap()["script"], "\"hold\"");
+    }
+
+    #[test]
+    fn test_lab_params_load_the_users_script() {
+        let user_id = 9_040;
+        let script_id = StrategyScriptRepository::build(user_id)
+            .save("lab-script", "\"hold\"")
+            .unwrap();
+
+        let run_params = lab_run_params(Some(user_id), &script_params(script_id)).unwrap();
+        assert_eq!(run_params["script"], "\"hold\"");
+        assert_eq!(run_params["maxOperations"], ScriptLimits::MAX_OPERATIONS);
+        assert!(StrategyFactory::new().build("script", &run_params).is_ok());
+
+        assert_eq!(
+            error_field(lab_run_params(
+                Some(user_id),
+                &script_params(script_id + 1_000)
+            )),
+            "params.scriptId"
+        );
+    }
+}
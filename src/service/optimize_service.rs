@@ -61,6 +61,9 @@ fn generate_combos(grid: &GridParams) -> Vec<StrategyRunParameters> {
                 entry_z_score: None,
                 band_multiplier: None,
                 cooldown_period: None,
+                rules: None,
+                regime: Default::default(),
+                extra: Default::default(),
             },
         )
         .collect()
@@ -7,12 +7,14 @@ use crate::{
     data::{
         duckdb::{
             repository::{
-                StrategyBuildsRepository, backtest_run_repository::BacktestRunHistoryRepository,
+                StrategyBuildsRepository, StrategyScriptRepository,
+                backtest_run_repository::BacktestRunHistoryRepository,
             },
-            schema::{StrategyBuilds, strategy_builds::ProgressInType},
+            schema::{StrategyBuilds, StrategyScript, strategy_builds::ProgressInType},
         },
         sleddb::ChartDB,
     },
+    strategy::{rule_strategy::RuleStrategy, script_strategy::ScriptStrategy},
     utils::params::split_params,
 };
 
@@ -157,6 +159,7 @@ pub struct StrategyBuilder {
 pub struct StrategyService {
     strategy_builds: Arc<StrategyBuildsRepository>,
     backtest_run_history: Arc<BacktestRunHistoryRepository>,
+    strategy_scripts: Arc<StrategyScriptRepository>,
     chart_db: Arc<ChartDB>,
 }
 
@@ -165,9 +168,11 @@ impl StrategyService {
         let chart_db = ChartDB::build(user_id);
         let strategy_builds = Arc::new(StrategyBuildsRepository::build(user_id));
         let backtest_run_history = Arc::new(BacktestRunHistoryRepository::build(user_id));
+        let strategy_scripts = Arc::new(StrategyScriptRepository::build(user_id));
         Self {
             strategy_builds,
             backtest_run_history,
+            strategy_scripts,
             chart_db: Arc::new(chart_db),
         }
     }
@@ -233,6 +238,11 @@ impl StrategyService {
             }
             ProgressStage::Parameters => match (builder.id, builder.progress_in_parameters) {
                 (Some(id), Some(params)) => {
+                    // 规则策略保存前先编译一遍，错误信息里带着出错节点的路径
+                    let build = self.strategy_builds.get_by_id(id)?;
+                    if build.is_some_and(|b| b.algorithm_type == "rules") {
+                        RuleStrategy::compile(&params)?;
+                    }
                     let next_state = self.build_next_stage(id, &progress_stage);
                     self.strategy_builds
                         .update_parameters(id, &params, next_state)?;
@@ -347,4 +357,24 @@ impl StrategyService {
         )?;
         if result == 0 { Ok(false) } else { Ok(true) }
     }
+
+    /// 保存前先编译，语法错误不会进库
+    pub fn save_script(&self, name: &str, source: &str) -> Result<StrategyScript, Box<dyn Error>> {
+        ScriptStrategy::check(source)?;
+        let id = self.strategy_scripts.save(name, source)?;
+        let script = self
+            .strategy_scripts
+            .get_by_id(id)?
+            .ok_or("saved script not found")?;
+        Ok(script)
+    }
+
+    pub fn get_scripts(&self) -> Result<Vec<StrategyScript>, Box<dyn Error>> {
+        Ok(self.strategy_scripts.get_all()?)
+    }
+
+    pub fn delete_script(&self, id: i64) -> Result<bool, Box<dyn Error>> {
+        let result = self.strategy_scripts.delete_by_id(id)?;
+        Ok(result > 0)
+    }
 }
@@ -0,0 +1,49 @@
+use std::error::Error;
+
+use crate::{domain::PositionSizer, strategy::strategy_context::StrategyContext};
+
+/// ATR 头寸计算器（海龟单位）：一个 ATR 的波动对应权益的 risk_per_unit
+pub struct AtrSizer {
+    risk_per_unit: f64,
+}
+
+impl AtrSizer {
+    /// risk_per_unit 来自用户参数，不在 (0,1] 内时返回错误
+    pub fn new(risk_per_unit: f64) -> Result<Self, Box<dyn Error>> {
+        if !(0.0 < risk_per_unit && risk_per_unit <= 1.0) {
+            return Err(format!("riskPerTrade must be in (0,1], got {}", risk_per_unit).into());
+        }
+        Ok(Self { risk_per_unit })
+    }
+}
+
+impl PositionSizer for AtrSizer {
+    /// 没有 ATR 时不开仓；数量不超过现有权益能买到的上限
+    fn calc(&self, price: f64, ctx: &StrategyContext) -> f64 {
+        let Some(atr) = ctx.atr.filter(|a| *a > 0.0) else {
+            return 0.0;
+        };
+        if price <= 0.0 {
+            return 0.0;
+        }
+        let equity = ctx.account_equity.max(0.0);
+        (equity * self.risk_per_unit / atr).min(equity / price)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::sizer::sizer_factory::SizerFactory;
+
+    #[test]
+    fn test_out_of_range_risk_is_an_error() {
+        assert!(AtrSizer::new(0.0).is_err());
+        assert!(AtrSizer::new(1.5).is_err());
+        assert!(AtrSizer::new(0.01).is_ok());
+        assert!(SizerFactory::build(&json!({ "sizing": "atr", "riskPerTrade": 0 })).is_err());
+        assert!(SizerFactory::build(&json!({ "sizing": "atr" })).is_ok());
+    }
+}
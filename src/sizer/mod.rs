@@ -1,3 +1,4 @@
+pub mod atr_sizer;
 pub mod fixed_fractional_sizer;
 pub mod fixed_size_sizer;
 pub mod sizer_factory;
@@ -1,13 +1,19 @@
+use std::error::Error;
+
 use serde_json::Value;
 
 use crate::domain::PositionSizer;
 
-use super::{fixed_fractional_sizer::FixedFractionalSizer, fixed_size_sizer::FixedSizeSizer};
+use super::{
+    atr_sizer::AtrSizer, fixed_fractional_sizer::FixedFractionalSizer,
+    fixed_size_sizer::FixedSizeSizer,
+};
 
 pub struct SizerFactory;
 
 impl SizerFactory {
-    pub fn build(params: &Value) -> Box<dyn PositionSizer> {
+    /// 参数超出仓位计算器允许的范围时返回错误
+    pub fn build(params: &Value) -> Result<Box<dyn PositionSizer>, Box<dyn Error>> {
         let risk_per_trade = params.get("riskPerTrade").and_then(Value::as_f64);
         let stop_loss = params
             .get("stopLoss")
@@ -18,10 +24,18 @@ impl SizerFactory {
             .and_then(Value::as_f64)
             .unwrap_or(1.0);
 
+        if params.get("sizing").and_then(Value::as_str) == Some("atr") {
+            return Ok(Box::new(AtrSizer::new(risk_per_trade.unwrap_or(0.01))?));
+        }
+
         if let Some(risk) = risk_per_trade {
-            Box::new(FixedFractionalSizer::new(risk, stop_loss, position_size))
+            Ok(Box::new(FixedFractionalSizer::new(
+                risk,
+                stop_loss,
+                position_size,
+            )))
         } else {
-            Box::new(FixedSizeSizer::new(position_size))
+            Ok(Box::new(FixedSizeSizer::new(position_size)))
         }
     }
 }
@@ -0,0 +1,144 @@
+use chrono::{DateTime, Datelike, Utc};
+use serde_json::Value;
+
+use super::param_schema::ParamSpec;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ClockPeriod {
+    Bar,
+    Day,
+    Week,
+    Month,
+    // 从不按时间触发（如只按偏离阈值再平衡）
+    Never,
+}
+
+impl ClockPeriod {
+    pub fn parse(s: &str) -> Self {
+        match s {
+            "day" | "daily" => ClockPeriod::Day,
+            "week" | "weekly" => ClockPeriod::Week,
+            "month" | "monthly" => ClockPeriod::Month,
+            "never" | "none" => ClockPeriod::Never,
+            _ => ClockPeriod::Bar,
+        }
+    }
+}
+
+/// 按 bar 数或自然日/周/月计时：每跨过 interval 个周期触发一次，第一根 bar 总是触发。
+/// 周期按 bar 的时间戳（UTC）划分，数据有缺口时在缺口后的第一根 bar 触发。
+pub struct BarClock {
+    period: ClockPeriod,
+    interval: i64,
+    bars: i64,
+    last: Option<i64>,
+}
+
+impl BarClock {
+    pub fn new(period: ClockPeriod, interval: u32) -> Self {
+        Self {
+            period,
+            interval: interval.max(1) as i64,
+            bars: 0,
+            last: None,
+        }
+    }
+
+    /// from_params 读取的两个参数，default_period 同 from_params
+    pub fn params(default_period: &'static str) -> Vec<ParamSpec> {
+        vec![
+            ParamSpec::choice(
+                "every",
+                &[
+                    "bar", "day", "daily", "week", "weekly", "month", "monthly", "never", "none",
+                ],
+                "触发周期",
+            )
+            .default(default_period),
+            ParamSpec::int("interval", "每隔几个周期触发一次")
+                .default(1)
+                .min(1.0),
+        ]
+    }
+
+    /// 读取 "every"（bar/day/week/month/never）和 "interval"
+    pub fn from_params(params: &Value, default_period: &str) -> Self {
+        let period = ClockPeriod::parse(
+            params
+                .get("every")
+                .and_then(Value::as_str)
+                .unwrap_or(default_period),
+        );
+        let interval = params.get("interval").and_then(Value::as_u64).unwrap_or(1) as u32;
+        Self::new(period, interval)
+    }
+
+    fn key(&self, ts: &DateTime<Utc>) -> i64 {
+        let days = ts.date_naive().num_days_from_ce() as i64;
+        match self.period {
+            ClockPeriod::Bar | ClockPeriod::Never => self.bars,
+            ClockPeriod::Day => days,
+            // 以周一为一周的开始
+            ClockPeriod::Week => (days - ts.weekday().num_days_from_monday() as i64) / 7,
+            ClockPeriod::Month => ts.year() as i64 * 12 + ts.month0() as i64,
+        }
+    }
+
+    /// 推进一根 bar，返回这根 bar 是否到了触发时点
+    pub fn tick(&mut self, ts: &DateTime<Utc>) -> bool {
+        let key = self.key(ts);
+        self.bars += 1;
+        if self.period == ClockPeriod::Never {
+            return false;
+        }
+        let due = self.last.is_none_or(|last| key - last >= self.interval);
+        if due {
+            self.last = Some(key);
+        }
+        due
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, TimeZone};
+
+    use super::*;
+
+    fn fired(clock: &mut BarClock, start: DateTime<Utc>, step: Duration, n: i64) -> Vec<i64> {
+        (0..n)
+            .filter(|&i| clock.tick(&(start + step * i as i32)))
+            .collect()
+    }
+
+    #[test]
+    fn test_bar_and_calendar_schedules() {
+        // 2024-01-01 是周一
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+
+        let mut every_three = BarClock::new(ClockPeriod::Bar, 3);
+        assert_eq!(
+            fired(&mut every_three, start, Duration::days(1), 8),
+            vec![0, 3, 6]
+        );
+
+        let mut weekly = BarClock::new(ClockPeriod::Week, 1);
+        assert_eq!(
+            fired(
+                &mut weekly,
+                start + Duration::days(2),
+                Duration::days(1),
+                14
+            ),
+            vec![0, 5, 12]
+        );
+
+        // 4 小时线上按月触发：1 月 1 日、2 月 1 日、3 月 1 日
+        let mut monthly = BarClock::new(ClockPeriod::Month, 1);
+        let hits = fired(&mut monthly, start, Duration::hours(4), 6 * 70);
+        assert_eq!(hits, vec![0, 6 * 31, 6 * 60]);
+
+        let mut never = BarClock::new(ClockPeriod::Never, 1);
+        assert!(fired(&mut never, start, Duration::days(1), 5).is_empty());
+    }
+}
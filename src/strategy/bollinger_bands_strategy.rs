@@ -0,0 +1,366 @@
+use std::collections::VecDeque;
+
+use serde_json::Value;
+
+use crate::indicators::{
+    indicator::Indicator, moving_average::SimpleMovingAverage, registry::IndicatorRegistry,
+    std_dev_indicator::StdDevIndicator,
+};
+
+use super::{
+    market_data::MarketData,
+    param_schema::{ParamSpec, RISK, StrategySchema, position_type},
+    position::{PositionType, TradePosition},
+    signal::Signal,
+    strategy_trait::Strategy,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BandMode {
+    // 收盘价突破上轨做多、跌破下轨做空
+    Breakout,
+    // 只在带宽收窄（squeeze）之后的突破入场
+    Squeeze,
+}
+
+impl BandMode {
+    pub fn parse(s: &str) -> Self {
+        match s {
+            "squeeze" => BandMode::Squeeze,
+            _ => BandMode::Breakout,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Bands {
+    pub upper: f64,
+    pub middle: f64,
+    pub lower: f64,
+}
+
+impl Bands {
+    pub fn percent_b(&self, price: f64) -> f64 {
+        let width = self.upper - self.lower;
+        if width == 0.0 {
+            0.5
+        } else {
+            (price - self.lower) / width
+        }
+    }
+
+    pub fn bandwidth(&self) -> f64 {
+        if self.middle == 0.0 {
+            0.0
+        } else {
+            (self.upper - self.lower) / self.middle
+        }
+    }
+}
+
+pub struct BollingerBandsStrategy {
+    name: String,
+    mean: Box<dyn Indicator>,
+    volatility: StdDevIndicator,
+    std_dev: f64,
+    mode: BandMode,
+    squeeze_lookback: usize,
+    squeeze_percentile: f64,
+    exit_percent_b: f64,
+    bandwidths: VecDeque<f64>,
+    squeezed: bool,
+    position_type: PositionType,
+}
+
+impl BollingerBandsStrategy {
+    pub fn new(name: String, mean_type: &str, period: usize, std_dev: f64, mode: BandMode) -> Self {
+        let mean: Box<dyn Indicator> = IndicatorRegistry::new()
+            .build(mean_type, &[period as f64])
+            .unwrap_or_else(|_| Box::new(SimpleMovingAverage::new(period)));
+        BollingerBandsStrategy {
+            name,
+            mean,
+            volatility: StdDevIndicator::new(period),
+            std_dev,
+            mode,
+            squeeze_lookback: 120,
+            squeeze_percentile: 20.0,
+            exit_percent_b: 0.5,
+            bandwidths: VecDeque::new(),
+            squeezed: false,
+            position_type: PositionType::Both,
+        }
+    }
+
+    /// 参数说明，见 StrategySchema
+    pub fn schema() -> StrategySchema {
+        StrategySchema::new(
+            "布林带：突破上下轨入场，可只做收窄后的突破",
+            vec![
+                ParamSpec::choice("meanType", &["sma", "ema", "wma"], "中轨均线类型"),
+                ParamSpec::int("period", "中轨和标准差的周期")
+                    .default(20)
+                    .min(1.0),
+                ParamSpec::float("stdDev", "上下轨的标准差倍数")
+                    .default(2.0)
+                    .min(0.0),
+                ParamSpec::choice("mode", &["breakout", "squeeze"], "入场方式"),
+                ParamSpec::int("squeezeLookback", "判断收窄的回看 bar 数")
+                    .default(120)
+                    .min(1.0),
+                ParamSpec::float("squeezePercentile", "带宽低于该分位数视为收窄")
+                    .default(20.0)
+                    .range(0.0, 100.0),
+                ParamSpec::float("exitPercentB", "%B 回到该值离场").default(0.5),
+                position_type(),
+            ],
+        )
+        .with_risk(RISK)
+    }
+
+    pub fn from_params(params: &Value) -> Box<dyn Strategy> {
+        let position_type = match params.get("positionType").and_then(Value::as_str) {
+            Some("long") => PositionType::Long,
+            Some("short") => PositionType::Short,
+            _ => PositionType::Both,
+        };
+
+        let mean_type = params
+            .get("meanType")
+            .and_then(Value::as_str)
+            .unwrap_or("sma");
+        let period = params
+            .get("period")
+            .and_then(Value::as_u64)
+            .unwrap_or(20)
+            .max(1) as usize;
+        let std_dev = params.get("stdDev").and_then(Value::as_f64).unwrap_or(2.0);
+        let mode = BandMode::parse(
+            params
+                .get("mode")
+                .and_then(Value::as_str)
+                .unwrap_or("breakout"),
+        );
+        let squeeze_lookback = params
+            .get("squeezeLookback")
+            .and_then(Value::as_u64)
+            .unwrap_or(120) as usize;
+        let squeeze_percentile = params
+            .get("squeezePercentile")
+            .and_then(Value::as_f64)
+            .unwrap_or(20.0);
+        let exit_percent_b = params
+            .get("exitPercentB")
+            .and_then(Value::as_f64)
+            .unwrap_or(0.5);
+
+        let mut strategy = Self::new(
+            "bollinger-bands".to_string(),
+            mean_type,
+            period,
+            std_dev,
+            mode,
+        );
+        strategy.squeeze_lookback = squeeze_lookback.max(1);
+        strategy.squeeze_percentile = squeeze_percentile;
+        strategy.exit_percent_b = exit_percent_b;
+        strategy.position_type = position_type;
+        Box::new(strategy)
+    }
+
+    /// `sub_type` selects the mode ("breakout" or "squeeze").
+    pub fn create(name: String, sub_type: &str, period: usize) -> Self {
+        Self::new(name, "sma", period, 2.0, BandMode::parse(sub_type))
+    }
+
+    pub fn bands(&self) -> Option<Bands> {
+        let middle = self.mean.value()?;
+        let sd = self.volatility.value()?;
+        Some(Bands {
+            upper: middle + self.std_dev * sd,
+            middle,
+            lower: middle - self.std_dev * sd,
+        })
+    }
+
+    // 当前带宽在最近 squeeze_lookback 根 bar 中的百分位（相同值取中间名次）
+    fn bandwidth_percentile(&self, bandwidth: f64) -> f64 {
+        let below = self.bandwidths.iter().filter(|&&b| b < bandwidth).count();
+        let equal = self.bandwidths.iter().filter(|&&b| b == bandwidth).count();
+        (below as f64 + equal as f64 / 2.0) / self.bandwidths.len() as f64 * 100.0
+    }
+
+    fn observe(&mut self, price: f64) -> Option<(Bands, bool)> {
+        self.mean.update(price);
+        self.volatility.update(price);
+        let bands = self.bands()?;
+
+        let bandwidth = bands.bandwidth();
+        self.bandwidths.push_back(bandwidth);
+        while self.bandwidths.len() > self.squeeze_lookback {
+            self.bandwidths.pop_front();
+        }
+        let was_squeezed = self.squeezed;
+        self.squeezed = self.bandwidths.len() == self.squeeze_lookback
+            && self.bandwidth_percentile(bandwidth) <= self.squeeze_percentile;
+        Some((bands, was_squeezed))
+    }
+}
+
+impl Strategy for BollingerBandsStrategy {
+    fn generate_signal(&mut self, price: f64, position: f64) -> Signal {
+        let Some((bands, was_squeezed)) = self.observe(price) else {
+            return Signal::Hold;
+        };
+        let percent_b = bands.percent_b(price);
+
+        // %B 回落/回升到阈值时平仓
+        if position > 0.0 && percent_b <= self.exit_percent_b {
+            return Signal::Exit;
+        }
+        if position < 0.0 && percent_b >= 1.0 - self.exit_percent_b {
+            return Signal::Exit;
+        }
+
+        let armed = match self.mode {
+            BandMode::Breakout => true,
+            BandMode::Squeeze => was_squeezed,
+        };
+        if armed && percent_b > 1.0 && position <= 0.0 {
+            return Signal::EnterLong(price);
+        }
+        if armed && percent_b < 0.0 && position >= 0.0 {
+            return Signal::EnterShort(price);
+        }
+        Signal::Hold
+    }
+
+    fn update(&mut self, market_data: &MarketData, _current_position: &Option<TradePosition>) {
+        self.observe(market_data.close_price);
+    }
+
+    fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    // entry_threshold 为带宽倍数，exit_threshold 为 %B 平仓阈值
+    fn apply_parameters(&mut self, entry_threshold: Option<f64>, exit_threshold: Option<f64>) {
+        if let Some(entry) = entry_threshold {
+            self.std_dev = entry;
+        }
+        if let Some(exit) = exit_threshold {
+            self.exit_percent_b = exit;
+        }
+    }
+
+    fn chart_values(&self) -> Vec<(String, Option<f64>)> {
+        let bands = self.bands();
+        vec![
+            ("middle".to_string(), bands.map(|b| b.middle)),
+            ("upper".to_string(), bands.map(|b| b.upper)),
+            ("lower".to_string(), bands.map(|b| b.lower)),
+        ]
+    }
+
+    fn position_type(&self) -> &PositionType {
+        &self.position_type
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn run(strategy: &mut dyn Strategy, prices: &[f64]) -> Vec<Signal> {
+        let mut position = 0.0;
+        prices
+            .iter()
+            .map(|&p| {
+                let signal = strategy.generate_signal(p, position);
+                match signal {
+                    Signal::EnterLong(_) => position = 1.0,
+                    Signal::EnterShort(_) => position = -1.0,
+                    Signal::Exit => position = 0.0,
+                    _ => {}
+                }
+                signal
+            })
+            .collect()
+    }
+
+    // 振幅逐渐放大的震荡（第 30 根冲高） -> 窄幅盘整 -> 向上突破 -> 回落
+    fn squeeze_then_breakout() -> Vec<f64> {
+        let mut prices: Vec<f64> = (0..40)
+            .map(|i| {
+                let amplitude = 2.0 + i as f64 * 0.1;
+                100.0 + if i % 2 == 0 { amplitude } else { -amplitude }
+            })
+            .collect();
+        prices[30] = 112.0;
+        prices.extend((0..20).map(|i| 100.0 + if i % 2 == 0 { 0.2 } else { -0.2 }));
+        prices.extend([103.0, 106.0, 108.0, 107.0, 103.0, 100.0, 98.0]);
+        prices
+    }
+
+    #[test]
+    fn test_bands_and_percent_b() {
+        let mut strategy =
+            BollingerBandsStrategy::new("bb".to_string(), "sma", 4, 2.0, BandMode::Breakout);
+        for p in [1.0, 2.0, 3.0, 4.0] {
+            strategy.observe(p);
+        }
+        let bands = strategy.bands().unwrap();
+        let sd = 1.25_f64.sqrt();
+        assert!((bands.middle - 2.5).abs() < 1e-12);
+        assert!((bands.upper - (2.5 + 2.0 * sd)).abs() < 1e-12);
+        assert!((bands.percent_b(bands.lower)).abs() < 1e-12);
+        assert!((bands.percent_b(bands.upper) - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_breakout_entry_and_percent_b_exit() {
+        let mut strategy = BollingerBandsStrategy::from_params(&json!({
+            "period": 10,
+            "stdDev": 2.0,
+            "mode": "breakout"
+        }));
+        let signals = run(strategy.as_mut(), &squeeze_then_breakout());
+
+        let entry = signals
+            .iter()
+            .position(|s| matches!(s, Signal::EnterLong(_)))
+            .unwrap();
+        assert_eq!(signals[entry], Signal::EnterLong(103.0));
+        let exit = signals.iter().skip(entry).position(|s| *s == Signal::Exit);
+        assert!(
+            exit.is_some(),
+            "%B exit never fired: {:?}",
+            &signals[entry..]
+        );
+    }
+
+    #[test]
+    fn test_squeeze_mode_only_trades_after_tight_bands() {
+        let prices = squeeze_then_breakout();
+        let params = json!({
+            "period": 10,
+            "stdDev": 1.5,
+            "squeezeLookback": 30,
+            "squeezePercentile": 20.0
+        });
+        let mut breakout = BollingerBandsStrategy::from_params(&params);
+        let mut params = params;
+        params["mode"] = json!("squeeze");
+        let mut squeeze = BollingerBandsStrategy::from_params(&params);
+
+        let breakout_signals = run(breakout.as_mut(), &prices);
+        let squeeze_signals = run(squeeze.as_mut(), &prices);
+
+        // 宽幅震荡中的冲高只有普通突破模式会追
+        assert_eq!(breakout_signals[30], Signal::EnterLong(112.0));
+        assert!(squeeze_signals[..60].iter().all(|s| *s == Signal::Hold));
+        assert_eq!(squeeze_signals[60], Signal::EnterLong(103.0));
+    }
+}
@@ -0,0 +1,28 @@
+use std::error::Error;
+
+use serde_json::Value;
+
+use crate::indicators::indicator::Indicator;
+
+/// 可断点续跑的策略：导出 / 恢复指标窗口和自身状态，重启后不必重放历史 bar 预热。
+/// 策略通过 `Strategy::checkpoint` 开启；恢复时必须用导出时相同的参数构建策略
+pub trait Checkpoint {
+    /// None 表示当前状态无法导出（例如用到了不支持导出的指标）
+    fn save_state(&self) -> Option<Value>;
+
+    /// 失败时策略可能只恢复了一部分，调用方应丢弃这个策略重新构建
+    fn load_state(&mut self, state: &Value) -> Result<(), Box<dyn Error>>;
+}
+
+/// 用 state[key] 恢复一个指标
+pub fn load_indicator(
+    indicator: &mut dyn Indicator,
+    state: &Value,
+    key: &str,
+) -> Result<(), Box<dyn Error>> {
+    if indicator.load_state(&state[key]) {
+        Ok(())
+    } else {
+        Err(format!("cannot restore indicator `{}`", key).into())
+    }
+}
@@ -0,0 +1,512 @@
+use std::error::Error;
+
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+
+use crate::data::timeframe::Timeframe;
+
+use super::{
+    direction::Direction,
+    market_data::MarketData,
+    param_schema::{FieldError, ParamSpec, RISK, StrategySchema, position_type},
+    position::{PositionType, TradePosition},
+    signal::Signal,
+    strategy_context::StrategyContext,
+    strategy_factory::StrategyFactory,
+    strategy_trait::Strategy,
+};
+
+/// 子策略的投票如何合成一个方向
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Combiner {
+    /// 全部子策略同向才持仓
+    Unanimous,
+    /// 超过半数同向才持仓
+    Majority,
+    /// 按权重加权的方向得分，|score| 达到 threshold 才持仓，score 归一化到 [-1, 1]
+    Weighted { threshold: f64 },
+    /// 第一个子策略决定方向，其余子策略都同向时才放行
+    PrimaryFilter,
+}
+
+impl Combiner {
+    pub fn parse(name: &str, threshold: f64) -> Option<Self> {
+        match name {
+            "unanimous" => Some(Combiner::Unanimous),
+            "majority" => Some(Combiner::Majority),
+            "weighted" => Some(Combiner::Weighted { threshold }),
+            "primary-filter" | "primaryFilter" => Some(Combiner::PrimaryFilter),
+            _ => None,
+        }
+    }
+}
+
+struct Child {
+    name: String,
+    strategy: Box<dyn Strategy>,
+    weight: f64,
+    // 子策略单独运行时的持仓方向：1 多，-1 空，0 空仓
+    stance: f64,
+}
+
+/// 某个子策略在最近一根 bar 上的原始信号和当前方向，调试用
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChildSignal {
+    pub name: String,
+    pub signal: Signal,
+    pub stance: f64,
+}
+
+// 组合多个方向型策略，例如
+// {"combiner": "majority", "strategies": [
+//     {"type": "ma-crossover", "params": {"fastPeriod": 10, "slowPeriod": 30}},
+//     {"type": "rsi", "params": {"rsiPeriod": 14}},
+//     {"type": "macd", "weight": 2}]}
+// 每个子策略按自己的虚拟持仓运行，EnterLong/EnterShort/Exit 改变其方向，Hold 保持；
+// 合成的目标方向与实际持仓不一致时开仓、反手或平仓。配对、网格、定投等非方向信号不参与投票。
+pub struct CompositeStrategy {
+    name: String,
+    children: Vec<Child>,
+    combiner: Combiner,
+    position_type: PositionType,
+    last_signals: Vec<ChildSignal>,
+}
+
+impl CompositeStrategy {
+    pub fn compile(params: &Value) -> Result<Self, Box<dyn Error>> {
+        let threshold = params
+            .get("threshold")
+            .and_then(Value::as_f64)
+            .unwrap_or(0.5);
+        let combiner_name = params
+            .get("combiner")
+            .and_then(Value::as_str)
+            .unwrap_or("majority");
+        let combiner = Combiner::parse(combiner_name, threshold)
+            .ok_or_else(|| format!("unknown combiner `{}`", combiner_name))?;
+        let position_type = match params.get("positionType").and_then(Value::as_str) {
+            Some("long") => PositionType::Long,
+            Some("short") => PositionType::Short,
+            _ => PositionType::Both,
+        };
+
+        let factory = StrategyFactory::new();
+        let specs = params
+            .get("strategies")
+            .and_then(Value::as_array)
+            .ok_or("`strategies` must be an array")?;
+        let mut children = Vec::with_capacity(specs.len());
+        for (i, spec) in specs.iter().enumerate() {
+            let kind = spec
+                .get("type")
+                .and_then(Value::as_str)
+                .ok_or_else(|| format!("strategies[{}].type is required", i))?;
+            let child_params = spec.get("params").cloned().unwrap_or(Value::Null);
+            let strategy = factory
+                .build(kind, &child_params)
+                .map_err(|e| format!("strategies[{}]: {}", i, e))?;
+            let weight = spec.get("weight").and_then(Value::as_f64).unwrap_or(1.0);
+            children.push(Child {
+                name: format!("{}#{}", kind, i),
+                strategy,
+                weight,
+                stance: 0.0,
+            });
+        }
+        if children.is_empty() {
+            return Err("`strategies` is empty".into());
+        }
+
+        Ok(CompositeStrategy {
+            name: "composite".to_string(),
+            children,
+            combiner,
+            position_type,
+            last_signals: Vec::new(),
+        })
+    }
+
+    /// 参数说明，见 StrategySchema；子策略的参数按各自的 schema 校验
+    pub fn schema() -> StrategySchema {
+        StrategySchema::new(
+            "组合：按投票、加权或主策略过滤合并多个子策略的信号",
+            vec![
+                ParamSpec::array("strategies", "子策略列表 [{type, params, weight}]"),
+                ParamSpec::choice(
+                    "combiner",
+                    &[
+                        "majority",
+                        "unanimous",
+                        "weighted",
+                        "primary-filter",
+                        "primaryFilter",
+                    ],
+                    "合并方式",
+                ),
+                ParamSpec::float("threshold", "weighted 方式的持仓阈值")
+                    .default(0.5)
+                    .range(0.0, 1.0),
+                position_type(),
+            ],
+        )
+        .with_risk(RISK)
+        .with_check(Self::check_children)
+    }
+
+    fn check_children(params: &Value) -> Vec<FieldError> {
+        let specs = match params.get("strategies").and_then(Value::as_array) {
+            Some(specs) if !specs.is_empty() => specs,
+            _ => {
+                return vec![FieldError::new(
+                    "strategies",
+                    "at least one strategy is required",
+                )];
+            }
+        };
+        let factory = StrategyFactory::new();
+        let mut errors = Vec::new();
+        for (i, spec) in specs.iter().enumerate() {
+            let field = |key: &str| format!("strategies[{}].{}", i, key);
+            let Some(kind) = spec.get("type").and_then(Value::as_str) else {
+                errors.push(FieldError::new(field("type"), "is required"));
+                continue;
+            };
+            let weight = spec.get("weight").filter(|w| !w.is_null());
+            if weight.is_some_and(|w| w.as_f64().is_none_or(|w| w < 0.0)) {
+                errors.push(FieldError::new(field("weight"), "must be a number >= 0"));
+            }
+            let child_params = spec.get("params").unwrap_or(&Value::Null);
+            if let Err(child_errors) = factory.validate(kind, child_params) {
+                errors.extend(child_errors.into_iter().map(|e| {
+                    // 未知类型的错误落在 type 上，其余落在 params 下
+                    let key = if e.field == "type" {
+                        e.field
+                    } else {
+                        format!("params.{}", e.field)
+                    };
+                    FieldError::new(field(&key), e.message)
+                }));
+            }
+        }
+        errors
+    }
+
+    /// 参数无法构建时返回错误，不会退化成一直观望的空组合
+    pub fn from_params(params: &Value) -> Result<Box<dyn Strategy>, Box<dyn Error>> {
+        Ok(Box::new(Self::compile(params)?))
+    }
+
+    /// 最近一根 bar 上各子策略的信号
+    pub fn child_signals(&self) -> &[ChildSignal] {
+        &self.last_signals
+    }
+
+    // 合成的目标方向：1 多，-1 空，0 空仓
+    fn target(&self) -> f64 {
+        let stances = self.children.iter().map(|c| c.stance);
+        match self.combiner {
+            _ if self.children.is_empty() => 0.0,
+            Combiner::Unanimous => {
+                let first = self.children[0].stance;
+                if stances.clone().all(|s| s == first) {
+                    first
+                } else {
+                    0.0
+                }
+            }
+            Combiner::Majority => {
+                let half = self.children.len() as f64 / 2.0;
+                let longs = stances.clone().filter(|s| *s > 0.0).count() as f64;
+                let shorts = stances.filter(|s| *s < 0.0).count() as f64;
+                if longs > half {
+                    1.0
+                } else if shorts > half {
+                    -1.0
+                } else {
+                    0.0
+                }
+            }
+            Combiner::Weighted { threshold } => {
+                let total: f64 = self.children.iter().map(|c| c.weight.abs()).sum();
+                if total == 0.0 {
+                    return 0.0;
+                }
+                let score = self
+                    .children
+                    .iter()
+                    .map(|c| c.weight * c.stance)
+                    .sum::<f64>()
+                    / total;
+                if score >= threshold {
+                    1.0
+                } else if score <= -threshold {
+                    -1.0
+                } else {
+                    0.0
+                }
+            }
+            Combiner::PrimaryFilter => {
+                let primary = self.children[0].stance;
+                if self.children[1..].iter().all(|c| c.stance == primary) {
+                    primary
+                } else {
+                    0.0
+                }
+            }
+        }
+    }
+}
+
+// 子策略信号对其方向的影响，非方向信号保持原方向
+fn stance_after(stance: f64, signal: &Signal) -> f64 {
+    match signal {
+        Signal::EnterLong(_) => 1.0,
+        Signal::EnterShort(_) => -1.0,
+        Signal::Enter(entry) if entry.direction == Direction::Long => 1.0,
+        Signal::Enter(_) => -1.0,
+        Signal::Reduce { fraction, .. } if *fraction >= 1.0 => 0.0,
+        Signal::TargetPercent(percent) if *percent > 0.0 => 1.0,
+        Signal::TargetPercent(percent) if *percent < 0.0 => -1.0,
+        Signal::TargetPercent(_) => 0.0,
+        Signal::Exit => 0.0,
+        Signal::Batch(signals) => signals.iter().fold(stance, stance_after),
+        _ => stance,
+    }
+}
+
+impl Strategy for CompositeStrategy {
+    fn generate_signal(&mut self, price: f64, position: f64) -> Signal {
+        let mut ctx = StrategyContext::new(0.0);
+        ctx.position = position;
+        self.on_tick(
+            &mut ctx,
+            &MarketData::from_close(DateTime::<Utc>::MIN_UTC, price),
+        )
+    }
+
+    fn update(&mut self, market_data: &MarketData, current_position: &Option<TradePosition>) {
+        for child in &mut self.children {
+            child.strategy.update(market_data, current_position);
+        }
+    }
+
+    fn on_tick(&mut self, ctx: &mut StrategyContext, data: &MarketData) -> Signal {
+        let position = ctx.position;
+        self.last_signals.clear();
+        for child in &mut self.children {
+            // 子策略看到的是自己的虚拟持仓
+            ctx.position = child.stance;
+            let signal = child.strategy.on_tick(ctx, data);
+            child.stance = stance_after(child.stance, &signal);
+            self.last_signals.push(ChildSignal {
+                name: child.name.clone(),
+                signal,
+                stance: child.stance,
+            });
+        }
+        ctx.position = position;
+
+        let target = match self.target() {
+            t if t > 0.0 && !self.supports_long() => 0.0,
+            t if t < 0.0 && !self.supports_short() => 0.0,
+            t => t,
+        };
+        let price = data.close_price;
+        let enter = if target > 0.0 {
+            Signal::EnterLong(price)
+        } else {
+            Signal::EnterShort(price)
+        };
+        match (target, position) {
+            (t, p) if t == 0.0 && p != 0.0 => Signal::Exit,
+            (t, p) if t == 0.0 || t * p > 0.0 => Signal::Hold,
+            (_, 0.0) => enter,
+            // 反手：先平后开
+            _ => Signal::Batch(vec![Signal::Exit, enter]),
+        }
+    }
+
+    fn timeframes(&self) -> Vec<Timeframe> {
+        let mut timeframes: Vec<Timeframe> = Vec::new();
+        for tf in self.children.iter().flat_map(|c| c.strategy.timeframes()) {
+            if !timeframes.contains(&tf) {
+                timeframes.push(tf);
+            }
+        }
+        timeframes
+    }
+
+    fn chart_values(&self) -> Vec<(String, Option<f64>)> {
+        let mut values = Vec::new();
+        for child in &self.children {
+            values.push((format!("{}.stance", child.name), Some(child.stance)));
+            values.extend(
+                child
+                    .strategy
+                    .chart_values()
+                    .into_iter()
+                    .map(|(name, value)| (format!("{}.{}", child.name, name), value)),
+            );
+        }
+        values
+    }
+
+    fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    fn apply_parameters(&mut self, entry_threshold: Option<f64>, exit_threshold: Option<f64>) {
+        for child in &mut self.children {
+            child
+                .strategy
+                .apply_parameters(entry_threshold, exit_threshold);
+        }
+    }
+
+    fn position_type(&self) -> &PositionType {
+        &self.position_type
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn composite(combiner: &str, extra: Value) -> CompositeStrategy {
+        let mut params = json!({
+            "combiner": combiner,
+            "strategies": [
+                { "type": "rules", "params": { "rules": [
+                    { "when": { "gt": [{ "price": "close" }, 100] }, "then": "enterLong" },
+                    { "when": { "lt": [{ "price": "close" }, 100] }, "then": "exit" }
+                ] } },
+                { "type": "rules", "params": { "rules": [
+                    { "when": { "gt": [{ "price": "close" }, 105] }, "then": "enterLong" },
+                    { "when": { "lt": [{ "price": "close" }, 95] }, "then": "enterShort" }
+                ] }, "weight": 2 },
+                { "type": "rules", "params": { "rules": [
+                    { "when": { "lt": [{ "price": "close" }, 110] }, "then": "enterShort" },
+                    { "when": { "gt": [{ "price": "close" }, 110] }, "then": "enterLong" }
+                ] } }
+            ]
+        });
+        if let Value::Object(extra) = extra {
+            params.as_object_mut().unwrap().extend(extra);
+        }
+        CompositeStrategy::compile(&params).unwrap()
+    }
+
+    fn run(strategy: &mut CompositeStrategy, prices: &[f64]) -> Vec<Signal> {
+        let mut position = 0.0;
+        prices
+            .iter()
+            .map(|&p| {
+                let signal = strategy.generate_signal(p, position);
+                position = stance_after(position, &signal);
+                signal
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_combiners_vote_on_child_stances() {
+        let prices = [101.0, 106.0, 111.0, 112.0, 90.0, 89.0];
+        // 规则子策略反手时先平仓，各子策略方向依次为
+        // (1, 0, -1) (1, 1, -1) (1, 1, 0) (1, 1, 1) (0, 0, 0) (0, -1, -1)
+        assert_eq!(
+            run(&mut composite("unanimous", json!({})), &prices),
+            vec![
+                Signal::Hold,
+                Signal::Hold,
+                Signal::Hold,
+                Signal::EnterLong(112.0),
+                Signal::Exit,
+                Signal::Hold
+            ]
+        );
+        assert_eq!(
+            run(&mut composite("majority", json!({})), &prices),
+            vec![
+                Signal::Hold,
+                Signal::EnterLong(106.0),
+                Signal::Hold,
+                Signal::Hold,
+                Signal::Exit,
+                Signal::EnterShort(89.0)
+            ]
+        );
+        // 权重 (1, 2, 1)，得分依次为 0, 0.5, 0.75, 1, 0, -0.75
+        assert_eq!(
+            run(
+                &mut composite("weighted", json!({ "threshold": 0.6 })),
+                &prices
+            ),
+            vec![
+                Signal::Hold,
+                Signal::Hold,
+                Signal::EnterLong(111.0),
+                Signal::Hold,
+                Signal::Exit,
+                Signal::EnterShort(89.0)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_primary_filter_and_child_signals() {
+        let mut strategy = composite("primary-filter", json!({}));
+        let signals = run(&mut strategy, &[101.0, 111.0, 112.0, 105.0]);
+        // 105 时第三个子策略平掉多头，主策略仍看多但被过滤
+        assert_eq!(
+            signals,
+            vec![
+                Signal::Hold,
+                Signal::Hold,
+                Signal::EnterLong(112.0),
+                Signal::Exit
+            ]
+        );
+        let children: Vec<(&str, f64)> = strategy
+            .child_signals()
+            .iter()
+            .map(|c| (c.name.as_str(), c.stance))
+            .collect();
+        assert_eq!(
+            children,
+            vec![("rules#0", 1.0), ("rules#1", 1.0), ("rules#2", 0.0)]
+        );
+        assert_eq!(strategy.child_signals()[2].signal, Signal::Exit);
+        // 子策略按自己的虚拟持仓运行，已经看多的不会重复发 EnterLong
+        assert_eq!(strategy.child_signals()[0].signal, Signal::Hold);
+
+        let mut reversing = composite("weighted", json!({ "threshold": 0.0 }));
+        let signals = run(&mut reversing, &[106.0, 111.0, 90.0, 89.0]);
+        // 得分依次为 0.5, 0.75, -0.25, -0.75，由正转负时直接反手
+        assert_eq!(
+            signals,
+            vec![
+                Signal::EnterLong(106.0),
+                Signal::Hold,
+                Signal::Batch(vec![Signal::Exit, Signal::EnterShort(90.0)]),
+                Signal::Hold
+            ]
+        );
+
+        let err = CompositeStrategy::compile(&json!({
+            "strategies": [{ "type": "ma-crossover" }, { "type": "kdj" }]
+        }))
+        .err()
+        .unwrap();
+        assert!(err.to_string().contains("strategies[1]"));
+        // 工厂构建时同样报错，而不是返回一直观望的空组合
+        let factory = StrategyFactory::new();
+        assert!(factory.build("composite", &json!({})).is_err());
+        assert!(
+            factory
+                .build("composite", &json!({ "strategies": [{ "type": "kdj" }] }))
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,200 @@
+use std::collections::VecDeque;
+
+use serde_json::Value;
+
+use super::{
+    bar_clock::BarClock,
+    market_data::MarketData,
+    param_schema::{ParamSpec, StrategySchema},
+    position::{PositionType, TradePosition},
+    signal::Signal,
+    strategy_context::StrategyContext,
+    strategy_trait::Strategy,
+};
+
+// 定投：按 BarClock 的节奏每次买入固定金额。
+// 收盘价比最近 dip_lookback 根 bar 的最高收盘价低 dip_percent% 以上时，金额乘以 dip_multiplier。
+pub struct DcaStrategy {
+    name: String,
+    clock: BarClock,
+    asset: usize,
+    amount: f64,
+    dip_multiplier: f64,
+    dip_percent: f64,
+    dip_lookback: usize,
+    highs: VecDeque<f64>,
+    position_type: PositionType,
+}
+
+impl DcaStrategy {
+    pub fn new(name: String, clock: BarClock, amount: f64) -> Self {
+        DcaStrategy {
+            name,
+            clock,
+            asset: 0,
+            amount,
+            dip_multiplier: 1.0,
+            dip_percent: 10.0,
+            dip_lookback: 20,
+            highs: VecDeque::new(),
+            position_type: PositionType::Long,
+        }
+    }
+
+    /// 参数说明，见 StrategySchema
+    pub fn schema() -> StrategySchema {
+        let mut params = vec![
+            ParamSpec::float("amount", "每次买入的金额")
+                .default(100.0)
+                .min(0.0),
+            ParamSpec::int("asset", "买入第几个资产")
+                .default(0)
+                .min(0.0),
+            ParamSpec::float("dipMultiplier", "回撤时买入金额的倍数")
+                .default(1.0)
+                .min(0.0),
+            ParamSpec::float("dipPercent", "距近期高点回撤超过该百分比视为回撤")
+                .default(10.0)
+                .min(0.0),
+            ParamSpec::int("dipLookback", "近期高点的回看 bar 数")
+                .default(20)
+                .min(1.0),
+        ];
+        params.extend(BarClock::params("bar"));
+        StrategySchema::new("定投：按固定周期买入，回撤时加大金额", params)
+    }
+
+    pub fn from_params(params: &Value) -> Box<dyn Strategy> {
+        let amount = params
+            .get("amount")
+            .and_then(Value::as_f64)
+            .unwrap_or(100.0);
+        let asset = params.get("asset").and_then(Value::as_u64).unwrap_or(0) as usize;
+        let dip_multiplier = params
+            .get("dipMultiplier")
+            .and_then(Value::as_f64)
+            .unwrap_or(1.0);
+        let dip_percent = params
+            .get("dipPercent")
+            .and_then(Value::as_f64)
+            .unwrap_or(10.0);
+        let dip_lookback = params
+            .get("dipLookback")
+            .and_then(Value::as_u64)
+            .unwrap_or(20) as usize;
+
+        let clock = BarClock::from_params(params, "bar");
+        let mut strategy = Self::new("dca".to_string(), clock, amount);
+        strategy.asset = asset;
+        strategy.dip_multiplier = dip_multiplier;
+        strategy.dip_percent = dip_percent;
+        strategy.dip_lookback = dip_lookback.max(1);
+        Box::new(strategy)
+    }
+
+    fn is_dip(&self, price: f64) -> bool {
+        self.highs
+            .iter()
+            .copied()
+            .reduce(f64::max)
+            .is_some_and(|high| price <= high * (1.0 - self.dip_percent / 100.0))
+    }
+
+    fn remember(&mut self, price: f64) {
+        self.highs.push_back(price);
+        while self.highs.len() > self.dip_lookback {
+            self.highs.pop_front();
+        }
+    }
+}
+
+impl Strategy for DcaStrategy {
+    // 定投依赖时间戳和组合账户，只通过 on_bars 工作
+    fn generate_signal(&mut self, _price: f64, _position: f64) -> Signal {
+        Signal::Hold
+    }
+
+    fn on_bars(&mut self, _ctx: &mut StrategyContext, bars: &[MarketData]) -> Signal {
+        let Some(bar) = bars.get(self.asset) else {
+            return Signal::Hold;
+        };
+        let price = bar.close_price;
+        let due = self.clock.tick(&bar.timestamp);
+        let dip = self.is_dip(price);
+        self.remember(price);
+        if !due {
+            return Signal::Hold;
+        }
+
+        let multiplier = if dip { self.dip_multiplier } else { 1.0 };
+        Signal::Invest {
+            index: self.asset,
+            quote: self.amount * multiplier,
+        }
+    }
+
+    fn update(&mut self, market_data: &MarketData, _current_position: &Option<TradePosition>) {
+        self.remember(market_data.close_price);
+    }
+
+    fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    // entry_threshold 为每期金额，exit_threshold 为抄底倍数
+    fn apply_parameters(&mut self, entry_threshold: Option<f64>, exit_threshold: Option<f64>) {
+        if let Some(amount) = entry_threshold {
+            self.amount = amount;
+        }
+        if let Some(multiplier) = exit_threshold {
+            self.dip_multiplier = multiplier;
+        }
+    }
+
+    fn position_type(&self) -> &PositionType {
+        &self.position_type
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, TimeZone, Utc};
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_dca_buys_on_schedule_and_doubles_on_dips() {
+        let mut strategy = DcaStrategy::from_params(&json!({
+            "amount": 50.0,
+            "interval": 2,
+            "dipMultiplier": 2.0,
+            "dipPercent": 10.0,
+            "dipLookback": 5
+        }));
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let prices = [100.0, 101.0, 102.0, 95.0, 90.0, 91.0];
+        let mut ctx = StrategyContext::new(1_000.0);
+        let signals: Vec<Signal> = prices
+            .iter()
+            .enumerate()
+            .map(|(i, &p)| {
+                let bar = MarketData::from_close(start + Duration::days(i as i64), p);
+                strategy.on_bars(&mut ctx, &[bar])
+            })
+            .collect();
+
+        let invest = |quote: f64| Signal::Invest { index: 0, quote };
+        assert_eq!(
+            signals,
+            vec![
+                invest(50.0),
+                Signal::Hold,
+                invest(50.0),
+                Signal::Hold,
+                invest(100.0),
+                Signal::Hold
+            ]
+        );
+    }
+}
@@ -1,4 +1,6 @@
-#[derive(Debug, PartialEq, PartialOrd, Clone)]
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, PartialEq, PartialOrd, Clone, Serialize, Deserialize)]
 pub enum Direction {
     Long,  // 多头：开仓时买入，平仓时卖出
     Short, // 空头：开仓时卖出，平仓时买入
@@ -0,0 +1,351 @@
+use serde_json::Value;
+
+use super::{
+    direction::Direction,
+    market_data::MarketData,
+    param_schema::{ParamSpec, StrategySchema},
+    position::{PositionType, TradePosition},
+    signal::{Lot, Signal},
+    strategy_context::StrategyContext,
+    strategy_trait::Strategy,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GridSpacing {
+    // 等差：每格价差相同
+    Arithmetic,
+    // 等比：每格涨跌幅相同
+    Geometric,
+}
+
+impl GridSpacing {
+    pub fn parse(s: &str) -> Self {
+        match s {
+            "geometric" => GridSpacing::Geometric,
+            _ => GridSpacing::Arithmetic,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GridMode {
+    // 起始价下方的格子做多、上方的格子做空
+    Neutral,
+    // 每格下沿买入、上沿卖出
+    Long,
+    // 每格上沿卖空、下沿买回
+    Short,
+}
+
+impl GridMode {
+    pub fn parse(s: &str) -> Self {
+        match s {
+            "long" => GridMode::Long,
+            "short" => GridMode::Short,
+            _ => GridMode::Neutral,
+        }
+    }
+}
+
+/// 在 [lower, upper] 之间划分 grids 个格子，返回 grids + 1 条网格线
+pub fn grid_levels(lower: f64, upper: f64, grids: usize, spacing: GridSpacing) -> Vec<f64> {
+    if grids == 0 || lower >= upper || (spacing == GridSpacing::Geometric && lower <= 0.0) {
+        return Vec::new();
+    }
+    (0..=grids)
+        .map(|i| {
+            let t = i as f64 / grids as f64;
+            match spacing {
+                GridSpacing::Arithmetic => lower + (upper - lower) * t,
+                GridSpacing::Geometric => lower * (upper / lower).powf(t),
+            }
+        })
+        .collect()
+}
+
+// 网格交易：第 i 格夹在 levels[i] 和 levels[i + 1] 之间，每格最多一笔 lot（id = i）。
+// 多头格在价格下穿下沿时按下沿买入，涨到上沿按上沿卖出；空头格反之。
+// 没有给出 lower/upper 时，以第一根 bar 的价格上下 range_percent 作为区间。
+pub struct GridStrategy {
+    name: String,
+    lower: Option<f64>,
+    upper: Option<f64>,
+    grids: usize,
+    spacing: GridSpacing,
+    mode: GridMode,
+    range_percent: f64,
+    levels: Vec<f64>,
+    // 中性网格的起始价
+    reference: f64,
+    prev: Option<f64>,
+    open: Vec<bool>,
+    position_type: PositionType,
+}
+
+impl GridStrategy {
+    pub fn new(
+        name: String,
+        lower: Option<f64>,
+        upper: Option<f64>,
+        grids: usize,
+        spacing: GridSpacing,
+        mode: GridMode,
+    ) -> Self {
+        let position_type = match mode {
+            GridMode::Long => PositionType::Long,
+            GridMode::Short => PositionType::Short,
+            GridMode::Neutral => PositionType::Both,
+        };
+        GridStrategy {
+            name,
+            lower,
+            upper,
+            grids,
+            spacing,
+            mode,
+            range_percent: 10.0,
+            levels: Vec::new(),
+            reference: 0.0,
+            prev: None,
+            open: Vec::new(),
+            position_type,
+        }
+    }
+
+    /// 参数说明，见 StrategySchema
+    pub fn schema() -> StrategySchema {
+        StrategySchema::new(
+            "网格：在价格区间内按格低买高卖",
+            vec![
+                ParamSpec::float(
+                    "lower",
+                    "网格下沿；不给则按首根 bar 价格和 rangePercent 推算",
+                )
+                .min(0.0),
+                ParamSpec::float(
+                    "upper",
+                    "网格上沿；不给则按首根 bar 价格和 rangePercent 推算",
+                )
+                .min(0.0),
+                ParamSpec::int("grids", "格数").default(10).min(1.0),
+                ParamSpec::choice("spacing", &["arithmetic", "geometric"], "格距方式"),
+                ParamSpec::choice("mode", &["neutral", "long", "short"], "网格方向"),
+                ParamSpec::float("rangePercent", "自动区间的上下幅度百分比")
+                    .default(10.0)
+                    .min(0.0),
+            ],
+        )
+        .with_risk(&["stopLoss", "takeProfit", "riskPerTrade", "positionSize"])
+        .less_than("lower", "upper")
+    }
+
+    pub fn from_params(params: &Value) -> Box<dyn Strategy> {
+        let lower = params.get("lower").and_then(Value::as_f64);
+        let upper = params.get("upper").and_then(Value::as_f64);
+        let grids = params.get("grids").and_then(Value::as_u64).unwrap_or(10) as usize;
+        let spacing = GridSpacing::parse(
+            params
+                .get("spacing")
+                .and_then(Value::as_str)
+                .unwrap_or("arithmetic"),
+        );
+        let mode = GridMode::parse(
+            params
+                .get("mode")
+                .and_then(Value::as_str)
+                .unwrap_or("neutral"),
+        );
+        let range_percent = params
+            .get("rangePercent")
+            .and_then(Value::as_f64)
+            .unwrap_or(10.0);
+
+        let mut strategy = Self::new("grid".to_string(), lower, upper, grids, spacing, mode);
+        strategy.range_percent = range_percent;
+        Box::new(strategy)
+    }
+
+    pub fn levels(&self) -> &[f64] {
+        &self.levels
+    }
+
+    fn init(&mut self, price: f64) {
+        let band = price * self.range_percent / 100.0;
+        let lower = self.lower.unwrap_or(price - band);
+        let upper = self.upper.unwrap_or(price + band);
+        self.levels = grid_levels(lower, upper, self.grids, self.spacing);
+        self.open = vec![false; self.levels.len().saturating_sub(1)];
+        self.reference = price;
+    }
+
+    fn direction(&self, grid: usize) -> Direction {
+        match self.mode {
+            GridMode::Long => Direction::Long,
+            GridMode::Short => Direction::Short,
+            GridMode::Neutral if self.levels[grid] < self.reference => Direction::Long,
+            GridMode::Neutral => Direction::Short,
+        }
+    }
+
+    fn step(&mut self, price: f64) -> Signal {
+        let Some(prev) = self.prev.replace(price) else {
+            self.init(price);
+            return Signal::Hold;
+        };
+
+        let mut signals = Vec::new();
+        for grid in 0..self.open.len() {
+            let (low, high) = (self.levels[grid], self.levels[grid + 1]);
+            let direction = self.direction(grid);
+            let long = direction == Direction::Long;
+            let signal = match (self.open[grid], long) {
+                (true, true) if price >= high => Signal::CloseLot(Lot {
+                    id: grid,
+                    price: high,
+                    direction,
+                }),
+                (true, false) if price <= low => Signal::CloseLot(Lot {
+                    id: grid,
+                    price: low,
+                    direction,
+                }),
+                (false, true) if prev > low && price <= low => Signal::OpenLot(Lot {
+                    id: grid,
+                    price: low,
+                    direction,
+                }),
+                (false, false) if prev < high && price >= high => Signal::OpenLot(Lot {
+                    id: grid,
+                    price: high,
+                    direction,
+                }),
+                _ => continue,
+            };
+            self.open[grid] = matches!(signal, Signal::OpenLot(_));
+            signals.push(signal);
+        }
+
+        match signals.len() {
+            0 => Signal::Hold,
+            1 => signals.remove(0),
+            _ => Signal::Batch(signals),
+        }
+    }
+}
+
+impl Strategy for GridStrategy {
+    fn generate_signal(&mut self, price: f64, _position: f64) -> Signal {
+        self.step(price)
+    }
+
+    // 以实际成交的 lot 为准，下单失败或被 Exit 清掉的格子会重新挂单
+    fn on_tick(&mut self, ctx: &mut StrategyContext, data: &MarketData) -> Signal {
+        for (grid, open) in self.open.iter_mut().enumerate() {
+            *open = ctx.lots.iter().any(|l| l.id == grid);
+        }
+        self.step(data.close_price)
+    }
+
+    fn update(&mut self, market_data: &MarketData, _current_position: &Option<TradePosition>) {
+        if self.prev.is_none() {
+            self.init(market_data.close_price);
+        }
+        self.prev = Some(market_data.close_price);
+    }
+
+    fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    fn apply_parameters(&mut self, _entry_threshold: Option<f64>, _exit_threshold: Option<f64>) {}
+
+    fn position_type(&self) -> &PositionType {
+        &self.position_type
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn lots(signal: &Signal) -> Vec<Signal> {
+        match signal {
+            Signal::Batch(signals) => signals.clone(),
+            Signal::Hold => Vec::new(),
+            other => vec![other.clone()],
+        }
+    }
+
+    #[test]
+    fn test_grid_levels() {
+        let arithmetic = grid_levels(90.0, 110.0, 4, GridSpacing::Arithmetic);
+        assert_eq!(arithmetic, vec![90.0, 95.0, 100.0, 105.0, 110.0]);
+
+        let geometric = grid_levels(100.0, 400.0, 2, GridSpacing::Geometric);
+        assert!((geometric[1] - 200.0).abs() < 1e-9);
+        assert!((geometric[2] - 400.0).abs() < 1e-9);
+        assert!(grid_levels(0.0, 10.0, 2, GridSpacing::Geometric).is_empty());
+    }
+
+    #[test]
+    fn test_long_grid_buys_low_and_sells_one_level_up() {
+        let mut strategy = GridStrategy::from_params(&json!({
+            "lower": 90.0,
+            "upper": 110.0,
+            "grids": 4,
+            "mode": "long"
+        }));
+        let signals: Vec<Signal> = [102.0, 99.0, 94.0, 96.0, 101.0]
+            .iter()
+            .map(|&p| strategy.generate_signal(p, 0.0))
+            .collect();
+
+        assert_eq!(signals[0], Signal::Hold);
+        let buy = |id: usize, price: f64| {
+            Signal::OpenLot(Lot {
+                id,
+                price,
+                direction: Direction::Long,
+            })
+        };
+        let sell = |id: usize, price: f64| {
+            Signal::CloseLot(Lot {
+                id,
+                price,
+                direction: Direction::Long,
+            })
+        };
+        assert_eq!(signals[1], buy(2, 100.0));
+        assert_eq!(signals[2], buy(1, 95.0));
+        // 反弹到 96 还没到上沿不平仓；101 平掉 95 买入的那一格，100 那一格要等到 105
+        assert_eq!(signals[3], Signal::Hold);
+        assert_eq!(lots(&signals[4]), vec![sell(1, 100.0)]);
+    }
+
+    #[test]
+    fn test_neutral_grid_shorts_above_start_and_batches_gaps() {
+        let mut strategy = GridStrategy::from_params(&json!({
+            "lower": 90.0,
+            "upper": 110.0,
+            "grids": 4,
+            "spacing": "arithmetic"
+        }));
+        strategy.generate_signal(100.0, 0.0);
+        // 一根 bar 连穿 105 和 110：上方两格都开空
+        let up = lots(&strategy.generate_signal(111.0, 0.0));
+        assert_eq!(up.len(), 2);
+        assert!(up.iter().all(|s| matches!(
+            s,
+            Signal::OpenLot(Lot {
+                direction: Direction::Short,
+                ..
+            })
+        )));
+
+        let down = lots(&strategy.generate_signal(99.0, 0.0));
+        assert_eq!(down.len(), 2);
+        assert!(down.iter().all(|s| matches!(s, Signal::CloseLot(_))));
+    }
+}
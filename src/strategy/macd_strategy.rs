@@ -0,0 +1,339 @@
+use std::error::Error;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+use crate::indicators::{
+    indicator::Indicator,
+    macd_indicator::{MacdIndicator, MacdValue},
+    overlay::named_values,
+};
+
+use super::{
+    checkpoint::{Checkpoint, load_indicator},
+    market_data::MarketData,
+    param_schema::{ParamSpec, RISK, StrategySchema, position_type},
+    position::{PositionType, TradePosition},
+    signal::Signal,
+    strategy_trait::Strategy,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MacdTrigger {
+    // MACD 线上穿/下穿信号线
+    SignalCross,
+    // MACD 线上穿/下穿零轴
+    ZeroCross,
+    // 柱状图在零轴一侧拐头
+    HistogramReversal,
+}
+
+impl MacdTrigger {
+    pub fn parse(s: &str) -> Self {
+        match s {
+            "zero" | "zero-cross" => MacdTrigger::ZeroCross,
+            "histogram" | "histogram-reversal" => MacdTrigger::HistogramReversal,
+            _ => MacdTrigger::SignalCross,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+enum Bias {
+    Bullish,
+    Bearish,
+}
+
+pub struct MacdStrategy {
+    name: String,
+    macd: MacdIndicator,
+    trigger: MacdTrigger,
+    // 最近两根 bar 的输出，newest last
+    prev: Option<MacdValue>,
+    prev2: Option<MacdValue>,
+    // 反向信号平仓后，下一根 bar 按该方向入场
+    reversal: Option<Bias>,
+    position_type: PositionType,
+}
+
+impl MacdStrategy {
+    pub fn new(
+        name: String,
+        fast_period: usize,
+        slow_period: usize,
+        signal_period: usize,
+        trigger: MacdTrigger,
+        position_type: PositionType,
+    ) -> Self {
+        MacdStrategy {
+            name,
+            macd: MacdIndicator::new(fast_period, slow_period, signal_period),
+            trigger,
+            prev: None,
+            prev2: None,
+            reversal: None,
+            position_type,
+        }
+    }
+
+    /// 参数说明，见 StrategySchema
+    pub fn schema() -> StrategySchema {
+        StrategySchema::new(
+            "MACD：按信号线交叉、零轴交叉或柱状图反转入场",
+            vec![
+                ParamSpec::int("fastPeriod", "快线 EMA 周期")
+                    .default(12)
+                    .min(1.0),
+                ParamSpec::int("slowPeriod", "慢线 EMA 周期")
+                    .default(26)
+                    .min(1.0),
+                ParamSpec::int("signalPeriod", "信号线周期")
+                    .default(9)
+                    .min(1.0),
+                ParamSpec::choice(
+                    "trigger",
+                    &[
+                        "signal",
+                        "zero",
+                        "zero-cross",
+                        "histogram",
+                        "histogram-reversal",
+                    ],
+                    "触发方式",
+                ),
+                position_type(),
+            ],
+        )
+        .with_risk(RISK)
+        .less_than("fastPeriod", "slowPeriod")
+    }
+
+    pub fn from_params(params: &Value) -> Box<dyn Strategy> {
+        let position_type = match params.get("positionType").and_then(Value::as_str) {
+            Some("long") => PositionType::Long,
+            Some("short") => PositionType::Short,
+            _ => PositionType::Both,
+        };
+
+        let fast = params
+            .get("fastPeriod")
+            .and_then(Value::as_u64)
+            .unwrap_or(12) as usize;
+        let slow = params
+            .get("slowPeriod")
+            .and_then(Value::as_u64)
+            .unwrap_or(26) as usize;
+        let signal = params
+            .get("signalPeriod")
+            .and_then(Value::as_u64)
+            .unwrap_or(9) as usize;
+        let trigger = MacdTrigger::parse(
+            params
+                .get("trigger")
+                .and_then(Value::as_str)
+                .unwrap_or("signal"),
+        );
+
+        Box::new(Self::new(
+            "macd".to_string(),
+            fast,
+            slow,
+            signal,
+            trigger,
+            position_type,
+        ))
+    }
+
+    /// `sub_type` selects the trigger ("signal", "zero" or "histogram").
+    pub fn create(name: String, sub_type: &str, fast_period: usize, slow_period: usize) -> Self {
+        Self::new(
+            name,
+            fast_period,
+            slow_period,
+            9,
+            MacdTrigger::parse(sub_type),
+            PositionType::Both,
+        )
+    }
+
+    fn bias(&self, cur: &MacdValue) -> Option<Bias> {
+        let prev = self.prev?;
+        match self.trigger {
+            MacdTrigger::SignalCross => cross(prev.histogram, cur.histogram),
+            MacdTrigger::ZeroCross => cross(prev.macd, cur.macd),
+            MacdTrigger::HistogramReversal => {
+                let prev2 = self.prev2?;
+                if cur.histogram < 0.0
+                    && prev.histogram < prev2.histogram
+                    && cur.histogram > prev.histogram
+                {
+                    Some(Bias::Bullish)
+                } else if cur.histogram > 0.0
+                    && prev.histogram > prev2.histogram
+                    && cur.histogram < prev.histogram
+                {
+                    Some(Bias::Bearish)
+                } else {
+                    None
+                }
+            }
+        }
+    }
+
+    fn observe(&mut self, price: f64) -> Option<(MacdValue, Option<Bias>)> {
+        self.macd.update(price);
+        let cur = self.macd.output()?;
+        let bias = self.bias(&cur);
+        self.prev2 = self.prev.replace(cur);
+        Some((cur, bias))
+    }
+}
+
+fn cross(prev: f64, cur: f64) -> Option<Bias> {
+    if prev <= 0.0 && cur > 0.0 {
+        Some(Bias::Bullish)
+    } else if prev >= 0.0 && cur < 0.0 {
+        Some(Bias::Bearish)
+    } else {
+        None
+    }
+}
+
+impl Strategy for MacdStrategy {
+    fn generate_signal(&mut self, price: f64, position: f64) -> Signal {
+        let Some((_, fresh)) = self.observe(price) else {
+            return Signal::Hold;
+        };
+        let pending = self.reversal.take();
+        let Some(bias) = fresh.or(pending) else {
+            return Signal::Hold;
+        };
+        match bias {
+            // 反向信号先平仓
+            Bias::Bearish if position > 0.0 => {
+                self.reversal = Some(bias);
+                Signal::Exit
+            }
+            Bias::Bullish if position < 0.0 => {
+                self.reversal = Some(bias);
+                Signal::Exit
+            }
+            Bias::Bullish if position == 0.0 => Signal::EnterLong(price),
+            Bias::Bearish if position == 0.0 => Signal::EnterShort(price),
+            _ => Signal::Hold,
+        }
+    }
+
+    fn update(&mut self, market_data: &MarketData, _current_position: &Option<TradePosition>) {
+        self.observe(market_data.close_price);
+    }
+
+    fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    fn apply_parameters(&mut self, _entry_threshold: Option<f64>, _exit_threshold: Option<f64>) {}
+
+    fn chart_values(&self) -> Vec<(String, Option<f64>)> {
+        named_values("", &self.macd)
+    }
+
+    fn checkpoint(&mut self) -> Option<&mut dyn Checkpoint> {
+        Some(self)
+    }
+
+    fn position_type(&self) -> &PositionType {
+        &self.position_type
+    }
+}
+
+impl Checkpoint for MacdStrategy {
+    fn save_state(&self) -> Option<Value> {
+        Some(json!({
+            "macd": self.macd.save_state()?,
+            "prev": self.prev,
+            "prev2": self.prev2,
+            "reversal": self.reversal,
+        }))
+    }
+
+    fn load_state(&mut self, state: &Value) -> Result<(), Box<dyn Error>> {
+        load_indicator(&mut self.macd, state, "macd")?;
+        self.prev = Option::deserialize(&state["prev"])?;
+        self.prev2 = Option::deserialize(&state["prev2"])?;
+        self.reversal = Option::deserialize(&state["reversal"])?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::strategy::strategy_context::StrategyContext;
+
+    fn wave(n: usize) -> Vec<f64> {
+        (0..n)
+            .map(|i| 100.0 + (i as f64 * 0.25).sin() * 10.0)
+            .collect()
+    }
+
+    fn run(strategy: &mut dyn Strategy, prices: &[f64]) -> Vec<Signal> {
+        let mut ctx = StrategyContext::new(1_000.0);
+        prices
+            .iter()
+            .map(|&p| {
+                let data = MarketData::from_close(Default::default(), p);
+                let signal = strategy.on_tick(&mut ctx, &data);
+                match signal {
+                    Signal::EnterLong(_) => ctx.position = 1.0,
+                    Signal::EnterShort(_) => ctx.position = -1.0,
+                    Signal::Exit => ctx.position = 0.0,
+                    _ => {}
+                }
+                signal
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_macd_triggers_alternate_on_a_wave() {
+        for trigger in ["signal", "zero", "histogram"] {
+            let mut strategy = MacdStrategy::from_params(&json!({
+                "fastPeriod": 3,
+                "slowPeriod": 8,
+                "signalPeriod": 3,
+                "trigger": trigger
+            }));
+            let signals = run(strategy.as_mut(), &wave(120));
+            let active: Vec<&Signal> = signals.iter().filter(|s| **s != Signal::Hold).collect();
+
+            assert!(active.len() >= 4, "{} produced {:?}", trigger, active);
+            assert!(active.iter().any(|s| matches!(s, Signal::EnterLong(_))));
+            assert!(active.iter().any(|s| matches!(s, Signal::EnterShort(_))));
+            // 入场与平仓交替出现，平仓后立刻反手
+            for pair in active.windows(2) {
+                match pair[0] {
+                    Signal::Exit => assert!(!matches!(pair[1], Signal::Exit)),
+                    _ => assert_eq!(*pair[1], Signal::Exit, "{}: {:?}", trigger, pair),
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_macd_long_only_never_enters_short() {
+        let mut strategy = MacdStrategy::from_params(&json!({
+            "fastPeriod": 3,
+            "slowPeriod": 8,
+            "signalPeriod": 3,
+            "positionType": "long"
+        }));
+        let signals = run(strategy.as_mut(), &wave(120));
+
+        assert!(!signals.iter().any(|s| matches!(s, Signal::EnterShort(_))));
+        assert!(signals.iter().any(|s| matches!(s, Signal::EnterLong(_))));
+        assert!(signals.contains(&Signal::Exit));
+    }
+}
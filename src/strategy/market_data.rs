@@ -1,5 +1,84 @@
-#[derive(Debug, Clone)]
+use chrono::{DateTime, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+
+/// 一根 K 线：品种、开始时间和 OHLCV
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MarketData {
-    pub timestamp: String,
+    pub symbol: String,
+    pub timestamp: DateTime<Utc>,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
     pub close_price: f64,
+    pub volume: f64,
+}
+
+impl MarketData {
+    /// 只有收盘价的数据（如组合净值）用收盘价填满 OHLC，成交量记 0
+    pub fn from_close(timestamp: DateTime<Utc>, close_price: f64) -> Self {
+        Self {
+            symbol: String::new(),
+            timestamp,
+            open: close_price,
+            high: close_price,
+            low: close_price,
+            close_price,
+            volume: 0.0,
+        }
+    }
+
+    pub fn with_symbol(mut self, symbol: impl Into<String>) -> Self {
+        self.symbol = symbol.into();
+        self
+    }
+
+    pub fn typical_price(&self) -> f64 {
+        (self.high + self.low + self.close_price) / 3.0
+    }
+
+    pub fn range(&self) -> f64 {
+        self.high - self.low
+    }
+
+    /// 支持 RFC3339、"YYYY-MM-DD HH:MM:SS"、"YYYY-MM-DD" 以及秒/毫秒时间戳
+    pub fn parse_timestamp(raw: &str) -> Option<DateTime<Utc>> {
+        let raw = raw.trim();
+        if let Ok(ts) = DateTime::parse_from_rfc3339(raw) {
+            return Some(ts.with_timezone(&Utc));
+        }
+        if let Ok(ts) = NaiveDateTime::parse_from_str(raw, "%Y-%m-%d %H:%M:%S") {
+            return Some(Utc.from_utc_datetime(&ts));
+        }
+        if let Ok(date) = NaiveDate::parse_from_str(raw, "%Y-%m-%d") {
+            return Some(Utc.from_utc_datetime(&date.and_hms_opt(0, 0, 0)?));
+        }
+        let n: i64 = raw.parse().ok()?;
+        // 13 位及以上按毫秒处理
+        if n.abs() >= 1_000_000_000_000 {
+            Utc.timestamp_millis_opt(n).single()
+        } else {
+            Utc.timestamp_opt(n, 0).single()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_timestamp_formats() {
+        let expected = Utc.with_ymd_and_hms(2024, 3, 1, 0, 0, 0).unwrap();
+        for raw in [
+            "2024-03-01T00:00:00Z",
+            "2024-03-01T08:00:00+08:00",
+            "2024-03-01 00:00:00",
+            "2024-03-01",
+            "1709251200",
+            "1709251200000",
+        ] {
+            assert_eq!(MarketData::parse_timestamp(raw), Some(expected), "{}", raw);
+        }
+        assert_eq!(MarketData::parse_timestamp("yesterday"), None);
+    }
 }
@@ -1,15 +1,21 @@
-use serde_json::Value;
+use std::error::Error;
+
+use serde_json::{Value, json};
 
 use crate::{
     indicators::{
-        indicator::Indicator,
-        moving_average::{SmaIndicator, ema_indicator::EmaIndicator, wma_indicator::WmaIndicator},
+        indicator::Indicator, moving_average::SimpleMovingAverage, registry::IndicatorRegistry,
         std_dev_indicator::StdDevIndicator,
     },
     strategy::position::PositionType,
 };
 
-use super::{signal::Signal, strategy_trait::Strategy};
+use super::{
+    checkpoint::{Checkpoint, load_indicator},
+    param_schema::{ParamSpec, RISK, StrategySchema, position_type},
+    signal::Signal,
+    strategy_trait::Strategy,
+};
 
 pub struct MeanReversionStrategy {
     name: String,
@@ -31,6 +37,35 @@ pub enum EntryStyle {
 }
 
 impl MeanReversionStrategy {
+    /// 参数说明，见 StrategySchema
+    pub fn schema() -> StrategySchema {
+        StrategySchema::new(
+            "均值回归：价格偏离均值过远时反向入场，回到均值附近离场",
+            vec![
+                ParamSpec::choice("meanType", &["sma", "ema", "wma"], "均值类型"),
+                ParamSpec::int("lookbackPeriod", "均值和标准差的周期")
+                    .default(20)
+                    .min(1.0),
+                ParamSpec::choice("reversionStyle", &["zscore", "bollinger"], "入场方式"),
+                ParamSpec::float("entryZScore", "zscore 方式的入场 z 值")
+                    .default(2.0)
+                    .min(0.0),
+                ParamSpec::float("exitZScore", "zscore 方式的离场 z 值")
+                    .default(0.5)
+                    .min(0.0),
+                ParamSpec::float("bandMultiplier", "bollinger 方式的带宽倍数")
+                    .default(2.0)
+                    .min(0.0),
+                ParamSpec::float("exitThreshold", "bollinger 方式的离场阈值")
+                    .default(0.5)
+                    .min(0.0),
+                position_type(),
+            ],
+        )
+        .with_risk(RISK)
+        .less_than("exitZScore", "entryZScore")
+    }
+
     pub fn from_params(params: &Value) -> Box<dyn Strategy> {
         let position_type = match params.get("positionType").and_then(Value::as_str) {
             Some("long") => PositionType::Long,
@@ -45,7 +80,8 @@ impl MeanReversionStrategy {
         let period = params
             .get("lookbackPeriod")
             .and_then(Value::as_u64)
-            .unwrap_or(20) as usize;
+            .unwrap_or(20)
+            .max(1) as usize;
         let style = match params
             .get("reversionStyle")
             .and_then(Value::as_str)
@@ -76,11 +112,9 @@ impl MeanReversionStrategy {
             .unwrap_or(2.0);
 
         let make_mean = |t: &str| -> Box<dyn Indicator> {
-            match t {
-                "wma" => Box::new(WmaIndicator::new(period)),
-                "ema" => Box::new(EmaIndicator::new(period)),
-                _ => Box::new(SmaIndicator::new(period)),
-            }
+            IndicatorRegistry::new()
+                .build(t, &[period as f64])
+                .unwrap_or_else(|_| Box::new(SimpleMovingAverage::new(period)))
         };
 
         Box::new(MeanReversionStrategy {
@@ -150,20 +184,71 @@ impl Strategy for MeanReversionStrategy {
     fn update(
         &mut self,
         market_data: &super::market_data::MarketData,
-        current_position: &Option<super::position::TradePosition>,
+        _current_position: &Option<super::position::TradePosition>,
     ) {
-        todo!()
+        self.mean.update(market_data.close_price);
+        self.volatility.update(market_data.close_price);
     }
 
     fn name(&self) -> &str {
         self.name.as_str()
     }
 
+    // 入场/平仓阈值按入场方式分别对应 z-score 或带宽倍数
     fn apply_parameters(&mut self, entry_threshold: Option<f64>, exit_threshold: Option<f64>) {
-        todo!()
+        match self.style {
+            EntryStyle::ZScore => {
+                if let Some(entry) = entry_threshold {
+                    self.entry_z_score = entry;
+                }
+                if let Some(exit) = exit_threshold {
+                    self.exit_z_score = exit;
+                }
+            }
+            EntryStyle::Bollinger => {
+                if let Some(entry) = entry_threshold {
+                    self.band_multiplier = entry;
+                }
+                if let Some(exit) = exit_threshold {
+                    self.exit_threshold = exit;
+                }
+            }
+        }
+    }
+
+    fn chart_values(&self) -> Vec<(String, Option<f64>)> {
+        let mean = self.mean.value();
+        let band = |k: f64| Some(mean? + k * self.volatility.value()?);
+        let width = match self.style {
+            EntryStyle::ZScore => self.entry_z_score,
+            EntryStyle::Bollinger => self.band_multiplier,
+        };
+        vec![
+            ("mean".to_string(), mean),
+            ("upper".to_string(), band(width)),
+            ("lower".to_string(), band(-width)),
+        ]
+    }
+
+    fn checkpoint(&mut self) -> Option<&mut dyn Checkpoint> {
+        Some(self)
     }
 
     fn position_type(&self) -> &PositionType {
         &self.position_type
     }
 }
+
+impl Checkpoint for MeanReversionStrategy {
+    fn save_state(&self) -> Option<Value> {
+        Some(json!({
+            "mean": self.mean.save_state()?,
+            "volatility": self.volatility.save_state()?,
+        }))
+    }
+
+    fn load_state(&mut self, state: &Value) -> Result<(), Box<dyn Error>> {
+        load_indicator(self.mean.as_mut(), state, "mean")?;
+        load_indicator(self.volatility.as_mut(), state, "volatility")
+    }
+}
@@ -1,10 +1,25 @@
+pub mod bar_clock;
+pub mod bollinger_bands_strategy;
+pub mod checkpoint;
+pub mod composite_strategy;
+pub mod dca_strategy;
 pub mod direction;
+pub mod grid_strategy;
+pub mod macd_strategy;
 pub mod market_data;
 pub mod mean_reversion_strategy;
 pub mod moving_average_strategy;
+pub mod pairs_strategy;
+pub mod param_schema;
 pub mod position;
+pub mod rebalance_strategy;
+pub mod rsi_strategy;
+pub mod rule_strategy;
+pub mod rules;
+pub mod script_strategy;
 pub mod signal;
 pub mod strategy_context;
 pub mod strategy_factory;
 pub mod strategy_trait;
 pub mod strategy_type;
+pub mod turtle_strategy;
@@ -1,21 +1,67 @@
-use serde_json::Value;
+use std::error::Error;
 
-use crate::indicators::moving_average::{MovingAverage, MovingAverageFactory, MovingAverageType};
+use serde_json::{Value, json};
+
+use crate::{
+    data::timeframe::Timeframe,
+    indicators::{
+        indicator::Indicator,
+        moving_average::{
+            ExponentialMovingAverage, MovingAverageFactory, MovingAverageType, SimpleMovingAverage,
+        },
+        overlay::named_values,
+        registry::IndicatorRegistry,
+    },
+};
 
 use super::{
+    checkpoint::{Checkpoint, load_indicator},
     market_data::MarketData,
+    param_schema::{FieldError, ParamSpec, RISK, StrategySchema, position_type},
     position::{PositionType, TradePosition},
     signal::Signal,
+    strategy_context::StrategyContext,
     strategy_trait::Strategy,
 };
 
+/// 高周期趋势过滤：高周期收盘价在其 EMA 之上只做多，之下只做空
+struct TrendFilter {
+    timeframe: Timeframe,
+    ema: ExponentialMovingAverage,
+    close: Option<f64>,
+}
+
+impl TrendFilter {
+    fn update(&mut self, closed: &[MarketData]) {
+        for bar in closed {
+            self.ema.update(bar.close_price);
+            self.close = Some(bar.close_price);
+        }
+    }
+
+    // EMA 未就绪时不允许任何方向开仓
+    fn allows(&self, long: bool) -> bool {
+        match (self.close, self.ema.value()) {
+            (Some(close), Some(ema)) => (close > ema) == long,
+            _ => false,
+        }
+    }
+}
+
+/// 均线交叉支持的均线类型，对应指标注册表里的名字
+const MA_KINDS: [&str; 3] = ["sma", "ema", "wma"];
+
+/// 快线和慢线
+type MaPair = (Box<dyn Indicator>, Box<dyn Indicator>);
+
 pub struct MovingAverageStrategy {
     name: String,
-    short_ma: Box<dyn MovingAverage>,
-    long_ma: Box<dyn MovingAverage>,
+    short_ma: Box<dyn Indicator>,
+    long_ma: Box<dyn Indicator>,
     entry_threshold: Option<f64>,
     exit_threshold: Option<f64>,
     position_type: PositionType,
+    trend: Option<TrendFilter>,
 }
 
 impl MovingAverageStrategy {
@@ -31,9 +77,48 @@ impl MovingAverageStrategy {
             entry_threshold: None,
             exit_threshold: None,
             position_type: PositionType::Both,
+            trend: None,
         }
     }
-    pub fn from_params(params: &Value) -> Box<dyn Strategy> {
+
+    /// 参数说明，见 StrategySchema
+    pub fn schema() -> StrategySchema {
+        StrategySchema::new(
+            "双均线交叉：快线上穿慢线做多，下穿做空",
+            vec![
+                ParamSpec::choice("maType", &MA_KINDS, "均线类型"),
+                ParamSpec::int("fastPeriod", "快线周期").default(5).min(1.0),
+                ParamSpec::int("slowPeriod", "慢线周期")
+                    .default(20)
+                    .min(1.0),
+                ParamSpec::float("entryThreshold", "入场阈值").min(0.0),
+                ParamSpec::float("exitThreshold", "离场阈值").min(0.0),
+                ParamSpec::string(
+                    "trendTimeframe",
+                    "趋势过滤用的高周期，如 4h、1d；不给则不过滤",
+                ),
+                ParamSpec::int("trendPeriod", "高周期 EMA 的周期")
+                    .default(20)
+                    .min(1.0),
+                position_type(),
+            ],
+        )
+        .with_risk(RISK)
+        .less_than("fastPeriod", "slowPeriod")
+        .with_check(
+            |params| match params.get("trendTimeframe").and_then(Value::as_str) {
+                Some(tf) if Timeframe::parse(tf).is_none() => {
+                    vec![FieldError::new(
+                        "trendTimeframe",
+                        format!("unknown timeframe `{}`", tf),
+                    )]
+                }
+                _ => Vec::new(),
+            },
+        )
+    }
+
+    pub fn from_params(params: &Value) -> Result<Box<dyn Strategy>, Box<dyn Error>> {
         let position_type = match params.get("positionType").and_then(Value::as_str) {
             Some("long") => PositionType::Long,
             Some("short") => PositionType::Short,
@@ -60,7 +145,7 @@ impl MovingAverageStrategy {
         let entry_threshold = params.get("entryThreshold").and_then(Value::as_f64);
 
         let exit_threshold = params.get("exitThreshold").and_then(Value::as_f64);
-        Box::new(Self::build(
+        let mut strategy = Self::build(
             "ma-crossover".to_string(),
             ma_type,
             fast,
@@ -68,7 +153,44 @@ impl MovingAverageStrategy {
             position_type,
             entry_threshold,
             exit_threshold,
-        ))
+        )?;
+
+        // 例如 {"trendTimeframe": "4h", "trendPeriod": 20}：用 4h 收盘价的 EMA 过滤入场方向
+        if let Some(timeframe) = params
+            .get("trendTimeframe")
+            .and_then(Value::as_str)
+            .and_then(Timeframe::parse)
+        {
+            let period = params
+                .get("trendPeriod")
+                .and_then(Value::as_u64)
+                .unwrap_or(20) as usize;
+            strategy.trend = Some(TrendFilter {
+                timeframe,
+                ema: ExponentialMovingAverage::new(period.max(1)),
+                close: None,
+            });
+        }
+        Ok(Box::new(strategy))
+    }
+
+    // 快慢两条均线都走指标注册表，只接受 MA_KINDS 里的均线
+    fn pair(
+        sub_type: &str,
+        fast_period: usize,
+        slow_period: usize,
+    ) -> Result<MaPair, Box<dyn Error>> {
+        if !MA_KINDS.contains(&sub_type) {
+            return Err(format!(
+                "unsupported moving average `{}`, expected one of {}",
+                sub_type,
+                MA_KINDS.join(", ")
+            )
+            .into());
+        }
+        let registry = IndicatorRegistry::new();
+        let build = |period: usize| registry.build(sub_type, &[period.max(1) as f64]);
+        Ok((build(fast_period)?, build(slow_period)?))
     }
 
     pub fn build(
@@ -79,78 +201,37 @@ impl MovingAverageStrategy {
         position_type: PositionType,
         entry_threshold: Option<f64>,
         exit_threshold: Option<f64>,
-    ) -> Self {
-        let short_ma = match sub_type {
-            "sma" => {
-                MovingAverageFactory::create_moving_average(MovingAverageType::SMA(fast_period))
-            }
-            "ema" => {
-                MovingAverageFactory::create_moving_average(MovingAverageType::EMA(fast_period))
-            }
-            "wma" => {
-                MovingAverageFactory::create_moving_average(MovingAverageType::WMA(fast_period))
-            }
-            _ => panic!("Unsupported moving average sub_type: {}", sub_type),
-        };
-
-        let long_ma = match sub_type {
-            "sma" => {
-                MovingAverageFactory::create_moving_average(MovingAverageType::SMA(slow_period))
-            }
-            "ema" => {
-                MovingAverageFactory::create_moving_average(MovingAverageType::EMA(slow_period))
-            }
-            "wma" => {
-                MovingAverageFactory::create_moving_average(MovingAverageType::WMA(slow_period))
-            }
-            _ => panic!("Unsupported moving average sub_type: {}", sub_type),
-        };
+    ) -> Result<Self, Box<dyn Error>> {
+        let (short_ma, long_ma) = Self::pair(sub_type, fast_period, slow_period)?;
 
-        MovingAverageStrategy {
+        Ok(MovingAverageStrategy {
             name,
             short_ma,
             long_ma,
             entry_threshold,
             exit_threshold,
             position_type,
-        }
+            trend: None,
+        })
     }
 
-    pub fn create(name: String, sub_type: &str, fast_period: usize, slow_period: usize) -> Self {
-        let short_ma = match sub_type {
-            "sma" => {
-                MovingAverageFactory::create_moving_average(MovingAverageType::SMA(fast_period))
-            }
-            "ema" => {
-                MovingAverageFactory::create_moving_average(MovingAverageType::EMA(fast_period))
-            }
-            "wma" => {
-                MovingAverageFactory::create_moving_average(MovingAverageType::WMA(fast_period))
-            }
-            _ => panic!("Unsupported moving average sub_type: {}", sub_type),
-        };
-
-        let long_ma = match sub_type {
-            "sma" => {
-                MovingAverageFactory::create_moving_average(MovingAverageType::SMA(slow_period))
-            }
-            "ema" => {
-                MovingAverageFactory::create_moving_average(MovingAverageType::EMA(slow_period))
-            }
-            "wma" => {
-                MovingAverageFactory::create_moving_average(MovingAverageType::WMA(slow_period))
-            }
-            _ => panic!("Unsupported moving average sub_type: {}", sub_type),
-        };
+    pub fn create(
+        name: String,
+        sub_type: &str,
+        fast_period: usize,
+        slow_period: usize,
+    ) -> Result<Self, Box<dyn Error>> {
+        let (short_ma, long_ma) = Self::pair(sub_type, fast_period, slow_period)?;
 
-        MovingAverageStrategy {
+        Ok(MovingAverageStrategy {
             name,
             short_ma,
             long_ma,
             entry_threshold: None,
             exit_threshold: None,
             position_type: PositionType::Both,
-        }
+            trend: None,
+        })
     }
 }
 
@@ -207,6 +288,41 @@ impl Strategy for MovingAverageStrategy {
         }
     }
 
+    fn on_tick(&mut self, ctx: &mut StrategyContext, data: &MarketData) -> Signal {
+        if let Some(trend) = &mut self.trend {
+            trend.update(ctx.new_closed_bars(trend.timeframe));
+        }
+        let (long_ok, short_ok) = match &self.trend {
+            Some(trend) => (trend.allows(true), trend.allows(false)),
+            None => (true, true),
+        };
+        let signal = self
+            .on_bar(data, ctx.position)
+            .restrict(self.position_type(), ctx.position);
+        match signal {
+            // 逆势的反手信号只平掉现有仓位
+            Signal::EnterLong(_) if !long_ok => {
+                if ctx.position < 0.0 {
+                    Signal::Exit
+                } else {
+                    Signal::Hold
+                }
+            }
+            Signal::EnterShort(_) if !short_ok => {
+                if ctx.position > 0.0 {
+                    Signal::Exit
+                } else {
+                    Signal::Hold
+                }
+            }
+            other => other,
+        }
+    }
+
+    fn timeframes(&self) -> Vec<Timeframe> {
+        self.trend.iter().map(|t| t.timeframe).collect()
+    }
+
     fn name(&self) -> &str {
         self.name.as_str()
     }
@@ -216,11 +332,108 @@ impl Strategy for MovingAverageStrategy {
         self.exit_threshold = exit_threshold;
     }
 
+    fn chart_values(&self) -> Vec<(String, Option<f64>)> {
+        let mut values = named_values("fast", self.short_ma.as_ref());
+        values.extend(named_values("slow", self.long_ma.as_ref()));
+        if let Some(trend) = &self.trend {
+            values.push(("trend".to_string(), trend.ema.value()));
+        }
+        values
+    }
+
+    fn checkpoint(&mut self) -> Option<&mut dyn Checkpoint> {
+        Some(self)
+    }
+
     fn position_type(&self) -> &PositionType {
         &self.position_type
     }
 }
 
+impl Checkpoint for MovingAverageStrategy {
+    fn save_state(&self) -> Option<Value> {
+        let trend = match &self.trend {
+            Some(trend) => json!({ "ema": trend.ema.save_state()?, "close": trend.close }),
+            None => Value::Null,
+        };
+        Some(json!({
+            "short": self.short_ma.save_state()?,
+            "long": self.long_ma.save_state()?,
+            "trend": trend,
+        }))
+    }
+
+    fn load_state(&mut self, state: &Value) -> Result<(), Box<dyn Error>> {
+        load_indicator(self.short_ma.as_mut(), state, "short")?;
+        load_indicator(self.long_ma.as_mut(), state, "long")?;
+        if let Some(trend) = &mut self.trend {
+            load_indicator(&mut trend.ema, &state["trend"], "ema")?;
+            trend.close = state["trend"]["close"].as_f64();
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, TimeZone, Utc};
+    use serde_json::json;
+
+    use super::*;
+
+    fn run(params: Value) -> Vec<Signal> {
+        let mut strategy = MovingAverageStrategy::from_params(&params).unwrap();
+        let mut ctx = StrategyContext::new(10_000.0);
+        ctx.subscribe(&strategy.timeframes());
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        (0..120)
+            .map(|i| {
+                // 小时线在上升趋势中以 4 小时为周期震荡，快慢线反复交叉，4h 收盘价逐根走高
+                let close = 100.0 + i as f64 * 0.5 + [0.0, 4.0, 0.0, -4.0][i as usize % 4];
+                let bar = MarketData::from_close(start + Duration::hours(i), close);
+                ctx.advance_timeframes(&bar);
+                let signal = strategy.on_tick(&mut ctx, &bar);
+                match signal {
+                    Signal::EnterLong(_) => ctx.position = 1.0,
+                    Signal::EnterShort(_) => ctx.position = -1.0,
+                    Signal::Exit => ctx.position = 0.0,
+                    _ => {}
+                }
+                signal
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_trend_filter_blocks_entries_against_higher_timeframe() {
+        let base = json!({ "fastPeriod": 2, "slowPeriod": 4 });
+        let unfiltered = run(base.clone());
+        assert!(
+            unfiltered
+                .iter()
+                .any(|s| matches!(s, Signal::EnterShort(_)))
+        );
+
+        let mut params = base;
+        params["trendTimeframe"] = json!("4h");
+        params["trendPeriod"] = json!(3);
+        let filtered = run(params);
+        // 4h 收盘价一直在 EMA 之上，只做多；EMA 就绪前不入场
+        assert!(filtered.iter().any(|s| matches!(s, Signal::EnterLong(_))));
+        assert!(!filtered.iter().any(|s| matches!(s, Signal::EnterShort(_))));
+        assert!(filtered[..12].iter().all(|s| *s == Signal::Hold));
+    }
+
+    #[test]
+    fn test_rejects_non_moving_average_kinds() {
+        for kind in ["rsi", "nope"] {
+            let params = json!({ "maType": kind, "fastPeriod": 2, "slowPeriod": 4 });
+            assert!(MovingAverageStrategy::from_params(&params).is_err());
+        }
+        assert!(MovingAverageStrategy::create("ma".into(), "wma", 2, 4).is_ok());
+    }
+}
+
 // #[cfg(test)]
 // mod tests {
 //     use super::*;
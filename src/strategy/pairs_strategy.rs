@@ -0,0 +1,318 @@
+use serde_json::Value;
+
+use crate::indicators::hedge_ratio::{HedgeEstimate, HedgeRatio};
+
+use super::{
+    direction::Direction,
+    market_data::MarketData,
+    param_schema::{ParamSpec, StrategySchema, position_type},
+    position::{PositionType, TradePosition},
+    signal::{Leg, Signal},
+    strategy_context::StrategyContext,
+    strategy_trait::Strategy,
+};
+
+// 配对交易：数据源第一个品种为 y，第二个为 x，价差 = y - alpha - beta * x。
+// z-score 超过 entry_z 做空价差（卖 y、买 beta 份 x），低于 -entry_z 做多价差，
+// 回到 exit_z 以内平仓；stop_z 大于 0 时价差继续发散超过该值也平仓。
+// PositionType::Long 只做多价差，Short 只做空价差。
+pub struct PairsStrategy {
+    name: String,
+    hedge: HedgeRatio,
+    entry_z: f64,
+    exit_z: f64,
+    stop_z: f64,
+    last: Option<HedgeEstimate>,
+    position_type: PositionType,
+}
+
+impl PairsStrategy {
+    pub fn new(name: String, hedge: HedgeRatio, entry_z: f64, exit_z: f64) -> Self {
+        PairsStrategy {
+            name,
+            hedge,
+            entry_z,
+            exit_z,
+            stop_z: 0.0,
+            last: None,
+            position_type: PositionType::Both,
+        }
+    }
+
+    /// 参数说明，见 StrategySchema
+    pub fn schema() -> StrategySchema {
+        StrategySchema::new(
+            "配对交易：价差 z 值偏离时做多一腿做空另一腿",
+            vec![
+                ParamSpec::choice("hedgeMethod", &["ols", "kalman"], "对冲比例的估计方法"),
+                ParamSpec::int("lookback", "估计窗口").default(60).min(2.0),
+                ParamSpec::float("kalmanDelta", "卡尔曼滤波的过程噪声")
+                    .default(1e-4)
+                    .min(0.0),
+                ParamSpec::float("kalmanObservationVar", "卡尔曼滤波的观测噪声")
+                    .default(1e-3)
+                    .min(0.0),
+                ParamSpec::float("entryZ", "入场 z 值")
+                    .default(2.0)
+                    .min(0.0),
+                ParamSpec::float("exitZ", "离场 z 值").default(0.5).min(0.0),
+                ParamSpec::float("stopZ", "止损 z 值，0 为不止损")
+                    .default(0.0)
+                    .min(0.0),
+                position_type(),
+            ],
+        )
+        .with_risk(&["riskPerTrade", "positionSize"])
+        .less_than("exitZ", "entryZ")
+    }
+
+    pub fn from_params(params: &Value) -> Box<dyn Strategy> {
+        let position_type = match params.get("positionType").and_then(Value::as_str) {
+            Some("long") => PositionType::Long,
+            Some("short") => PositionType::Short,
+            _ => PositionType::Both,
+        };
+
+        let method = params
+            .get("hedgeMethod")
+            .and_then(Value::as_str)
+            .unwrap_or("ols");
+        let lookback = params.get("lookback").and_then(Value::as_u64).unwrap_or(60) as usize;
+        let delta = params
+            .get("kalmanDelta")
+            .and_then(Value::as_f64)
+            .unwrap_or(1e-4);
+        let observation_var = params
+            .get("kalmanObservationVar")
+            .and_then(Value::as_f64)
+            .unwrap_or(1e-3);
+        let entry_z = params.get("entryZ").and_then(Value::as_f64).unwrap_or(2.0);
+        let exit_z = params.get("exitZ").and_then(Value::as_f64).unwrap_or(0.5);
+        let stop_z = params.get("stopZ").and_then(Value::as_f64).unwrap_or(0.0);
+
+        let hedge = HedgeRatio::parse(method, lookback, delta, observation_var);
+        let mut strategy = Self::new("pairs".to_string(), hedge, entry_z, exit_z);
+        strategy.stop_z = stop_z;
+        strategy.position_type = position_type;
+        Box::new(strategy)
+    }
+
+    pub fn estimate(&self) -> Option<HedgeEstimate> {
+        self.last
+    }
+
+    fn decide(
+        &self,
+        est: &HedgeEstimate,
+        y: &MarketData,
+        x: &MarketData,
+        open: Option<&Direction>,
+    ) -> Signal {
+        let z = est.zscore;
+        match open {
+            Some(Direction::Long) => {
+                if z >= -self.exit_z || (self.stop_z > 0.0 && z <= -self.stop_z) {
+                    return Signal::Exit;
+                }
+                Signal::Hold
+            }
+            Some(Direction::Short) => {
+                if z <= self.exit_z || (self.stop_z > 0.0 && z >= self.stop_z) {
+                    return Signal::Exit;
+                }
+                Signal::Hold
+            }
+            None => {
+                let stopped = self.stop_z > 0.0 && z.abs() >= self.stop_z;
+                if z >= self.entry_z && !stopped && self.supports_short() {
+                    return Signal::EnterPair([
+                        Leg {
+                            index: 0,
+                            price: y.close_price,
+                            ratio: -1.0,
+                        },
+                        Leg {
+                            index: 1,
+                            price: x.close_price,
+                            ratio: est.beta,
+                        },
+                    ]);
+                }
+                if z <= -self.entry_z && !stopped && self.supports_long() {
+                    return Signal::EnterPair([
+                        Leg {
+                            index: 0,
+                            price: y.close_price,
+                            ratio: 1.0,
+                        },
+                        Leg {
+                            index: 1,
+                            price: x.close_price,
+                            ratio: -est.beta,
+                        },
+                    ]);
+                }
+                Signal::Hold
+            }
+        }
+    }
+}
+
+impl Strategy for PairsStrategy {
+    // 单个价格无法计算价差
+    fn generate_signal(&mut self, _price: f64, _position: f64) -> Signal {
+        Signal::Hold
+    }
+
+    fn on_bars(&mut self, ctx: &mut StrategyContext, bars: &[MarketData]) -> Signal {
+        let [y, x, ..] = bars else {
+            return Signal::Hold;
+        };
+        self.last = self.hedge.update(x.close_price, y.close_price);
+        let Some(est) = self.last else {
+            return Signal::Hold;
+        };
+        let open = ctx.pair_entry.as_ref().map(|p| &p.direction);
+        self.decide(&est, y, x, open)
+    }
+
+    fn update(&mut self, _market_data: &MarketData, _current_position: &Option<TradePosition>) {}
+
+    fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    // entry_threshold / exit_threshold 为入场、平仓的 z-score
+    fn apply_parameters(&mut self, entry_threshold: Option<f64>, exit_threshold: Option<f64>) {
+        if let Some(entry) = entry_threshold {
+            self.entry_z = entry;
+        }
+        if let Some(exit) = exit_threshold {
+            self.exit_z = exit;
+        }
+    }
+
+    fn position_type(&self) -> &PositionType {
+        &self.position_type
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::strategy::strategy_context::PairEntry;
+
+    // x 缓慢波动，y = 2x + 5 + 周期性偏离
+    fn cointegrated(n: usize) -> Vec<(f64, f64)> {
+        (0..n)
+            .map(|i| {
+                let x = 40.0 + (i as f64 * 0.05).sin() * 5.0;
+                let deviation = (i as f64 * 0.2).sin() * 1.5 + (i as f64 * 1.3).sin() * 0.2;
+                (x, 2.0 * x + 5.0 + deviation)
+            })
+            .collect()
+    }
+
+    fn bars(x: f64, y: f64) -> Vec<MarketData> {
+        vec![
+            MarketData::from_close(Default::default(), y).with_symbol("YYY"),
+            MarketData::from_close(Default::default(), x).with_symbol("XXX"),
+        ]
+    }
+
+    fn run(strategy: &mut dyn Strategy, series: &[(f64, f64)]) -> Vec<Signal> {
+        let mut ctx = StrategyContext::new(10_000.0);
+        series
+            .iter()
+            .map(|&(x, y)| {
+                let signal = strategy.on_bars(&mut ctx, &bars(x, y));
+                match signal {
+                    Signal::EnterPair(legs) => {
+                        ctx.pair_entry = Some(PairEntry {
+                            entry_time: String::new(),
+                            units: 1.0,
+                            direction: if legs[0].ratio > 0.0 {
+                                Direction::Long
+                            } else {
+                                Direction::Short
+                            },
+                            legs: Vec::new(),
+                        })
+                    }
+                    Signal::Exit => ctx.pair_entry = None,
+                    _ => {}
+                }
+                signal
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_pairs_enters_both_legs_and_exits_on_reversion() {
+        let mut strategy = PairsStrategy::from_params(&json!({
+            "lookback": 40,
+            "entryZ": 1.5,
+            "exitZ": 0.3
+        }));
+        let signals = run(strategy.as_mut(), &cointegrated(300));
+
+        let entries: Vec<[Leg; 2]> = signals
+            .iter()
+            .filter_map(|s| match s {
+                Signal::EnterPair(legs) => Some(*legs),
+                _ => None,
+            })
+            .collect();
+        assert!(entries.len() >= 2, "{:?}", entries);
+        for legs in &entries {
+            // 两条腿方向相反，x 腿数量约为 beta = 2
+            assert!(legs[0].ratio.abs() == 1.0);
+            assert!(legs[0].ratio * legs[1].ratio < 0.0);
+            assert!((legs[1].ratio.abs() - 2.0).abs() < 0.5, "{:?}", legs);
+        }
+        assert!(entries.iter().any(|l| l[0].ratio > 0.0));
+        assert!(entries.iter().any(|l| l[0].ratio < 0.0));
+
+        // 入场和平仓交替出现
+        let active: Vec<&Signal> = signals.iter().filter(|s| **s != Signal::Hold).collect();
+        for pair in active.windows(2) {
+            assert_ne!(
+                matches!(pair[0], Signal::Exit),
+                matches!(pair[1], Signal::Exit)
+            );
+        }
+    }
+
+    #[test]
+    fn test_pairs_kalman_and_long_only() {
+        let mut kalman = PairsStrategy::from_params(&json!({
+            "hedgeMethod": "kalman",
+            "lookback": 30,
+            "kalmanDelta": 1e-5,
+            "entryZ": 1.0,
+            "exitZ": 0.0,
+            "positionType": "long"
+        }));
+        let signals = run(kalman.as_mut(), &cointegrated(400));
+
+        assert!(signals.iter().any(|s| matches!(s, Signal::EnterPair(_))));
+        assert!(
+            !signals
+                .iter()
+                .any(|s| matches!(s, Signal::EnterPair(l) if l[0].ratio < 0.0))
+        );
+    }
+
+    #[test]
+    fn test_pairs_holds_on_single_leg() {
+        let mut strategy = PairsStrategy::from_params(&json!({ "lookback": 2 }));
+        let mut ctx = StrategyContext::new(1_000.0);
+        let single = [MarketData::from_close(Default::default(), 10.0)];
+        for _ in 0..5 {
+            assert_eq!(strategy.on_bars(&mut ctx, &single), Signal::Hold);
+        }
+        assert_eq!(strategy.generate_signal(10.0, 0.0), Signal::Hold);
+    }
+}
@@ -0,0 +1,583 @@
+use std::fmt;
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use crate::regime::REGIME_LABELS;
+
+/// 参数值的类型；enum 的可选值在 ParamSpec::options 里，array 给了 options 时元素只能取其中的值
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ParamKind {
+    Int,
+    Float,
+    Bool,
+    String,
+    Enum,
+    Array,
+}
+
+/// 参数归属，应用回测结果时按它把参数拆成策略 / 风控 / 执行三份
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ParamGroup {
+    Strategy,
+    Risk,
+    Exec,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ParamSpec {
+    pub name: &'static str,
+    #[serde(rename = "type")]
+    pub kind: ParamKind,
+    pub group: ParamGroup,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max: Option<f64>,
+    /// 为 true 时取值必须大于 min，不能等于
+    #[serde(rename = "exclusiveMin", skip_serializing_if = "std::ops::Not::not")]
+    pub exclusive_min: bool,
+    #[serde(skip_serializing_if = "<[_]>::is_empty")]
+    pub options: &'static [&'static str],
+    pub description: &'static str,
+}
+
+impl ParamSpec {
+    fn new(name: &'static str, kind: ParamKind, description: &'static str) -> Self {
+        Self {
+            name,
+            kind,
+            group: ParamGroup::Strategy,
+            default: None,
+            min: None,
+            max: None,
+            exclusive_min: false,
+            options: &[],
+            description,
+        }
+    }
+
+    pub fn int(name: &'static str, description: &'static str) -> Self {
+        Self::new(name, ParamKind::Int, description)
+    }
+
+    pub fn float(name: &'static str, description: &'static str) -> Self {
+        Self::new(name, ParamKind::Float, description)
+    }
+
+    pub fn bool(name: &'static str, description: &'static str) -> Self {
+        Self::new(name, ParamKind::Bool, description)
+    }
+
+    pub fn string(name: &'static str, description: &'static str) -> Self {
+        Self::new(name, ParamKind::String, description)
+    }
+
+    pub fn array(name: &'static str, description: &'static str) -> Self {
+        Self::new(name, ParamKind::Array, description)
+    }
+
+    /// 取值只能是 options 之一，默认值取第一个
+    pub fn choice(
+        name: &'static str,
+        options: &'static [&'static str],
+        description: &'static str,
+    ) -> Self {
+        Self {
+            options,
+            default: options.first().map(|o| Value::from(*o)),
+            ..Self::new(name, ParamKind::Enum, description)
+        }
+    }
+
+    pub fn options(mut self, options: &'static [&'static str]) -> Self {
+        self.options = options;
+        self
+    }
+
+    pub fn default(mut self, value: impl Into<Value>) -> Self {
+        self.default = Some(value.into());
+        self
+    }
+
+    pub fn min(mut self, min: f64) -> Self {
+        self.min = Some(min);
+        self
+    }
+
+    /// 取值必须大于 min，例如不能为 0 的百分比
+    pub fn min_exclusive(mut self, min: f64) -> Self {
+        self.min = Some(min);
+        self.exclusive_min = true;
+        self
+    }
+
+    pub fn max(mut self, max: f64) -> Self {
+        self.max = Some(max);
+        self
+    }
+
+    pub fn range(mut self, min: f64, max: f64) -> Self {
+        self.min = Some(min);
+        self.max = Some(max);
+        self
+    }
+
+    pub fn group(mut self, group: ParamGroup) -> Self {
+        self.group = group;
+        self
+    }
+
+    fn check(&self, value: &Value) -> Result<(), String> {
+        let number = match self.kind {
+            ParamKind::Int => Some(value.as_i64().ok_or("must be an integer")? as f64),
+            ParamKind::Float => Some(value.as_f64().ok_or("must be a number")?),
+            ParamKind::Bool => value.as_bool().map(|_| None).ok_or("must be a boolean")?,
+            ParamKind::String => value.as_str().map(|_| None).ok_or("must be a string")?,
+            ParamKind::Array => match value.as_array() {
+                Some(items)
+                    if !self.options.is_empty()
+                        && !items.iter().all(|item| {
+                            item.as_str().is_some_and(|s| self.options.contains(&s))
+                        }) =>
+                {
+                    return Err(format!("items must be one of {}", self.options.join(", ")));
+                }
+                Some(_) => None,
+                None => return Err("must be an array".into()),
+            },
+            ParamKind::Enum => match value.as_str() {
+                Some(s) if self.options.contains(&s) => None,
+                _ => return Err(format!("must be one of {}", self.options.join(", "))),
+            },
+        };
+        if let Some(n) = number {
+            let below = match self.min {
+                Some(min) if self.exclusive_min => n <= min,
+                Some(min) => n < min,
+                None => false,
+            };
+            let above = self.max.is_some_and(|max| n > max);
+            match (self.min, self.max) {
+                (Some(min), Some(max)) if (below || above) && self.exclusive_min => {
+                    return Err(format!("must be > {} and <= {}", min, max));
+                }
+                (Some(min), Some(max)) if below || above => {
+                    return Err(format!("must be between {} and {}", min, max));
+                }
+                (Some(min), _) if below && self.exclusive_min => {
+                    return Err(format!("must be > {}", min));
+                }
+                (Some(min), _) if below => return Err(format!("must be >= {}", min)),
+                (_, Some(max)) if above => return Err(format!("must be <= {}", max)),
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 参数之间的依赖，两边都有值（给出或有默认值）时才检查
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "rule", rename_all = "camelCase")]
+pub enum Constraint {
+    LessThan {
+        left: &'static str,
+        right: &'static str,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FieldError {
+    pub field: String,
+    pub message: String,
+}
+
+impl FieldError {
+    pub fn new(field: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            field: field.into(),
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for FieldError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.field, self.message)
+    }
+}
+
+/// 类型和范围之外的深层校验，例如规则列表能否编译
+pub type SchemaCheck = fn(&Value) -> Vec<FieldError>;
+
+/// 大多数策略共用的风控参数
+pub const RISK: &[&str] = &[
+    "stopLoss",
+    "takeProfit",
+    "riskPerTrade",
+    "maxConcurrentPositions",
+    "positionSize",
+];
+
+fn risk_param(name: &str) -> Option<ParamSpec> {
+    // 仓位计算要除以这些百分比，0 没有意义
+    let percent = |name, description| {
+        ParamSpec::float(name, description)
+            .min_exclusive(0.0)
+            .max(100.0)
+            .group(ParamGroup::Risk)
+    };
+    let spec = match name {
+        "stopLoss" => percent("stopLoss", "止损百分比"),
+        "takeProfit" => ParamSpec::float("takeProfit", "止盈百分比")
+            .min(0.0)
+            .group(ParamGroup::Risk),
+        "riskPerTrade" => percent("riskPerTrade", "每笔交易承担的权益风险百分比"),
+        "positionSize" => percent("positionSize", "每次开仓占权益的百分比"),
+        "maxConcurrentPositions" => {
+            ParamSpec::int("maxConcurrentPositions", "最多同时持有的仓位数")
+                .default(1)
+                .min(1.0)
+                .group(ParamGroup::Risk)
+        }
+        "sizing" => ParamSpec::choice(
+            "sizing",
+            &["fixed", "atr"],
+            "仓位计算方式，atr 按波动率定仓",
+        )
+        .group(ParamGroup::Risk),
+        _ => return None,
+    };
+    Some(spec)
+}
+
+/// 所有策略都接受的执行参数
+fn exec_params() -> Vec<ParamSpec> {
+    let bars = |name, description| {
+        ParamSpec::int(name, description)
+            .min(0.0)
+            .group(ParamGroup::Exec)
+    };
+    vec![
+        ParamSpec::float("slippage", "滑点百分比")
+            .range(0.0, 100.0)
+            .group(ParamGroup::Exec),
+        ParamSpec::float("commission", "手续费百分比")
+            .range(0.0, 100.0)
+            .group(ParamGroup::Exec),
+        bars("entryDelay", "信号出现后延迟入场的 bar 数"),
+        bars("minHoldingPeriod", "最少持仓 bar 数"),
+        bars("maxHoldingPeriod", "最多持仓 bar 数，0 为不限"),
+        bars("cooldownPeriod", "平仓后冷却的 bar 数"),
+        ParamSpec::array(
+            "tradeRegimes",
+            "只在这些市场状态下开仓，同一维度的标签取或、不同维度取且",
+        )
+        .options(REGIME_LABELS)
+        .group(ParamGroup::Exec),
+        ParamSpec::choice(
+            "regimeMethod",
+            &["rules", "hmm"],
+            "市场状态识别方式：ADX/波动率百分位规则，或预先拟合保存的 HMM 模型",
+        )
+        .group(ParamGroup::Exec),
+        ParamSpec::int("regimeAdxPeriod", "识别趋势的 ADX 周期")
+            .default(14)
+            .min(1.0)
+            .group(ParamGroup::Exec),
+        ParamSpec::float("regimeAdxThreshold", "ADX 不低于该值为趋势市")
+            .default(25.0)
+            .range(0.0, 100.0)
+            .group(ParamGroup::Exec),
+        ParamSpec::int("regimeVolPeriod", "计算波动率的 bar 数")
+            .default(20)
+            .min(2.0)
+            .group(ParamGroup::Exec),
+        ParamSpec::int("regimeVolLookback", "波动率百分位的回看 bar 数")
+            .default(100)
+            .min(2.0)
+            .group(ParamGroup::Exec),
+        ParamSpec::float("regimeVolPercentile", "波动率百分位不低于该值为高波动")
+            .default(70.0)
+            .range(0.0, 100.0)
+            .group(ParamGroup::Exec),
+        ParamSpec::int(
+            "regimeModelId",
+            "regimeMethod 为 hmm 时使用的模型，由 POST /api/regime/models 拟合保存",
+        )
+        .min(0.0)
+        .group(ParamGroup::Exec),
+    ]
+}
+
+/// 多空方向，大多数策略都读它
+pub fn position_type() -> ParamSpec {
+    ParamSpec::choice("positionType", &["both", "long", "short"], "允许的持仓方向")
+}
+
+/// 一个策略的参数说明：类型、范围、默认值和依赖，前端据此生成表单，回测请求据此校验
+#[derive(Debug, Clone, Serialize)]
+pub struct StrategySchema {
+    pub description: &'static str,
+    pub params: Vec<ParamSpec>,
+    pub constraints: Vec<Constraint>,
+    #[serde(skip)]
+    pub check: Option<SchemaCheck>,
+}
+
+impl StrategySchema {
+    /// 执行参数自动附上，风控参数用 with_risk 按需添加
+    pub fn new(description: &'static str, params: Vec<ParamSpec>) -> Self {
+        let mut params = params;
+        params.extend(exec_params());
+        Self {
+            description,
+            params,
+            constraints: Vec::new(),
+            check: None,
+        }
+    }
+
+    pub fn with_risk(mut self, names: &[&str]) -> Self {
+        self.params
+            .extend(names.iter().filter_map(|name| risk_param(name)));
+        self
+    }
+
+    pub fn less_than(mut self, left: &'static str, right: &'static str) -> Self {
+        self.constraints.push(Constraint::LessThan { left, right });
+        self
+    }
+
+    pub fn with_check(mut self, check: SchemaCheck) -> Self {
+        self.check = Some(check);
+        self
+    }
+
+    pub fn param(&self, name: &str) -> Option<&ParamSpec> {
+        self.params.iter().find(|p| p.name == name)
+    }
+
+    pub fn keys(&self, group: ParamGroup) -> Vec<&'static str> {
+        self.params
+            .iter()
+            .filter(|p| p.group == group)
+            .map(|p| p.name)
+            .collect()
+    }
+
+    /// null 视为没给；未知键、类型、范围和依赖的错误一次全部返回
+    pub fn validate(&self, params: &Value) -> Result<(), Vec<FieldError>> {
+        let empty = Map::new();
+        let given = match params {
+            Value::Null => &empty,
+            Value::Object(map) => map,
+            _ => return Err(vec![FieldError::new("params", "must be an object")]),
+        };
+
+        let mut errors = Vec::new();
+        for (key, value) in given.iter().filter(|(_, v)| !v.is_null()) {
+            match self.param(key) {
+                Some(spec) => {
+                    if let Err(message) = spec.check(value) {
+                        errors.push(FieldError::new(key.as_str(), message));
+                    }
+                }
+                None => errors.push(FieldError::new(key.as_str(), self.unknown(key))),
+            }
+        }
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+
+        let effective = |name: &str| {
+            given
+                .get(name)
+                .filter(|v| !v.is_null())
+                .or_else(|| self.param(name).and_then(|p| p.default.as_ref()))
+                .and_then(Value::as_f64)
+        };
+        for constraint in &self.constraints {
+            match constraint {
+                Constraint::LessThan { left, right } => match (effective(left), effective(right)) {
+                    (Some(l), Some(r)) if l >= r => errors.push(FieldError::new(
+                        *left,
+                        format!("must be less than {} ({} >= {})", right, l, r),
+                    )),
+                    _ => {}
+                },
+            }
+        }
+        match self.check {
+            Some(check) if errors.is_empty() => errors = check(params),
+            _ => {}
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    // 拼错的键给出最接近的参数名
+    fn unknown(&self, key: &str) -> String {
+        let closest = self
+            .params
+            .iter()
+            .map(|p| {
+                (
+                    edit_distance(&key.to_lowercase(), &p.name.to_lowercase()),
+                    p.name,
+                )
+            })
+            .min_by_key(|(d, _)| *d)
+            .filter(|(d, _)| *d <= 2);
+        match closest {
+            Some((_, name)) => format!("unknown parameter, did you mean `{}`?", name),
+            None => "unknown parameter".to_string(),
+        }
+    }
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut prev = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let cur = row[j + 1];
+            row[j + 1] = if ca == *cb {
+                prev
+            } else {
+                1 + prev.min(cur).min(row[j])
+            };
+            prev = cur;
+        }
+    }
+    row[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn schema() -> StrategySchema {
+        StrategySchema::new(
+            "test",
+            vec![
+                ParamSpec::int("fastPeriod", "fast").default(5).min(1.0),
+                ParamSpec::int("slowPeriod", "slow").default(20).min(1.0),
+                ParamSpec::choice("maType", &["sma", "ema"], "type"),
+                position_type(),
+            ],
+        )
+        .with_risk(RISK)
+        .less_than("fastPeriod", "slowPeriod")
+    }
+
+    #[test]
+    fn test_validate_reports_every_bad_field() {
+        let schema = schema();
+        assert!(schema.validate(&json!({})).is_ok());
+        assert!(schema.validate(&Value::Null).is_ok());
+        assert!(
+            schema
+                .validate(&json!({
+                    "fastPeriod": 10,
+                    "stopLoss": 2.5,
+                    "meanType": null,
+                    "tradeRegimes": ["trending", "lowVol"]
+                }))
+                .is_ok()
+        );
+
+        let errors = schema
+            .validate(&json!({
+                "fastPeriod": 2.5,
+                "slowPeriod": 0,
+                "maType": "hma",
+                "stopLos": 2,
+                "foo": 1,
+                "tradeRegimes": ["trending", "sideways"],
+            }))
+            .unwrap_err();
+        let fields: Vec<(&str, &str)> = errors
+            .iter()
+            .map(|e| (e.field.as_str(), e.message.as_str()))
+            .collect();
+        assert_eq!(
+            fields,
+            vec![
+                ("fastPeriod", "must be an integer"),
+                ("foo", "unknown parameter"),
+                ("maType", "must be one of sma, ema"),
+                ("slowPeriod", "must be >= 1"),
+                ("stopLos", "unknown parameter, did you mean `stopLoss`?"),
+                (
+                    "tradeRegimes",
+                    "items must be one of trending, ranging, highVol, lowVol"
+                ),
+            ]
+        );
+        assert_eq!(
+            schema.validate(&json!([1])).unwrap_err(),
+            vec![FieldError::new("params", "must be an object")]
+        );
+
+        // 风控百分比不能为 0，否则仓位计算会出错
+        let errors = schema
+            .validate(&json!({ "riskPerTrade": 0, "stopLoss": 0.0, "positionSize": 150 }))
+            .unwrap_err();
+        assert_eq!(
+            errors,
+            vec![
+                FieldError::new("positionSize", "must be > 0 and <= 100"),
+                FieldError::new("riskPerTrade", "must be > 0 and <= 100"),
+                FieldError::new("stopLoss", "must be > 0 and <= 100"),
+            ]
+        );
+        assert!(schema.validate(&json!({ "riskPerTrade": 0.5 })).is_ok());
+    }
+
+    #[test]
+    fn test_dependencies_use_defaults_for_missing_side() {
+        let schema = schema();
+        assert!(
+            schema
+                .validate(&json!({ "fastPeriod": 10, "slowPeriod": 30 }))
+                .is_ok()
+        );
+        // slowPeriod 没给，取默认值 20
+        let errors = schema.validate(&json!({ "fastPeriod": 30 })).unwrap_err();
+        assert_eq!(
+            errors,
+            vec![FieldError::new(
+                "fastPeriod",
+                "must be less than slowPeriod (30 >= 20)"
+            )]
+        );
+        assert_eq!(
+            schema.keys(ParamGroup::Risk),
+            vec![
+                "stopLoss",
+                "takeProfit",
+                "riskPerTrade",
+                "maxConcurrentPositions",
+                "positionSize"
+            ]
+        );
+        assert_eq!(
+            serde_json::to_value(&schema.constraints).unwrap(),
+            json!([{ "rule": "lessThan", "left": "fastPeriod", "right": "slowPeriod" }])
+        );
+    }
+}
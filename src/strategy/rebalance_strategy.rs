@@ -0,0 +1,256 @@
+use serde_json::Value;
+
+use crate::engine::backtester::AssetAllocation;
+
+use super::{
+    bar_clock::BarClock,
+    market_data::MarketData,
+    param_schema::{ParamSpec, StrategySchema},
+    position::{PositionType, TradePosition},
+    signal::Signal,
+    strategy_context::StrategyContext,
+    strategy_trait::Strategy,
+};
+
+// 目标权重的来源：按品种名配置的 allocations，或按数据源顺序给出的 weights
+enum Targets {
+    Allocations(Vec<AssetAllocation>),
+    Weights(Vec<f64>),
+    Equal,
+}
+
+// 定期再平衡：BarClock 到点时调回目标权重；任一品种的实际权重偏离目标超过
+// drift_threshold 个百分点时也立即再平衡。第一根 bar 按目标权重建仓。
+pub struct RebalanceStrategy {
+    name: String,
+    clock: BarClock,
+    targets: Targets,
+    drift_threshold: f64,
+    weights: Option<Vec<f64>>,
+    position_type: PositionType,
+}
+
+impl RebalanceStrategy {
+    fn new(name: String, clock: BarClock, targets: Targets, drift_threshold: f64) -> Self {
+        RebalanceStrategy {
+            name,
+            clock,
+            targets,
+            drift_threshold,
+            weights: None,
+            position_type: PositionType::Long,
+        }
+    }
+
+    pub fn with_allocations(
+        name: String,
+        clock: BarClock,
+        allocations: Vec<AssetAllocation>,
+        drift_threshold: f64,
+    ) -> Self {
+        Self::new(
+            name,
+            clock,
+            Targets::Allocations(allocations),
+            drift_threshold,
+        )
+    }
+
+    /// 参数说明，见 StrategySchema
+    pub fn schema() -> StrategySchema {
+        let mut params = vec![
+            ParamSpec::array(
+                "allocations",
+                "目标配置 [{symbol, allocation}]，回测时由资产列表生成",
+            ),
+            ParamSpec::array("weights", "按资产顺序的目标权重；都不给则等权"),
+            ParamSpec::float("driftThreshold", "偏离目标超过该百分比时再平衡")
+                .default(5.0)
+                .min(0.0),
+        ];
+        params.extend(BarClock::params("month"));
+        StrategySchema::new("再平衡：定期或偏离过大时调回目标权重", params)
+    }
+
+    pub fn from_params(params: &Value) -> Box<dyn Strategy> {
+        let allocations = params
+            .get("allocations")
+            .and_then(|v| serde_json::from_value::<Vec<AssetAllocation>>(v.clone()).ok());
+        let weights = params.get("weights").and_then(Value::as_array).map(|arr| {
+            arr.iter()
+                .map(|w| w.as_f64().unwrap_or(0.0))
+                .collect::<Vec<f64>>()
+        });
+        let targets = match (allocations, weights) {
+            (Some(allocations), _) => Targets::Allocations(allocations),
+            (None, Some(weights)) => Targets::Weights(weights),
+            (None, None) => Targets::Equal,
+        };
+        let drift_threshold = params
+            .get("driftThreshold")
+            .and_then(Value::as_f64)
+            .unwrap_or(5.0);
+
+        let clock = BarClock::from_params(params, "month");
+        Box::new(Self::new(
+            "rebalance".to_string(),
+            clock,
+            targets,
+            drift_threshold,
+        ))
+    }
+
+    /// 第一根 bar 到来时把目标解析成与 bars 下标对应的权重；权重和超过 1 时按比例缩放
+    fn resolve(&self, bars: &[MarketData]) -> Vec<f64> {
+        let mut weights = match &self.targets {
+            Targets::Allocations(allocations) => bars
+                .iter()
+                .map(|bar| {
+                    allocations
+                        .iter()
+                        .filter(|a| a.symbol == bar.symbol)
+                        .map(|a| a.allocation as f64 / 100.0)
+                        .sum()
+                })
+                .collect(),
+            Targets::Weights(weights) => {
+                let mut w = weights.clone();
+                w.resize(bars.len(), 0.0);
+                w
+            }
+            Targets::Equal => vec![1.0 / bars.len() as f64; bars.len()],
+        };
+        let total: f64 = weights.iter().sum();
+        if total > 1.0 {
+            weights.iter_mut().for_each(|w| *w /= total);
+        }
+        weights
+    }
+
+    fn drifted(&self, weights: &[f64], ctx: &StrategyContext, bars: &[MarketData]) -> bool {
+        if self.drift_threshold <= 0.0 || ctx.holdings.is_empty() {
+            return false;
+        }
+        let equity = ctx.portfolio_value(bars);
+        if equity <= 0.0 {
+            return false;
+        }
+        weights
+            .iter()
+            .zip(bars)
+            .enumerate()
+            .any(|(i, (target, bar))| {
+                let actual = ctx.holding(i) * bar.close_price / equity;
+                (actual - target).abs() * 100.0 > self.drift_threshold
+            })
+    }
+}
+
+impl Strategy for RebalanceStrategy {
+    // 再平衡需要所有品种的价格，只通过 on_bars 工作
+    fn generate_signal(&mut self, _price: f64, _position: f64) -> Signal {
+        Signal::Hold
+    }
+
+    fn on_bars(&mut self, ctx: &mut StrategyContext, bars: &[MarketData]) -> Signal {
+        let Some(first) = bars.first() else {
+            return Signal::Hold;
+        };
+        let weights = match self.weights.take() {
+            Some(weights) => weights,
+            None => self.resolve(bars),
+        };
+        let due = self.clock.tick(&first.timestamp);
+        let signal = if due || self.drifted(&weights, ctx, bars) {
+            Signal::Rebalance(weights.clone())
+        } else {
+            Signal::Hold
+        };
+        self.weights = Some(weights);
+        signal
+    }
+
+    fn update(&mut self, _market_data: &MarketData, _current_position: &Option<TradePosition>) {}
+
+    fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    // entry_threshold 为偏离阈值（百分点）
+    fn apply_parameters(&mut self, entry_threshold: Option<f64>, _exit_threshold: Option<f64>) {
+        if let Some(threshold) = entry_threshold {
+            self.drift_threshold = threshold;
+        }
+    }
+
+    fn position_type(&self) -> &PositionType {
+        &self.position_type
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, TimeZone, Utc};
+    use serde_json::json;
+
+    use super::*;
+    use crate::strategy::strategy_context::Holding;
+
+    fn bars(day: i64, a: f64, b: f64) -> Vec<MarketData> {
+        let t = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap() + Duration::days(day);
+        vec![
+            MarketData::from_close(t, a).with_symbol("BTC"),
+            MarketData::from_close(t, b).with_symbol("ETH"),
+        ]
+    }
+
+    #[test]
+    fn test_rebalance_resolves_allocations_and_triggers_on_drift() {
+        let mut strategy = RebalanceStrategy::from_params(&json!({
+            "allocations": [
+                { "symbol": "ETH", "allocation": 40 },
+                { "symbol": "BTC", "allocation": 60 }
+            ],
+            "every": "month",
+            "driftThreshold": 10.0
+        }));
+        let mut ctx = StrategyContext::new(1_000.0);
+
+        let first = strategy.on_bars(&mut ctx, &bars(0, 100.0, 10.0));
+        assert_eq!(first, Signal::Rebalance(vec![0.6, 0.4]));
+
+        // 假设已经按 60/40 建仓
+        ctx.cash = 0.0;
+        ctx.holdings = vec![
+            Holding {
+                index: 0,
+                symbol: "BTC".to_string(),
+                quantity: 6.0,
+                cost: 100.0,
+                entry_time: String::new(),
+            },
+            Holding {
+                index: 1,
+                symbol: "ETH".to_string(),
+                quantity: 40.0,
+                cost: 10.0,
+                entry_time: String::new(),
+            },
+        ];
+        // BTC 涨 10%：权重 62.3%，未超过阈值
+        assert_eq!(
+            strategy.on_bars(&mut ctx, &bars(1, 110.0, 10.0)),
+            Signal::Hold
+        );
+        // BTC 翻倍：权重 75%，偏离 15 个百分点
+        assert!(matches!(
+            strategy.on_bars(&mut ctx, &bars(2, 200.0, 10.0)),
+            Signal::Rebalance(_)
+        ));
+        // 下个月初按日历再平衡
+        assert!(matches!(
+            strategy.on_bars(&mut ctx, &bars(31, 100.0, 10.0)),
+            Signal::Rebalance(_)
+        ));
+    }
+}
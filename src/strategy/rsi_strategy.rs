@@ -0,0 +1,348 @@
+use std::{collections::VecDeque, error::Error};
+
+use serde::Deserialize;
+use serde_json::{Value, json};
+
+use crate::indicators::{indicator::Indicator, overlay::named_values, rsi_indicator::RsiIndicator};
+
+use super::{
+    checkpoint::{Checkpoint, load_indicator},
+    market_data::MarketData,
+    param_schema::{ParamSpec, RISK, StrategySchema, position_type},
+    position::{PositionType, TradePosition},
+    signal::Signal,
+    strategy_trait::Strategy,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RsiExitMode {
+    // 回到中线平仓
+    Midline,
+    // 触及对侧阈值平仓
+    OppositeBand,
+}
+
+impl RsiExitMode {
+    pub fn parse(s: &str) -> Self {
+        match s {
+            "opposite" | "opposite-band" => RsiExitMode::OppositeBand,
+            _ => RsiExitMode::Midline,
+        }
+    }
+}
+
+pub struct RsiStrategy {
+    name: String,
+    rsi: RsiIndicator,
+    overbought: f64,
+    oversold: f64,
+    midline: f64,
+    exit_mode: RsiExitMode,
+    divergence: bool,
+    divergence_lookback: usize,
+    // (price, rsi) of the previous bars, newest last
+    history: VecDeque<(f64, f64)>,
+    position_type: PositionType,
+}
+
+impl RsiStrategy {
+    pub fn new(name: String, period: usize, overbought: f64, oversold: f64) -> Self {
+        RsiStrategy {
+            name,
+            rsi: RsiIndicator::new(period),
+            overbought,
+            oversold,
+            midline: 50.0,
+            exit_mode: RsiExitMode::Midline,
+            divergence: false,
+            divergence_lookback: 14,
+            history: VecDeque::new(),
+            position_type: PositionType::Both,
+        }
+    }
+
+    /// 参数说明，见 StrategySchema
+    pub fn schema() -> StrategySchema {
+        StrategySchema::new(
+            "RSI：超卖做多、超买做空，可选背离确认",
+            vec![
+                ParamSpec::int("rsiPeriod", "RSI 周期").default(14).min(1.0),
+                ParamSpec::float("overbought", "超买阈值")
+                    .default(70.0)
+                    .range(0.0, 100.0),
+                ParamSpec::float("oversold", "超卖阈值")
+                    .default(30.0)
+                    .range(0.0, 100.0),
+                ParamSpec::float("midline", "中线，midline 离场方式用")
+                    .default(50.0)
+                    .range(0.0, 100.0),
+                ParamSpec::choice(
+                    "exitMode",
+                    &["midline", "opposite", "opposite-band"],
+                    "离场方式",
+                ),
+                ParamSpec::bool("divergence", "只在出现背离时入场").default(false),
+                ParamSpec::int("divergenceLookback", "寻找背离的 bar 数")
+                    .default(14)
+                    .min(2.0),
+                position_type(),
+            ],
+        )
+        .with_risk(RISK)
+        .less_than("oversold", "overbought")
+    }
+
+    pub fn from_params(params: &Value) -> Box<dyn Strategy> {
+        let position_type = match params.get("positionType").and_then(Value::as_str) {
+            Some("long") => PositionType::Long,
+            Some("short") => PositionType::Short,
+            _ => PositionType::Both,
+        };
+
+        let period = params
+            .get("rsiPeriod")
+            .and_then(Value::as_u64)
+            .unwrap_or(14) as usize;
+        let overbought = params
+            .get("overbought")
+            .and_then(Value::as_f64)
+            .unwrap_or(70.0);
+        let oversold = params
+            .get("oversold")
+            .and_then(Value::as_f64)
+            .unwrap_or(30.0);
+        let midline = params
+            .get("midline")
+            .and_then(Value::as_f64)
+            .unwrap_or(50.0);
+        let exit_mode = RsiExitMode::parse(
+            params
+                .get("exitMode")
+                .and_then(Value::as_str)
+                .unwrap_or("midline"),
+        );
+        let divergence = params
+            .get("divergence")
+            .and_then(Value::as_bool)
+            .unwrap_or(false);
+        let divergence_lookback = params
+            .get("divergenceLookback")
+            .and_then(Value::as_u64)
+            .unwrap_or(14) as usize;
+
+        let mut strategy = Self::new("rsi".to_string(), period, overbought, oversold);
+        strategy.midline = midline;
+        strategy.exit_mode = exit_mode;
+        strategy.divergence = divergence;
+        strategy.divergence_lookback = divergence_lookback.max(1);
+        strategy.position_type = position_type;
+        Box::new(strategy)
+    }
+
+    /// `sub_type` selects the exit mode ("midline" or "opposite").
+    pub fn create(name: String, sub_type: &str, period: usize) -> Self {
+        let mut strategy = Self::new(name, period, 70.0, 30.0);
+        strategy.exit_mode = RsiExitMode::parse(sub_type);
+        strategy
+    }
+
+    pub fn rsi(&self) -> Option<f64> {
+        self.rsi.value()
+    }
+
+    // 价格创新低而 RSI 未创新低
+    fn bullish_divergence(&self, price: f64, rsi: f64) -> bool {
+        self.history
+            .iter()
+            .min_by(|a, b| a.0.total_cmp(&b.0))
+            .is_some_and(|&(low, low_rsi)| price < low && rsi > low_rsi)
+    }
+
+    // 价格创新高而 RSI 未创新高
+    fn bearish_divergence(&self, price: f64, rsi: f64) -> bool {
+        self.history
+            .iter()
+            .max_by(|a, b| a.0.total_cmp(&b.0))
+            .is_some_and(|&(high, high_rsi)| price > high && rsi < high_rsi)
+    }
+
+    fn signal_for(&self, price: f64, rsi: f64, position: f64) -> Signal {
+        // 平仓优先
+        let (long_exit, short_exit) = match self.exit_mode {
+            RsiExitMode::Midline => (rsi >= self.midline, rsi <= self.midline),
+            RsiExitMode::OppositeBand => (rsi >= self.overbought, rsi <= self.oversold),
+        };
+        if position > 0.0 && long_exit {
+            return Signal::Exit;
+        }
+        if position < 0.0 && short_exit {
+            return Signal::Exit;
+        }
+
+        // 入场
+        if rsi <= self.oversold
+            && position <= 0.0
+            && (!self.divergence || self.bullish_divergence(price, rsi))
+        {
+            return Signal::EnterLong(price);
+        }
+        if rsi >= self.overbought
+            && position >= 0.0
+            && (!self.divergence || self.bearish_divergence(price, rsi))
+        {
+            return Signal::EnterShort(price);
+        }
+        Signal::Hold
+    }
+
+    fn observe(&mut self, price: f64) -> Option<f64> {
+        self.rsi.update(price);
+        self.rsi.value()
+    }
+
+    fn remember(&mut self, price: f64, rsi: f64) {
+        self.history.push_back((price, rsi));
+        while self.history.len() > self.divergence_lookback {
+            self.history.pop_front();
+        }
+    }
+}
+
+impl Strategy for RsiStrategy {
+    fn generate_signal(&mut self, price: f64, position: f64) -> Signal {
+        let Some(rsi) = self.observe(price) else {
+            return Signal::Hold;
+        };
+        let signal = self.signal_for(price, rsi, position);
+        self.remember(price, rsi);
+        signal
+    }
+
+    fn update(&mut self, market_data: &MarketData, _current_position: &Option<TradePosition>) {
+        let price = market_data.close_price;
+        if let Some(rsi) = self.observe(price) {
+            self.remember(price, rsi);
+        }
+    }
+
+    fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    // entry_threshold 为超卖线（超买线取 100 - entry），exit_threshold 为中线
+    fn apply_parameters(&mut self, entry_threshold: Option<f64>, exit_threshold: Option<f64>) {
+        if let Some(entry) = entry_threshold {
+            self.oversold = entry;
+            self.overbought = 100.0 - entry;
+        }
+        if let Some(exit) = exit_threshold {
+            self.midline = exit;
+        }
+    }
+
+    fn chart_values(&self) -> Vec<(String, Option<f64>)> {
+        named_values("rsi", &self.rsi)
+    }
+
+    fn checkpoint(&mut self) -> Option<&mut dyn Checkpoint> {
+        Some(self)
+    }
+
+    fn position_type(&self) -> &PositionType {
+        &self.position_type
+    }
+}
+
+impl Checkpoint for RsiStrategy {
+    fn save_state(&self) -> Option<Value> {
+        Some(json!({
+            "rsi": self.rsi.save_state()?,
+            "history": self.history,
+        }))
+    }
+
+    fn load_state(&mut self, state: &Value) -> Result<(), Box<dyn Error>> {
+        load_indicator(&mut self.rsi, state, "rsi")?;
+        self.history = VecDeque::deserialize(&state["history"])?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn run(strategy: &mut dyn Strategy, prices: &[f64]) -> Vec<Signal> {
+        let mut position = 0.0;
+        prices
+            .iter()
+            .map(|&p| {
+                let signal = strategy.generate_signal(p, position);
+                match signal {
+                    Signal::EnterLong(_) => position = 1.0,
+                    Signal::EnterShort(_) => position = -1.0,
+                    Signal::Exit => position = 0.0,
+                    _ => {}
+                }
+                signal
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_rsi_oversold_entry_and_midline_exit() {
+        let mut strategy = RsiStrategy::from_params(&json!({
+            "rsiPeriod": 3,
+            "positionType": "long"
+        }));
+        let prices = [10.0, 9.0, 8.0, 7.0, 6.0, 7.0, 8.0, 9.0];
+        let signals = run(strategy.as_mut(), &prices);
+
+        assert!(signals[..3].iter().all(|s| *s == Signal::Hold));
+        assert_eq!(signals[3], Signal::EnterLong(7.0));
+        assert_eq!(signals[5], Signal::Hold);
+        assert_eq!(signals[6], Signal::Exit);
+        assert_eq!(strategy.name(), "rsi");
+    }
+
+    #[test]
+    fn test_rsi_opposite_band_exit_holds_through_midline() {
+        let mut strategy = RsiStrategy::create("rsi".to_string(), "opposite", 3);
+        let prices = [10.0, 9.0, 8.0, 7.0, 6.0, 7.0, 8.0, 9.0, 10.0, 11.0];
+        let signals = run(&mut strategy, &prices);
+
+        assert_eq!(signals[3], Signal::EnterLong(7.0));
+        let exit = signals.iter().position(|s| *s == Signal::Exit).unwrap();
+        assert!(
+            exit > 5,
+            "midline must not close the trade, exited at {}",
+            exit
+        );
+    }
+
+    #[test]
+    fn test_rsi_divergence_filter_requires_higher_rsi_low() {
+        let prices = [
+            20.0, 19.0, 18.0, 17.0, 16.0, 17.5, 17.0, 16.5, 16.2, 15.9, 16.5,
+        ];
+        let params = json!({ "rsiPeriod": 3, "positionType": "long", "exitMode": "opposite" });
+        let mut plain = RsiStrategy::from_params(&params);
+        let mut params = params;
+        params["divergence"] = json!(true);
+        params["divergenceLookback"] = json!(8);
+        let mut filtered = RsiStrategy::from_params(&params);
+
+        let plain_signals = run(plain.as_mut(), &prices);
+        let filtered_signals = run(filtered.as_mut(), &prices);
+
+        // 第一次下跌没有背离，不入场；价格新低但 RSI 抬高时才入场
+        assert_eq!(plain_signals[3], Signal::EnterLong(17.0));
+        let first = filtered_signals
+            .iter()
+            .position(|s| matches!(s, Signal::EnterLong(_)))
+            .unwrap();
+        assert_eq!(filtered_signals[first], Signal::EnterLong(15.9));
+    }
+}
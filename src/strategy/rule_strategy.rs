@@ -0,0 +1,279 @@
+use std::error::Error;
+
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+
+use crate::indicators::registry::IndicatorRegistry;
+
+use super::{
+    market_data::MarketData,
+    param_schema::{FieldError, ParamSpec, RISK, StrategySchema, position_type},
+    position::{PositionType, TradePosition},
+    rules::{Action, RuleError, RuleProgram, RuleSet},
+    signal::Signal,
+    strategy_trait::Strategy,
+};
+
+// 用户在策略构建器里拼出来的规则，例如
+// {"when": {"and": [{"crossesAbove": [{"indicator": "ema", "params": [12]}, {"indicator": "ema", "params": [26]}]},
+//                   {"lt": [{"indicator": "rsi", "params": [14]}, 70]}]},
+//  "then": "enterLong"}
+// 每根 bar 按顺序检查规则，第一条成立且在当前持仓下可执行的规则生效。
+pub struct RuleStrategy {
+    name: String,
+    program: RuleProgram,
+    position_type: PositionType,
+}
+
+impl RuleStrategy {
+    pub fn compile(params: &Value) -> Result<Self, RuleError> {
+        let rule_set = RuleSet::parse(params)?;
+        let program = RuleProgram::compile(&rule_set, &IndicatorRegistry::new())?;
+        let position_type = match params.get("positionType").and_then(Value::as_str) {
+            Some("long") => PositionType::Long,
+            Some("short") => PositionType::Short,
+            _ => PositionType::Both,
+        };
+        Ok(RuleStrategy {
+            name: "rules".to_string(),
+            program,
+            position_type,
+        })
+    }
+
+    /// 参数说明，见 StrategySchema；规则列表能否编译也在这里检查
+    pub fn schema() -> StrategySchema {
+        StrategySchema::new(
+            "规则：按 when / then 规则列表交易，格式见 strategy::rules",
+            vec![ParamSpec::array("rules", "规则列表"), position_type()],
+        )
+        .with_risk(RISK)
+        .with_check(|params| match Self::compile(params) {
+            Ok(_) => Vec::new(),
+            Err(e) => vec![FieldError::new(e.path, e.message)],
+        })
+    }
+
+    /// 规则无法编译时返回错误，由调用方按字段报给用户
+    pub fn from_params(params: &Value) -> Result<Box<dyn Strategy>, Box<dyn Error>> {
+        Ok(Box::new(Self::compile(params)?))
+    }
+}
+
+impl Strategy for RuleStrategy {
+    fn generate_signal(&mut self, price: f64, position: f64) -> Signal {
+        self.on_bar(
+            &MarketData::from_close(DateTime::<Utc>::MIN_UTC, price),
+            position,
+        )
+    }
+
+    fn on_bar(&mut self, bar: &MarketData, position: f64) -> Signal {
+        self.program.update(bar);
+        for action in self.program.fired() {
+            match action {
+                Action::EnterLong if position == 0.0 && self.supports_long() => {
+                    return Signal::EnterLong(bar.close_price);
+                }
+                Action::EnterShort if position == 0.0 && self.supports_short() => {
+                    return Signal::EnterShort(bar.close_price);
+                }
+                // 反向开仓先平掉现有仓位
+                Action::EnterLong if position < 0.0 => return Signal::Exit,
+                Action::EnterShort if position > 0.0 => return Signal::Exit,
+                Action::Exit if position != 0.0 => return Signal::Exit,
+                _ => {}
+            }
+        }
+        Signal::Hold
+    }
+
+    fn update(&mut self, market_data: &MarketData, _current_position: &Option<TradePosition>) {
+        self.program.update(market_data);
+    }
+
+    fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    fn apply_parameters(&mut self, _entry_threshold: Option<f64>, _exit_threshold: Option<f64>) {}
+
+    fn position_type(&self) -> &PositionType {
+        &self.position_type
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn ema_cross_rules() -> Value {
+        json!({
+            "positionType": "long",
+            "rules": [
+                {
+                    "when": { "and": [
+                        { "crossesAbove": [
+                            { "indicator": "ema", "params": [2] },
+                            { "indicator": "ema", "params": [4] }
+                        ] },
+                        { "lt": [{ "indicator": "rsi", "params": [3] }, 101] }
+                    ] },
+                    "then": "enterLong"
+                },
+                {
+                    "when": { "crossesBelow": [{ "price": "close" }, { "indicator": "sma", "params": [3] }] },
+                    "then": "exit"
+                }
+            ]
+        })
+    }
+
+    #[test]
+    fn test_rules_enter_on_cross_and_exit_below_sma() {
+        let mut strategy = RuleStrategy::from_params(&ema_cross_rules()).unwrap();
+        let prices = [10.0, 9.0, 8.0, 7.0, 8.0, 10.0, 12.0, 13.0, 9.0];
+        let mut position = 0.0;
+        let mut signals = Vec::new();
+        for &p in &prices {
+            let signal = strategy.generate_signal(p, position);
+            match signal {
+                Signal::EnterLong(_) => position = 1.0,
+                Signal::Exit => position = 0.0,
+                _ => {}
+            }
+            signals.push(signal);
+        }
+
+        let entry = signals
+            .iter()
+            .position(|s| matches!(s, Signal::EnterLong(_)))
+            .expect("fast ema crosses above slow ema on the rebound");
+        // RSI(3) 要 4 根 bar 才有值，在此之前 AND 不成立
+        assert!(entry >= 3);
+        assert_eq!(signals.last(), Some(&Signal::Exit));
+        assert_eq!(signals.iter().filter(|s| **s == Signal::Exit).count(), 1);
+    }
+
+    #[test]
+    fn test_within_and_offset_look_back() {
+        let mut strategy = RuleStrategy::from_params(&json!({
+            "rules": [{
+                "when": { "and": [
+                    { "within": { "bars": 3, "condition": { "gt": [{ "price": "close" }, 100] } } },
+                    { "lt": [{ "price": "close" }, { "price": "close", "offset": 1 }] }
+                ] },
+                "then": "enterShort"
+            }]
+        }))
+        .unwrap();
+        let signals: Vec<Signal> = [95.0, 101.0, 99.0, 98.0, 97.0, 96.0]
+            .iter()
+            .map(|&p| strategy.generate_signal(p, 0.0))
+            .collect();
+        // 101 之后的两根下跌 bar 仍在 3 根窗口内
+        assert_eq!(
+            signals,
+            vec![
+                Signal::Hold,
+                Signal::Hold,
+                Signal::EnterShort(99.0),
+                Signal::EnterShort(98.0),
+                Signal::Hold,
+                Signal::Hold
+            ]
+        );
+    }
+
+    #[test]
+    fn test_series_operand_composes_indicators() {
+        let crossing = |left: Value, right: Value| {
+            RuleStrategy::from_params(&json!({
+                "rules": [{ "when": { "crossesAbove": [left, right] }, "then": "enterLong" }]
+            }))
+            .unwrap()
+        };
+        let mut by_indicator = crossing(
+            json!({ "indicator": "ema", "params": [2] }),
+            json!({ "indicator": "ema", "params": [4] }),
+        );
+        // 快慢线之差上穿 0 与快线上穿慢线等价
+        let mut by_series = crossing(
+            json!({ "series": {
+                "type": "sub",
+                "left": { "type": "ema", "period": 2 },
+                "right": { "type": "ema", "period": 4, "source": "close" }
+            } }),
+            json!(0),
+        );
+        let prices = [10.0, 9.0, 8.0, 7.0, 8.0, 10.0, 12.0, 13.0, 9.0, 11.0];
+        let signals: Vec<(Signal, Signal)> = prices
+            .iter()
+            .map(|&p| {
+                (
+                    by_indicator.generate_signal(p, 0.0),
+                    by_series.generate_signal(p, 0.0),
+                )
+            })
+            .collect();
+        assert!(
+            signals
+                .iter()
+                .any(|(a, _)| matches!(a, Signal::EnterLong(_)))
+        );
+        assert!(signals.iter().all(|(a, b)| a == b));
+
+        let err = RuleStrategy::compile(&json!({ "rules": [
+            { "when": { "gt": [{ "series": { "type": "div", "left": "close",
+                "right": { "type": "sma", "length": 5 } } }, 1] }, "then": "exit" }
+        ] }))
+        .err()
+        .unwrap();
+        assert_eq!(err.path, "rules[0].when.gt[0].series.right.length");
+    }
+
+    #[test]
+    fn test_validation_errors_point_at_the_node() {
+        let err = |params: Value| RuleStrategy::compile(&params).err().unwrap();
+
+        let unknown = err(json!({ "rules": [
+            { "when": { "gt": [{ "price": "close" }, 1] }, "then": "exit" },
+            { "when": { "or": [
+                { "gt": [{ "price": "close" }, 1] },
+                { "lt": [{ "indicator": "kdj", "params": [9] }, 20] }
+            ] }, "then": "exit" }
+        ] }));
+        assert_eq!(unknown.path, "rules[1].when.or[1].lt[0].indicator");
+        assert!(unknown.message.contains("kdj"));
+
+        let arity = err(json!({ "rules": [
+            { "when": { "not": { "crossesAbove": [{ "indicator": "ema", "params": [12, 26] }, 0] } }, "then": "exit" }
+        ] }));
+        assert_eq!(arity.path, "rules[0].when.not.crossesAbove[0].params");
+
+        let operator = err(json!({ "rules": [
+            { "when": { "and": [{ "between": [1, 2] }] }, "then": "exit" }
+        ] }));
+        assert_eq!(operator.path, "rules[0].when.and[0].between");
+
+        let action = err(json!({ "rules": [
+            { "when": { "gt": [1, 0] }, "then": "buy" }
+        ] }));
+        assert_eq!(action.path, "rules[0].then");
+        assert_eq!(err(json!({ "rules": [] })).path, "rules");
+
+        // 构建时同样报错，而不是返回一直观望的空规则
+        assert!(RuleStrategy::from_params(&json!({ "rules": [] })).is_err());
+    }
+
+    #[test]
+    fn test_rule_set_round_trips_through_json() {
+        let params = ema_cross_rules();
+        let rule_set = RuleSet::parse(&params).unwrap();
+        let json = serde_json::to_value(&rule_set).unwrap();
+        assert_eq!(RuleSet::parse(&json).unwrap(), rule_set);
+        assert_eq!(json["rules"][0]["then"], "enterLong");
+    }
+}
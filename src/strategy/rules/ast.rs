@@ -0,0 +1,292 @@
+use std::fmt;
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::indicators::spec::{IndicatorSpec, PriceSource, join_path};
+
+/// 规则校验/编译错误，path 指向出错的节点，如 `rules[0].when.and[1].lt[0].indicator`
+#[derive(Debug, Clone, PartialEq)]
+pub struct RuleError {
+    pub path: String,
+    pub message: String,
+}
+
+impl RuleError {
+    pub fn new(path: &str, message: impl Into<String>) -> Self {
+        Self {
+            path: path.to_string(),
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for RuleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.path, self.message)
+    }
+}
+
+impl std::error::Error for RuleError {}
+
+fn is_zero(n: &usize) -> bool {
+    *n == 0
+}
+
+/// 比较的一边：常数、K 线价格、指标或任意指标描述（见 `IndicatorSpec`）；
+/// offset 表示取 offset 根 bar 之前的值
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum Operand {
+    Const(f64),
+    Price {
+        price: PriceSource,
+        #[serde(skip_serializing_if = "is_zero")]
+        offset: usize,
+    },
+    Indicator {
+        indicator: String,
+        params: Vec<f64>,
+        #[serde(skip_serializing_if = "is_zero")]
+        offset: usize,
+    },
+    Series {
+        series: IndicatorSpec,
+        #[serde(skip_serializing_if = "is_zero")]
+        offset: usize,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Condition {
+    CrossesAbove(Operand, Operand),
+    CrossesBelow(Operand, Operand),
+    Gt(Operand, Operand),
+    Gte(Operand, Operand),
+    Lt(Operand, Operand),
+    Lte(Operand, Operand),
+    And(Vec<Condition>),
+    Or(Vec<Condition>),
+    Not(Box<Condition>),
+    /// 最近 bars 根 bar（含当前）内任意一根满足
+    Within {
+        bars: usize,
+        condition: Box<Condition>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Action {
+    EnterLong,
+    EnterShort,
+    Exit,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Rule {
+    pub when: Condition,
+    pub then: Action,
+}
+
+/// 存在 trade_strategy.parameters 的 "rules" 键下，按顺序求值
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RuleSet {
+    pub rules: Vec<Rule>,
+}
+
+impl RuleSet {
+    pub fn parse(params: &Value) -> Result<Self, RuleError> {
+        let rules = params
+            .get("rules")
+            .ok_or_else(|| RuleError::new("rules", "missing rule list"))?
+            .as_array()
+            .ok_or_else(|| RuleError::new("rules", "expected an array of rules"))?;
+        if rules.is_empty() {
+            return Err(RuleError::new("rules", "at least one rule is required"));
+        }
+        let rules = rules
+            .iter()
+            .enumerate()
+            .map(|(i, rule)| Rule::parse(rule, &format!("rules[{}]", i)))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(RuleSet { rules })
+    }
+}
+
+impl Rule {
+    fn parse(value: &Value, path: &str) -> Result<Self, RuleError> {
+        let when = value
+            .get("when")
+            .ok_or_else(|| RuleError::new(path, "missing `when`"))?;
+        let then = value
+            .get("then")
+            .ok_or_else(|| RuleError::new(path, "missing `then`"))?;
+        let then_path = format!("{}.then", path);
+        let then = match then.as_str() {
+            Some("enterLong") => Action::EnterLong,
+            Some("enterShort") => Action::EnterShort,
+            Some("exit") => Action::Exit,
+            _ => {
+                return Err(RuleError::new(
+                    &then_path,
+                    format!(
+                        "expected \"enterLong\", \"enterShort\" or \"exit\", got {}",
+                        then
+                    ),
+                ));
+            }
+        };
+        Ok(Rule {
+            when: Condition::parse(when, &format!("{}.when", path))?,
+            then,
+        })
+    }
+}
+
+impl Condition {
+    pub fn parse(value: &Value, path: &str) -> Result<Self, RuleError> {
+        let (op, arg) = match value.as_object() {
+            Some(obj) if obj.len() == 1 => obj.iter().next().unwrap(),
+            _ => {
+                return Err(RuleError::new(
+                    path,
+                    "expected an object with exactly one operator",
+                ));
+            }
+        };
+        let path = format!("{}.{}", path, op);
+
+        let pair = |arg: &Value| -> Result<(Operand, Operand), RuleError> {
+            match arg.as_array().map(Vec::as_slice) {
+                Some([a, b]) => Ok((
+                    Operand::parse(a, &format!("{}[0]", path))?,
+                    Operand::parse(b, &format!("{}[1]", path))?,
+                )),
+                _ => Err(RuleError::new(&path, "expected [left, right]")),
+            }
+        };
+        let list = |arg: &Value| -> Result<Vec<Condition>, RuleError> {
+            match arg.as_array() {
+                Some(items) if !items.is_empty() => items
+                    .iter()
+                    .enumerate()
+                    .map(|(i, c)| Condition::parse(c, &format!("{}[{}]", path, i)))
+                    .collect(),
+                _ => Err(RuleError::new(
+                    &path,
+                    "expected a non-empty array of conditions",
+                )),
+            }
+        };
+
+        let condition = match op.as_str() {
+            "crossesAbove" => pair(arg).map(|(a, b)| Condition::CrossesAbove(a, b))?,
+            "crossesBelow" => pair(arg).map(|(a, b)| Condition::CrossesBelow(a, b))?,
+            "gt" => pair(arg).map(|(a, b)| Condition::Gt(a, b))?,
+            "gte" => pair(arg).map(|(a, b)| Condition::Gte(a, b))?,
+            "lt" => pair(arg).map(|(a, b)| Condition::Lt(a, b))?,
+            "lte" => pair(arg).map(|(a, b)| Condition::Lte(a, b))?,
+            "and" => Condition::And(list(arg)?),
+            "or" => Condition::Or(list(arg)?),
+            "not" => Condition::Not(Box::new(Condition::parse(arg, &path)?)),
+            "within" => {
+                let bars = arg
+                    .get("bars")
+                    .and_then(Value::as_u64)
+                    .filter(|&n| n > 0)
+                    .ok_or_else(|| {
+                        RuleError::new(&format!("{}.bars", path), "expected an integer > 0")
+                    })?;
+                let condition = arg
+                    .get("condition")
+                    .ok_or_else(|| RuleError::new(&path, "missing `condition`"))?;
+                Condition::Within {
+                    bars: bars as usize,
+                    condition: Box::new(Condition::parse(
+                        condition,
+                        &format!("{}.condition", path),
+                    )?),
+                }
+            }
+            other => {
+                return Err(RuleError::new(
+                    &path,
+                    format!("unknown operator `{}`", other),
+                ));
+            }
+        };
+        Ok(condition)
+    }
+}
+
+impl Operand {
+    pub fn parse(value: &Value, path: &str) -> Result<Self, RuleError> {
+        if let Some(n) = value.as_f64() {
+            return Ok(Operand::Const(n));
+        }
+        let Some(obj) = value.as_object() else {
+            return Err(RuleError::new(
+                path,
+                "expected a number, {\"indicator\": ...}, {\"price\": ...} or {\"series\": ...}",
+            ));
+        };
+        let offset = match obj.get("offset") {
+            None => 0,
+            Some(v) => v.as_u64().ok_or_else(|| {
+                RuleError::new(&format!("{}.offset", path), "expected an integer >= 0")
+            })? as usize,
+        };
+
+        if let Some(field) = obj.get("price") {
+            let price = field.as_str().and_then(PriceSource::parse).ok_or_else(|| {
+                RuleError::new(
+                    &format!("{}.price", path),
+                    format!(
+                        "expected one of {}",
+                        PriceSource::ALL.map(|p| p.name()).join(", ")
+                    ),
+                )
+            })?;
+            return Ok(Operand::Price { price, offset });
+        }
+
+        if let Some(series) = obj.get("series") {
+            let series = IndicatorSpec::parse(series).map_err(|e| {
+                RuleError::new(&join_path(&format!("{}.series", path), &e.path), e.message)
+            })?;
+            return Ok(Operand::Series { series, offset });
+        }
+
+        let indicator = obj
+            .get("indicator")
+            .ok_or_else(|| RuleError::new(path, "expected `indicator`, `price` or `series`"))?
+            .as_str()
+            .ok_or_else(|| RuleError::new(&format!("{}.indicator", path), "expected a name"))?
+            .to_string();
+        let params = match obj.get("params") {
+            None => Vec::new(),
+            Some(v) => {
+                let params_path = format!("{}.params", path);
+                let items = v
+                    .as_array()
+                    .ok_or_else(|| RuleError::new(&params_path, "expected an array"))?;
+                items
+                    .iter()
+                    .enumerate()
+                    .map(|(i, p)| {
+                        p.as_f64().ok_or_else(|| {
+                            RuleError::new(&format!("{}[{}]", params_path, i), "expected a number")
+                        })
+                    })
+                    .collect::<Result<Vec<_>, _>>()?
+            }
+        };
+        Ok(Operand::Indicator {
+            indicator,
+            params,
+            offset,
+        })
+    }
+}
@@ -0,0 +1,5 @@
+mod ast;
+mod program;
+
+pub use ast::{Action, Condition, Operand, Rule, RuleError, RuleSet};
+pub use program::RuleProgram;
@@ -0,0 +1,310 @@
+use std::collections::{HashMap, VecDeque};
+
+use crate::{
+    indicators::{
+        indicator::Indicator,
+        registry::IndicatorRegistry,
+        spec::{IndicatorSpec, join_path},
+    },
+    strategy::market_data::MarketData,
+};
+
+use super::ast::{Action, Condition, Operand, RuleError, RuleSet};
+
+// 一条被规则引用的序列，history[0] 为当前 bar 的值；K 线价格也是一个指标
+struct Series {
+    source: Box<dyn Indicator>,
+    history: VecDeque<Option<f64>>,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Ref {
+    Const(f64),
+    Series { id: usize, offset: usize },
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Cmp {
+    Gt,
+    Gte,
+    Lt,
+    Lte,
+}
+
+enum Node {
+    Compare(Cmp, Ref, Ref),
+    Cross { above: bool, left: Ref, right: Ref },
+    And(Vec<Node>),
+    Or(Vec<Node>),
+    Not(Box<Node>),
+    Within(usize, Box<Node>),
+}
+
+/// 编译后的规则：同名同参数的指标只算一次，每根 bar 先 update 再 fired
+pub struct RuleProgram {
+    series: Vec<Series>,
+    rules: Vec<(Node, Action)>,
+    depth: usize,
+}
+
+impl RuleProgram {
+    pub fn compile(rule_set: &RuleSet, registry: &IndicatorRegistry) -> Result<Self, RuleError> {
+        let mut compiler = Compiler {
+            registry,
+            series: Vec::new(),
+            keys: HashMap::new(),
+        };
+        let rules = rule_set
+            .rules
+            .iter()
+            .enumerate()
+            .map(|(i, rule)| {
+                let node = compiler.condition(&rule.when, &format!("rules[{}].when", i))?;
+                Ok((node, rule.then))
+            })
+            .collect::<Result<Vec<_>, RuleError>>()?;
+        let depth = rules
+            .iter()
+            .map(|(node, _)| node.depth())
+            .max()
+            .unwrap_or(1);
+
+        Ok(RuleProgram {
+            series: compiler.series,
+            rules,
+            depth,
+        })
+    }
+
+    pub fn update(&mut self, bar: &MarketData) {
+        for series in &mut self.series {
+            series.source.update_bar(bar);
+            series.history.push_front(series.source.value());
+            series.history.truncate(self.depth);
+        }
+    }
+
+    /// 当前 bar 上条件成立的规则动作，按规则顺序
+    pub fn fired(&self) -> Vec<Action> {
+        self.rules
+            .iter()
+            .filter(|(node, _)| self.eval(node, 0) == Some(true))
+            .map(|(_, action)| *action)
+            .collect()
+    }
+
+    fn value(&self, r: Ref, lag: usize) -> Option<f64> {
+        match r {
+            Ref::Const(v) => Some(v),
+            Ref::Series { id, offset } => {
+                self.series[id].history.get(offset + lag).copied().flatten()
+            }
+        }
+    }
+
+    // 三值逻辑：数据还没就绪时为 None，NOT 不会把预热期变成 true
+    fn eval(&self, node: &Node, lag: usize) -> Option<bool> {
+        match node {
+            Node::Compare(cmp, left, right) => {
+                let (a, b) = (self.value(*left, lag)?, self.value(*right, lag)?);
+                Some(match cmp {
+                    Cmp::Gt => a > b,
+                    Cmp::Gte => a >= b,
+                    Cmp::Lt => a < b,
+                    Cmp::Lte => a <= b,
+                })
+            }
+            Node::Cross { above, left, right } => {
+                let now = self.value(*left, lag)? - self.value(*right, lag)?;
+                let before = self.value(*left, lag + 1)? - self.value(*right, lag + 1)?;
+                Some(if *above {
+                    before <= 0.0 && now > 0.0
+                } else {
+                    before >= 0.0 && now < 0.0
+                })
+            }
+            Node::And(nodes) => {
+                let values: Vec<Option<bool>> = nodes.iter().map(|n| self.eval(n, lag)).collect();
+                if values.contains(&Some(false)) {
+                    Some(false)
+                } else if values.contains(&None) {
+                    None
+                } else {
+                    Some(true)
+                }
+            }
+            Node::Or(nodes) => {
+                let values: Vec<Option<bool>> = nodes.iter().map(|n| self.eval(n, lag)).collect();
+                if values.contains(&Some(true)) {
+                    Some(true)
+                } else if values.contains(&None) {
+                    None
+                } else {
+                    Some(false)
+                }
+            }
+            Node::Not(node) => self.eval(node, lag).map(|v| !v),
+            Node::Within(bars, node) => {
+                let values: Vec<Option<bool>> =
+                    (0..*bars).map(|k| self.eval(node, lag + k)).collect();
+                if values.contains(&Some(true)) {
+                    Some(true)
+                } else if values.contains(&None) {
+                    None
+                } else {
+                    Some(false)
+                }
+            }
+        }
+    }
+}
+
+impl Node {
+    // 求值时需要回看的 bar 数（含当前）
+    fn depth(&self) -> usize {
+        let offset = |r: &Ref| match r {
+            Ref::Const(_) => 0,
+            Ref::Series { offset, .. } => *offset,
+        };
+        match self {
+            Node::Compare(_, left, right) => offset(left).max(offset(right)) + 1,
+            Node::Cross { left, right, .. } => offset(left).max(offset(right)) + 2,
+            Node::And(nodes) | Node::Or(nodes) => nodes.iter().map(Node::depth).max().unwrap_or(1),
+            Node::Not(node) => node.depth(),
+            Node::Within(bars, node) => node.depth() + bars - 1,
+        }
+    }
+}
+
+struct Compiler<'a> {
+    registry: &'a IndicatorRegistry,
+    series: Vec<Series>,
+    keys: HashMap<String, usize>,
+}
+
+impl Compiler<'_> {
+    fn condition(&mut self, condition: &Condition, path: &str) -> Result<Node, RuleError> {
+        let node = match condition {
+            Condition::Gt(a, b) => self.compare(Cmp::Gt, a, b, &format!("{}.gt", path))?,
+            Condition::Gte(a, b) => self.compare(Cmp::Gte, a, b, &format!("{}.gte", path))?,
+            Condition::Lt(a, b) => self.compare(Cmp::Lt, a, b, &format!("{}.lt", path))?,
+            Condition::Lte(a, b) => self.compare(Cmp::Lte, a, b, &format!("{}.lte", path))?,
+            Condition::CrossesAbove(a, b) | Condition::CrossesBelow(a, b) => {
+                let above = matches!(condition, Condition::CrossesAbove(..));
+                let op = if above {
+                    "crossesAbove"
+                } else {
+                    "crossesBelow"
+                };
+                Node::Cross {
+                    above,
+                    left: self.operand(a, &format!("{}.{}[0]", path, op))?,
+                    right: self.operand(b, &format!("{}.{}[1]", path, op))?,
+                }
+            }
+            Condition::And(items) | Condition::Or(items) => {
+                let op = if matches!(condition, Condition::And(_)) {
+                    "and"
+                } else {
+                    "or"
+                };
+                if items.is_empty() {
+                    return Err(RuleError::new(
+                        &format!("{}.{}", path, op),
+                        "expected a non-empty array of conditions",
+                    ));
+                }
+                let nodes = items
+                    .iter()
+                    .enumerate()
+                    .map(|(i, c)| self.condition(c, &format!("{}.{}[{}]", path, op, i)))
+                    .collect::<Result<Vec<_>, _>>()?;
+                if op == "and" {
+                    Node::And(nodes)
+                } else {
+                    Node::Or(nodes)
+                }
+            }
+            Condition::Not(inner) => {
+                Node::Not(Box::new(self.condition(inner, &format!("{}.not", path))?))
+            }
+            Condition::Within { bars, condition } => {
+                if *bars == 0 {
+                    return Err(RuleError::new(
+                        &format!("{}.within.bars", path),
+                        "expected an integer > 0",
+                    ));
+                }
+                Node::Within(
+                    *bars,
+                    Box::new(self.condition(condition, &format!("{}.within.condition", path))?),
+                )
+            }
+        };
+        Ok(node)
+    }
+
+    fn compare(
+        &mut self,
+        cmp: Cmp,
+        left: &Operand,
+        right: &Operand,
+        path: &str,
+    ) -> Result<Node, RuleError> {
+        Ok(Node::Compare(
+            cmp,
+            self.operand(left, &format!("{}[0]", path))?,
+            self.operand(right, &format!("{}[1]", path))?,
+        ))
+    }
+
+    fn operand(&mut self, operand: &Operand, path: &str) -> Result<Ref, RuleError> {
+        let (key, offset) = match operand {
+            Operand::Const(v) => return Ok(Ref::Const(*v)),
+            Operand::Price { price, offset } => (format!("{:?}", price), *offset),
+            Operand::Series { series, offset } => (format!("{:?}", series), *offset),
+            Operand::Indicator {
+                indicator,
+                params,
+                offset,
+            } => {
+                let resolved = self.registry.resolve(indicator, params).map_err(|e| {
+                    let field = if self.registry.get(indicator).is_some() {
+                        "params"
+                    } else {
+                        "indicator"
+                    };
+                    RuleError::new(&format!("{}.{}", path, field), e)
+                })?;
+                (format!("{}{:?}", indicator, resolved), *offset)
+            }
+        };
+        if let Some(&id) = self.keys.get(&key) {
+            return Ok(Ref::Series { id, offset });
+        }
+
+        let source = match operand {
+            Operand::Price { price, .. } => self
+                .registry
+                .build_spec(&IndicatorSpec::Price(*price))
+                .map_err(|e| RuleError::new(&format!("{}.price", path), e.message))?,
+            Operand::Indicator {
+                indicator, params, ..
+            } => self
+                .registry
+                .build(indicator, params)
+                .map_err(|e| RuleError::new(&format!("{}.indicator", path), e))?,
+            Operand::Series { series, .. } => self.registry.build_spec(series).map_err(|e| {
+                RuleError::new(&join_path(&format!("{}.series", path), &e.path), e.message)
+            })?,
+            Operand::Const(_) => unreachable!(),
+        };
+        let id = self.series.len();
+        self.series.push(Series {
+            source,
+            history: VecDeque::new(),
+        });
+        self.keys.insert(key, id);
+        Ok(Ref::Series { id, offset })
+    }
+}
@@ -0,0 +1,539 @@
+use std::{
+    collections::VecDeque,
+    error::Error,
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
+use chrono::{DateTime, Utc};
+use rhai::{AST, Array, Dynamic, Engine, EvalAltResult, INT, ImmutableString, Map, Scope};
+use serde_json::Value;
+
+use crate::indicators::registry::IndicatorRegistry;
+
+use super::{
+    market_data::MarketData,
+    param_schema::{FieldError, ParamSpec, RISK, StrategySchema, position_type},
+    position::{PositionType, TradePosition},
+    signal::Signal,
+    strategy_context::StrategyContext,
+    strategy_trait::Strategy,
+};
+
+/// 每根 bar 上脚本的资源上限
+#[derive(Debug, Clone, Copy)]
+pub struct ScriptLimits {
+    /// Rhai 的操作数上限，防止死循环
+    pub max_operations: u64,
+    /// 单根 bar 的执行时间上限
+    pub max_tick: Duration,
+    /// history 中保留的 bar 数
+    pub history_size: usize,
+    /// 字符串长度、数组和 map 元素个数的上限，限制脚本能占用的内存
+    pub max_collection: usize,
+}
+
+impl ScriptLimits {
+    /// 服务端允许的上限，用户参数超出时截断
+    pub const MAX_OPERATIONS: u64 = 1_000_000;
+    pub const MAX_MILLIS_PER_TICK: u64 = 1_000;
+    pub const MAX_HISTORY_SIZE: u64 = 5_000;
+
+    pub fn from_params(params: &Value) -> Self {
+        let max_operations = params
+            .get("maxOperations")
+            .and_then(Value::as_u64)
+            .unwrap_or(100_000);
+        let max_millis = params
+            .get("maxMillisPerTick")
+            .and_then(Value::as_u64)
+            .unwrap_or(50);
+        let history_size = params
+            .get("historySize")
+            .and_then(Value::as_u64)
+            .unwrap_or(500);
+        Self {
+            max_operations: max_operations.clamp(1, Self::MAX_OPERATIONS),
+            max_tick: Duration::from_millis(max_millis.clamp(1, Self::MAX_MILLIS_PER_TICK)),
+            history_size: history_size.clamp(1, Self::MAX_HISTORY_SIZE) as usize,
+            max_collection: 10_000,
+        }
+    }
+
+    /// 写回参数，后端把保存的上限拷进运行参数时使用
+    pub fn write_params(&self, params: &mut Value) {
+        params["maxOperations"] = self.max_operations.into();
+        params["maxMillisPerTick"] = (self.max_tick.as_millis() as u64).into();
+        params["historySize"] = self.history_size.into();
+    }
+}
+
+fn as_float(value: &Dynamic) -> Result<f64, Box<EvalAltResult>> {
+    value
+        .as_float()
+        .or_else(|_| value.as_int().map(|v| v as f64))
+        .map_err(|t| format!("expected a number, got {}", t).into())
+}
+
+fn as_period(value: INT) -> Result<usize, Box<EvalAltResult>> {
+    usize::try_from(value)
+        .ok()
+        .filter(|&p| p > 0)
+        .ok_or_else(|| format!("period must be > 0, got {}", value).into())
+}
+
+// 把 data 逐个喂给指标，返回每个位置的值，未就绪为 ()
+fn run_series(
+    registry: &IndicatorRegistry,
+    name: &str,
+    data: &Array,
+    params: &[f64],
+) -> Result<Array, Box<EvalAltResult>> {
+    let mut indicator = registry.build(name, params)?;
+    data.iter()
+        .map(|v| {
+            indicator.update(as_float(v)?);
+            Ok(indicator.value().map_or(Dynamic::UNIT, Dynamic::from))
+        })
+        .collect()
+}
+
+fn last_value(series: Array) -> Dynamic {
+    series.into_iter().last().unwrap_or(Dynamic::UNIT)
+}
+
+/// 沙箱化的 Rhai 引擎：没有文件/网络访问，print/debug 被吞掉，禁用 eval，
+/// 按 limits 限制操作数、调用深度、集合大小和单根 bar 的执行时间
+fn sandboxed_engine(limits: &ScriptLimits, deadline: Arc<AtomicU64>, origin: Instant) -> Engine {
+    let mut engine = Engine::new();
+    engine.set_max_operations(limits.max_operations);
+    engine.set_max_call_levels(32);
+    engine.set_max_expr_depths(64, 32);
+    engine.set_max_string_size(limits.max_collection);
+    engine.set_max_array_size(limits.max_collection);
+    engine.set_max_map_size(limits.max_collection);
+    engine.set_max_modules(0);
+    engine.disable_symbol("eval");
+    engine.on_print(|_| {});
+    engine.on_debug(|_, _, _| {});
+    engine.on_progress(move |_| {
+        let elapsed = origin.elapsed().as_micros() as u64;
+        (elapsed > deadline.load(Ordering::Relaxed)).then(|| Dynamic::from("time limit exceeded"))
+    });
+
+    let registry = Arc::new(IndicatorRegistry::new());
+    let r = registry.clone();
+    engine.register_fn(
+        "series",
+        move |name: ImmutableString, data: Array, period: INT| {
+            run_series(&r, &name, &data, &[as_period(period)? as f64])
+        },
+    );
+    let r = registry.clone();
+    engine.register_fn(
+        "series",
+        move |name: ImmutableString, data: Array, params: Array| {
+            let params = params.iter().map(as_float).collect::<Result<Vec<_>, _>>()?;
+            run_series(&r, &name, &data, &params)
+        },
+    );
+    let r = registry.clone();
+    engine.register_fn(
+        "indicator",
+        move |name: ImmutableString, data: Array, period: INT| {
+            run_series(&r, &name, &data, &[as_period(period)? as f64]).map(last_value)
+        },
+    );
+    for name in ["sma", "ema", "wma", "rsi", "stddev"] {
+        let r = registry.clone();
+        engine.register_fn(name, move |data: Array, period: INT| {
+            run_series(&r, name, &data, &[as_period(period)? as f64]).map(last_value)
+        });
+    }
+    engine.register_fn(
+        "highest",
+        |data: Array, n: INT| -> Result<Dynamic, Box<EvalAltResult>> {
+            let n = as_period(n)?;
+            let start = data.len().saturating_sub(n);
+            let values = data[start..]
+                .iter()
+                .map(as_float)
+                .collect::<Result<Vec<f64>, _>>()?;
+            Ok(values
+                .into_iter()
+                .reduce(f64::max)
+                .map_or(Dynamic::UNIT, Dynamic::from))
+        },
+    );
+    engine.register_fn(
+        "lowest",
+        |data: Array, n: INT| -> Result<Dynamic, Box<EvalAltResult>> {
+            let n = as_period(n)?;
+            let start = data.len().saturating_sub(n);
+            let values = data[start..]
+                .iter()
+                .map(as_float)
+                .collect::<Result<Vec<f64>, _>>()?;
+            Ok(values
+                .into_iter()
+                .reduce(f64::min)
+                .map_or(Dynamic::UNIT, Dynamic::from))
+        },
+    );
+
+    engine
+}
+
+// 用户脚本策略（Rhai）。每根 bar 运行一次脚本，脚本可读取：
+//   bar      当前 K 线：open/high/low/close/volume/time/index
+//   history  最近 history_size 根 K 线的 open/high/low/close/volume 数组，最后一个是当前 bar
+//   position 当前净持仓（>0 多头，<0 空头）；equity 账户净值；entry_price 持仓均价或 ()
+//   state    跨 bar 保留的 map，脚本可随意读写
+// 以及指标函数 sma/ema/wma/rsi/stddev(data, period)、indicator(name, data, period)、
+// series(name, data, period 或 [params])、highest/lowest(data, n)。
+// 脚本最后一个表达式为信号："enterLong"、"enterShort"、"exit"，其余（含 ()）视为 "hold"。
+// 超出资源上限或运行出错的 bar 按 hold 处理，不会中断回测。
+pub struct ScriptStrategy {
+    name: String,
+    engine: Engine,
+    ast: AST,
+    scope: Scope<'static>,
+    // scope 中输入变量的个数，每次运行后回退到这里，丢弃脚本顶层的 let
+    inputs: usize,
+    limits: ScriptLimits,
+    deadline: Arc<AtomicU64>,
+    origin: Instant,
+    history: VecDeque<MarketData>,
+    bars: INT,
+    errors: usize,
+    position_type: PositionType,
+}
+
+impl ScriptStrategy {
+    pub fn compile(source: &str, limits: ScriptLimits) -> Result<Self, Box<dyn Error>> {
+        if source.trim().is_empty() {
+            return Err("script is empty".into());
+        }
+        let deadline = Arc::new(AtomicU64::new(u64::MAX));
+        let origin = Instant::now();
+        let engine = sandboxed_engine(&limits, deadline.clone(), origin);
+        let ast = engine.compile(source)?;
+
+        let mut scope = Scope::new();
+        scope.push("state", Map::new());
+        scope.push("bar", Map::new());
+        scope.push("history", Map::new());
+        scope.push("position", 0.0_f64);
+        scope.push("equity", 0.0_f64);
+        scope.push("entry_price", ());
+        let inputs = scope.len();
+
+        Ok(ScriptStrategy {
+            name: "script".to_string(),
+            engine,
+            ast,
+            scope,
+            inputs,
+            limits,
+            deadline,
+            origin,
+            history: VecDeque::with_capacity(limits.history_size),
+            bars: 0,
+            errors: 0,
+            position_type: PositionType::Both,
+        })
+    }
+
+    /// 保存脚本前的语法检查
+    pub fn check(source: &str) -> Result<(), Box<dyn Error>> {
+        Self::compile(source, ScriptLimits::from_params(&Value::Null)).map(|_| ())
+    }
+
+    /// 参数说明，见 StrategySchema；给了源码时检查能否编译，scriptId 和源码都没有时报错
+    pub fn schema() -> StrategySchema {
+        StrategySchema::new(
+            "脚本：用 rhai 脚本编写的策略",
+            vec![
+                ParamSpec::int("scriptId", "strategy_scripts 表中的脚本 id").min(1.0),
+                ParamSpec::string("script", "脚本源码，通常由后端按 scriptId 注入"),
+                ParamSpec::int("maxOperations", "每根 bar 的最大运算次数")
+                    .default(100_000)
+                    .range(1.0, ScriptLimits::MAX_OPERATIONS as f64),
+                ParamSpec::int("maxMillisPerTick", "每根 bar 的最长执行毫秒数")
+                    .default(50)
+                    .range(1.0, ScriptLimits::MAX_MILLIS_PER_TICK as f64),
+                ParamSpec::int("historySize", "脚本可见的历史 bar 数")
+                    .default(500)
+                    .range(1.0, ScriptLimits::MAX_HISTORY_SIZE as f64),
+                position_type(),
+            ],
+        )
+        .with_risk(RISK)
+        .with_check(
+            |params| match params.get("script").and_then(Value::as_str) {
+                Some(source) => match Self::check(source) {
+                    Ok(()) => Vec::new(),
+                    Err(e) => vec![FieldError::new("script", e.to_string())],
+                },
+                None if params.get("scriptId").is_some() => Vec::new(),
+                None => vec![FieldError::new(
+                    "scriptId",
+                    "scriptId or script is required",
+                )],
+            },
+        )
+    }
+
+    /// 源码在 "script" 中；后端按 "scriptId" 从 strategy_scripts 表取出后注入。
+    /// 没有源码或无法编译时返回错误
+    pub fn from_params(params: &Value) -> Result<Box<dyn Strategy>, Box<dyn Error>> {
+        let source = params.get("script").and_then(Value::as_str).unwrap_or("");
+        let limits = ScriptLimits::from_params(params);
+        let mut strategy =
+            Self::compile(source, limits).map_err(|e| format!("invalid script: {}", e))?;
+        strategy.position_type = match params.get("positionType").and_then(Value::as_str) {
+            Some("long") => PositionType::Long,
+            Some("short") => PositionType::Short,
+            _ => PositionType::Both,
+        };
+        Ok(Box::new(strategy))
+    }
+
+    pub fn errors(&self) -> usize {
+        self.errors
+    }
+
+    fn remember(&mut self, bar: &MarketData) {
+        self.history.push_back(bar.clone());
+        while self.history.len() > self.limits.history_size {
+            self.history.pop_front();
+        }
+        self.bars += 1;
+    }
+
+    fn history_map(&self) -> Map {
+        let column = |f: fn(&MarketData) -> f64| -> Dynamic {
+            Dynamic::from_array(self.history.iter().map(|b| Dynamic::from(f(b))).collect())
+        };
+        let mut map = Map::new();
+        map.insert("open".into(), column(|b| b.open));
+        map.insert("high".into(), column(|b| b.high));
+        map.insert("low".into(), column(|b| b.low));
+        map.insert("close".into(), column(|b| b.close_price));
+        map.insert("volume".into(), column(|b| b.volume));
+        map
+    }
+
+    fn bar_map(&self, bar: &MarketData) -> Map {
+        let mut map = Map::new();
+        map.insert("open".into(), Dynamic::from(bar.open));
+        map.insert("high".into(), Dynamic::from(bar.high));
+        map.insert("low".into(), Dynamic::from(bar.low));
+        map.insert("close".into(), Dynamic::from(bar.close_price));
+        map.insert("volume".into(), Dynamic::from(bar.volume));
+        map.insert("time".into(), Dynamic::from(bar.timestamp.to_rfc3339()));
+        map.insert("index".into(), Dynamic::from(self.bars - 1));
+        map
+    }
+
+    fn run(
+        &mut self,
+        bar: &MarketData,
+        position: f64,
+        equity: f64,
+        entry_price: Option<f64>,
+    ) -> Signal {
+        self.remember(bar);
+        let bar_map = self.bar_map(bar);
+        let history = self.history_map();
+        self.scope.set_value("bar", bar_map);
+        self.scope.set_value("history", history);
+        self.scope.set_value("position", position);
+        self.scope.set_value("equity", equity);
+        self.scope.set_value(
+            "entry_price",
+            entry_price.map_or(Dynamic::UNIT, Dynamic::from),
+        );
+
+        let deadline = self.origin.elapsed() + self.limits.max_tick;
+        self.deadline
+            .store(deadline.as_micros() as u64, Ordering::Relaxed);
+        let result = self
+            .engine
+            .eval_ast_with_scope::<Dynamic>(&mut self.scope, &self.ast);
+        self.scope.rewind(self.inputs);
+
+        let action = match result {
+            Ok(value) => value.into_string().unwrap_or_default(),
+            Err(e) => {
+                self.errors += 1;
+                if self.errors <= 3 {
+                    eprintln!("script error at bar {}: {}", self.bars - 1, e);
+                }
+                return Signal::Hold;
+            }
+        };
+        match action.as_str() {
+            "enterLong" if position == 0.0 && self.supports_long() => {
+                Signal::EnterLong(bar.close_price)
+            }
+            "enterShort" if position == 0.0 && self.supports_short() => {
+                Signal::EnterShort(bar.close_price)
+            }
+            "exit" if position != 0.0 => Signal::Exit,
+            _ => Signal::Hold,
+        }
+    }
+}
+
+impl Strategy for ScriptStrategy {
+    fn generate_signal(&mut self, price: f64, position: f64) -> Signal {
+        let bar = MarketData::from_close(DateTime::<Utc>::MIN_UTC, price);
+        self.run(&bar, position, 0.0, None)
+    }
+
+    fn on_tick(&mut self, ctx: &mut StrategyContext, data: &MarketData) -> Signal {
+        let entry_price = ctx.current_entry.as_ref().map(|(_, price, _, _)| *price);
+        self.run(data, ctx.position, ctx.account_equity, entry_price)
+            .restrict(&self.position_type, ctx.position)
+    }
+
+    fn update(&mut self, market_data: &MarketData, _current_position: &Option<TradePosition>) {
+        self.remember(market_data);
+    }
+
+    fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    fn apply_parameters(&mut self, _entry_threshold: Option<f64>, _exit_threshold: Option<f64>) {}
+
+    fn position_type(&self) -> &PositionType {
+        &self.position_type
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn run(strategy: &mut Box<dyn Strategy>, prices: &[f64]) -> Vec<Signal> {
+        let mut position = 0.0;
+        prices
+            .iter()
+            .map(|&p| {
+                let signal = strategy.generate_signal(p, position);
+                match signal {
+                    Signal::EnterLong(_) => position = 1.0,
+                    Signal::EnterShort(_) => position = -1.0,
+                    Signal::Exit => position = 0.0,
+                    _ => {}
+                }
+                signal
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_script_uses_history_indicators_and_state() {
+        let mut strategy = ScriptStrategy::from_params(&json!({
+            "script": r#"
+                state.seen = (state.seen ?? 0) + 1;
+                let fast = sma(history.close, 2);
+                let slow = sma(history.close, 4);
+                if fast == () || slow == () {
+                    "hold"
+                } else if position == 0.0 && fast > slow {
+                    "enterLong"
+                } else if position > 0.0 && bar.close < highest(history.close, 3) * 0.95 {
+                    "exit"
+                }
+            "#
+        }))
+        .unwrap();
+        let signals = run(
+            &mut strategy,
+            &[10.0, 10.0, 10.0, 10.0, 11.0, 12.0, 13.0, 12.0],
+        );
+
+        assert_eq!(
+            signals[..4],
+            [Signal::Hold, Signal::Hold, Signal::Hold, Signal::Hold]
+        );
+        assert_eq!(signals[4], Signal::EnterLong(11.0));
+        // 12、13 都没有从最高点回落 5%；从 13 回落到 12 时平仓
+        assert_eq!(signals[5..7], [Signal::Hold, Signal::Hold]);
+        assert_eq!(signals[7], Signal::Exit);
+        assert_eq!(run(&mut strategy, &[12.0])[0], Signal::Hold);
+    }
+
+    #[test]
+    fn test_runaway_scripts_are_stopped_and_hold() {
+        let limits = ScriptLimits {
+            max_operations: 10_000,
+            max_tick: Duration::from_millis(50),
+            history_size: 10,
+            max_collection: 100,
+        };
+        let mut looping = ScriptStrategy::compile("loop { }", limits).unwrap();
+        let mut hog = ScriptStrategy::compile(
+            "let a = []; for i in 0..1000 { a.push(i); } \"enterLong\"",
+            limits,
+        )
+        .unwrap();
+        let mut slow = ScriptStrategy::compile(
+            "let x = 0; loop { x += 1; }",
+            ScriptLimits {
+                max_operations: u64::MAX,
+                max_tick: Duration::from_millis(20),
+                ..limits
+            },
+        )
+        .unwrap();
+
+        let started = Instant::now();
+        for strategy in [&mut looping, &mut hog, &mut slow] {
+            assert_eq!(strategy.generate_signal(100.0, 0.0), Signal::Hold);
+            assert_eq!(strategy.errors(), 1);
+        }
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_compile_errors_are_reported() {
+        assert!(ScriptStrategy::check("if (").is_err());
+        assert!(ScriptStrategy::check("   ").is_err());
+        assert!(ScriptStrategy::check("\"hold\"").is_ok());
+        assert!(ScriptStrategy::from_params(&json!({})).is_err());
+        assert!(ScriptStrategy::from_params(&json!({ "script": "if (" })).is_err());
+    }
+
+    #[test]
+    fn test_limits_are_capped_at_server_maxima() {
+        let limits = ScriptLimits::from_params(&json!({
+            "maxOperations": u64::MAX,
+            "maxMillisPerTick": 3_600_000,
+            "historySize": 0,
+        }));
+        assert_eq!(limits.max_operations, ScriptLimits::MAX_OPERATIONS);
+        assert_eq!(
+            limits.max_tick,
+            Duration::from_millis(ScriptLimits::MAX_MILLIS_PER_TICK)
+        );
+        assert_eq!(limits.history_size, 1);
+
+        let schema = ScriptStrategy::schema();
+        assert!(
+            schema
+                .validate(&json!({ "script": "()", "maxMillisPerTick": 3_600_000 }))
+                .is_err()
+        );
+        assert!(schema.validate(&json!({})).is_err());
+        assert_eq!(schema.validate(&json!({ "scriptId": 3 })), Ok(()));
+    }
+}
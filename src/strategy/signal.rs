@@ -1,7 +1,204 @@
-#[derive(Debug, Clone, Copy, PartialEq)]
+use serde::{Deserialize, Serialize};
+
+use crate::domain::OrderType;
+
+use super::{direction::Direction, market_data::MarketData, position::PositionType};
+
+/// 多腿信号中的一条腿
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Leg {
+    /// 该品种在对齐数据源 bars 中的下标
+    pub index: usize,
+    /// 下单参考价
+    pub price: f64,
+    /// 每一份组合对应的数量，>0 买入，<0 卖出
+    pub ratio: f64,
+}
+
+/// 可与其他仓位并存的一笔独立持仓（如网格的每一格），按 id 开平
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Lot {
+    pub id: usize,
+    /// 挂单价，按该价格成交
+    pub price: f64,
+    pub direction: Direction,
+}
+
+/// 下单方式和价格：市价单的 price 是参考价；限价/止损单从下一根 bar 起挂单，
+/// 触价才成交，在成交或 Exit 之前一直有效
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Order {
+    pub order_type: OrderType,
+    pub price: f64,
+}
+
+impl Order {
+    pub fn market(price: f64) -> Self {
+        Self {
+            order_type: OrderType::Market,
+            price,
+        }
+    }
+
+    pub fn limit(price: f64) -> Self {
+        Self {
+            order_type: OrderType::Limit,
+            price,
+        }
+    }
+
+    pub fn stop(price: f64) -> Self {
+        Self {
+            order_type: OrderType::Stop,
+            price,
+        }
+    }
+
+    /// 挂单在这根 bar 上是否触价，返回成交价；跳空越过挂单价时按开盘价成交
+    pub fn touched(&self, buy: bool, bar: &MarketData) -> Option<f64> {
+        let price = self.price;
+        match (self.order_type, buy) {
+            (OrderType::Market, _) => Some(price),
+            (OrderType::Limit, true) | (OrderType::Stop, false) => {
+                (bar.low <= price).then(|| price.min(bar.open))
+            }
+            (OrderType::Limit, false) | (OrderType::Stop, true) => {
+                (bar.high >= price).then(|| price.max(bar.open))
+            }
+        }
+    }
+}
+
+/// 开仓意图，可附带止损/止盈价，持仓期间每根 bar 按最高/最低价检查
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Entry {
+    pub direction: Direction,
+    pub order: Order,
+    pub stop_loss: Option<f64>,
+    pub take_profit: Option<f64>,
+}
+
+impl Entry {
+    pub fn new(direction: Direction, order: Order) -> Self {
+        Self {
+            direction,
+            order,
+            stop_loss: None,
+            take_profit: None,
+        }
+    }
+
+    pub fn with_stop_loss(mut self, price: f64) -> Self {
+        self.stop_loss = Some(price);
+        self
+    }
+
+    pub fn with_take_profit(mut self, price: f64) -> Self {
+        self.take_profit = Some(price);
+        self
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum Signal {
+    /// 市价开空，等同于 Enter(Entry::new(Direction::Short, Order::market(price)))
     EnterShort(f64),
+    /// 市价开多，等同于 Enter(Entry::new(Direction::Long, Order::market(price)))
     EnterLong(f64),
+    /// 空仓时按指定下单方式开仓
+    Enter(Entry),
+    /// 平掉当前持仓的 fraction（0~1）
+    Reduce {
+        fraction: f64,
+        order: Order,
+    },
+    /// 调整到目标仓位：净值的 percent%，负数为空头，按收盘价市价成交
+    TargetPercent(f64),
+    /// 按当前持仓方向再加一个单位
+    ScaleIn(f64),
+    /// 两条腿同时开仓（配对交易），平仓沿用 Exit
+    EnterPair([Leg; 2]),
+    OpenLot(Lot),
+    CloseLot(Lot),
+    /// 用 quote 金额的现金按收盘价买入第 index 个品种（定投）
+    Invest {
+        index: usize,
+        quote: f64,
+    },
+    /// 按目标权重调整各品种持仓，下标与 bars 对应，剩余部分留作现金
+    Rebalance(Vec<f64>),
+    /// 同一根 bar 上按顺序执行的多个信号
+    Batch(Vec<Signal>),
+    /// 平掉全部持仓，包括所有 lot，并撤掉挂单；相当于市价 Reduce { fraction: 1.0 } 加上撤单
     Exit,
     Hold,
 }
+
+impl Signal {
+    /// 图表标记和日志里用的名字，与规则 DSL 的动作名一致
+    pub fn name(&self) -> &'static str {
+        match self {
+            Signal::EnterShort(_) => "enterShort",
+            Signal::EnterLong(_) => "enterLong",
+            Signal::Enter(entry) => match entry.direction {
+                Direction::Long => "enterLong",
+                Direction::Short => "enterShort",
+            },
+            Signal::Reduce { .. } => "reduce",
+            Signal::TargetPercent(_) => "targetPercent",
+            Signal::ScaleIn(_) => "scaleIn",
+            Signal::EnterPair(_) => "enterPair",
+            Signal::OpenLot(_) => "openLot",
+            Signal::CloseLot(_) => "closeLot",
+            Signal::Invest { .. } => "invest",
+            Signal::Rebalance(_) => "rebalance",
+            Signal::Batch(_) => "batch",
+            Signal::Exit => "exit",
+            Signal::Hold => "hold",
+        }
+    }
+
+    /// 信号要开仓或加仓的方向，position 为当前净持仓；平仓、持有、配对和组合类信号为 None
+    pub fn entry_direction(&self, position: f64) -> Option<Direction> {
+        match self {
+            Signal::EnterLong(_) => Some(Direction::Long),
+            Signal::EnterShort(_) => Some(Direction::Short),
+            Signal::Enter(entry) => Some(entry.direction.clone()),
+            Signal::OpenLot(lot) => Some(lot.direction.clone()),
+            Signal::TargetPercent(percent) if *percent > 0.0 => Some(Direction::Long),
+            Signal::TargetPercent(percent) if *percent < 0.0 => Some(Direction::Short),
+            Signal::ScaleIn(_) if position > 0.0 => Some(Direction::Long),
+            Signal::ScaleIn(_) if position < 0.0 => Some(Direction::Short),
+            _ => None,
+        }
+    }
+
+    /// 去掉 position_type 不允许方向的开仓信号：目标仓位改为空仓，其余改为 Hold；
+    /// 批量信号逐个过滤
+    pub fn restrict(self, position_type: &PositionType, position: f64) -> Signal {
+        if let Signal::Batch(signals) = self {
+            let kept: Vec<Signal> = signals
+                .into_iter()
+                .map(|s| s.restrict(position_type, position))
+                .filter(|s| *s != Signal::Hold)
+                .collect();
+            return if kept.is_empty() {
+                Signal::Hold
+            } else {
+                Signal::Batch(kept)
+            };
+        }
+        let allowed = matches!(
+            (self.entry_direction(position), position_type),
+            (None, _)
+                | (_, PositionType::Both)
+                | (Some(Direction::Long), PositionType::Long)
+                | (Some(Direction::Short), PositionType::Short)
+        );
+        match self {
+            _ if allowed => self,
+            Signal::TargetPercent(_) => Signal::TargetPercent(0.0),
+            _ => Signal::Hold,
+        }
+    }
+}
@@ -1,6 +1,14 @@
-use super::direction::Direction;
+use serde::{Deserialize, Serialize};
 
-/// 上下文仅存储状态和配对信息，不直接持有 executor 或 sizer
+use crate::{
+    data::timeframe::{Resampler, Timeframe},
+    regime::Regime,
+};
+
+use super::{direction::Direction, market_data::MarketData};
+
+/// 上下文仅存储状态和配对信息，不直接持有 executor 或 sizer；可整体序列化，用于断点续跑
+#[derive(Clone, Serialize, Deserialize)]
 pub struct StrategyContext {
     /// 当前净持仓，>0 多头，<0 空头，==0 空仓
     pub position: f64,
@@ -8,6 +16,74 @@ pub struct StrategyContext {
     pub account_equity: f64,
     /// 当前未平仓入场信息：(entry_time, entry_price, quantity, direction)
     pub current_entry: Option<(String, f64, f64, Direction)>,
+    /// current_entry 附带的止损/止盈价，由 Signal::Enter 设置，平仓后清空
+    pub stop_loss: Option<f64>,
+    pub take_profit: Option<f64>,
+    /// 最新 ATR，由基于波动率的策略写入，供 AtrSizer 计算头寸
+    pub atr: Option<f64>,
+    /// 未平仓的配对组合，与 current_entry 互斥
+    pub pair_entry: Option<PairEntry>,
+    /// 按 id 管理、可同时存在的多笔持仓（网格等），计入 position
+    pub lots: Vec<LotEntry>,
+    /// 组合类策略（定投、再平衡）的现金和各品种持仓
+    pub cash: f64,
+    pub holdings: Vec<Holding>,
+    /// 策略订阅的高周期，由引擎用基础 bar 推进
+    pub timeframes: Vec<TimeframeBars>,
+    /// 当前市场状态，配置了状态识别时由引擎逐 bar 更新，预热期为 None
+    #[serde(default)]
+    pub regime: Option<Regime>,
+}
+
+/// 一个订阅周期上已经收完的 K 线
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimeframeBars {
+    resampler: Resampler,
+    /// 最近一根收完的 K 线
+    pub last: Option<MarketData>,
+    /// 当前基础 bar 上刚收完的 K 线
+    pub closed: Vec<MarketData>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Holding {
+    pub index: usize,
+    pub symbol: String,
+    pub quantity: f64,
+    /// 持仓均价
+    pub cost: f64,
+    /// 第一次买入的成交时间，加仓不变
+    #[serde(default)]
+    pub entry_time: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LotEntry {
+    pub id: usize,
+    pub entry_time: String,
+    pub entry_price: f64,
+    pub quantity: f64,
+    pub direction: Direction,
+}
+
+/// 配对组合中一条腿的成交
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LegFill {
+    pub index: usize,
+    pub symbol: String,
+    /// 带符号的成交数量，>0 多头，<0 空头
+    pub quantity: f64,
+    pub price: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PairEntry {
+    pub entry_time: String,
+    /// 组合份数，每条腿数量 = units * |ratio|
+    pub units: f64,
+    /// 第一条腿为多头时记为 Long（做多价差）
+    pub direction: Direction,
+    pub legs: Vec<LegFill>,
 }
 
 impl StrategyContext {
@@ -16,9 +92,76 @@ impl StrategyContext {
             position: 0.0,
             account_equity: initial_capital,
             current_entry: None,
+            stop_loss: None,
+            take_profit: None,
+            atr: None,
+            pair_entry: None,
+            lots: Vec::new(),
+            cash: initial_capital,
+            holdings: Vec::new(),
+            timeframes: Vec::new(),
+            regime: None,
+        }
+    }
+
+    /// 订阅高周期，重复的周期只保留一份
+    pub fn subscribe(&mut self, timeframes: &[Timeframe]) {
+        for &timeframe in timeframes {
+            if self.bars_of(timeframe).is_none() {
+                self.timeframes.push(TimeframeBars {
+                    resampler: Resampler::new(timeframe),
+                    last: None,
+                    closed: Vec::new(),
+                });
+            }
+        }
+    }
+
+    /// 推进一根基础 bar，在策略决策之前调用
+    pub fn advance_timeframes(&mut self, bar: &MarketData) {
+        for tf in &mut self.timeframes {
+            tf.closed = tf.resampler.push(bar);
+            if let Some(last) = tf.closed.last() {
+                tf.last = Some(last.clone());
+            }
         }
     }
 
+    fn bars_of(&self, timeframe: Timeframe) -> Option<&TimeframeBars> {
+        self.timeframes
+            .iter()
+            .find(|tf| tf.resampler.timeframe() == timeframe)
+    }
+
+    /// 该周期最近一根收完的 K 线；未订阅或还没有收完的 K 线时为 None
+    pub fn closed_bar(&self, timeframe: Timeframe) -> Option<&MarketData> {
+        self.bars_of(timeframe)?.last.as_ref()
+    }
+
+    /// 该周期在当前基础 bar 上刚收完的 K 线，用来逐根更新高周期指标
+    pub fn new_closed_bars(&self, timeframe: Timeframe) -> &[MarketData] {
+        self.bars_of(timeframe)
+            .map_or(&[], |tf| tf.closed.as_slice())
+    }
+
+    /// 某个品种的持仓数量
+    pub fn holding(&self, index: usize) -> f64 {
+        self.holdings
+            .iter()
+            .find(|h| h.index == index)
+            .map_or(0.0, |h| h.quantity)
+    }
+
+    /// 组合市值：现金 + 各品种按 bars 收盘价计价
+    pub fn portfolio_value(&self, bars: &[MarketData]) -> f64 {
+        self.cash
+            + self
+                .holdings
+                .iter()
+                .map(|h| h.quantity * bars.get(h.index).map_or(h.cost, |b| b.close_price))
+                .sum::<f64>()
+    }
+
     /// 清理与实际 position 不符的 entry
     pub fn reconcile_entry(&mut self) {
         if let Some((_, _, _, dir)) = &self.current_entry {
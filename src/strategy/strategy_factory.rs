@@ -1,6 +1,7 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, error::Error};
 
 use duckdb::arrow::array::StringBuilder;
+use serde::Serialize;
 use serde_json::Value;
 
 use crate::{
@@ -8,16 +9,40 @@ use crate::{
 };
 
 use super::{
+    bollinger_bands_strategy::BollingerBandsStrategy,
+    composite_strategy::CompositeStrategy,
+    dca_strategy::DcaStrategy,
+    grid_strategy::GridStrategy,
+    macd_strategy::MacdStrategy,
     mean_reversion_strategy::MeanReversionStrategy,
     moving_average_strategy::MovingAverageStrategy,
+    pairs_strategy::PairsStrategy,
+    param_schema::{FieldError, StrategySchema},
+    rebalance_strategy::RebalanceStrategy,
+    rsi_strategy::RsiStrategy,
+    rule_strategy::RuleStrategy,
+    script_strategy::ScriptStrategy,
     strategy_trait::Strategy,
     strategy_type::{StrategyType, SupportStrategyType},
+    turtle_strategy::TurtleStrategy,
 };
 
-type StrategyBuilder = fn(&Value) -> Box<dyn Strategy>;
+type StrategyBuilder = fn(&Value) -> Result<Box<dyn Strategy>, Box<dyn Error>>;
+type SchemaBuilder = fn() -> StrategySchema;
+
+/// 要按时间对齐多个品种行情的策略，其余策略只跑第一个资产
+const MULTI_ASSET: [&str; 3] = ["dca", "rebalance", "pairs"];
+
+/// 给前端的策略参数目录
+#[derive(Debug, Clone, Serialize)]
+pub struct StrategyInfo {
+    pub name: String,
+    #[serde(flatten)]
+    pub schema: StrategySchema,
+}
 
 pub struct StrategyFactory {
-    registry: HashMap<String, StrategyBuilder>,
+    registry: HashMap<String, (StrategyBuilder, SchemaBuilder)>,
 }
 
 impl StrategyFactory {
@@ -26,18 +51,114 @@ impl StrategyFactory {
             registry: HashMap::new(),
         };
 
-        factory.registry("ma-crossover", MovingAverageStrategy::from_params);
-        factory.registry("mean-reversion", MeanReversionStrategy::from_params);
+        factory.registry(
+            "ma-crossover",
+            MovingAverageStrategy::from_params,
+            MovingAverageStrategy::schema,
+        );
+        factory.registry(
+            "mean-reversion",
+            |p| Ok(MeanReversionStrategy::from_params(p)),
+            MeanReversionStrategy::schema,
+        );
+        factory.registry(
+            "rsi",
+            |p| Ok(RsiStrategy::from_params(p)),
+            RsiStrategy::schema,
+        );
+        factory.registry(
+            "macd",
+            |p| Ok(MacdStrategy::from_params(p)),
+            MacdStrategy::schema,
+        );
+        factory.registry(
+            "bollinger-bands",
+            |p| Ok(BollingerBandsStrategy::from_params(p)),
+            BollingerBandsStrategy::schema,
+        );
+        factory.registry(
+            "turtle",
+            |p| Ok(TurtleStrategy::from_params(p)),
+            TurtleStrategy::schema,
+        );
+        factory.registry(
+            "pairs",
+            |p| Ok(PairsStrategy::from_params(p)),
+            PairsStrategy::schema,
+        );
+        factory.registry(
+            "grid",
+            |p| Ok(GridStrategy::from_params(p)),
+            GridStrategy::schema,
+        );
+        factory.registry(
+            "dca",
+            |p| Ok(DcaStrategy::from_params(p)),
+            DcaStrategy::schema,
+        );
+        factory.registry(
+            "rebalance",
+            |p| Ok(RebalanceStrategy::from_params(p)),
+            RebalanceStrategy::schema,
+        );
+        factory.registry("rules", RuleStrategy::from_params, RuleStrategy::schema);
+        factory.registry(
+            "script",
+            ScriptStrategy::from_params,
+            ScriptStrategy::schema,
+        );
+        factory.registry(
+            "composite",
+            CompositeStrategy::from_params,
+            CompositeStrategy::schema,
+        );
 
         factory
     }
 
-    pub fn registry(&mut self, name: &str, builder: StrategyBuilder) {
-        self.registry.insert(name.to_string(), builder);
+    pub fn registry(&mut self, name: &str, builder: StrategyBuilder, schema: SchemaBuilder) {
+        self.registry.insert(name.to_string(), (builder, schema));
+    }
+
+    /// 未注册的策略或参数无法构建时返回错误
+    pub fn build(&self, name: &str, params: &Value) -> Result<Box<dyn Strategy>, Box<dyn Error>> {
+        match self.registry.get(name) {
+            Some((build, _)) => build(params),
+            None => Err(format!("unknown strategy `{}`", name).into()),
+        }
+    }
+
+    pub fn is_multi_asset(name: &str) -> bool {
+        MULTI_ASSET.contains(&name)
+    }
+
+    pub fn schema(&self, name: &str) -> Option<StrategySchema> {
+        self.registry.get(name).map(|(_, schema)| schema())
+    }
+
+    /// 所有已注册策略的参数说明，按名字排序
+    pub fn catalog(&self) -> Vec<StrategyInfo> {
+        let mut catalog: Vec<StrategyInfo> = self
+            .registry
+            .iter()
+            .map(|(name, (_, schema))| StrategyInfo {
+                name: name.clone(),
+                schema: schema(),
+            })
+            .collect();
+        catalog.sort_by(|a, b| a.name.cmp(&b.name));
+        catalog
     }
 
-    pub fn build(&self, name: &str, params: &Value) -> Option<Box<dyn Strategy>> {
-        self.registry.get(name).map(|b| b(params))
+    /// 按策略的 schema 校验参数，未注册的策略报在 type 上
+    pub fn validate(&self, name: &str, params: &Value) -> Result<(), Vec<FieldError>> {
+        match self.schema(name) {
+            Some(schema) => schema.validate(params),
+            None => Err(vec![FieldError::new(
+                "type",
+                format!("unknown strategy `{}`", name),
+            )]),
+        }
     }
 
     pub fn create_strategy(name: String, strategy_type: StrategyType) -> Box<dyn Strategy> {
@@ -65,18 +186,167 @@ impl StrategyFactory {
         sub_type: &str, // e.g., "sma" or "ema"
         fast_period: u32,
         slow_period: u32,
-    ) -> Box<dyn Strategy> {
-        match r#type.parse().unwrap() {
+    ) -> Result<Box<dyn Strategy>, Box<dyn Error>> {
+        Ok(match r#type.parse::<SupportStrategyType>()? {
             SupportStrategyType::MovingAverageCrossover => Box::new(MovingAverageStrategy::create(
                 name,
                 sub_type,
                 fast_period as usize,
                 slow_period as usize,
-            )),
+            )?),
             SupportStrategyType::RSI => {
-                panic!("RSI strategy not implemented yet");
+                Box::new(RsiStrategy::create(name, sub_type, fast_period as usize))
             }
-            _ => panic!("Unsupported strategy type"),
+            SupportStrategyType::MACD => Box::new(MacdStrategy::create(
+                name,
+                sub_type,
+                fast_period as usize,
+                slow_period as usize,
+            )),
+            SupportStrategyType::BollingerBands => Box::new(BollingerBandsStrategy::create(
+                name,
+                sub_type,
+                fast_period as usize,
+            )),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, TimeZone, Utc};
+    use serde_json::json;
+
+    use super::*;
+    use crate::strategy::{market_data::MarketData, strategy_context::StrategyContext};
+
+    #[test]
+    fn test_every_strategy_survives_every_entry_point() {
+        let factory = StrategyFactory::new();
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let bars: Vec<MarketData> = (0..60)
+            .map(|i| {
+                MarketData::from_close(
+                    start + Duration::days(i),
+                    100.0 + (i as f64 * 0.4).sin() * 10.0,
+                )
+            })
+            .collect();
+        for name in factory.registry.keys() {
+            // 规则、脚本、组合策略没有规则 / 源码 / 子策略时无法构建
+            let params = match name.as_str() {
+                "rules" => {
+                    let when = json!({ "gt": [{ "price": "close" }, 100] });
+                    json!({ "rules": [{ "when": when, "then": "enterLong" }] })
+                }
+                "script" => json!({ "script": "\"hold\"" }),
+                "composite" => json!({ "strategies": [{ "type": "rsi" }] }),
+                _ => json!({}),
+            };
+            // 旧引擎走 update/apply_parameters，TradingEngine 走 on_bars 和各个回调
+            let mut old = factory.build(name, &params).unwrap();
+            old.apply_parameters(Some(1.0), Some(0.5));
+            for bar in &bars {
+                old.update(bar, &None);
+            }
+
+            let mut strategy = factory.build(name, &params).unwrap();
+            let mut ctx = StrategyContext::new(10_000.0);
+            strategy.on_start(&ctx);
+            for bar in &bars {
+                strategy.on_bars(&mut ctx, std::slice::from_ref(bar));
+                strategy.chart_values();
+            }
+            strategy.on_finish(&ctx);
         }
     }
+
+    #[test]
+    fn test_only_portfolio_strategies_are_multi_asset() {
+        for name in ["dca", "rebalance", "pairs"] {
+            assert!(StrategyFactory::is_multi_asset(name));
+        }
+        for name in ["ma-crossover", "rsi", "grid", "script", "composite"] {
+            assert!(!StrategyFactory::is_multi_asset(name));
+        }
+    }
+
+    #[test]
+    fn test_every_strategy_publishes_a_schema_its_defaults_pass() {
+        let factory = StrategyFactory::new();
+        let catalog = factory.catalog();
+        assert_eq!(catalog.len(), factory.registry.len());
+        for info in catalog {
+            let defaults: serde_json::Map<String, Value> = info
+                .schema
+                .params
+                .iter()
+                .filter_map(|p| p.default.clone().map(|d| (p.name.to_string(), d)))
+                .collect();
+            let result = factory.validate(&info.name, &Value::Object(defaults));
+            match info.name.as_str() {
+                // 没有规则 / 子策略 / 脚本无法运行
+                "rules" | "composite" | "script" => assert!(result.is_err()),
+                _ => assert_eq!(result, Ok(()), "{}", info.name),
+            }
+        }
+        assert_eq!(
+            factory.validate("nope", &json!({})),
+            Err(vec![FieldError::new("type", "unknown strategy `nope`")])
+        );
+
+        let split = crate::utils::params::split_params(
+            &json!({ "maType": "ema", "meanType": "ema", "stopLoss": 2, "slippage": 0.1 }),
+            "ma-crossover",
+        );
+        assert_eq!(split.strategy, json!({ "maType": "ema" }));
+        assert_eq!(split.risk, json!({ "stopLoss": 2 }));
+        assert_eq!(split.exec, json!({ "slippage": 0.1 }));
+    }
+
+    #[test]
+    fn test_nested_and_compiled_params_report_field_paths() {
+        let factory = StrategyFactory::new();
+        let errors = factory
+            .validate(
+                "composite",
+                &json!({ "strategies": [
+                    { "type": "ma-crossover", "params": { "fastPeriod": 30, "slowPeriod": 10 } },
+                    { "type": "nope" },
+                    { "type": "rsi", "params": { "rsiPeriod": 14 }, "weight": -1 },
+                    { "type": "rsi", "params": { "rsiPeriods": 14 } },
+                ] }),
+            )
+            .unwrap_err();
+        assert_eq!(
+            errors,
+            vec![
+                FieldError::new(
+                    "strategies[0].params.fastPeriod",
+                    "must be less than slowPeriod (30 >= 10)"
+                ),
+                FieldError::new("strategies[1].type", "unknown strategy `nope`"),
+                FieldError::new("strategies[2].weight", "must be a number >= 0"),
+                FieldError::new(
+                    "strategies[3].params.rsiPeriods",
+                    "unknown parameter, did you mean `rsiPeriod`?"
+                ),
+            ]
+        );
+
+        assert_eq!(
+            factory.validate("rules", &json!({ "rules": [] })),
+            Err(vec![FieldError::new(
+                "rules",
+                "at least one rule is required"
+            )])
+        );
+        assert_eq!(
+            factory.validate("ma-crossover", &json!({ "trendTimeframe": "7x" })),
+            Err(vec![FieldError::new(
+                "trendTimeframe",
+                "unknown timeframe `7x`"
+            )])
+        );
+    }
 }
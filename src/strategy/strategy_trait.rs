@@ -1,4 +1,10 @@
+use crate::{
+    data::timeframe::Timeframe,
+    domain::{OrderRequest, order::OrderResponse, trade_observer::TradeRecord},
+};
+
 use super::{
+    checkpoint::Checkpoint,
     market_data::MarketData,
     position::{PositionType, TradePosition},
     signal::Signal,
@@ -10,16 +16,48 @@ pub trait Strategy {
     fn update(&mut self, market_data: &MarketData, current_position: &Option<TradePosition>);
     fn name(&self) -> &str;
     fn apply_parameters(&mut self, entry_threshold: Option<f64>, exit_threshold: Option<f64>);
+    /// 拿到完整 K 线的入口；只看收盘价的策略沿用 generate_signal
+    fn on_bar(&mut self, bar: &MarketData, position: f64) -> Signal {
+        self.generate_signal(bar.close_price, position)
+    }
     fn on_tick(&mut self, ctx: &mut StrategyContext, data: &MarketData) -> Signal {
-        let raw_sig = self.generate_signal(data.close_price, ctx.position);
+        let raw_sig = self.on_bar(data, ctx.position);
         // ctx.apply_signal(sig, data.timestamp.clone(), data.close_price);
-        match raw_sig {
-            Signal::EnterLong(_) if !self.supports_long() => Signal::Hold,
-            Signal::EnterShort(_) if !self.supports_short() => Signal::Hold,
-            other => other,
+        raw_sig.restrict(self.position_type(), ctx.position)
+    }
+    /// 多品种对齐后的入口，bars 顺序与数据源一致；单品种策略只看第一个
+    fn on_bars(&mut self, ctx: &mut StrategyContext, bars: &[MarketData]) -> Signal {
+        match bars.first() {
+            Some(bar) => self.on_tick(ctx, bar),
+            None => Signal::Hold,
         }
     }
 
+    /// 订阅的高周期，引擎据此在 StrategyContext 中维护各周期最近收完的 K 线
+    fn timeframes(&self) -> Vec<Timeframe> {
+        Vec::new()
+    }
+    /// 图表上叠加的指标值（名称, 值），每根 bar 决策后读取，未就绪为 None
+    fn chart_values(&self) -> Vec<(String, Option<f64>)> {
+        Vec::new()
+    }
+
+    /// 开始推送行情前调用一次
+    fn on_start(&mut self, _ctx: &StrategyContext) {}
+    /// 订单有成交时调用，response 是实际成交价和数量，可能少于请求数量
+    fn on_fill(&mut self, _request: &OrderRequest, _response: &OrderResponse) {}
+    /// 订单完全没有成交时调用，持仓不变
+    fn on_order_rejected(&mut self, _request: &OrderRequest) {}
+    /// 一笔持仓（包括 lot、配对组合、组合持仓）平掉后调用
+    fn on_position_closed(&mut self, _trade: &TradeRecord) {}
+    /// 数据源耗尽后调用一次
+    fn on_finish(&mut self, _ctx: &StrategyContext) {}
+
+    /// 支持断点续跑的策略返回 Some(self)，引擎据此定期存 checkpoint 并从中恢复
+    fn checkpoint(&mut self) -> Option<&mut dyn Checkpoint> {
+        None
+    }
+
     fn position_type(&self) -> &PositionType;
 
     fn supports_long(&self) -> bool {
@@ -35,3 +73,104 @@ pub trait Strategy {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use super::*;
+    use crate::strategy::{
+        direction::Direction,
+        signal::{Entry, Lot, Order},
+    };
+
+    // 每根 bar 都发出同一个信号
+    struct Fixed(Signal, PositionType);
+
+    impl Strategy for Fixed {
+        fn generate_signal(&mut self, _price: f64, _position: f64) -> Signal {
+            self.0.clone()
+        }
+        fn update(&mut self, _market_data: &MarketData, _current_position: &Option<TradePosition>) {
+        }
+        fn name(&self) -> &str {
+            "fixed"
+        }
+        fn apply_parameters(&mut self, _entry: Option<f64>, _exit: Option<f64>) {}
+        fn position_type(&self) -> &PositionType {
+            &self.1
+        }
+    }
+
+    fn tick(signal: &Signal, position_type: PositionType, position: f64) -> Signal {
+        let mut ctx = StrategyContext::new(1_000.0);
+        ctx.position = position;
+        Fixed(signal.clone(), position_type)
+            .on_tick(&mut ctx, &MarketData::from_close(Utc::now(), 100.0))
+    }
+
+    #[test]
+    fn test_position_type_filters_every_entry_variant() {
+        let enter = |direction| Signal::Enter(Entry::new(direction, Order::limit(95.0)));
+        let lot = |direction| {
+            Signal::OpenLot(Lot {
+                id: 0,
+                price: 100.0,
+                direction,
+            })
+        };
+        // (信号, 当前持仓, 只做多时的结果, 只做空时的结果)
+        let cases = [
+            (Signal::EnterLong(100.0), 0.0, None, Some(Signal::Hold)),
+            (Signal::EnterShort(100.0), 0.0, Some(Signal::Hold), None),
+            (enter(Direction::Long), 0.0, None, Some(Signal::Hold)),
+            (enter(Direction::Short), 0.0, Some(Signal::Hold), None),
+            (lot(Direction::Long), 0.0, None, Some(Signal::Hold)),
+            (lot(Direction::Short), 0.0, Some(Signal::Hold), None),
+            (
+                Signal::TargetPercent(50.0),
+                0.0,
+                None,
+                Some(Signal::TargetPercent(0.0)),
+            ),
+            (
+                Signal::TargetPercent(-50.0),
+                0.0,
+                Some(Signal::TargetPercent(0.0)),
+                None,
+            ),
+            (Signal::ScaleIn(100.0), 1.0, None, Some(Signal::Hold)),
+            (Signal::ScaleIn(100.0), -1.0, Some(Signal::Hold), None),
+            (
+                Signal::Batch(vec![Signal::Exit, Signal::EnterShort(100.0)]),
+                1.0,
+                Some(Signal::Batch(vec![Signal::Exit])),
+                None,
+            ),
+            (Signal::Exit, 1.0, None, None),
+        ];
+        for (signal, position, long_only, short_only) in cases {
+            let name = signal.name();
+            assert_eq!(
+                tick(&signal, PositionType::Both, position),
+                signal,
+                "{}",
+                name
+            );
+            let expected = long_only.unwrap_or_else(|| signal.clone());
+            assert_eq!(
+                tick(&signal, PositionType::Long, position),
+                expected,
+                "{}",
+                name
+            );
+            let expected = short_only.unwrap_or_else(|| signal.clone());
+            assert_eq!(
+                tick(&signal, PositionType::Short, position),
+                expected,
+                "{}",
+                name
+            );
+        }
+    }
+}
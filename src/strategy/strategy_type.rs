@@ -52,7 +52,9 @@ impl FromStr for SupportStrategyType {
                 Ok(SupportStrategyType::MovingAverageCrossover)
             }
             "RSI" | "rsi" => Ok(SupportStrategyType::RSI),
-            "BollingerBands" | "bollinger_bands" => Ok(SupportStrategyType::BollingerBands),
+            "BollingerBands" | "bollinger_bands" | "bollinger-bands" => {
+                Ok(SupportStrategyType::BollingerBands)
+            }
             "MACD" | "macd" => Ok(SupportStrategyType::MACD),
             _ => Err(format!("Unknown strategy type: {}", s)),
         }
@@ -0,0 +1,341 @@
+use serde_json::Value;
+
+use crate::indicators::{
+    atr_indicator::AtrIndicator, donchian_indicator::DonchianIndicator, indicator::Indicator,
+};
+
+use super::{
+    market_data::MarketData,
+    param_schema::{ParamSpec, RISK, StrategySchema, position_type},
+    position::{PositionType, TradePosition},
+    signal::Signal,
+    strategy_context::StrategyContext,
+    strategy_trait::Strategy,
+};
+
+// 海龟交易法：N 日通道突破入场，M 日反向通道离场，每涨 pyramid_atr 个 N 加一个单位，
+// 止损放在最后一个单位入场价外 stop_atr 个 N。头寸大小交给 AtrSizer。
+pub struct TurtleStrategy {
+    name: String,
+    entry_channel: DonchianIndicator,
+    exit_channel: DonchianIndicator,
+    atr: AtrIndicator,
+    stop_atr: f64,
+    pyramid_atr: f64,
+    max_units: u32,
+    units: u32,
+    last_fill: f64,
+    stop: f64,
+    position_type: PositionType,
+}
+
+impl TurtleStrategy {
+    pub fn new(name: String, entry_period: usize, exit_period: usize, atr_period: usize) -> Self {
+        TurtleStrategy {
+            name,
+            entry_channel: DonchianIndicator::new(entry_period),
+            exit_channel: DonchianIndicator::new(exit_period),
+            atr: AtrIndicator::new(atr_period),
+            stop_atr: 2.0,
+            pyramid_atr: 0.5,
+            max_units: 4,
+            units: 0,
+            last_fill: 0.0,
+            stop: 0.0,
+            position_type: PositionType::Both,
+        }
+    }
+
+    /// 参数说明，见 StrategySchema
+    pub fn schema() -> StrategySchema {
+        StrategySchema::new(
+            "海龟：唐奇安通道突破入场，按 ATR 止损和加仓",
+            vec![
+                ParamSpec::int("entryPeriod", "入场通道周期")
+                    .default(20)
+                    .min(1.0),
+                ParamSpec::int("exitPeriod", "离场通道周期")
+                    .default(10)
+                    .min(1.0),
+                ParamSpec::int("atrPeriod", "ATR 周期").default(20).min(1.0),
+                ParamSpec::float("stopAtr", "止损距离，ATR 倍数")
+                    .default(2.0)
+                    .min(0.0),
+                ParamSpec::float("pyramidAtr", "每上涨多少 ATR 加一个单位")
+                    .default(0.5)
+                    .min(0.0),
+                ParamSpec::int("maxUnits", "最多持有的单位数")
+                    .default(4)
+                    .min(1.0),
+                position_type(),
+            ],
+        )
+        .with_risk(RISK)
+        .with_risk(&["sizing"])
+    }
+
+    pub fn from_params(params: &Value) -> Box<dyn Strategy> {
+        let position_type = match params.get("positionType").and_then(Value::as_str) {
+            Some("long") => PositionType::Long,
+            Some("short") => PositionType::Short,
+            _ => PositionType::Both,
+        };
+
+        let entry_period = params
+            .get("entryPeriod")
+            .and_then(Value::as_u64)
+            .unwrap_or(20) as usize;
+        let exit_period = params
+            .get("exitPeriod")
+            .and_then(Value::as_u64)
+            .unwrap_or(10) as usize;
+        let atr_period = params
+            .get("atrPeriod")
+            .and_then(Value::as_u64)
+            .unwrap_or(20) as usize;
+        let stop_atr = params.get("stopAtr").and_then(Value::as_f64).unwrap_or(2.0);
+        let pyramid_atr = params
+            .get("pyramidAtr")
+            .and_then(Value::as_f64)
+            .unwrap_or(0.5);
+        let max_units = params.get("maxUnits").and_then(Value::as_u64).unwrap_or(4) as u32;
+
+        let mut strategy = Self::new("turtle".to_string(), entry_period, exit_period, atr_period);
+        strategy.stop_atr = stop_atr;
+        strategy.pyramid_atr = pyramid_atr;
+        strategy.max_units = max_units.max(1);
+        strategy.position_type = position_type;
+        Box::new(strategy)
+    }
+
+    pub fn atr(&self) -> Option<f64> {
+        self.atr.value()
+    }
+
+    pub fn stop(&self) -> Option<f64> {
+        (self.units > 0).then_some(self.stop)
+    }
+
+    fn open_unit(&mut self, price: f64, n: f64, long: bool) {
+        self.units += 1;
+        self.last_fill = price;
+        self.stop = if long {
+            price - self.stop_atr * n
+        } else {
+            price + self.stop_atr * n
+        };
+    }
+
+    fn decide(&mut self, bar: &MarketData, position: f64) -> Signal {
+        // 通道只看之前的 bar，当前 bar 用来判断是否突破
+        let entry = self.entry_channel.channel();
+        let exit = self.exit_channel.channel();
+        let price = bar.close_price;
+        let Some(n) = self.atr.value() else {
+            return Signal::Hold;
+        };
+
+        if position == 0.0 {
+            self.units = 0;
+            let Some(entry) = entry else {
+                return Signal::Hold;
+            };
+            if price > entry.upper && self.supports_long() {
+                self.open_unit(price, n, true);
+                return Signal::EnterLong(price);
+            }
+            if price < entry.lower && self.supports_short() {
+                self.open_unit(price, n, false);
+                return Signal::EnterShort(price);
+            }
+            return Signal::Hold;
+        }
+
+        let long = position > 0.0;
+        if self.units == 0 {
+            // 外部开的仓，从当前价开始接管止损
+            self.open_unit(price, n, long);
+        }
+
+        let stopped = if long {
+            bar.low <= self.stop
+        } else {
+            bar.high >= self.stop
+        };
+        let channel_exit = exit.is_some_and(|c| {
+            if long {
+                price < c.lower
+            } else {
+                price > c.upper
+            }
+        });
+        if stopped || channel_exit {
+            self.units = 0;
+            return Signal::Exit;
+        }
+
+        let step = self.pyramid_atr * n;
+        let add = if long {
+            price >= self.last_fill + step
+        } else {
+            price <= self.last_fill - step
+        };
+        if add && self.units < self.max_units {
+            self.open_unit(price, n, long);
+            return Signal::ScaleIn(price);
+        }
+        Signal::Hold
+    }
+
+    fn observe(&mut self, bar: &MarketData) {
+        self.entry_channel.update_bar(bar);
+        self.exit_channel.update_bar(bar);
+    }
+}
+
+impl Strategy for TurtleStrategy {
+    // 只有收盘价时按 high = low = close 处理
+    fn generate_signal(&mut self, price: f64, position: f64) -> Signal {
+        self.on_bar(&MarketData::from_close(Default::default(), price), position)
+    }
+
+    fn on_bar(&mut self, bar: &MarketData, position: f64) -> Signal {
+        self.atr.update_bar(bar);
+        let signal = self.decide(bar, position);
+        self.observe(bar);
+        signal
+    }
+
+    fn on_tick(&mut self, ctx: &mut StrategyContext, data: &MarketData) -> Signal {
+        let signal = self.on_bar(data, ctx.position);
+        ctx.atr = self.atr.value();
+        signal.restrict(self.position_type(), ctx.position)
+    }
+
+    fn update(&mut self, market_data: &MarketData, _current_position: &Option<TradePosition>) {
+        self.atr.update_bar(market_data);
+        self.observe(market_data);
+    }
+
+    fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    fn apply_parameters(&mut self, _entry_threshold: Option<f64>, _exit_threshold: Option<f64>) {}
+
+    fn chart_values(&self) -> Vec<(String, Option<f64>)> {
+        let entry = self.entry_channel.channel();
+        let exit = self.exit_channel.channel();
+        vec![
+            ("entry.upper".to_string(), entry.map(|c| c.upper)),
+            ("entry.lower".to_string(), entry.map(|c| c.lower)),
+            ("exit.upper".to_string(), exit.map(|c| c.upper)),
+            ("exit.lower".to_string(), exit.map(|c| c.lower)),
+            ("atr".to_string(), self.atr.value()),
+        ]
+    }
+
+    fn position_type(&self) -> &PositionType {
+        &self.position_type
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn bar(close: f64, range: f64) -> MarketData {
+        MarketData {
+            symbol: "TEST".to_string(),
+            timestamp: Default::default(),
+            open: close,
+            high: close + range,
+            low: close - range,
+            close_price: close,
+            volume: 0.0,
+        }
+    }
+
+    fn run(strategy: &mut dyn Strategy, bars: &[MarketData]) -> (Vec<Signal>, StrategyContext) {
+        let mut ctx = StrategyContext::new(100_000.0);
+        let signals = bars
+            .iter()
+            .map(|b| {
+                let signal = strategy.on_tick(&mut ctx, b);
+                match signal {
+                    Signal::EnterLong(_) | Signal::ScaleIn(_) if ctx.position >= 0.0 => {
+                        ctx.position += 1.0
+                    }
+                    Signal::EnterShort(_) | Signal::ScaleIn(_) => ctx.position -= 1.0,
+                    Signal::Exit => ctx.position = 0.0,
+                    _ => {}
+                }
+                signal
+            })
+            .collect();
+        (signals, ctx)
+    }
+
+    fn params() -> Value {
+        json!({
+            "entryPeriod": 5,
+            "exitPeriod": 3,
+            "atrPeriod": 3,
+            "maxUnits": 3
+        })
+    }
+
+    #[test]
+    fn test_breakout_entry_pyramids_and_channel_exit() {
+        let mut bars: Vec<MarketData> = (0..6).map(|_| bar(100.0, 1.0)).collect();
+        // 突破后每根涨 1，ATR = 2，每 0.5N = 1 加一个单位，最多 3 个
+        bars.extend((1..=6).map(|i| bar(101.5 + i as f64, 1.0)));
+        bars.extend([bar(106.0, 1.0), bar(103.0, 1.0)]);
+
+        let mut strategy = TurtleStrategy::from_params(&params());
+        let (signals, ctx) = run(strategy.as_mut(), &bars);
+
+        assert_eq!(signals[6], Signal::EnterLong(102.5));
+        assert!(ctx.atr.is_some());
+        let adds = signals
+            .iter()
+            .filter(|s| matches!(s, Signal::ScaleIn(_)))
+            .count();
+        assert_eq!(adds, 2);
+        assert_eq!(signals[12], Signal::Hold);
+        assert_eq!(signals[13], Signal::Exit);
+        assert_eq!(ctx.position, 0.0);
+    }
+
+    #[test]
+    fn test_two_n_stop_uses_intrabar_low() {
+        let mut bars: Vec<MarketData> = (0..6).map(|_| bar(100.0, 1.0)).collect();
+        bars.push(bar(102.5, 1.0));
+        // 收盘价在出场通道内，但盘中最低价打穿 2N 止损
+        let mut gap = bar(101.5, 1.0);
+        gap.low = 97.0;
+        bars.push(gap);
+
+        let mut strategy = TurtleStrategy::new("turtle".to_string(), 5, 20, 3);
+        let (signals, _) = run(&mut strategy, &bars);
+
+        assert_eq!(signals[6], Signal::EnterLong(102.5));
+        assert_eq!(signals[7], Signal::Exit);
+    }
+
+    #[test]
+    fn test_long_only_ignores_downside_breakout() {
+        let mut bars: Vec<MarketData> = (0..6).map(|_| bar(100.0, 1.0)).collect();
+        bars.push(bar(97.0, 1.0));
+
+        let mut params = params();
+        params["positionType"] = json!("long");
+        let mut long_only = TurtleStrategy::from_params(&params);
+        let mut both = TurtleStrategy::from_params(&self::params());
+
+        assert_eq!(run(long_only.as_mut(), &bars).0[6], Signal::Hold);
+        assert_eq!(run(both.as_mut(), &bars).0[6], Signal::EnterShort(97.0));
+    }
+}
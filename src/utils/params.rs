@@ -1,10 +1,12 @@
 use serde::Serialize;
 use serde_json::{Map, Value};
 
+use crate::strategy::{param_schema::ParamGroup, strategy_factory::StrategyFactory};
+
 pub struct ParamSchema {
-    pub strategy_keys: &'static [&'static str],
-    pub risk_keys: &'static [&'static str],
-    pub exec_keys: &'static [&'static str],
+    pub strategy_keys: Vec<&'static str>,
+    pub risk_keys: Vec<&'static str>,
+    pub exec_keys: Vec<&'static str>,
 }
 
 #[derive(Serialize)]
@@ -41,50 +43,18 @@ pub fn split_params(all: &Value, name: &str) -> SplitParams {
     }
 }
 
+/// 键列表取自策略注册的 StrategySchema，未注册的策略三份都为空
 pub fn schema_for_strategy(name: &str) -> ParamSchema {
-    match name {
-        "ma-crossover" => ParamSchema {
-            strategy_keys: &[
-                "meanType",
-                "fastPeriod",
-                "slowPeriod",
-                "entryThreshold",
-                "exitThreshold",
-                "rebalanceInterval",
-                "entryDelay",
-            ],
-            risk_keys: &[
-                "stopLoss",
-                "takeProfit",
-                "riskPerTrade",
-                "maxConcurrentPositions",
-                "positionSize",
-            ],
-            exec_keys: &["slippage", "commission"],
-        },
-        "mean-reversion" => ParamSchema {
-            strategy_keys: &[
-                "meanType",
-                "lookbackPeriod",
-                "entryZScore",
-                "exitZScore",
-                "reversionStyle",
-                "bandMultiplier",
-                "exitThreshold",
-            ],
-            risk_keys: &[
-                "stopLoss",
-                "takeProfit",
-                "riskPerTrade",
-                "maxConcurrentPositions",
-                "positionSize",
-            ],
-            exec_keys: &["slippage", "commission"],
+    match StrategyFactory::new().schema(name) {
+        Some(schema) => ParamSchema {
+            strategy_keys: schema.keys(ParamGroup::Strategy),
+            risk_keys: schema.keys(ParamGroup::Risk),
+            exec_keys: schema.keys(ParamGroup::Exec),
         },
-        _ => ParamSchema {
-            strategy_keys: &[],
-            risk_keys: &[],
-            exec_keys: &[],
+        None => ParamSchema {
+            strategy_keys: Vec::new(),
+            risk_keys: Vec::new(),
+            exec_keys: Vec::new(),
         },
     }
 }